@@ -25,8 +25,9 @@
 //! - Create command aliases that expand to longer commands
 //! - Create shell aliases that execute through the system shell
 //! - Import/export aliases for sharing configurations
+//! - `$1`..`$9` and `$@` argument substitution
 //! - Automatic circular reference detection
-//! - Reserved command protection
+//! - Built-in command protection
 //!
 //! # Example
 //!
@@ -118,6 +119,37 @@ pub struct AliasConfig {
     pub aliases: HashMap<String, AliasEntry>,
 }
 
+impl AliasConfig {
+    /// Builds an alias configuration from the `[aliases]` section of the
+    /// main configuration file.
+    ///
+    /// `bb alias set` stores each alias there as a plain string, with shell
+    /// aliases prefixed by `!` (see [`AliasEntry::parse`]).
+    ///
+    /// # Parameters
+    ///
+    /// * `aliases` - Map of alias name to stored expansion, e.g. `Config::aliases`
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::collections::HashMap;
+    /// use bitbucket_cli::alias::AliasConfig;
+    ///
+    /// let stored = HashMap::from([("br".to_string(), "!git branch".to_string())]);
+    /// let config = AliasConfig::from_config_aliases(&stored);
+    /// assert!(config.aliases["br"].shell);
+    /// ```
+    pub fn from_config_aliases(aliases: &HashMap<String, String>) -> Self {
+        Self {
+            aliases: aliases
+                .iter()
+                .map(|(name, value)| (name.clone(), AliasEntry::parse(value)))
+                .collect(),
+        }
+    }
+}
+
 /// Represents a single command alias definition.
 ///
 /// An alias entry contains the command expansion string and a flag indicating
@@ -133,10 +165,11 @@ pub struct AliasConfig {
 /// There are two types of aliases:
 ///
 /// 1. **Command aliases** (`shell = false`): The expansion is parsed into
-///    arguments and passed to the CLI. Additional arguments are appended.
+///    arguments and passed to the CLI. `$1`..`$9` and `$@` are replaced by
+///    the arguments; arguments not used by a placeholder are appended.
 ///
 /// 2. **Shell aliases** (`shell = true`): The expansion is passed directly
-///    to the system shell for execution. Additional arguments are ignored.
+///    to `sh -c`, with the arguments available as `$1`, `$2`, ... and `$@`.
 ///
 /// # Example
 ///
@@ -173,6 +206,30 @@ pub struct AliasEntry {
 }
 
 impl AliasEntry {
+    /// Parses an alias as stored in the configuration file.
+    ///
+    /// A leading `!` marks a shell alias, as written by `bb alias set --shell`.
+    ///
+    /// # Parameters
+    ///
+    /// * `value` - The stored expansion
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bitbucket_cli::alias::AliasEntry;
+    ///
+    /// assert!(AliasEntry::parse("!git status").shell);
+    /// assert_eq!(AliasEntry::parse("!git status").expansion, "git status");
+    /// assert!(!AliasEntry::parse("pr list").shell);
+    /// ```
+    pub fn parse(value: &str) -> Self {
+        match value.strip_prefix('!') {
+            Some(command) => Self::shell_command(command.to_string()),
+            None => Self::command(value.to_string()),
+        }
+    }
+
     /// Creates a new alias entry with the specified expansion and shell flag.
     ///
     /// This is the primary constructor for creating alias entries with full
@@ -259,7 +316,7 @@ impl AliasEntry {
     ///
     /// # Notes
     ///
-    /// - Shell aliases do not support argument appending
+    /// - Arguments are passed to the shell as `$1`, `$2`, ... rather than appended
     /// - The command is passed to the shell with the `-c` flag
     /// - Be cautious with user input to avoid shell injection vulnerabilities
     pub fn shell_command(expansion: String) -> Self {
@@ -489,20 +546,19 @@ impl AliasManager {
     /// Returns an error if:
     /// - The alias name is empty
     /// - The alias name contains whitespace
-    /// - The alias name is a reserved command (help, version, alias, etc.)
+    /// - The alias name is a built-in command (help, pr, alias, etc.)
     /// - The alias would create a circular reference
     /// - The configuration file cannot be written
     ///
     /// # Notes
     ///
-    /// Reserved commands that cannot be aliased: `help`, `version`, `alias`,
-    /// `extension`, `config`, `completion`
+    /// Built-in commands cannot be aliased; see [`is_builtin_command`].
     pub fn set(&mut self, name: &str, expansion: &str, shell: bool) -> Result<()> {
         // Validate alias name
         validate_alias_name(name)?;
 
-        // Check for circular references (simple check)
-        if !shell && expansion.starts_with(name) {
+        // Check for an alias that expands to itself
+        if !shell && expansion.split_whitespace().next() == Some(name) {
             anyhow::bail!("Alias would create a circular reference: {}", name);
         }
 
@@ -779,7 +835,7 @@ impl ExpandedAlias {
     }
 }
 
-/// Validates an alias name against naming rules and reserved words.
+/// Validates an alias name against naming rules and built-in commands.
 ///
 /// # Parameters
 ///
@@ -795,17 +851,8 @@ impl ExpandedAlias {
 /// An alias name is valid if it:
 /// 1. Is not empty
 /// 2. Contains no whitespace characters
-/// 3. Is not a reserved command name
-///
-/// # Reserved Commands
-///
-/// The following command names cannot be aliased:
-/// - `help`
-/// - `version`
-/// - `alias`
-/// - `extension`
-/// - `config`
-/// - `completion`
+/// 3. Does not start with `-`, so it cannot be mistaken for a flag
+/// 4. Does not shadow a built-in command (see [`is_builtin_command`])
 ///
 /// # Example
 ///
@@ -821,8 +868,9 @@ impl ExpandedAlias {
 /// assert!(validate_alias_name("").is_err());
 /// assert!(validate_alias_name("has space").is_err());
 /// assert!(validate_alias_name("help").is_err());
+/// assert!(validate_alias_name("pr").is_err());
 /// ```
-fn validate_alias_name(name: &str) -> Result<()> {
+pub fn validate_alias_name(name: &str) -> Result<()> {
     if name.is_empty() {
         anyhow::bail!("Alias name cannot be empty");
     }
@@ -832,91 +880,242 @@ fn validate_alias_name(name: &str) -> Result<()> {
         anyhow::bail!("Alias name cannot contain whitespace");
     }
 
-    // Reserved commands that cannot be aliased
-    const RESERVED: &[&str] = &[
-        "help",
-        "version",
-        "alias",
-        "extension",
-        "config",
-        "completion",
-    ];
-
-    if RESERVED.contains(&name) {
-        anyhow::bail!("Cannot create alias for reserved command: {}", name);
+    if name.starts_with('-') {
+        anyhow::bail!("Alias name cannot start with '-'");
+    }
+
+    if is_builtin_command(name) {
+        anyhow::bail!(
+            "Cannot create alias '{}': it would shadow the built-in '{}' command",
+            name,
+            name
+        );
     }
 
     Ok(())
 }
 
+/// Checks whether a name is a built-in `bb` command.
+///
+/// Built-in commands are the top-level subcommands of the CLI, including
+/// their visible and hidden aliases, and `help`.
+///
+/// # Parameters
+///
+/// * `name` - The command name to check
+///
+/// # Returns
+///
+/// `true` if running `bb <name>` would run a built-in command.
+///
+/// # Example
+///
+/// ```rust
+/// use bitbucket_cli::alias::is_builtin_command;
+///
+/// assert!(is_builtin_command("pr"));
+/// assert!(is_builtin_command("help"));
+/// assert!(!is_builtin_command("co"));
+/// ```
+pub fn is_builtin_command(name: &str) -> bool {
+    use clap::CommandFactory;
+
+    name == "help"
+        || crate::cli::Cli::command()
+            .get_subcommands()
+            .any(|cmd| cmd.get_name() == name || cmd.get_all_aliases().any(|a| a == name))
+}
+
+/// The result of expanding an alias on the command line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expansion {
+    /// Arguments to parse as a `bb` command, without the program name.
+    ///
+    /// These are the original arguments when no alias applies.
+    Args(Vec<String>),
+
+    /// A shell alias to run with `sh -c`.
+    Shell {
+        /// The alias name, passed to the shell as `$0`
+        name: String,
+        /// The shell command
+        command: String,
+        /// The remaining arguments, available as `$1`, `$2`, ... and `$@`
+        args: Vec<String>,
+    },
+}
+
 /// Expands aliases in command line arguments.
 ///
-/// This function checks if the first argument is a defined alias and
-/// expands it accordingly. For command aliases, remaining arguments
-/// are appended. For shell aliases, only the expansion is returned.
+/// If the first argument is an alias, it is replaced by its expansion.
+/// Command aliases are expanded repeatedly, so an alias may refer to
+/// another alias, until the first argument is no longer an alias.
 ///
 /// # Parameters
 ///
-/// * `args` - The command line arguments to process
-/// * `aliases` - The alias manager to use for expansion
+/// * `args` - The command line arguments, without the program name
+/// * `aliases` - The defined aliases, e.g. from [`AliasConfig::from_config_aliases`]
 ///
 /// # Returns
 ///
-/// A vector of strings containing the expanded (or original) arguments.
+/// The expanded arguments, or the shell command to run for a shell alias.
 ///
 /// # Expansion Behavior
 ///
-/// - **Empty args**: Returns empty vector
-/// - **First arg is command alias**: Expansion + remaining args
-/// - **First arg is shell alias**: Shell execution args only
 /// - **First arg not an alias**: Returns original args unchanged
+/// - **First arg is a built-in command**: Returns original args unchanged;
+///   aliases never shadow built-in commands
+/// - **First arg is command alias**: `$1`..`$9` in the expansion are
+///   replaced by the corresponding arguments and `$@` by all of them;
+///   arguments not used by a placeholder are appended
+/// - **First arg is shell alias**: [`Expansion::Shell`] with the remaining
+///   arguments, which the shell sees as `$1`, `$2`, ... and `$@`
 ///
-/// # Example
+/// # Errors
 ///
-/// ```rust,no_run
-/// use std::path::PathBuf;
-/// use bitbucket_cli::alias::{AliasManager, expand_args};
+/// Returns an error if:
+/// - An alias refers back to itself, directly or through other aliases
+/// - An expansion uses `$N` but fewer than N arguments were given
+/// - An expansion cannot be split into words, e.g. an unclosed quote
 ///
-/// let mut manager = AliasManager::new(PathBuf::from("aliases.toml"))?;
-/// manager.set("prs", "pr list --state open", false)?;
+/// # Example
 ///
-/// // Command alias expansion with additional args
-/// let args = vec!["prs".to_string(), "--author".to_string(), "me".to_string()];
-/// let expanded = expand_args(&args, &manager);
-/// // Result: ["pr", "list", "--state", "open", "--author", "me"]
+/// ```rust
+/// use std::collections::HashMap;
+/// use bitbucket_cli::alias::{expand_args, AliasEntry, Expansion};
+///
+/// let aliases = HashMap::from([(
+///     "co".to_string(),
+///     AliasEntry::command("pr checkout $1".to_string()),
+/// )]);
 ///
-/// // Non-alias passthrough
-/// let args = vec!["pr".to_string(), "create".to_string()];
-/// let expanded = expand_args(&args, &manager);
-/// // Result: ["pr", "create"]
+/// let args = vec!["co".to_string(), "12".to_string()];
+/// assert_eq!(
+///     expand_args(&args, &aliases)?,
+///     Expansion::Args(vec!["pr".into(), "checkout".into(), "12".into()])
+/// );
 /// # Ok::<(), anyhow::Error>(())
 /// ```
-///
-/// # Notes
-///
-/// - Only the first argument is checked for alias expansion
-/// - Nested aliases are not expanded (no recursive expansion)
-/// - Shell aliases replace all arguments with shell invocation format
-pub fn expand_args(args: &[String], aliases: &AliasManager) -> Vec<String> {
-    if args.is_empty() {
-        return args.to_vec();
+pub fn expand_args(args: &[String], aliases: &HashMap<String, AliasEntry>) -> Result<Expansion> {
+    let mut args = args.to_vec();
+    let mut chain: Vec<String> = Vec::new();
+
+    loop {
+        let Some(name) = args.first().cloned() else {
+            return Ok(Expansion::Args(args));
+        };
+
+        if is_builtin_command(&name) {
+            return Ok(Expansion::Args(args));
+        }
+
+        let Some(entry) = aliases.get(&name) else {
+            return Ok(Expansion::Args(args));
+        };
+
+        if chain.contains(&name) {
+            chain.push(name);
+            anyhow::bail!("Alias expansion is recursive: {}", chain.join(" -> "));
+        }
+        chain.push(name.clone());
+
+        let rest = args[1..].to_vec();
+
+        if entry.shell {
+            return Ok(Expansion::Shell {
+                name,
+                command: entry.expansion.clone(),
+                args: rest,
+            });
+        }
+
+        args = substitute_args(&name, &entry.expansion, &rest)?;
     }
+}
 
-    let first = &args[0];
+/// Splits a command alias expansion into words and substitutes `$1`..`$9`
+/// and `$@` with the given arguments.
+fn substitute_args(name: &str, expansion: &str, args: &[String]) -> Result<Vec<String>> {
+    let words = shell_words::split(expansion)
+        .map_err(|e| anyhow::anyhow!("Invalid expansion for alias '{}': {}", name, e))?;
 
-    if let Some(expanded) = aliases.expand(first) {
-        if expanded.shell {
-            // Shell aliases replace everything
-            expanded.to_args()
-        } else {
-            // Command aliases expand and append remaining args
-            let mut result = expanded.to_args();
-            result.extend(args[1..].iter().cloned());
-            result
+    let mut used = vec![false; args.len()];
+    let mut all_used = false;
+    let mut result = Vec::new();
+
+    for word in words {
+        if word == "$@" {
+            result.extend(args.iter().cloned());
+            all_used = true;
+            continue;
+        }
+
+        let mut expanded = String::new();
+        let mut chars = word.chars().peekable();
+        while let Some(c) = chars.next() {
+            let index = match (c, chars.peek()) {
+                ('$', Some(d @ '1'..='9')) => *d as usize - '0' as usize,
+                _ => {
+                    expanded.push(c);
+                    continue;
+                }
+            };
+            chars.next();
+
+            let Some(arg) = args.get(index - 1) else {
+                anyhow::bail!(
+                    "Alias '{}' needs at least {} argument(s): {}",
+                    name,
+                    index,
+                    expansion
+                );
+            };
+            expanded.push_str(arg);
+            used[index - 1] = true;
         }
-    } else {
-        args.to_vec()
+        result.push(expanded);
+    }
+
+    if !all_used {
+        result.extend(
+            args.iter()
+                .zip(used)
+                .filter(|(_, used)| !used)
+                .map(|(arg, _)| arg.clone()),
+        );
     }
+
+    Ok(result)
+}
+
+/// Runs a shell alias with `sh -c`.
+///
+/// The alias name is passed as `$0` and the arguments as `$1`, `$2`, ...,
+/// so the command can use them like any shell script.
+///
+/// # Parameters
+///
+/// * `name` - The alias name
+/// * `command` - The shell command
+/// * `args` - The arguments given after the alias name
+///
+/// # Returns
+///
+/// The exit code of the shell, or `1` if it was killed by a signal.
+///
+/// # Errors
+///
+/// Returns an error if `sh` cannot be started.
+pub fn run_shell_alias(name: &str, command: &str, args: &[String]) -> Result<i32> {
+    let status = std::process::Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .arg(name)
+        .args(args)
+        .status()
+        .map_err(|e| anyhow::anyhow!("Failed to run shell alias '{}': {}", name, e))?;
+
+    Ok(status.code().unwrap_or(1))
 }
 
 #[cfg(test)]
@@ -937,5 +1136,91 @@ mod tests {
         assert!(validate_alias_name("").is_err());
         assert!(validate_alias_name("has space").is_err());
         assert!(validate_alias_name("help").is_err());
+        assert!(validate_alias_name("pr").is_err());
+        assert!(validate_alias_name("ls").is_ok());
+    }
+
+    fn aliases(entries: &[(&str, &str)]) -> HashMap<String, AliasEntry> {
+        AliasConfig::from_config_aliases(
+            &entries
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+        )
+        .aliases
+    }
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_expand_appends_unused_args() {
+        let aliases = aliases(&[("co", "pr checkout")]);
+        assert_eq!(
+            expand_args(&args(&["co", "12", "--force"]), &aliases).unwrap(),
+            Expansion::Args(args(&["pr", "checkout", "12", "--force"]))
+        );
+        assert_eq!(
+            expand_args(&args(&["repo", "list"]), &aliases).unwrap(),
+            Expansion::Args(args(&["repo", "list"]))
+        );
+    }
+
+    #[test]
+    fn test_expand_positional_placeholders() {
+        let aliases = aliases(&[
+            ("mine", "pr list --author $1 --state '$2 only'"),
+            ("all", "pr comment $1 --body $@"),
+        ]);
+        assert_eq!(
+            expand_args(&args(&["mine", "jane", "OPEN", "extra"]), &aliases).unwrap(),
+            Expansion::Args(args(&[
+                "pr",
+                "list",
+                "--author",
+                "jane",
+                "--state",
+                "OPEN only",
+                "extra"
+            ]))
+        );
+        assert_eq!(
+            expand_args(&args(&["all", "7", "hi"]), &aliases).unwrap(),
+            Expansion::Args(args(&["pr", "comment", "7", "--body", "7", "hi"]))
+        );
+
+        let err = expand_args(&args(&["mine"]), &aliases).unwrap_err();
+        assert!(err.to_string().contains("at least 1 argument"));
+    }
+
+    #[test]
+    fn test_expand_nested_and_recursive_aliases() {
+        let nested = aliases(&[("co", "pr checkout"), ("co1", "co 1")]);
+        assert_eq!(
+            expand_args(&args(&["co1"]), &nested).unwrap(),
+            Expansion::Args(args(&["pr", "checkout", "1"]))
+        );
+
+        let recursive = aliases(&[("a", "b --x"), ("b", "a")]);
+        let err = expand_args(&args(&["a"]), &recursive).unwrap_err();
+        assert_eq!(err.to_string(), "Alias expansion is recursive: a -> b -> a");
+    }
+
+    #[test]
+    fn test_expand_shell_alias_and_builtin_shadowing() {
+        let aliases = aliases(&[("br", "!git branch --show-current"), ("pr", "repo list")]);
+        assert_eq!(
+            expand_args(&args(&["br", "x"]), &aliases).unwrap(),
+            Expansion::Shell {
+                name: "br".to_string(),
+                command: "git branch --show-current".to_string(),
+                args: args(&["x"]),
+            }
+        );
+        assert_eq!(
+            expand_args(&args(&["pr", "list"]), &aliases).unwrap(),
+            Expansion::Args(args(&["pr", "list"]))
+        );
     }
 }
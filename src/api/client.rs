@@ -23,6 +23,8 @@ use anyhow::Result;
 use reqwest::{Client, StatusCode};
 use serde::de::DeserializeOwned;
 
+use super::replay::ReplayExt;
+use super::server::capabilities::cached_server_info;
 use crate::auth::AuthCredential;
 use crate::config::HostConfig;
 
@@ -219,7 +221,8 @@ impl BitbucketClient {
     /// Creates a new client configured for Bitbucket Server/Data Center.
     ///
     /// This creates a client that targets a Bitbucket Server/DC instance at
-    /// `https://<host>/rest/api/1.0`.
+    /// `https://<host>/rest/api/1.0`. The server version is taken from the
+    /// version cache if the host has been seen before.
     ///
     /// # Parameters
     ///
@@ -246,7 +249,9 @@ impl BitbucketClient {
                 .user_agent(format!("bb/{}", crate::VERSION))
                 .build()?,
             host: host.to_string(),
-            host_type: HostType::Server { version: None },
+            host_type: HostType::Server {
+                version: cached_server_info(host).map(|info| info.version),
+            },
             auth: None,
         })
     }
@@ -269,7 +274,8 @@ impl BitbucketClient {
     ///
     /// The platform type is automatically detected based on the host:
     /// - `bitbucket.org` or `api.bitbucket.org` → Cloud
-    /// - Any other host → Server/DC
+    /// - Any other host → Server/DC, with the version taken from the version
+    ///   cache, or from `api_version` if the host has not been seen before
     ///
     /// # Example
     ///
@@ -291,7 +297,9 @@ impl BitbucketClient {
             HostType::Cloud
         } else {
             HostType::Server {
-                version: config.api_version.clone(),
+                version: cached_server_info(&config.host)
+                    .map(|info| info.version)
+                    .or_else(|| config.api_version.clone()),
             }
         };
 
@@ -372,6 +380,23 @@ impl BitbucketClient {
         matches!(self.host_type, HostType::Server { .. })
     }
 
+    /// Returns the Server/DC version, if known.
+    ///
+    /// The version is populated from the version cache when the client is
+    /// created; [`capabilities::detect`](super::server::capabilities::detect)
+    /// queries and caches it.
+    ///
+    /// # Returns
+    ///
+    /// `Some(version)` for a Server/DC client whose version is known,
+    /// `None` for Cloud or when the version has not been detected yet.
+    pub fn server_version(&self) -> Option<&str> {
+        match &self.host_type {
+            HostType::Server { version } => version.as_deref(),
+            HostType::Cloud => None,
+        }
+    }
+
     /// Returns the base URL for API requests.
     ///
     /// The base URL depends on the platform type:
@@ -447,7 +472,7 @@ impl BitbucketClient {
             request = auth.apply_to_request(request);
         }
 
-        let response = request.send().await?;
+        let response = request.send_replayable().await?;
         let status = response.status();
 
         if !status.is_success() {
@@ -517,7 +542,7 @@ impl BitbucketClient {
             request = auth.apply_to_request(request);
         }
 
-        let response = request.send().await?;
+        let response = request.send_replayable().await?;
         let status = response.status();
 
         if !status.is_success() {
@@ -567,7 +592,7 @@ impl BitbucketClient {
             request = auth.apply_to_request(request);
         }
 
-        let response = request.send().await?;
+        let response = request.send_replayable().await?;
         let status = response.status();
 
         if !status.is_success() {
@@ -617,7 +642,7 @@ impl BitbucketClient {
             request = auth.apply_to_request(request);
         }
 
-        let response = request.send().await?;
+        let response = request.send_replayable().await?;
         let status = response.status();
 
         if !status.is_success() {
@@ -73,6 +73,7 @@
 use serde::{Deserialize, Serialize};
 
 use crate::api::common::UserRef;
+use crate::output::JsonFields;
 
 /// Represents an issue in a Bitbucket Cloud repository.
 ///
@@ -152,6 +153,8 @@ pub struct Issue {
     pub votes: u32,
 }
 
+impl JsonFields for Issue {}
+
 /// Content of an issue in multiple format representations.
 ///
 /// Bitbucket provides issue content in multiple formats to support
@@ -72,6 +72,8 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::output::JsonFields;
+
 /// Represents a Bitbucket Pipeline run.
 ///
 /// A pipeline is a single execution of your CI/CD workflow, triggered by
@@ -131,6 +133,8 @@ pub struct Pipeline {
     pub duration_in_seconds: Option<u64>,
 }
 
+impl JsonFields for Pipeline {}
+
 /// The state of a pipeline execution.
 ///
 /// Represents the current execution state and optional result for
@@ -43,6 +43,7 @@
 //!     },
 //!     reviewers: vec![],
 //!     close_source_branch: Some(true),
+//!     draft: None,
 //! };
 //! ```
 //!
@@ -55,7 +56,8 @@
 use serde::{Deserialize, Serialize};
 
 use super::repositories::Branch;
-use crate::api::common::UserRef;
+use crate::api::common::{Link, UserRef};
+use crate::output::JsonFields;
 
 /// Represents a pull request in Bitbucket Cloud.
 ///
@@ -79,6 +81,7 @@ use crate::api::common::UserRef;
 /// * `merge_commit` - The merge commit (if merged)
 /// * `comment_count` - Number of comments on the PR
 /// * `task_count` - Number of tasks/todos in the PR
+/// * `links` - Links to related resources, such as the web page (`html`)
 ///
 /// # Example
 ///
@@ -147,6 +150,24 @@ pub struct PullRequest {
     /// Total number of tasks/todos on this pull request.
     #[serde(default)]
     pub task_count: u32,
+
+    /// Whether the pull request is a draft, not yet ready for review.
+    #[serde(default)]
+    pub draft: bool,
+
+    /// Links to related resources, such as the pull request's web page.
+    #[serde(default)]
+    pub links: PrLinks,
+}
+
+impl JsonFields for PullRequest {}
+
+/// Links attached to a pull request.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PrLinks {
+    /// The pull request's page on bitbucket.org.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub html: Option<Link>,
 }
 
 /// Branch reference within a pull request context.
@@ -286,6 +307,10 @@ pub struct Participant {
     #[serde(default)]
     pub approved: bool,
 
+    /// The user's review state: `approved`, `changes_requested`, or none.
+    #[serde(default)]
+    pub state: Option<String>,
+
     /// ISO 8601 timestamp of the user's last participation.
     #[serde(default)]
     pub participated_on: Option<String>,
@@ -325,6 +350,7 @@ pub struct Participant {
 ///         UserUuid { uuid: "{reviewer-uuid}".to_string() },
 ///     ],
 ///     close_source_branch: Some(true),
+///     draft: None,
 /// };
 /// ```
 ///
@@ -354,6 +380,10 @@ pub struct CreatePullRequestRequest {
     /// Whether to delete the source branch after a successful merge.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub close_source_branch: Option<bool>,
+
+    /// Whether to open the pull request as a draft.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub draft: Option<bool>,
 }
 
 /// Branch specification for pull request creation.
@@ -40,6 +40,9 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::output::JsonFields;
+use crate::util::glob_match;
+
 /// Represents a Bitbucket Cloud repository.
 ///
 /// A repository contains all the source code, history, branches, and associated
@@ -122,6 +125,8 @@ pub struct Repository {
     pub updated_on: String,
 }
 
+impl JsonFields for Repository {}
+
 /// Represents a branch within a repository.
 ///
 /// Branches are used to isolate development work and represent different
@@ -350,3 +355,137 @@ pub struct ProjectKey {
     /// The short alphanumeric key identifying the project.
     pub key: String,
 }
+
+/// A branch restriction (branch permission or merge check) on a repository.
+///
+/// Returned by `GET /repositories/{workspace}/{repo_slug}/branch-restrictions`.
+/// Merge checks are the restrictions whose `kind` starts with `require_`,
+/// such as `require_approvals_to_merge` or `require_passing_builds_to_merge`.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use bitbucket_cli::api::cloud::repositories::{BranchRestriction, BranchingModel};
+///
+/// let restriction = BranchRestriction {
+///     kind: "require_approvals_to_merge".to_string(),
+///     value: Some(2),
+///     branch_match_kind: "glob".to_string(),
+///     pattern: "release/*".to_string(),
+///     branch_type: None,
+/// };
+/// assert!(restriction.applies_to("release/2.0", &BranchingModel::default()));
+/// ```
+///
+/// # Notes
+///
+/// - Restrictions matched by branching model (`branch_match_kind` of
+///   `branching_model`) name a branch type instead of a pattern, so they
+///   need the repository's [`BranchingModel`] to be matched
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BranchRestriction {
+    /// What the restriction enforces, e.g. `require_approvals_to_merge`.
+    pub kind: String,
+
+    /// Threshold for counting restrictions, such as the number of approvals.
+    #[serde(default)]
+    pub value: Option<u32>,
+
+    /// How branches are selected: `glob` or `branching_model`.
+    #[serde(default)]
+    pub branch_match_kind: String,
+
+    /// Glob pattern for the branches the restriction applies to.
+    #[serde(default)]
+    pub pattern: String,
+
+    /// Branch type for branching model restrictions, e.g. `production`.
+    #[serde(default)]
+    pub branch_type: Option<String>,
+}
+
+impl BranchRestriction {
+    /// Whether the restriction applies to pull requests targeting `branch`,
+    /// using `model` to match restrictions that select a branch type
+    pub fn applies_to(&self, branch: &str, model: &BranchingModel) -> bool {
+        match self.branch_match_kind.as_str() {
+            "glob" => glob_match(&self.pattern, branch),
+            "branching_model" => self
+                .branch_type
+                .as_deref()
+                .is_some_and(|kind| model.is_branch_type(kind, branch)),
+            _ => false,
+        }
+    }
+}
+
+/// A repository's branching model.
+///
+/// Returned by `GET /repositories/{workspace}/{repo_slug}/branching-model`.
+/// It names the development and production branches and the prefixes of
+/// the other branch types, such as `feature/`.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use bitbucket_cli::api::cloud::repositories::BranchingModel;
+///
+/// let model: BranchingModel = serde_json::from_str(
+///     r#"{"development": {"branch": {"name": "main"}},
+///         "branch_types": [{"kind": "release", "prefix": "release/"}]}"#,
+/// ).unwrap();
+/// assert!(model.is_branch_type("development", "main"));
+/// assert!(model.is_branch_type("release", "release/2.0"));
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BranchingModel {
+    /// The development branch, which defaults to the main branch.
+    #[serde(default)]
+    pub development: Option<BranchingModelBranch>,
+
+    /// The production branch, if the model has one.
+    #[serde(default)]
+    pub production: Option<BranchingModelBranch>,
+
+    /// The enabled branch types and their prefixes.
+    #[serde(default)]
+    pub branch_types: Vec<BranchTypePrefix>,
+}
+
+/// The development or production branch of a [`BranchingModel`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BranchingModelBranch {
+    /// The branch; absent when the configured branch does not exist.
+    #[serde(default)]
+    pub branch: Option<Branch>,
+}
+
+/// A branch type of a [`BranchingModel`], e.g. `feature` with `feature/`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BranchTypePrefix {
+    /// The branch type: `feature`, `bugfix`, `release` or `hotfix`.
+    pub kind: String,
+
+    /// The prefix of branch names of this type.
+    pub prefix: String,
+}
+
+impl BranchingModel {
+    /// Whether `branch` is of the branch type `kind`, e.g. `production`
+    pub fn is_branch_type(&self, kind: &str, branch: &str) -> bool {
+        let named = |role: &Option<BranchingModelBranch>| {
+            role.as_ref()
+                .and_then(|r| r.branch.as_ref())
+                .is_some_and(|b| b.name == branch)
+        };
+
+        match kind {
+            "development" => named(&self.development),
+            "production" => named(&self.production),
+            _ => self
+                .branch_types
+                .iter()
+                .any(|t| t.kind == kind && branch.starts_with(&t.prefix)),
+        }
+    }
+}
@@ -0,0 +1,435 @@
+//
+//  bitbucket-cli
+//  api/diff.rs
+//
+//  Created by Ngonidzashe Mangudya on 2026/01/12.
+//  Copyright (c) 2025 IAMNGONI. All rights reserved.
+//
+
+//! # Unified Diff Parsing
+//!
+//! Parses the unified diffs returned by the pull request diff endpoints into
+//! files, hunks and numbered lines, so that commands can check that a
+//! position exists before anchoring a comment to it and can show the code a
+//! comment refers to.
+//!
+//! Both the `a/` and `b/` prefixes used by Cloud and the `src://` and
+//! `dst://` prefixes used by Server are understood.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use bitbucket_cli::api::diff::{self, LineKind, Side};
+//!
+//! let files = diff::parse(
+//!     "diff --git a/src/lib.rs b/src/lib.rs\n\
+//!      --- a/src/lib.rs\n\
+//!      +++ b/src/lib.rs\n\
+//!      @@ -1,2 +1,3 @@\n \
+//!      fn main() {\n\
+//!      +    run();\n \
+//!      }\n",
+//! );
+//!
+//! let file = diff::find_file(&files, "src/lib.rs").unwrap();
+//! assert_eq!(file.line(Side::New, 2).unwrap().kind, LineKind::Added);
+//! assert!(file.line(Side::Old, 2).is_some());
+//! assert!(file.line(Side::New, 9).is_none());
+//! ```
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// Which version of a file a line number refers to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Side {
+    /// The destination (base) version, where removed lines live
+    Old,
+    /// The source (head) version, where added lines live
+    New,
+}
+
+impl fmt::Display for Side {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Side::Old => "old",
+            Side::New => "new",
+        })
+    }
+}
+
+/// How a line appears in a hunk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineKind {
+    /// Only in the new version (`+`)
+    Added,
+    /// Only in the old version (`-`)
+    Removed,
+    /// Unchanged, present in both versions (` `)
+    Context,
+}
+
+/// One line of a hunk with its position in each version.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffLine {
+    /// Whether the line was added, removed or is context
+    pub kind: LineKind,
+    /// Line number in the old version, if the line exists there
+    pub old: Option<u32>,
+    /// Line number in the new version, if the line exists there
+    pub new: Option<u32>,
+    /// The line's text without the leading `+`, `-` or space
+    pub text: String,
+}
+
+impl DiffLine {
+    /// Returns the line number on `side`, if the line exists there.
+    pub fn number(&self, side: Side) -> Option<u32> {
+        match side {
+            Side::Old => self.old,
+            Side::New => self.new,
+        }
+    }
+}
+
+impl fmt::Display for DiffLine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let prefix = match self.kind {
+            LineKind::Added => '+',
+            LineKind::Removed => '-',
+            LineKind::Context => ' ',
+        };
+        write!(f, "{}{}", prefix, self.text)
+    }
+}
+
+/// A contiguous block of changes, introduced by an `@@` header.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hunk {
+    /// The `@@ -a,b +c,d @@` header line as it appeared in the diff
+    pub header: String,
+    /// The lines of the hunk, in order
+    pub lines: Vec<DiffLine>,
+}
+
+impl Hunk {
+    /// Returns the index of the line numbered `number` on `side`.
+    pub fn position(&self, side: Side, number: u32) -> Option<usize> {
+        self.lines
+            .iter()
+            .position(|line| line.number(side) == Some(number))
+    }
+
+    /// Returns the first and last line numbers the hunk covers on `side`.
+    pub fn range(&self, side: Side) -> Option<(u32, u32)> {
+        let mut numbers = self.lines.iter().filter_map(|line| line.number(side));
+        let first = numbers.next()?;
+        Some((first, numbers.next_back().unwrap_or(first)))
+    }
+}
+
+/// The changes to one file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DiffFile {
+    /// Path before the change, or `None` for an added file
+    pub old_path: Option<String>,
+    /// Path after the change, or `None` for a deleted file
+    pub new_path: Option<String>,
+    /// The file's hunks; empty for binary files and pure renames
+    pub hunks: Vec<Hunk>,
+}
+
+impl DiffFile {
+    /// Returns the path the file is known by: the new path, or the old one
+    /// if the file was deleted.
+    pub fn path(&self) -> &str {
+        self.new_path
+            .as_deref()
+            .or(self.old_path.as_deref())
+            .unwrap_or_default()
+    }
+
+    /// Returns the hunk containing the line numbered `number` on `side`.
+    pub fn hunk(&self, side: Side, number: u32) -> Option<&Hunk> {
+        self.hunks
+            .iter()
+            .find(|hunk| hunk.position(side, number).is_some())
+    }
+
+    /// Returns the line numbered `number` on `side`, if it is in the diff.
+    pub fn line(&self, side: Side, number: u32) -> Option<&DiffLine> {
+        self.hunk(side, number)
+            .and_then(|hunk| hunk.position(side, number).map(|i| &hunk.lines[i]))
+    }
+
+    /// Returns the line ranges on `side` that appear in the diff, one per
+    /// hunk.
+    pub fn ranges(&self, side: Side) -> Vec<(u32, u32)> {
+        self.hunks
+            .iter()
+            .filter_map(|hunk| hunk.range(side))
+            .collect()
+    }
+}
+
+/// Finds a file by its old or new path.
+pub fn find_file<'a>(files: &'a [DiffFile], path: &str) -> Option<&'a DiffFile> {
+    files.iter().find(|file| {
+        file.new_path.as_deref() == Some(path) || file.old_path.as_deref() == Some(path)
+    })
+}
+
+/// Parses a unified diff.
+///
+/// Lines that are not part of a file header or hunk, such as `index` and
+/// mode lines, are ignored. Malformed hunk headers end the current hunk.
+///
+/// # Parameters
+///
+/// * `diff` - The diff text, as returned by the pull request diff endpoint
+///
+/// # Returns
+///
+/// The files in the order they appear in the diff.
+pub fn parse(diff: &str) -> Vec<DiffFile> {
+    let mut files: Vec<DiffFile> = Vec::new();
+    // Lines still expected in the current hunk, for each side
+    let mut remaining = (0u32, 0u32);
+    let mut next = (0u32, 0u32);
+
+    for raw in diff.lines() {
+        if remaining.0 > 0 || remaining.1 > 0 {
+            if let Some(hunk) = files.last_mut().and_then(|f| f.hunks.last_mut()) {
+                let (kind, text) = match raw.chars().next() {
+                    Some('+') => (LineKind::Added, &raw[1..]),
+                    Some('-') => (LineKind::Removed, &raw[1..]),
+                    Some(' ') => (LineKind::Context, &raw[1..]),
+                    Some('\\') => continue,
+                    // Some tools strip the space from empty context lines
+                    None => (LineKind::Context, ""),
+                    Some(_) => {
+                        remaining = (0, 0);
+                        (LineKind::Context, "")
+                    }
+                };
+                if remaining != (0, 0) {
+                    let mut line = DiffLine {
+                        kind,
+                        old: None,
+                        new: None,
+                        text: text.to_string(),
+                    };
+                    if kind != LineKind::Added {
+                        line.old = Some(next.0);
+                        next.0 += 1;
+                        remaining.0 = remaining.0.saturating_sub(1);
+                    }
+                    if kind != LineKind::Removed {
+                        line.new = Some(next.1);
+                        next.1 += 1;
+                        remaining.1 = remaining.1.saturating_sub(1);
+                    }
+                    hunk.lines.push(line);
+                    continue;
+                }
+            }
+        }
+        remaining = (0, 0);
+
+        if let Some(rest) = raw.strip_prefix("diff --git ") {
+            let (old, new) = split_git_paths(rest);
+            files.push(DiffFile {
+                old_path: old,
+                new_path: new,
+                hunks: Vec::new(),
+            });
+        } else if let Some(path) = raw.strip_prefix("--- ") {
+            // A plain unified diff has no `diff --git` line to start a file
+            if files.last().is_none_or(|f| !f.hunks.is_empty()) {
+                files.push(DiffFile::default());
+            }
+            if let Some(file) = files.last_mut() {
+                file.old_path = header_path(path);
+            }
+        } else if let Some(path) = raw.strip_prefix("+++ ") {
+            if let Some(file) = files.last_mut() {
+                file.new_path = header_path(path);
+            }
+        } else if let Some(path) = raw.strip_prefix("rename from ") {
+            if let Some(file) = files.last_mut() {
+                file.old_path = Some(path.to_string());
+            }
+        } else if let Some(path) = raw.strip_prefix("rename to ") {
+            if let Some(file) = files.last_mut() {
+                file.new_path = Some(path.to_string());
+            }
+        } else if raw.starts_with("@@") {
+            let Some(file) = files.last_mut() else {
+                continue;
+            };
+            let Some((old_start, old_lines, new_start, new_lines)) = parse_hunk_header(raw) else {
+                continue;
+            };
+            file.hunks.push(Hunk {
+                header: raw.to_string(),
+                lines: Vec::new(),
+            });
+            next = (old_start, new_start);
+            remaining = (old_lines, new_lines);
+        }
+    }
+
+    files
+}
+
+/// Parses `@@ -a,b +c,d @@` into `(a, b, c, d)`; omitted counts are 1.
+fn parse_hunk_header(header: &str) -> Option<(u32, u32, u32, u32)> {
+    let mut parts = header.strip_prefix("@@ ")?.split_whitespace();
+    let old = parts.next()?.strip_prefix('-')?;
+    let new = parts.next()?.strip_prefix('+')?;
+
+    let span = |s: &str| -> Option<(u32, u32)> {
+        match s.split_once(',') {
+            Some((start, count)) => Some((start.parse().ok()?, count.parse().ok()?)),
+            None => Some((s.parse().ok()?, 1)),
+        }
+    };
+    let (old_start, old_lines) = span(old)?;
+    let (new_start, new_lines) = span(new)?;
+    Some((old_start, old_lines, new_start, new_lines))
+}
+
+/// Strips the version prefix from a `---`/`+++` path; `/dev/null` is `None`.
+fn header_path(path: &str) -> Option<String> {
+    // Git appends a tab when the path contains spaces
+    let path = path.trim_end_matches('\t');
+    if path == "/dev/null" {
+        return None;
+    }
+    Some(strip_prefix(path).to_string())
+}
+
+/// Splits the paths of a `diff --git a/x b/y` line.
+///
+/// Paths containing spaces make the line ambiguous, so the split assumes
+/// both paths are the same length when there is no rename; the `---`,
+/// `+++` and `rename` lines that follow override the result anyway.
+fn split_git_paths(rest: &str) -> (Option<String>, Option<String>) {
+    let len = rest.len();
+    if len % 2 == 1 {
+        let (old, new) = (&rest[..len / 2], &rest[len / 2 + 1..]);
+        if strip_prefix(old) == strip_prefix(new) {
+            return (
+                Some(strip_prefix(old).to_string()),
+                Some(strip_prefix(new).to_string()),
+            );
+        }
+    }
+    match rest.split_once(' ') {
+        Some((old, new)) => (
+            Some(strip_prefix(old).to_string()),
+            Some(strip_prefix(new).to_string()),
+        ),
+        None => (None, None),
+    }
+}
+
+/// Removes a `a/`, `b/`, `src://` or `dst://` prefix.
+fn strip_prefix(path: &str) -> &str {
+    ["a/", "b/", "src://", "dst://"]
+        .iter()
+        .find_map(|prefix| path.strip_prefix(prefix))
+        .unwrap_or(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DIFF: &str = "\
+diff --git a/src/cache.rs b/src/cache.rs
+index 1111111..2222222 100644
+--- a/src/cache.rs
++++ b/src/cache.rs
+@@ -10,4 +10,5 @@ impl Cache {
+     fn get(&self) {
+-        self.load()
++        self.lookup()
++            .or_else(|| self.load())
+     }
+
+@@ -40,2 +41,2 @@ impl Cache {
+-    // TODO
++    // Expire after five minutes
+     const TTL: u64 = 300;
+\\ No newline at end of file
+diff --git src://docs/old name.md dst://docs/new name.md
+similarity index 100%
+rename from docs/old name.md
+rename to docs/new name.md
+diff --git a/gone.txt b/gone.txt
+deleted file mode 100644
+--- a/gone.txt
++++ /dev/null
+@@ -1 +0,0 @@
+-bye
+";
+
+    #[test]
+    fn test_parse_files_and_paths() {
+        let files = parse(DIFF);
+        assert_eq!(files.len(), 3);
+
+        assert_eq!(files[0].path(), "src/cache.rs");
+        assert_eq!(files[0].hunks.len(), 2);
+
+        assert_eq!(files[1].old_path.as_deref(), Some("docs/old name.md"));
+        assert_eq!(files[1].new_path.as_deref(), Some("docs/new name.md"));
+        assert!(files[1].hunks.is_empty());
+
+        assert_eq!(files[2].new_path, None);
+        assert_eq!(files[2].path(), "gone.txt");
+        assert_eq!(files[2].line(Side::Old, 1).unwrap().text, "bye");
+    }
+
+    #[test]
+    fn test_line_numbers() {
+        let files = parse(DIFF);
+        let file = find_file(&files, "src/cache.rs").unwrap();
+
+        let removed = file.line(Side::Old, 11).unwrap();
+        assert_eq!(removed.kind, LineKind::Removed);
+        assert_eq!(removed.to_string(), "-        self.load()");
+
+        assert_eq!(file.line(Side::New, 12).unwrap().kind, LineKind::Added);
+        assert_eq!(file.line(Side::New, 13).unwrap().old, Some(12));
+        // The empty context line without its leading space
+        assert_eq!(file.line(Side::New, 14).unwrap().text, "");
+        assert_eq!(file.line(Side::New, 42).unwrap().old, Some(41));
+        assert!(file.line(Side::New, 20).is_none());
+        assert!(file.line(Side::Old, 12 + 30).is_none());
+
+        assert_eq!(file.ranges(Side::New), vec![(10, 14), (41, 42)]);
+        assert_eq!(file.ranges(Side::Old), vec![(10, 13), (40, 41)]);
+    }
+
+    #[test]
+    fn test_parse_plain_unified_diff() {
+        let files = parse("--- lib.rs\n+++ lib.rs\n@@ -1 +1 @@\n-a\n+b\n");
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].path(), "lib.rs");
+        assert_eq!(files[0].line(Side::New, 1).unwrap().text, "b");
+    }
+
+    #[test]
+    fn test_parse_hunk_header() {
+        assert_eq!(
+            parse_hunk_header("@@ -10,4 +10,5 @@ fn x()"),
+            Some((10, 4, 10, 5))
+        );
+        assert_eq!(parse_hunk_header("@@ -1 +0,0 @@"), Some((1, 1, 0, 0)));
+        assert_eq!(parse_hunk_header("@@ bogus @@"), None);
+    }
+}
@@ -23,6 +23,9 @@
 //! - [`cloud`]: Cloud-specific API implementations (repositories, PRs, pipelines)
 //! - [`server`]: Server/DC-specific API implementations (repositories, PRs, projects)
 //! - [`common`]: Shared types (pagination, errors, user references)
+//! - [`replay`]: Record/replay of API traffic for tests and bug reports
+//! - [`query`]: URL-encoded query strings and BBQL filters
+//! - [`diff`]: Unified diff parsing for pull request diffs
 //!
 //! ## Usage
 //!
@@ -80,6 +83,24 @@ pub mod cloud;
 /// - [`server::projects`]: Project operations
 pub mod server;
 
+/// Record/replay layer for API traffic.
+///
+/// Captures request/response pairs to fixture files when `BB_RECORD` is set
+/// and serves them back instead of the network when `BB_REPLAY` is set.
+pub mod replay;
+
+/// Query string and BBQL builders.
+///
+/// Builds URL-encoded query strings and Cloud `q=` filter expressions
+/// without hand-formatting user input into URLs.
+pub mod query;
+
+/// Unified diff parser.
+///
+/// Splits pull request diffs into files, hunks and numbered lines for
+/// anchoring and displaying inline comments.
+pub mod diff;
+
 /// Common types shared between Cloud and Server APIs.
 ///
 /// Includes:
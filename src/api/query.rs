@@ -0,0 +1,262 @@
+//
+//  bitbucket-cli
+//  api/query.rs
+//
+//  Created by Ngonidzashe Mangudya on 2026/01/12.
+//  Copyright (c) 2025 IAMNGONI. All rights reserved.
+//
+
+//! # Query Builders
+//!
+//! Helpers for building request query strings without hand-written
+//! `format!` calls, so that every value is quoted and URL-encoded the same
+//! way.
+//!
+//! - [`QueryParams`]: an ordered list of `key=value` pairs, percent-encoded
+//!   when rendered
+//! - [`Bbql`]: a Bitbucket Cloud query language (BBQL) expression for the
+//!   `q` parameter
+//!
+//! ## Example
+//!
+//! ```rust
+//! use bitbucket_cli::api::query::{Bbql, QueryParams};
+//!
+//! let mut q = Bbql::new();
+//! q.equals("destination.branch.name", "main");
+//! q.contains("title", "fix \"cache\"");
+//!
+//! let mut params = QueryParams::new();
+//! params.push("pagelen", 30);
+//! params.push_opt("q", q.build());
+//!
+//! assert_eq!(
+//!     params.to_string(),
+//!     "pagelen=30&q=destination.branch.name%20%3D%20%22main%22%20AND%20title%20~%20%22fix%20%5C%22cache%5C%22%22"
+//! );
+//! ```
+
+use std::fmt;
+
+use chrono::{DateTime, SecondsFormat, Utc};
+
+/// Percent-encodes a query string component.
+///
+/// Every byte except the RFC 3986 unreserved characters
+/// (`A-Z a-z 0-9 - . _ ~`) is encoded, so the result is safe as either a
+/// key or a value.
+///
+/// # Example
+///
+/// ```rust
+/// use bitbucket_cli::api::query::encode;
+///
+/// assert_eq!(encode("on hold"), "on%20hold");
+/// assert_eq!(encode("refs/heads/main"), "refs%2Fheads%2Fmain");
+/// ```
+pub fn encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+/// An ordered list of query parameters.
+///
+/// Keys may repeat. Rendering with `to_string()` produces `key=value` pairs
+/// joined by `&`, with keys and values percent-encoded by [`encode`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct QueryParams {
+    pairs: Vec<(String, String)>,
+}
+
+impl QueryParams {
+    /// Creates an empty parameter list.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends a parameter.
+    pub fn push(&mut self, key: impl Into<String>, value: impl ToString) -> &mut Self {
+        self.pairs.push((key.into(), value.to_string()));
+        self
+    }
+
+    /// Appends a parameter if it has a value.
+    pub fn push_opt(&mut self, key: impl Into<String>, value: Option<impl ToString>) -> &mut Self {
+        if let Some(value) = value {
+            self.push(key, value);
+        }
+        self
+    }
+
+    /// Whether no parameters have been added.
+    pub fn is_empty(&self) -> bool {
+        self.pairs.is_empty()
+    }
+
+    /// Returns the value of the first parameter named `key`.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.pairs
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+impl fmt::Display for QueryParams {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, (key, value)) in self.pairs.iter().enumerate() {
+            if i > 0 {
+                f.write_str("&")?;
+            }
+            write!(f, "{}={}", encode(key), encode(value))?;
+        }
+        Ok(())
+    }
+}
+
+/// A BBQL filter expression, as accepted by Cloud's `q` parameter.
+///
+/// Conditions are combined with `AND`. String values are quoted and
+/// escaped, so user input cannot change the structure of the query.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Bbql {
+    clauses: Vec<String>,
+}
+
+impl Bbql {
+    /// Creates an empty expression.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `field = "value"`.
+    pub fn equals(&mut self, field: &str, value: &str) -> &mut Self {
+        self.clauses.push(format!("{} = {}", field, quote(value)));
+        self
+    }
+
+    /// Adds `field ~ "value"` (case-insensitive substring match).
+    pub fn contains(&mut self, field: &str, value: &str) -> &mut Self {
+        self.clauses.push(format!("{} ~ {}", field, quote(value)));
+        self
+    }
+
+    /// Adds `field IN ("a", "b")`.
+    pub fn in_list(&mut self, field: &str, values: &[&str]) -> &mut Self {
+        let values: Vec<String> = values.iter().map(|value| quote(value)).collect();
+        self.clauses
+            .push(format!("{} IN ({})", field, values.join(", ")));
+        self
+    }
+
+    /// Adds a substring match that succeeds if any of `fields` matches.
+    pub fn contains_any(&mut self, fields: &[&str], value: &str) -> &mut Self {
+        let alternatives: Vec<String> = fields
+            .iter()
+            .map(|field| format!("{} ~ {}", field, quote(value)))
+            .collect();
+        match alternatives.len() {
+            0 => {}
+            1 => self.clauses.extend(alternatives),
+            _ => self
+                .clauses
+                .push(format!("({})", alternatives.join(" OR "))),
+        }
+        self
+    }
+
+    /// Adds `field = true` or `field = false` for a boolean field.
+    pub fn is(&mut self, field: &str, value: bool) -> &mut Self {
+        self.clauses.push(format!("{} = {}", field, value));
+        self
+    }
+
+    /// Adds `field >= <time>` for a date-time field.
+    pub fn on_or_after(&mut self, field: &str, time: &DateTime<Utc>) -> &mut Self {
+        self.clauses.push(format!(
+            "{} >= {}",
+            field,
+            time.to_rfc3339_opts(SecondsFormat::Secs, false)
+        ));
+        self
+    }
+
+    /// Whether no conditions have been added.
+    pub fn is_empty(&self) -> bool {
+        self.clauses.is_empty()
+    }
+
+    /// Returns the expression, or `None` if there are no conditions.
+    pub fn build(&self) -> Option<String> {
+        if self.is_empty() {
+            None
+        } else {
+            Some(self.clauses.join(" AND "))
+        }
+    }
+}
+
+/// Quotes a BBQL string literal.
+fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode() {
+        assert_eq!(encode("abc-1.2_3~"), "abc-1.2_3~");
+        assert_eq!(encode("a b&c=d"), "a%20b%26c%3Dd");
+        assert_eq!(encode("+00:00"), "%2B00%3A00");
+        assert_eq!(encode("é"), "%C3%A9");
+    }
+
+    #[test]
+    fn test_query_params() {
+        let mut params = QueryParams::new();
+        assert!(params.is_empty());
+
+        params
+            .push("role.1", "AUTHOR")
+            .push("username.1", "j doe")
+            .push_opt("at", None::<&str>)
+            .push_opt("order", Some("NEWEST"));
+
+        assert_eq!(
+            params.to_string(),
+            "role.1=AUTHOR&username.1=j%20doe&order=NEWEST"
+        );
+        assert_eq!(params.get("order"), Some("NEWEST"));
+        assert_eq!(params.get("at"), None);
+    }
+
+    #[test]
+    fn test_bbql() {
+        let mut q = Bbql::new();
+        assert_eq!(q.build(), None);
+
+        q.equals("author.nickname", "jdoe")
+            .contains_any(&["title", "description"], "say \"hi\" \\o/")
+            .on_or_after("updated_on", &"2026-01-31T00:00:00Z".parse().unwrap())
+            .is("draft", false)
+            .in_list("user.email", &["a@corp.com", "b\") OR (x@corp.com"]);
+
+        assert_eq!(
+            q.build().unwrap(),
+            "author.nickname = \"jdoe\" AND \
+             (title ~ \"say \\\"hi\\\" \\\\o/\" OR description ~ \"say \\\"hi\\\" \\\\o/\") AND \
+             updated_on >= 2026-01-31T00:00:00+00:00 AND draft = false AND \
+             user.email IN (\"a@corp.com\", \"b\\\") OR (x@corp.com\")"
+        );
+    }
+}
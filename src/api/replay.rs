@@ -0,0 +1,699 @@
+//
+//  bitbucket-cli
+//  api/replay.rs
+//
+//  Created by Ngonidzashe Mangudya on 2026/01/12.
+//  Copyright (c) 2025 IAMNGONI. All rights reserved.
+//
+
+//! # Record/Replay of API Traffic
+//!
+//! This module lets `bb` capture real API sessions to disk and serve them back
+//! later without touching the network. It is the foundation for reproducible
+//! regression tests and for attaching sanitised traffic to bug reports.
+//!
+//! ## Modes
+//!
+//! The mode is selected once per process from the environment:
+//!
+//! | Variable | Behaviour |
+//! |----------|-----------|
+//! | `BB_RECORD=<dir>` | Requests go to the network; each request/response pair is written to `<dir>` |
+//! | `BB_REPLAY=<dir>` | Responses are served from the fixtures in `<dir>`; unmatched requests fail |
+//!
+//! Setting both variables at once is an error.
+//!
+//! ## Fixture Format
+//!
+//! Every exchange is stored as one pretty-printed JSON file named
+//! `<sequence>-<method>-<path>.json`:
+//!
+//! ```json
+//! {
+//!   "method": "GET",
+//!   "url": "https://api.bitbucket.org/2.0/repositories/acme/widgets/pullrequests/42",
+//!   "status": 200,
+//!   "headers": { "content-type": "application/json" },
+//!   "body": { "id": 42, "title": "Add widgets" }
+//! }
+//! ```
+//!
+//! Non-JSON text responses (diffs, logs) are stored in `raw_body` instead of
+//! `body`, and binary responses (artifacts, downloads) are stored
+//! base64-encoded in `raw_body_base64`.
+//!
+//! ## Redaction
+//!
+//! Authorization headers are never written. Credential-like JSON keys
+//! (`password`, `token`, `access_token`, ...), the `value` of secured pipeline
+//! variables, and token query parameters are replaced with `[REDACTED]`. Text
+//! bodies get the same treatment for `key=value` and `"key": "value"` pairs,
+//! and any occurrence of the request's own credential is redacted too.
+//!
+//! ## Usage
+//!
+//! Any request built with `reqwest` can be routed through this layer by
+//! calling [`ReplayExt::send_replayable`] instead of `send`:
+//!
+//! ```rust,no_run
+//! use bitbucket_cli::api::replay::ReplayExt;
+//!
+//! # async fn example() -> anyhow::Result<()> {
+//! let client = reqwest::Client::new();
+//! let response = client
+//!     .get("https://api.bitbucket.org/2.0/user")
+//!     .send_replayable()
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use reqwest::{RequestBuilder, Response};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use url::Url;
+
+/// Environment variable that enables recording into a directory.
+pub const RECORD_ENV: &str = "BB_RECORD";
+
+/// Environment variable that enables replaying from a directory.
+pub const REPLAY_ENV: &str = "BB_REPLAY";
+
+/// Placeholder written in place of secrets.
+const REDACTED: &str = "[REDACTED]";
+
+/// JSON keys whose values are always redacted (compared case-insensitively).
+const SECRET_KEYS: &[&str] = &[
+    "password",
+    "token",
+    "access_token",
+    "refresh_token",
+    "id_token",
+    "client_secret",
+    "secret",
+];
+
+/// Query parameters whose values are always redacted.
+const SECRET_PARAMS: &[&str] = &["access_token", "token", "password"];
+
+/// Response headers preserved in fixtures. Everything else is dropped.
+const RECORDED_HEADERS: &[&str] = &["content-type", "location", "link", "retry-after"];
+
+/// How API traffic is handled for the current process.
+///
+/// # Variants
+///
+/// * `Live` - Requests go straight to the network (default)
+/// * `Record` - Requests go to the network and are saved to the given directory
+/// * `Replay` - Responses are served from fixtures in the given directory
+#[derive(Debug, Clone, PartialEq)]
+pub enum TrafficMode {
+    /// Normal network access.
+    Live,
+    /// Record every exchange into the directory.
+    Record(PathBuf),
+    /// Serve every exchange from fixtures in the directory.
+    Replay(PathBuf),
+}
+
+impl TrafficMode {
+    /// Determines the traffic mode from `BB_RECORD` and `BB_REPLAY`.
+    ///
+    /// Empty values are treated as unset.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if both variables are set.
+    pub fn from_env() -> Result<Self> {
+        let record = std::env::var(RECORD_ENV).ok().filter(|v| !v.is_empty());
+        let replay = std::env::var(REPLAY_ENV).ok().filter(|v| !v.is_empty());
+
+        match (record, replay) {
+            (Some(_), Some(_)) => {
+                bail!("{} and {} cannot be used together", RECORD_ENV, REPLAY_ENV)
+            }
+            (Some(dir), None) => Ok(Self::Record(PathBuf::from(dir))),
+            (None, Some(dir)) => Ok(Self::Replay(PathBuf::from(dir))),
+            (None, None) => Ok(Self::Live),
+        }
+    }
+}
+
+/// A single recorded request/response exchange.
+///
+/// Fixtures are plain JSON so they can be written by hand for tests or
+/// trimmed down before attaching them to a bug report.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Fixture {
+    /// HTTP method (e.g., `GET`)
+    pub method: String,
+
+    /// Full request URL, with secret query parameters redacted
+    pub url: String,
+
+    /// Request body, if the request carried a JSON body
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_body: Option<Value>,
+
+    /// HTTP status code of the response
+    pub status: u16,
+
+    /// Selected response headers
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+
+    /// Response body when it was valid JSON
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<Value>,
+
+    /// Response body when it was not JSON (diffs, logs, plain text)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub raw_body: Option<String>,
+
+    /// Response body, base64-encoded, when it was not valid UTF-8
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub raw_body_base64: Option<String>,
+}
+
+impl Fixture {
+    /// Returns the response body as bytes, as it would have come off the wire.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `raw_body_base64` is not valid base64.
+    fn body_bytes(&self) -> Result<Vec<u8>> {
+        Ok(match (&self.body, &self.raw_body, &self.raw_body_base64) {
+            (Some(json), _, _) => serde_json::to_vec(json).unwrap_or_default(),
+            (None, Some(raw), _) => raw.clone().into_bytes(),
+            (None, None, Some(encoded)) => STANDARD
+                .decode(encoded)
+                .with_context(|| format!("Invalid raw_body_base64 for {}", self.url))?,
+            (None, None, None) => Vec::new(),
+        })
+    }
+
+    /// Checks whether this fixture answers the given request.
+    ///
+    /// Methods must match exactly, URLs are compared with their query
+    /// parameters sorted, and request bodies are compared only when the
+    /// fixture recorded one.
+    fn matches(&self, method: &str, url: &str, body: Option<&Value>) -> bool {
+        if !self.method.eq_ignore_ascii_case(method) {
+            return false;
+        }
+        if normalize_url(&self.url) != normalize_url(url) {
+            return false;
+        }
+        match &self.request_body {
+            Some(expected) => body == Some(expected),
+            None => true,
+        }
+    }
+}
+
+/// The process-wide traffic mode, read once on first use.
+static MODE: Lazy<Result<TrafficMode, String>> =
+    Lazy::new(|| TrafficMode::from_env().map_err(|e| e.to_string()));
+
+/// Loaded fixtures paired with a flag recording whether each has been served.
+type FixtureStore = Vec<(Fixture, bool)>;
+
+/// Fixtures loaded for replay, populated on the first replayed request.
+static REPLAY_STORE: Lazy<Mutex<Option<FixtureStore>>> = Lazy::new(|| Mutex::new(None));
+
+/// Sequence counter for naming recorded fixtures.
+static RECORD_SEQUENCE: AtomicUsize = AtomicUsize::new(0);
+
+/// Returns the traffic mode for this process.
+///
+/// # Errors
+///
+/// Returns an error if the environment is misconfigured.
+pub fn mode() -> Result<TrafficMode> {
+    MODE.clone().map_err(|e| anyhow::anyhow!(e))
+}
+
+/// Checks whether responses are being served from fixtures.
+///
+/// Credential lookups use this to skip the keyring, since no request
+/// leaves the machine in replay mode.
+pub fn is_replaying() -> bool {
+    matches!(mode(), Ok(TrafficMode::Replay(_)))
+}
+
+/// Extension trait that routes a `reqwest` request through the record/replay layer.
+#[async_trait]
+pub trait ReplayExt {
+    /// Sends the request, honouring `BB_RECORD` and `BB_REPLAY`.
+    ///
+    /// In live mode this is equivalent to `send()`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails, a fixture cannot be written,
+    /// or (in replay mode) no fixture matches the request.
+    async fn send_replayable(self) -> Result<Response>;
+}
+
+#[async_trait]
+impl ReplayExt for RequestBuilder {
+    async fn send_replayable(self) -> Result<Response> {
+        send(self).await
+    }
+}
+
+/// Sends a request, recording or replaying it according to [`mode`].
+///
+/// # Parameters
+///
+/// * `builder` - The fully configured request builder
+///
+/// # Returns
+///
+/// The response, either from the network or reconstructed from a fixture.
+pub async fn send(builder: RequestBuilder) -> Result<Response> {
+    let mode = mode()?;
+    let (client, request) = builder.build_split();
+    let request = request?;
+
+    match mode {
+        TrafficMode::Live => Ok(client.execute(request).await?),
+        TrafficMode::Record(dir) => {
+            let method = request.method().to_string();
+            let url = redact_url(request.url().as_str());
+            let request_body = request_json(&request).map(redact_json);
+            let credential = request_credential(&request);
+
+            let response = client.execute(request).await?;
+            let status = response.status().as_u16();
+            let headers = recorded_headers(response.headers());
+            let bytes = response.bytes().await?;
+
+            let (mut body, mut raw_body, mut raw_body_base64) = (None, None, None);
+            if let Ok(json) = serde_json::from_slice::<Value>(&bytes) {
+                body = Some(redact_json(json));
+            } else if let Ok(text) = std::str::from_utf8(&bytes) {
+                if !text.is_empty() {
+                    raw_body = Some(redact_text(text, credential.as_deref()));
+                }
+            } else {
+                raw_body_base64 = Some(STANDARD.encode(&bytes));
+            }
+
+            let fixture = Fixture {
+                method,
+                url,
+                request_body,
+                status,
+                headers,
+                body,
+                raw_body,
+                raw_body_base64,
+            };
+            write_fixture(&dir, &fixture)?;
+
+            build_response(&fixture, bytes.to_vec())
+        }
+        TrafficMode::Replay(dir) => {
+            let method = request.method().to_string();
+            // Fixtures hold redacted URLs and bodies, so compare like with like
+            let url = redact_url(request.url().as_str());
+            let body = request_json(&request).map(redact_json);
+            let fixture = next_fixture(&dir, &method, &url, body.as_ref())?;
+            let bytes = fixture.body_bytes()?;
+            build_response(&fixture, bytes)
+        }
+    }
+}
+
+/// Loads every fixture in a directory, ordered by file name.
+///
+/// # Errors
+///
+/// Returns an error if the directory cannot be read or a fixture is malformed.
+pub fn load_fixtures(dir: &Path) -> Result<Vec<Fixture>> {
+    let mut paths: Vec<PathBuf> = fs::read_dir(dir)
+        .with_context(|| format!("Failed to read fixture directory {}", dir.display()))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    paths.sort();
+
+    paths
+        .iter()
+        .map(|path| {
+            let content = fs::read_to_string(path)?;
+            serde_json::from_str(&content)
+                .with_context(|| format!("Invalid fixture {}", path.display()))
+        })
+        .collect()
+}
+
+/// Finds the fixture answering a request in replay mode.
+///
+/// Unused fixtures are served first, in file order, so repeated polls of the
+/// same URL replay the recorded sequence. Once a sequence is exhausted the
+/// last matching fixture keeps being served.
+fn next_fixture(dir: &Path, method: &str, url: &str, body: Option<&Value>) -> Result<Fixture> {
+    let mut guard = REPLAY_STORE
+        .lock()
+        .map_err(|_| anyhow::anyhow!("Replay fixture store is poisoned"))?;
+
+    if guard.is_none() {
+        let fixtures = load_fixtures(dir)?;
+        *guard = Some(fixtures.into_iter().map(|f| (f, false)).collect());
+    }
+    let store = guard.as_mut().expect("fixtures loaded above");
+
+    if let Some((fixture, used)) = store
+        .iter_mut()
+        .find(|(f, used)| !*used && f.matches(method, url, body))
+    {
+        *used = true;
+        return Ok(fixture.clone());
+    }
+
+    if let Some((fixture, _)) = store
+        .iter()
+        .rev()
+        .find(|(f, _)| f.matches(method, url, body))
+    {
+        return Ok(fixture.clone());
+    }
+
+    bail!(
+        "No recorded response for {} {} in {} ({} is set)",
+        method,
+        url,
+        dir.display(),
+        REPLAY_ENV
+    )
+}
+
+/// Writes a fixture to the record directory with the next sequence number.
+fn write_fixture(dir: &Path, fixture: &Fixture) -> Result<()> {
+    fs::create_dir_all(dir)
+        .with_context(|| format!("Failed to create record directory {}", dir.display()))?;
+
+    // Continue numbering after any fixtures already in the directory
+    if RECORD_SEQUENCE.load(Ordering::SeqCst) == 0 {
+        let existing = fs::read_dir(dir)?.filter_map(|e| e.ok()).count();
+        let _ = RECORD_SEQUENCE.compare_exchange(0, existing, Ordering::SeqCst, Ordering::SeqCst);
+    }
+    let sequence = RECORD_SEQUENCE.fetch_add(1, Ordering::SeqCst) + 1;
+
+    let path = dir.join(fixture_file_name(sequence, &fixture.method, &fixture.url));
+    fs::write(&path, serde_json::to_string_pretty(fixture)? + "\n")
+        .with_context(|| format!("Failed to write fixture {}", path.display()))?;
+
+    tracing::debug!(
+        "Recorded {} {} to {}",
+        fixture.method,
+        fixture.url,
+        path.display()
+    );
+    Ok(())
+}
+
+/// Builds a fixture file name such as `0003-get-repositories-acme-widgets.json`.
+fn fixture_file_name(sequence: usize, method: &str, url: &str) -> String {
+    let path = Url::parse(url)
+        .map(|u| u.path().to_string())
+        .unwrap_or_else(|_| url.to_string());
+
+    let mut slug: String = path
+        .trim_matches('/')
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    slug.truncate(80);
+
+    format!("{:04}-{}-{}.json", sequence, method.to_lowercase(), slug)
+}
+
+/// Reconstructs a `reqwest::Response` from a fixture and body bytes.
+fn build_response(fixture: &Fixture, body: Vec<u8>) -> Result<Response> {
+    let mut builder = http::Response::builder().status(fixture.status);
+    for (name, value) in &fixture.headers {
+        builder = builder.header(name.as_str(), value.as_str());
+    }
+    if !fixture.headers.contains_key("content-type") && fixture.body.is_some() {
+        builder = builder.header("content-type", "application/json");
+    }
+    Ok(Response::from(builder.body(body)?))
+}
+
+/// Extracts the JSON body of a request, if it has one.
+fn request_json(request: &reqwest::Request) -> Option<Value> {
+    request
+        .body()
+        .and_then(|body| body.as_bytes())
+        .and_then(|bytes| serde_json::from_slice(bytes).ok())
+}
+
+/// Returns the token or password sent in the request's `Authorization`
+/// header, so that it can be redacted wherever a response repeats it.
+fn request_credential(request: &reqwest::Request) -> Option<String> {
+    let value = request
+        .headers()
+        .get(reqwest::header::AUTHORIZATION)?
+        .to_str()
+        .ok()?;
+    let credential = value.split_once(' ').map_or(value, |(_, rest)| rest).trim();
+    (!credential.is_empty()).then(|| credential.to_string())
+}
+
+/// Keeps only the response headers listed in [`RECORDED_HEADERS`].
+fn recorded_headers(headers: &reqwest::header::HeaderMap) -> BTreeMap<String, String> {
+    RECORDED_HEADERS
+        .iter()
+        .filter_map(|name| {
+            headers
+                .get(*name)
+                .and_then(|v| v.to_str().ok())
+                .map(|v| (name.to_string(), v.to_string()))
+        })
+        .collect()
+}
+
+/// Normalizes a URL for matching by sorting its query parameters.
+fn normalize_url(url: &str) -> String {
+    let Ok(mut parsed) = Url::parse(url) else {
+        return url.to_string();
+    };
+
+    let mut pairs: Vec<(String, String)> = parsed
+        .query_pairs()
+        .map(|(k, v)| (k.into_owned(), v.into_owned()))
+        .collect();
+
+    if pairs.is_empty() {
+        parsed.set_query(None);
+    } else {
+        pairs.sort();
+        parsed.query_pairs_mut().clear().extend_pairs(pairs);
+    }
+
+    parsed.to_string()
+}
+
+/// Replaces secret query parameter values in a URL.
+pub fn redact_url(url: &str) -> String {
+    let Ok(mut parsed) = Url::parse(url) else {
+        return url.to_string();
+    };
+
+    if parsed.query().is_none() {
+        return url.to_string();
+    }
+
+    let pairs: Vec<(String, String)> = parsed
+        .query_pairs()
+        .map(|(k, v)| {
+            if SECRET_PARAMS.contains(&k.to_lowercase().as_str()) {
+                (k.into_owned(), REDACTED.to_string())
+            } else {
+                (k.into_owned(), v.into_owned())
+            }
+        })
+        .collect();
+
+    parsed.query_pairs_mut().clear().extend_pairs(pairs);
+    parsed.to_string()
+}
+
+/// Matches `key=value` pairs, as in form bodies and logs, whose key is one
+/// of [`SECRET_KEYS`].
+static SECRET_ASSIGNMENT: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(r#"(?i)\b({})=[^&\s"']+"#, SECRET_KEYS.join("|")))
+        .expect("valid secret assignment pattern")
+});
+
+/// Matches `"key": "value"` pairs in text that is not a JSON document.
+static SECRET_JSON_PAIR: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(
+        r#"(?i)"({})"\s*:\s*"(?:[^"\\]|\\.)*""#,
+        SECRET_KEYS.join("|")
+    ))
+    .expect("valid secret pair pattern")
+});
+
+/// Replaces secret values in a text response body.
+///
+/// # Parameters
+///
+/// * `text` - The response body
+/// * `credential` - The request's own token or password, if it sent one
+pub fn redact_text(text: &str, credential: Option<&str>) -> String {
+    let mut text = SECRET_ASSIGNMENT
+        .replace_all(text, format!("${{1}}={}", REDACTED))
+        .into_owned();
+    text = SECRET_JSON_PAIR
+        .replace_all(&text, format!(r#""${{1}}": "{}""#, REDACTED))
+        .into_owned();
+    match credential {
+        Some(credential) => text.replace(credential, REDACTED),
+        None => text,
+    }
+}
+
+/// Recursively replaces secret values in a JSON document.
+///
+/// Keys listed in [`SECRET_KEYS`] are always redacted. Objects flagged as
+/// `"secured": true` (pipeline and deployment variables) also have their
+/// `value` redacted.
+pub fn redact_json(value: Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            let secured = map.get("secured").and_then(|v| v.as_bool()) == Some(true);
+            Value::Object(
+                map.into_iter()
+                    .map(|(key, value)| {
+                        let lower = key.to_lowercase();
+                        let is_secret =
+                            SECRET_KEYS.contains(&lower.as_str()) || (secured && lower == "value");
+                        if is_secret && !value.is_null() {
+                            (key, Value::String(REDACTED.to_string()))
+                        } else {
+                            (key, redact_json(value))
+                        }
+                    })
+                    .collect(),
+            )
+        }
+        Value::Array(items) => Value::Array(items.into_iter().map(redact_json).collect()),
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_redact_json_secrets() {
+        let value = json!({
+            "username": "jdoe",
+            "password": "hunter2",
+            "nested": [{"access_token": "abc", "name": "x"}],
+            "variable": {"key": "API_KEY", "value": "s3cr3t", "secured": true},
+            "plain": {"key": "REGION", "value": "eu-west-1", "secured": false}
+        });
+
+        let redacted = redact_json(value);
+        assert_eq!(redacted["username"], "jdoe");
+        assert_eq!(redacted["password"], REDACTED);
+        assert_eq!(redacted["nested"][0]["access_token"], REDACTED);
+        assert_eq!(redacted["nested"][0]["name"], "x");
+        assert_eq!(redacted["variable"]["value"], REDACTED);
+        assert_eq!(redacted["plain"]["value"], "eu-west-1");
+    }
+
+    #[test]
+    fn test_redact_text_secrets() {
+        let text = "login ok\naccess_token=abc123&scope=repo\n{\"Token\": \"x\\\"y\", \"name\": \"n\"}\nsent s3cr3t\n";
+        assert_eq!(
+            redact_text(text, Some("s3cr3t")),
+            "login ok\naccess_token=[REDACTED]&scope=repo\n{\"Token\": \"[REDACTED]\", \"name\": \"n\"}\nsent [REDACTED]\n"
+        );
+    }
+
+    #[test]
+    fn test_binary_body_round_trips_through_base64() {
+        let bytes = vec![0x89, b'P', b'N', b'G', 0xff, 0x00, 0xfe];
+        let fixture = Fixture {
+            method: "GET".to_string(),
+            url: "https://api.bitbucket.org/2.0/x".to_string(),
+            request_body: None,
+            status: 200,
+            headers: BTreeMap::new(),
+            body: None,
+            raw_body: None,
+            raw_body_base64: Some(STANDARD.encode(&bytes)),
+        };
+        assert_eq!(fixture.body_bytes().unwrap(), bytes);
+    }
+
+    #[test]
+    fn test_redact_url_query() {
+        let url = redact_url("https://example.com/rest?access_token=abc&limit=10");
+        assert!(url.contains("access_token=%5BREDACTED%5D"));
+        assert!(url.contains("limit=10"));
+    }
+
+    #[test]
+    fn test_fixture_matches_with_reordered_query() {
+        let fixture = Fixture {
+            method: "GET".to_string(),
+            url: "https://api.bitbucket.org/2.0/x?state=OPEN&pagelen=30".to_string(),
+            request_body: None,
+            status: 200,
+            headers: BTreeMap::new(),
+            body: Some(json!({})),
+            raw_body: None,
+            raw_body_base64: None,
+        };
+
+        assert!(fixture.matches(
+            "GET",
+            "https://api.bitbucket.org/2.0/x?pagelen=30&state=OPEN",
+            None
+        ));
+        assert!(!fixture.matches(
+            "POST",
+            "https://api.bitbucket.org/2.0/x?pagelen=30&state=OPEN",
+            None
+        ));
+        assert!(!fixture.matches("GET", "https://api.bitbucket.org/2.0/y", None));
+    }
+
+    #[test]
+    fn test_fixture_file_name() {
+        assert_eq!(
+            fixture_file_name(
+                3,
+                "GET",
+                "https://api.bitbucket.org/2.0/repositories/acme/widgets?x=1"
+            ),
+            "0003-get-2-0-repositories-acme-widgets.json"
+        );
+    }
+}
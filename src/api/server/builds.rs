@@ -0,0 +1,290 @@
+//
+//  bitbucket-cli
+//  api/server/builds.rs
+//
+//  Created by Ngonidzashe Mangudya on 2026/01/12.
+//  Copyright (c) 2025 IAMNGONI. All rights reserved.
+//
+
+//! Server/DC build status API types.
+//!
+//! CI systems report build results against commits, not pull requests. The
+//! statuses of a commit are read from:
+//!
+//! ```text
+//! GET /rest/build-status/1.0/commits/{commitId}
+//! ```
+//!
+//! Since Data Center 7.14 a repository can also require builds to pass
+//! before a pull request is merged. Those merge checks are listed by:
+//!
+//! ```text
+//! GET /rest/required-builds/latest/projects/{projectKey}/repos/{repositorySlug}/conditions
+//! ```
+//!
+//! Conditions that select branches through the branching model need the
+//! repository's model to be matched:
+//!
+//! ```text
+//! GET /rest/branch-utils/1.0/projects/{projectKey}/repos/{repositorySlug}/branchmodel
+//! ```
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use bitbucket_cli::api::server::builds::{BranchModel, BuildStatus, RequiredBuildCondition};
+//!
+//! fn is_required(
+//!     status: &BuildStatus,
+//!     conditions: &[RequiredBuildCondition],
+//!     model: &BranchModel,
+//! ) -> bool {
+//!     conditions
+//!         .iter()
+//!         .filter(|c| c.applies_to("feature/cache", "main", model))
+//!         .any(|c| c.build_parent_keys.contains(&status.key))
+//! }
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+use crate::util::glob_match;
+
+/// A build result reported against a commit.
+///
+/// # Notes
+///
+/// - `state` is `SUCCESSFUL`, `FAILED` or `INPROGRESS`; newer versions also
+///   report `CANCELLED` and `UNKNOWN`
+/// - `duration` is only reported by newer versions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildStatus {
+    /// Build result.
+    pub state: String,
+
+    /// Identifier of the build plan, unique per commit.
+    pub key: String,
+
+    /// Display name of the build.
+    #[serde(default)]
+    pub name: Option<String>,
+
+    /// Link to the build in the CI system.
+    #[serde(default)]
+    pub url: Option<String>,
+
+    /// Short summary of the result.
+    #[serde(default)]
+    pub description: Option<String>,
+
+    /// When the status was reported, in milliseconds since the epoch.
+    #[serde(default)]
+    pub date_added: Option<u64>,
+
+    /// How long the build ran, in milliseconds.
+    #[serde(default)]
+    pub duration: Option<u64>,
+}
+
+/// A required-builds merge check.
+///
+/// Pull requests into branches matched by `ref_matcher` cannot be merged
+/// until a successful build has been reported for each key in
+/// `build_parent_keys`, unless the source branch matches
+/// `exempt_ref_matcher`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RequiredBuildCondition {
+    /// Keys of the builds that must pass.
+    #[serde(default)]
+    pub build_parent_keys: Vec<String>,
+
+    /// Target branches the check applies to.
+    pub ref_matcher: RefMatcher,
+
+    /// Source branches that are exempt from the check.
+    #[serde(default)]
+    pub exempt_ref_matcher: Option<RefMatcher>,
+}
+
+impl RequiredBuildCondition {
+    /// Whether the check applies to a pull request from `source` into
+    /// `target` (branch names without `refs/heads/`), using `model` to match
+    /// branching model matchers
+    pub fn applies_to(&self, source: &str, target: &str, model: &BranchModel) -> bool {
+        self.ref_matcher.matches(target, model)
+            && !self
+                .exempt_ref_matcher
+                .as_ref()
+                .is_some_and(|m| m.matches(source, model))
+    }
+
+    /// Whether matching the check needs the repository's [`BranchModel`]
+    pub fn uses_branch_model(&self) -> bool {
+        self.ref_matcher.uses_branch_model()
+            || self
+                .exempt_ref_matcher
+                .as_ref()
+                .is_some_and(RefMatcher::uses_branch_model)
+    }
+}
+
+/// Selects the branches a merge check applies to.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RefMatcher {
+    /// The ref or pattern, e.g. `refs/heads/main` or `release/*`; for
+    /// branching model matchers the branch (`development`, `production`)
+    /// or branch type (`FEATURE`, `RELEASE`, ...).
+    pub id: String,
+
+    /// How `id` is matched.
+    #[serde(rename = "type")]
+    pub matcher_type: RefMatcherType,
+}
+
+/// Kind of a [`RefMatcher`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RefMatcherType {
+    /// `ANY_REF`, `BRANCH`, `PATTERN`, `MODEL_BRANCH` or `MODEL_CATEGORY`.
+    pub id: String,
+}
+
+impl RefMatcher {
+    /// Whether the matcher selects `branch`, using `model` for branching
+    /// model matchers
+    pub fn matches(&self, branch: &str, model: &BranchModel) -> bool {
+        match self.matcher_type.id.as_str() {
+            "ANY_REF" => true,
+            "BRANCH" => self.id.strip_prefix("refs/heads/").unwrap_or(&self.id) == branch,
+            "PATTERN" => glob_match(&self.id, branch),
+            "MODEL_BRANCH" => model.is_branch(&self.id, branch),
+            "MODEL_CATEGORY" => model.is_category(&self.id, branch),
+            _ => false,
+        }
+    }
+
+    /// Whether the matcher selects branches through the branching model
+    pub fn uses_branch_model(&self) -> bool {
+        matches!(
+            self.matcher_type.id.as_str(),
+            "MODEL_BRANCH" | "MODEL_CATEGORY"
+        )
+    }
+}
+
+/// A repository's branching model.
+///
+/// It names the development and production branches and the prefixes of
+/// the branch types, such as `feature/`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BranchModel {
+    /// The development branch, which defaults to the default branch.
+    #[serde(default)]
+    pub development: Option<ModelBranch>,
+
+    /// The production branch, if the model has one.
+    #[serde(default)]
+    pub production: Option<ModelBranch>,
+
+    /// The enabled branch types and their prefixes.
+    #[serde(default)]
+    pub types: Vec<ModelCategory>,
+}
+
+/// The development or production branch of a [`BranchModel`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelBranch {
+    /// Branch name without `refs/heads/`.
+    pub display_id: String,
+}
+
+/// A branch type of a [`BranchModel`], e.g. `FEATURE` with `feature/`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelCategory {
+    /// The branch type: `BUGFIX`, `FEATURE`, `HOTFIX` or `RELEASE`.
+    pub id: String,
+
+    /// The prefix of branch names of this type.
+    pub prefix: String,
+}
+
+impl BranchModel {
+    /// Whether `branch` is the model's `development` or `production` branch
+    pub fn is_branch(&self, role: &str, branch: &str) -> bool {
+        let named = match role.to_ascii_lowercase().as_str() {
+            "development" => self.development.as_ref(),
+            "production" => self.production.as_ref(),
+            _ => None,
+        };
+        named.is_some_and(|b| b.display_id == branch)
+    }
+
+    /// Whether `branch` is of the branch type `category`, e.g. `FEATURE`
+    pub fn is_category(&self, category: &str, branch: &str) -> bool {
+        self.types
+            .iter()
+            .any(|t| t.id.eq_ignore_ascii_case(category) && branch.starts_with(&t.prefix))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matcher(kind: &str, id: &str) -> RefMatcher {
+        RefMatcher {
+            id: id.to_string(),
+            matcher_type: RefMatcherType {
+                id: kind.to_string(),
+            },
+        }
+    }
+
+    #[test]
+    fn test_required_build_condition_applies_to() {
+        let model = BranchModel::default();
+        let condition = RequiredBuildCondition {
+            build_parent_keys: vec!["WID-BUILD".to_string()],
+            ref_matcher: matcher("BRANCH", "refs/heads/main"),
+            exempt_ref_matcher: Some(matcher("PATTERN", "hotfix/*")),
+        };
+
+        assert!(condition.applies_to("feature/cache", "main", &model));
+        assert!(!condition.applies_to("feature/cache", "develop", &model));
+        assert!(!condition.applies_to("hotfix/login", "main", &model));
+        assert!(!condition.uses_branch_model());
+        assert!(matcher("ANY_REF", "").matches("develop", &model));
+    }
+
+    #[test]
+    fn test_branch_model_matchers() {
+        let model: BranchModel = serde_json::from_str(
+            r#"{"development": {"id": "refs/heads/develop", "displayId": "develop"},
+                "types": [{"id": "FEATURE", "displayName": "Feature", "prefix": "feature/"},
+                          {"id": "RELEASE", "displayName": "Release", "prefix": "release/"}]}"#,
+        )
+        .unwrap();
+
+        assert!(matcher("MODEL_BRANCH", "development").matches("develop", &model));
+        assert!(!matcher("MODEL_BRANCH", "development").matches("main", &model));
+        assert!(!matcher("MODEL_BRANCH", "production").matches("main", &model));
+        assert!(matcher("MODEL_CATEGORY", "FEATURE").matches("feature/cache", &model));
+        assert!(!matcher("MODEL_CATEGORY", "RELEASE").matches("feature/cache", &model));
+        assert!(
+            !matcher("MODEL_CATEGORY", "FEATURE").matches("feature/cache", &BranchModel::default())
+        );
+
+        let condition = RequiredBuildCondition {
+            build_parent_keys: vec!["WID-BUILD".to_string()],
+            ref_matcher: matcher("MODEL_BRANCH", "development"),
+            exempt_ref_matcher: Some(matcher("MODEL_CATEGORY", "RELEASE")),
+        };
+        assert!(condition.uses_branch_model());
+        assert!(condition.applies_to("feature/cache", "develop", &model));
+        assert!(!condition.applies_to("release/2.0", "develop", &model));
+    }
+}
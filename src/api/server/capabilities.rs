@@ -0,0 +1,503 @@
+//
+//  bitbucket-cli
+//  api/server/capabilities.rs
+//
+//  Created by Ngonidzashe Mangudya on 2026/01/12.
+//  Copyright (c) 2025 IAMNGONI. All rights reserved.
+//
+
+//! # Bitbucket Server/DC Version and Capability Detection
+//!
+//! Bitbucket Data Center gains features release by release, so a command that
+//! works against 8.18 may not exist on 7.21. This module detects the version of
+//! a Server/DC instance and derives the set of features the CLI may use.
+//!
+//! ## Detection
+//!
+//! The version is read from:
+//!
+//! ```text
+//! GET /rest/api/1.0/application-properties
+//! ```
+//!
+//! which returns `version`, `buildNumber`, `buildDate` and `displayName`.
+//! The result is cached per host in `server_info.json` under the CLI data
+//! directory and refreshed after [`CACHE_TTL_HOURS`] hours.
+//!
+//! ## Capabilities
+//!
+//! | Capability | Minimum version |
+//! |------------|-----------------|
+//! | Pull request tasks (blocker comments) | 7.2 |
+//! | Required builds merge check | 7.14 |
+//! | Deployments | 7.16 |
+//! | Archived repositories | 8.0 |
+//! | Auto-merge | 8.15 |
+//! | Draft pull requests | 8.18 |
+//!
+//! ## Example
+//!
+//! ```rust,no_run
+//! use bitbucket_cli::api::server::capabilities::{self, Capability};
+//!
+//! # async fn example() -> anyhow::Result<()> {
+//! let caps = capabilities::detect("bitbucket.example.com", "my-token").await?;
+//! caps.require(Capability::DraftPullRequests)?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+
+use crate::api::format_api_error;
+use crate::api::replay::ReplayExt;
+use crate::config::Config;
+use crate::output::theme;
+
+/// Number of hours a cached server version stays valid.
+pub const CACHE_TTL_HOURS: i64 = 24;
+
+/// Name of the cache file in the CLI data directory.
+const CACHE_FILE: &str = "server_info.json";
+
+/// Response of `/rest/api/1.0/application-properties`.
+///
+/// # Fields
+///
+/// * `version` - Product version (e.g., "8.9.0")
+/// * `build_number` - Numeric build identifier (e.g., "8009000")
+/// * `build_date` - Build timestamp in Unix milliseconds, as a string
+/// * `display_name` - Product name (usually "Bitbucket")
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApplicationProperties {
+    /// Product version string.
+    pub version: String,
+
+    /// Build number of the running instance.
+    #[serde(rename = "buildNumber")]
+    pub build_number: String,
+
+    /// Build date in Unix milliseconds.
+    #[serde(rename = "buildDate", default)]
+    pub build_date: Option<String>,
+
+    /// Product display name.
+    #[serde(rename = "displayName", default)]
+    pub display_name: Option<String>,
+}
+
+/// Cached version information for a Server/DC host.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServerInfo {
+    /// Product version string (e.g., "8.9.0").
+    pub version: String,
+
+    /// Build number of the running instance.
+    pub build: String,
+
+    /// Product display name.
+    #[serde(default)]
+    pub display_name: Option<String>,
+
+    /// When the information was fetched.
+    pub fetched_at: DateTime<Utc>,
+}
+
+impl ServerInfo {
+    /// Checks whether this cache entry is older than [`CACHE_TTL_HOURS`].
+    pub fn is_stale(&self) -> bool {
+        Utc::now() - self.fetched_at > Duration::hours(CACHE_TTL_HOURS)
+    }
+
+    /// Parses the version string.
+    pub fn parsed_version(&self) -> Option<ServerVersion> {
+        ServerVersion::parse(&self.version)
+    }
+
+    /// Returns the capability set for this server.
+    pub fn capabilities(&self) -> CapabilitySet {
+        CapabilitySet::new(self.parsed_version())
+    }
+}
+
+/// A parsed `major.minor.patch` Server/DC version.
+///
+/// Versions compare numerically, so `8.10.0 > 8.9.0`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ServerVersion {
+    /// Major version
+    pub major: u32,
+    /// Minor version
+    pub minor: u32,
+    /// Patch version
+    pub patch: u32,
+}
+
+impl ServerVersion {
+    /// Creates a version from its components.
+    pub const fn new(major: u32, minor: u32, patch: u32) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+
+    /// Parses strings such as `"8.9.0"`, `"8.9"` or `"8.19.1-rc1"`.
+    ///
+    /// Returns `None` if the major version is not numeric.
+    pub fn parse(version: &str) -> Option<Self> {
+        let mut parts = version.trim().split('.').map(|part| {
+            part.chars()
+                .take_while(|c| c.is_ascii_digit())
+                .collect::<String>()
+                .parse::<u32>()
+                .ok()
+        });
+
+        let major = parts.next().flatten()?;
+        let minor = parts.next().flatten().unwrap_or(0);
+        let patch = parts.next().flatten().unwrap_or(0);
+
+        Some(Self::new(major, minor, patch))
+    }
+}
+
+impl fmt::Display for ServerVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.patch == 0 {
+            write!(f, "{}.{}", self.major, self.minor)
+        } else {
+            write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+        }
+    }
+}
+
+/// Features whose availability depends on the Server/DC version.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Capability {
+    /// Draft pull requests
+    DraftPullRequests,
+    /// Native pull request auto-merge
+    AutoMerge,
+    /// Required builds merge check
+    RequiredBuilds,
+    /// Archiving and unarchiving repositories
+    ArchivedRepositories,
+    /// Pull request tasks stored as blocker comments
+    BlockerComments,
+    /// Deployment tracking
+    Deployments,
+}
+
+impl Capability {
+    /// All known capabilities, in display order.
+    pub const ALL: [Capability; 6] = [
+        Capability::DraftPullRequests,
+        Capability::AutoMerge,
+        Capability::RequiredBuilds,
+        Capability::ArchivedRepositories,
+        Capability::BlockerComments,
+        Capability::Deployments,
+    ];
+
+    /// The first Server/DC version providing this capability.
+    pub fn minimum_version(&self) -> ServerVersion {
+        match self {
+            Capability::BlockerComments => ServerVersion::new(7, 2, 0),
+            Capability::RequiredBuilds => ServerVersion::new(7, 14, 0),
+            Capability::Deployments => ServerVersion::new(7, 16, 0),
+            Capability::ArchivedRepositories => ServerVersion::new(8, 0, 0),
+            Capability::AutoMerge => ServerVersion::new(8, 15, 0),
+            Capability::DraftPullRequests => ServerVersion::new(8, 18, 0),
+        }
+    }
+
+    /// Human-readable feature name used in messages.
+    pub fn description(&self) -> &'static str {
+        match self {
+            Capability::DraftPullRequests => "Draft pull requests",
+            Capability::AutoMerge => "Auto-merge",
+            Capability::RequiredBuilds => "Required builds",
+            Capability::ArchivedRepositories => "Archived repositories",
+            Capability::BlockerComments => "Pull request tasks",
+            Capability::Deployments => "Deployments",
+        }
+    }
+}
+
+/// The set of capabilities available on a Server/DC instance.
+///
+/// When the version is unknown every capability is assumed to be available,
+/// so the server itself gets the final say instead of the CLI refusing
+/// operations it cannot verify. Writes an older server may silently ignore
+/// or half apply, such as archiving, creating drafts or enabling auto-merge,
+/// use [`CapabilitySet::require_verified`], which refuses them instead.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CapabilitySet {
+    version: Option<ServerVersion>,
+}
+
+impl CapabilitySet {
+    /// Creates a capability set for the given version.
+    pub fn new(version: Option<ServerVersion>) -> Self {
+        Self { version }
+    }
+
+    /// The detected server version, if known.
+    pub fn version(&self) -> Option<ServerVersion> {
+        self.version
+    }
+
+    /// Checks whether a capability is available.
+    pub fn supports(&self, capability: Capability) -> bool {
+        match self.version {
+            Some(version) => version >= capability.minimum_version(),
+            None => true,
+        }
+    }
+
+    /// Returns the supported capabilities, in display order.
+    pub fn supported(&self) -> Vec<Capability> {
+        Capability::ALL
+            .into_iter()
+            .filter(|c| self.supports(*c))
+            .collect()
+    }
+
+    /// Fails with a clear message if a capability is unavailable.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error such as
+    /// `Draft pull requests requires Bitbucket DC 8.18+ (this server runs 8.9)`.
+    pub fn require(&self, capability: Capability) -> Result<()> {
+        if self.supports(capability) {
+            return Ok(());
+        }
+
+        let running = self
+            .version
+            .map(|v| v.to_string())
+            .unwrap_or_else(|| "an unknown version".to_string());
+
+        anyhow::bail!(
+            "{} requires Bitbucket DC {}+ (this server runs {})",
+            capability.description(),
+            capability.minimum_version(),
+            running
+        )
+    }
+
+    /// Like [`CapabilitySet::require`], but also fails when the version is
+    /// unknown.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the capability is unavailable or the server
+    /// version could not be detected.
+    pub fn require_verified(&self, capability: Capability) -> Result<()> {
+        if self.version.is_none() {
+            anyhow::bail!(
+                "{} requires Bitbucket DC {}+, but the server version could not be detected",
+                capability.description(),
+                capability.minimum_version()
+            );
+        }
+        self.require(capability)
+    }
+}
+
+/// Fetches application properties directly from the server, bypassing the cache.
+///
+/// # Parameters
+///
+/// * `host` - Server hostname (e.g., "bitbucket.example.com")
+/// * `token` - Personal access token
+///
+/// # Errors
+///
+/// Returns an error if the request fails or the response cannot be parsed.
+pub async fn fetch_application_properties(
+    host: &str,
+    token: &str,
+) -> Result<ApplicationProperties> {
+    let client = Client::builder()
+        .user_agent(format!("bb/{}", crate::VERSION))
+        .build()?;
+
+    let url = format!("https://{}/rest/api/1.0/application-properties", host);
+
+    let response = client
+        .get(&url)
+        .bearer_auth(token)
+        .send_replayable()
+        .await
+        .context("Failed to connect to Bitbucket Server")?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let text = response.text().await.unwrap_or_default();
+        return Err(format_api_error(status, &text));
+    }
+
+    Ok(response.json().await?)
+}
+
+/// Returns version information for a host, using the cache when fresh.
+///
+/// # Parameters
+///
+/// * `host` - Server hostname
+/// * `token` - Personal access token
+///
+/// # Errors
+///
+/// Returns an error if the cache is stale or missing and the server cannot
+/// be queried.
+pub async fn server_info(host: &str, token: &str) -> Result<ServerInfo> {
+    let mut cache = load_cache();
+
+    if let Some(info) = cache.get(host) {
+        if !info.is_stale() {
+            return Ok(info.clone());
+        }
+    }
+
+    let props = fetch_application_properties(host, token).await?;
+    let info = ServerInfo {
+        version: props.version,
+        build: props.build_number,
+        display_name: props.display_name,
+        fetched_at: Utc::now(),
+    };
+
+    cache.insert(host.to_string(), info.clone());
+    if let Err(e) = save_cache(&cache) {
+        tracing::debug!("Failed to write server info cache: {}", e);
+    }
+
+    Ok(info)
+}
+
+/// Returns the cached version information for a host without any network access.
+pub fn cached_server_info(host: &str) -> Option<ServerInfo> {
+    load_cache().remove(host)
+}
+
+/// Detects the capability set of a host.
+///
+/// Detection failures (for example, an instance that hides
+/// `application-properties` from the token) are reported once as a warning
+/// and yield an unknown-version set, so commands fall back to letting the
+/// server decide.
+pub async fn detect(host: &str, token: &str) -> Result<CapabilitySet> {
+    static WARNED: AtomicBool = AtomicBool::new(false);
+
+    match server_info(host, token).await {
+        Ok(info) => Ok(info.capabilities()),
+        Err(e) => {
+            if !WARNED.swap(true, Ordering::Relaxed) {
+                eprintln!(
+                    "{} Could not detect the Bitbucket Server version of {}: {:#}",
+                    theme().warning_mark(),
+                    host,
+                    e
+                );
+            }
+            Ok(CapabilitySet::new(None))
+        }
+    }
+}
+
+fn cache_path() -> Result<PathBuf> {
+    Ok(Config::data_dir()?.join(CACHE_FILE))
+}
+
+fn load_cache() -> HashMap<String, ServerInfo> {
+    cache_path()
+        .ok()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_cache(cache: &HashMap<String, ServerInfo>) -> Result<()> {
+    let path = cache_path()?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, serde_json::to_string_pretty(cache)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_versions() {
+        assert_eq!(
+            ServerVersion::parse("8.9.0"),
+            Some(ServerVersion::new(8, 9, 0))
+        );
+        assert_eq!(
+            ServerVersion::parse("7.21"),
+            Some(ServerVersion::new(7, 21, 0))
+        );
+        assert_eq!(
+            ServerVersion::parse("8.19.1-rc1"),
+            Some(ServerVersion::new(8, 19, 1))
+        );
+        assert_eq!(ServerVersion::parse("unknown"), None);
+        assert!(ServerVersion::new(8, 10, 0) > ServerVersion::new(8, 9, 5));
+    }
+
+    #[test]
+    fn test_capabilities_by_version() {
+        let caps = CapabilitySet::new(ServerVersion::parse("8.9.0"));
+        assert!(caps.supports(Capability::RequiredBuilds));
+        assert!(caps.supports(Capability::ArchivedRepositories));
+        assert!(caps.supports(Capability::BlockerComments));
+        assert!(!caps.supports(Capability::AutoMerge));
+        assert!(!caps.supports(Capability::DraftPullRequests));
+
+        let err = caps.require(Capability::DraftPullRequests).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Draft pull requests requires Bitbucket DC 8.18+ (this server runs 8.9)"
+        );
+
+        let caps = CapabilitySet::new(ServerVersion::parse("7.1.0"));
+        assert!(!caps.supports(Capability::BlockerComments));
+        assert!(!caps.supports(Capability::Deployments));
+    }
+
+    #[test]
+    fn test_unknown_version_allows_everything() {
+        let caps = CapabilitySet::new(None);
+        assert_eq!(caps.supported().len(), Capability::ALL.len());
+        assert!(caps.require(Capability::AutoMerge).is_ok());
+
+        let err = caps
+            .require_verified(Capability::ArchivedRepositories)
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Archived repositories requires Bitbucket DC 8.0+, but the server version could not be detected"
+        );
+        let known = CapabilitySet::new(ServerVersion::parse("8.9.0"));
+        assert!(known
+            .require_verified(Capability::ArchivedRepositories)
+            .is_ok());
+        assert!(known.require_verified(Capability::AutoMerge).is_err());
+    }
+}
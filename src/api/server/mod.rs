@@ -19,6 +19,8 @@
 //! - [`repositories`] - Repository management (create, list, clone links)
 //! - [`pullrequests`] - Pull request operations (create, merge, review)
 //! - [`projects`] - Project management (create, update, list)
+//! - [`capabilities`] - Version detection and version-dependent feature checks
+//! - [`builds`] - Commit build statuses and required-builds merge checks
 //!
 //! ## API Differences from Cloud
 //!
@@ -45,6 +47,8 @@
 //! - Optional fields use `Option<T>` and default to `None` during deserialization
 //! - Boolean fields default to `false` when not present in the API response
 
+pub mod builds;
+pub mod capabilities;
 pub mod projects;
 pub mod pullrequests;
 pub mod repositories;
@@ -52,6 +52,7 @@
 //!         },
 //!     },
 //!     reviewers: vec![],
+//!     draft: None,
 //! };
 //! ```
 //!
@@ -63,6 +64,9 @@
 
 use serde::{Deserialize, Serialize};
 
+use super::repositories::SelfLink;
+use crate::output::JsonFields;
+
 /// Represents a pull request in Bitbucket Server/Data Center.
 ///
 /// A pull request is a proposal to merge changes from one branch (source) into
@@ -127,6 +131,14 @@ pub struct PullRequest {
     /// Whether the pull request has been closed (merged or declined).
     pub closed: bool,
 
+    /// Whether the pull request is a draft (Bitbucket DC 8.18+).
+    #[serde(default)]
+    pub draft: bool,
+
+    /// Revision of the pull request, required when updating it.
+    #[serde(default)]
+    pub version: u32,
+
     /// Unix timestamp in milliseconds when the PR was created.
     #[serde(rename = "createdDate")]
     pub created_date: u64,
@@ -154,6 +166,44 @@ pub struct PullRequest {
     /// Includes the author, reviewers, and commenters.
     #[serde(default)]
     pub participants: Vec<PrParticipant>,
+
+    /// Counters Server computes for the pull request, such as comments
+    /// and open tasks.
+    #[serde(default)]
+    pub properties: PrProperties,
+
+    /// Links to the pull request; `self` is its web page.
+    #[serde(default)]
+    pub links: PrLinks,
+}
+
+impl JsonFields for PullRequest {}
+
+/// Links attached to a pull request.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PrLinks {
+    /// The pull request's page in the web UI.
+    #[serde(default, rename = "self")]
+    pub self_link: Vec<SelfLink>,
+}
+
+/// Summary counters attached to a pull request.
+///
+/// Server only includes the counters that apply, so every field defaults
+/// to zero when absent.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PrProperties {
+    /// Number of comments on the pull request.
+    #[serde(rename = "commentCount", default)]
+    pub comment_count: u32,
+
+    /// Number of unresolved tasks (blocker comments).
+    #[serde(rename = "openTaskCount", default)]
+    pub open_task_count: u32,
+
+    /// Number of resolved tasks (blocker comments).
+    #[serde(rename = "resolvedTaskCount", default)]
+    pub resolved_task_count: u32,
 }
 
 /// Branch reference within a pull request context.
@@ -399,6 +449,7 @@ pub struct User {
 ///     reviewers: vec![
 ///         UserRef { user: UserName { name: "jsmith".to_string() } },
 ///     ],
+///     draft: None,
 /// };
 /// ```
 ///
@@ -428,6 +479,10 @@ pub struct CreatePullRequestRequest {
     /// Empty list is valid; omitted from JSON if empty.
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub reviewers: Vec<UserRef>,
+
+    /// Whether to open the pull request as a draft (Bitbucket DC 8.18+).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub draft: Option<bool>,
 }
 
 /// Branch reference specification for pull request creation.
@@ -596,3 +651,82 @@ pub struct MergePullRequestRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub message: Option<String>,
 }
+
+/// Whether a pull request can be merged, from `GET .../pull-requests/{id}/merge`.
+///
+/// The server evaluates its merge checks (required approvals, builds,
+/// incomplete tasks, plugins, ...) and reports each one that blocks the
+/// merge as a veto.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use bitbucket_cli::api::server::pullrequests::MergeStatus;
+///
+/// let status: MergeStatus = response.json().await?;
+/// for veto in &status.vetoes {
+///     println!("{}", veto.summary_message);
+/// }
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MergeStatus {
+    /// Whether the pull request can be merged right now.
+    pub can_merge: bool,
+
+    /// Whether merging would produce conflicts.
+    #[serde(default)]
+    pub conflicted: bool,
+
+    /// Result of the trial merge: `CLEAN`, `CONFLICTED` or `UNKNOWN`.
+    #[serde(default)]
+    pub outcome: Option<String>,
+
+    /// Merge checks that currently block the merge.
+    #[serde(default)]
+    pub vetoes: Vec<MergeVeto>,
+}
+
+/// A merge check that blocks a pull request from being merged.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MergeVeto {
+    /// One-line summary, e.g. "Requires approvals".
+    pub summary_message: String,
+
+    /// Longer explanation of what is needed.
+    #[serde(default)]
+    pub detailed_message: Option<String>,
+}
+
+/// Request body for enabling auto-merge on a pull request.
+///
+/// Sent as a POST to `.../pull-requests/{id}/auto-merge` (Bitbucket DC
+/// 8.15+). The server merges the pull request as soon as all merge checks
+/// pass.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use bitbucket_cli::api::server::pullrequests::AutoMergeRequest;
+///
+/// let request = AutoMergeRequest {
+///     strategy_id: Some("squash".to_string()),
+///     message: None,
+/// };
+/// ```
+///
+/// # Notes
+///
+/// - Without `strategy_id` the repository's default merge strategy is used
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AutoMergeRequest {
+    /// Merge strategy ID, e.g. `no-ff`, `squash` or `rebase-no-ff`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub strategy_id: Option<String>,
+
+    /// Custom message for the merge commit.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
@@ -48,6 +48,8 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::output::JsonFields;
+
 /// Represents a repository in Bitbucket Server/Data Center.
 ///
 /// This struct contains the complete information about a repository as returned
@@ -137,6 +139,8 @@ pub struct Repository {
     pub links: RepositoryLinks,
 }
 
+impl JsonFields for Repository {}
+
 /// Reference to a project within a repository context.
 ///
 /// This is a lightweight representation of a project containing only the
@@ -71,6 +71,9 @@ use keyring::Entry;
 /// allowing them to be grouped together and managed as a unit.
 const SERVICE_NAME: &str = "bitbucket-cli";
 
+/// Placeholder credential handed out while replaying recorded API traffic.
+const REPLAY_CREDENTIAL: &str = "replay-token";
+
 /// Secure credential storage using the system's native keyring service.
 ///
 /// This struct provides methods for storing, retrieving, and deleting
@@ -200,7 +203,13 @@ impl KeyringStore {
     /// - The credential is encrypted by the system keyring.
     /// - May trigger a system authentication prompt on first use.
     /// - Existing entries for the same host are silently replaced.
+    /// - While replaying recorded API traffic nothing is stored, so replayed
+    ///   token refreshes leave the real keyring alone.
     pub fn store(&self, host: &str, credential: &str) -> Result<()> {
+        if crate::api::replay::is_replaying() {
+            return Ok(());
+        }
+
         let entry = Entry::new(&self.service, host)?;
         entry.set_password(credential)?;
         Ok(())
@@ -248,7 +257,13 @@ impl KeyringStore {
     /// - Returns `None` for missing entries, not an error.
     /// - May trigger system authentication on first access.
     /// - The returned credential is decrypted by the keyring service.
+    /// - When `BB_REPLAY` is set, a placeholder is returned without touching
+    ///   the keyring, since replayed requests never leave the machine.
     pub fn get(&self, host: &str) -> Result<Option<String>> {
+        if crate::api::replay::is_replaying() {
+            return Ok(Some(REPLAY_CREDENTIAL.to_string()));
+        }
+
         let entry = Entry::new(&self.service, host)?;
         match entry.get_password() {
             Ok(password) => Ok(Some(password)),
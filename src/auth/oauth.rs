@@ -74,6 +74,9 @@ use std::sync::mpsc;
 use std::time::Duration;
 use url::Url;
 
+use super::KeyringStore;
+use crate::api::replay::ReplayExt;
+
 /// The default OAuth client ID for the Bitbucket CLI.
 /// Users can override this with their own OAuth consumer.
 pub const DEFAULT_CLIENT_ID: &str = "Pyydmsf5kLpEqs24kw";
@@ -593,7 +596,7 @@ pub async fn refresh_oauth_token(
     let client = Client::new();
     let client_id = client_id.unwrap_or(DEFAULT_CLIENT_ID);
 
-    let params = [
+    let mut params = vec![
         ("grant_type", "refresh_token"),
         ("refresh_token", refresh_token),
     ];
@@ -603,12 +606,12 @@ pub async fn refresh_oauth_token(
     if let Some(secret) = client_secret {
         request = request.basic_auth(client_id, Some(secret));
     } else {
-        request = request.form(&[("client_id", client_id)]);
+        params.push(("client_id", client_id));
     }
 
     let response = request
         .form(&params)
-        .send()
+        .send_replayable()
         .await
         .context("Failed to refresh token")?;
 
@@ -626,6 +629,43 @@ pub async fn refresh_oauth_token(
     Ok(token_response.into())
 }
 
+/// Refreshes the OAuth login stored for a host.
+///
+/// Exchanges the refresh token stored as `{host}.refresh` for a new access
+/// token and stores the new tokens in place of the old ones.
+///
+/// # Parameters
+///
+/// - `keyring`: The credential store holding the tokens.
+/// - `host`: The host the tokens belong to, e.g. `bitbucket.org`.
+///
+/// # Returns
+///
+/// The new tokens, or `None` if no refresh token is stored for `host`.
+///
+/// # Errors
+///
+/// Returns an error if the refresh is rejected or the new tokens cannot be
+/// stored.
+pub async fn refresh_stored_token(
+    keyring: &KeyringStore,
+    host: &str,
+) -> Result<Option<OAuthTokenResponse>> {
+    let refresh_key = format!("{}.refresh", host);
+    let Some(refresh_token) = keyring.get(&refresh_key)? else {
+        return Ok(None);
+    };
+
+    let tokens = refresh_oauth_token(&refresh_token, None, None).await?;
+
+    keyring.store(host, &tokens.access_token)?;
+    if let Some(ref new_refresh) = tokens.refresh_token {
+        keyring.store(&refresh_key, new_refresh)?;
+    }
+
+    Ok(Some(tokens))
+}
+
 /// Legacy function for backward compatibility.
 /// Use [`refresh_oauth_token`] instead.
 pub async fn refresh_token(refresh_token: &str) -> Result<OAuthTokenResponse> {
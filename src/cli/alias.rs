@@ -20,6 +20,12 @@
 //! # Use the alias
 //! bb co 123  # expands to: bb pr checkout 123
 //!
+//! # Substitute arguments with $1..$9 and $@
+//! bb alias set mine 'pr list --author $1'
+//!
+//! # Shell aliases get the arguments as $1, $2, ... too
+//! bb alias set --shell br 'git branch --list "$1*"'
+//!
 //! # List aliases
 //! bb alias list
 //!
@@ -33,10 +39,11 @@ use std::path::Path;
 
 use anyhow::{bail, Result};
 use clap::{Args, Subcommand};
-use console::style;
 use serde::{Deserialize, Serialize};
 
+use crate::alias::validate_alias_name;
 use crate::config::Config;
+use crate::output::{theme, write_json, Color};
 
 use super::GlobalOptions;
 
@@ -124,45 +131,7 @@ impl AliasCommand {
 
     /// Set an alias
     async fn set(&self, args: &SetArgs, global: &GlobalOptions) -> Result<()> {
-        // Validate alias name
-        if args.alias.is_empty() {
-            bail!("Alias name cannot be empty");
-        }
-
-        if args.alias.contains(' ') || args.alias.contains('\t') {
-            bail!("Alias name cannot contain whitespace");
-        }
-
-        // Reserved commands that can't be aliased
-        let reserved = [
-            "auth",
-            "repo",
-            "pr",
-            "issue",
-            "pipeline",
-            "workspace",
-            "project",
-            "browse",
-            "api",
-            "config",
-            "alias",
-            "extension",
-            "webhook",
-            "deploy",
-            "artifact",
-            "secret",
-            "ssh-key",
-            "completion",
-            "version",
-            "help",
-        ];
-
-        if reserved.contains(&args.alias.as_str()) {
-            bail!(
-                "Cannot create alias '{}' - this is a reserved command name",
-                args.alias
-            );
-        }
+        validate_alias_name(&args.alias)?;
 
         let mut config = Config::load()?;
 
@@ -186,16 +155,16 @@ impl AliasCommand {
                 "shell": args.shell,
                 "updated": existed,
             });
-            println!("{}", serde_json::to_string_pretty(&result)?);
+            write_json(&result)?;
         } else {
             let action = if existed { "Updated" } else { "Created" };
             let alias_type = if args.shell { "shell alias" } else { "alias" };
             println!(
                 "{} {} {}: {} -> {}",
-                style("✓").green(),
+                theme().success_mark(),
                 action,
                 alias_type,
-                style(&args.alias).cyan().bold(),
+                theme().emphasis(&args.alias),
                 expansion
             );
 
@@ -203,7 +172,7 @@ impl AliasCommand {
                 println!();
                 println!(
                     "  {} Shell aliases execute in a subshell.",
-                    style("Note:").yellow()
+                    theme().paint("Note:", Color::DarkYellow)
                 );
             }
         }
@@ -221,7 +190,7 @@ impl AliasCommand {
                     "success": false,
                     "error": format!("Alias '{}' not found", args.alias),
                 });
-                println!("{}", serde_json::to_string_pretty(&result)?);
+                write_json(&result)?;
             } else {
                 bail!("Alias '{}' not found", args.alias);
             }
@@ -237,12 +206,12 @@ impl AliasCommand {
                 "alias": args.alias,
                 "expansion": expansion,
             });
-            println!("{}", serde_json::to_string_pretty(&result)?);
+            write_json(&result)?;
         } else {
             println!(
                 "{} Deleted alias: {}",
-                style("✓").green(),
-                style(&args.alias).cyan().bold()
+                theme().success_mark(),
+                theme().emphasis(&args.alias)
             );
         }
 
@@ -258,7 +227,7 @@ impl AliasCommand {
                 "aliases": config.aliases,
                 "count": config.aliases.len(),
             });
-            println!("{}", serde_json::to_string_pretty(&result)?);
+            write_json(&result)?;
             return Ok(());
         }
 
@@ -276,7 +245,7 @@ impl AliasCommand {
         }
 
         println!();
-        println!("{}", style("Configured Aliases").bold());
+        println!("{}", theme().heading("Configured Aliases"));
         println!("{}", "-".repeat(60));
 
         // Sort aliases for consistent output
@@ -290,16 +259,12 @@ impl AliasCommand {
             if is_shell {
                 println!(
                     "  {} = {} {}",
-                    style(alias).cyan().bold(),
+                    theme().emphasis(alias),
                     display_expansion,
-                    style("(shell)").dim()
+                    theme().muted("(shell)")
                 );
             } else {
-                println!(
-                    "  {} = bb {}",
-                    style(alias).cyan().bold(),
-                    display_expansion
-                );
+                println!("  {} = bb {}", theme().emphasis(alias), display_expansion);
             }
         }
 
@@ -365,18 +330,18 @@ impl AliasCommand {
                 "skipped": skipped,
                 "total": config.aliases.len(),
             });
-            println!("{}", serde_json::to_string_pretty(&result)?);
+            write_json(&result)?;
         } else {
             println!(
                 "{} Imported {} alias(es) from {}",
-                style("✓").green(),
+                theme().success_mark(),
                 imported,
                 args.file
             );
             if skipped > 0 {
                 println!(
                     "  {} {} existing alias(es) skipped (use --overwrite to replace)",
-                    style("!").yellow(),
+                    theme().warning_mark(),
                     skipped
                 );
             }
@@ -416,11 +381,11 @@ impl AliasCommand {
                 "format": args.format,
                 "count": config.aliases.len(),
             });
-            println!("{}", serde_json::to_string_pretty(&result)?);
+            write_json(&result)?;
         } else {
             println!(
                 "{} Exported {} alias(es) to {}",
-                style("✓").green(),
+                theme().success_mark(),
                 config.aliases.len(),
                 args.file
             );
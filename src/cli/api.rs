@@ -34,14 +34,15 @@ use std::time::Duration;
 
 use anyhow::{bail, Result};
 use clap::Args;
-use console::style;
 use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
 use reqwest::{Client, Method};
 use serde_json::Value;
 
+use crate::api::replay::ReplayExt;
 use crate::auth::KeyringStore;
 use crate::config::Config;
 use crate::context::{ContextResolver, HostType};
+use crate::output::{output_format, theme, write_formatted, write_json, OutputFormat};
 
 use super::GlobalOptions;
 
@@ -336,13 +337,13 @@ impl ApiCommand {
                 .json(&body);
         }
 
-        let response = request.send().await?;
+        let response = request.send_replayable().await?;
         let status = response.status();
         let response_headers = response.headers().clone();
 
         // Print headers if requested
         if self.include {
-            println!("{} {}", style("HTTP").dim(), status);
+            println!("{} {}", theme().muted("HTTP"), status);
             for (name, value) in response_headers.iter() {
                 println!("{}: {}", name, value.to_str().unwrap_or(""));
             }
@@ -363,7 +364,7 @@ impl ApiCommand {
         // Try to parse as JSON and pretty-print
         if let Ok(json) = serde_json::from_str::<Value>(&body_text) {
             if global.json || self.pretty {
-                println!("{}", serde_json::to_string_pretty(&json)?);
+                write_json(&json)?;
             } else {
                 println!("{}", json);
             }
@@ -391,6 +392,7 @@ impl ApiCommand {
         body: Option<Value>,
         global: &GlobalOptions,
     ) -> Result<()> {
+        let format = output_format();
         let mut all_values: Vec<Value> = Vec::new();
         let mut current_url = url.to_string();
         let mut page = 1;
@@ -407,7 +409,7 @@ impl ApiCommand {
                     .json(body);
             }
 
-            let response = request.send().await?;
+            let response = request.send_replayable().await?;
             let status = response.status();
 
             if !status.is_success() {
@@ -420,11 +422,17 @@ impl ApiCommand {
 
             // Extract values from paginated response
             if let Some(values) = json.get("values").and_then(|v| v.as_array()) {
-                all_values.extend(values.clone());
+                // NDJSON is streamed page by page so large listings start
+                // flowing immediately
+                if format == Some(OutputFormat::Ndjson) {
+                    write_formatted(values, OutputFormat::Ndjson)?;
+                } else {
+                    all_values.extend(values.clone());
+                }
             } else {
                 // Not a paginated response, just return it
                 if global.json || self.pretty {
-                    println!("{}", serde_json::to_string_pretty(&json)?);
+                    write_json(&json)?;
                 } else {
                     println!("{}", json);
                 }
@@ -438,7 +446,7 @@ impl ApiCommand {
 
                 // Safety limit
                 if page > 100 {
-                    eprintln!("{} Stopping at 100 pages", style("!").yellow());
+                    eprintln!("{} Stopping at 100 pages", theme().warning_mark());
                     break;
                 }
             } else {
@@ -446,14 +454,22 @@ impl ApiCommand {
             }
         }
 
-        // Output all values
+        // Output all values; export formats get the bare list
+        match format {
+            Some(OutputFormat::Ndjson) => return Ok(()),
+            Some(format) if format != OutputFormat::Json => {
+                return write_formatted(&all_values, format);
+            }
+            _ => {}
+        }
+
         let result = serde_json::json!({
             "values": all_values,
             "size": all_values.len(),
         });
 
         if global.json || self.pretty {
-            println!("{}", serde_json::to_string_pretty(&result)?);
+            write_json(&result)?;
         } else {
             println!("{}", result);
         }
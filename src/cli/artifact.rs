@@ -33,16 +33,16 @@ use std::path::Path;
 
 use anyhow::{bail, Result};
 use clap::{Args, Subcommand};
-use console::style;
 use indicatif::{ProgressBar, ProgressStyle};
 use serde::{Deserialize, Serialize};
 
 use crate::api::common::PaginatedResponse;
+use crate::api::replay::ReplayExt;
 use crate::api::BitbucketClient;
 use crate::auth::{AuthCredential, KeyringStore};
 use crate::config::Config;
 use crate::context::{ContextResolver, HostType, RepoContext};
-use crate::output::OutputWriter;
+use crate::output::{print_row, stdout_is_tty, theme, write_json, OutputWriter, TableRow};
 
 use super::GlobalOptions;
 
@@ -145,23 +145,27 @@ struct ArtifactListItem {
     step: Option<String>,
 }
 
+impl TableRow for ArtifactListItem {
+    const COLUMNS: &'static [&'static str] = &["name", "size", "step", "pipeline"];
+    const DEFAULT_COLUMNS: &'static [&'static str] = &["name", "size", "step"];
+
+    fn cell(&self, column: &str) -> String {
+        match column {
+            "name" => self.name.clone(),
+            "size" => self
+                .size
+                .map(format_bytes)
+                .unwrap_or_else(|| "-".to_string()),
+            "step" => self.step.clone().unwrap_or_else(|| "-".to_string()),
+            "pipeline" => format!("#{}", self.pipeline),
+            _ => String::new(),
+        }
+    }
+}
+
 impl crate::output::TableOutput for ArtifactListItem {
     fn print_table(&self, _color: bool) {
-        let size_str = self
-            .size
-            .map(format_bytes)
-            .unwrap_or_else(|| "-".to_string());
-        let step_str = self
-            .step
-            .as_ref()
-            .map(|s| truncate(s, 15))
-            .unwrap_or_else(|| "-".to_string());
-        println!(
-            "{:<40} {:<15} {}",
-            truncate(&self.name, 38),
-            size_str,
-            step_str
-        );
+        print_row(self);
     }
 
     fn print_markdown(&self) {
@@ -268,45 +272,22 @@ impl ArtifactCommand {
 
         let writer = OutputWriter::new(self.get_format(global));
 
-        if !global.json {
+        let decorate = !global.json && stdout_is_tty();
+
+        if decorate {
             println!();
             println!(
                 "{}",
-                style(format!("Artifacts from Pipeline #{}", pipeline_num)).bold()
-            );
-            println!("{}", "-".repeat(70));
-            println!(
-                "{} {} {}",
-                style(format!("{:<40}", "NAME")).bold(),
-                style(format!("{:<15}", "SIZE")).bold(),
-                style("STEP").bold()
+                theme().heading(format!("Artifacts from Pipeline #{}", pipeline_num))
             );
-            println!("{}", "-".repeat(70));
-
-            for item in &items {
-                let size_str = item
-                    .size
-                    .map(format_bytes)
-                    .unwrap_or_else(|| "-".to_string());
-
-                let step_str = item
-                    .step
-                    .as_ref()
-                    .map(|s| truncate(s, 15))
-                    .unwrap_or_else(|| "-".to_string());
-
-                println!(
-                    "{:<40} {:<15} {}",
-                    truncate(&item.name, 38),
-                    size_str,
-                    step_str
-                );
-            }
+            println!();
+        }
 
+        writer.write_table(&items)?;
+
+        if decorate {
             println!();
             println!("Showing {} artifact(s)", items.len());
-        } else {
-            writer.write_list(&items)?;
         }
 
         Ok(())
@@ -325,7 +306,7 @@ impl ArtifactCommand {
 
         println!(
             "{} Downloading artifact '{}' from pipeline #{}...",
-            style("→").cyan(),
+            theme().info_mark(),
             args.name,
             args.pipeline
         );
@@ -346,7 +327,7 @@ impl ArtifactCommand {
         let response = http_client
             .get(format!("https://api.bitbucket.org/2.0{}", url))
             .bearer_auth(&token)
-            .send()
+            .send_replayable()
             .await?;
 
         if !response.status().is_success() {
@@ -377,12 +358,12 @@ impl ArtifactCommand {
                 "path": output_path.display().to_string(),
                 "size": bytes.len(),
             });
-            println!("{}", serde_json::to_string_pretty(&result)?);
+            write_json(&result)?;
         } else {
             println!();
             println!(
                 "{} Downloaded to {}",
-                style("✓").green(),
+                theme().success_mark(),
                 output_path.display()
             );
             println!("  Size: {}", format_bytes(bytes.len() as u64));
@@ -408,7 +389,7 @@ impl ArtifactCommand {
                 .interact()?;
 
             if !confirmed {
-                println!("{} Cancelled.", style("!").yellow());
+                println!("{} Cancelled.", theme().warning_mark());
                 return Ok(());
             }
         }
@@ -426,12 +407,12 @@ impl ArtifactCommand {
                 "name": args.name,
                 "pipeline": args.pipeline,
             });
-            println!("{}", serde_json::to_string_pretty(&result)?);
+            write_json(&result)?;
         } else {
             println!(
                 "{} Deleted artifact '{}' from pipeline #{}",
-                style("✓").green(),
-                style(&args.name).cyan(),
+                theme().success_mark(),
+                theme().emphasis(&args.name),
                 args.pipeline
             );
         }
@@ -456,12 +437,3 @@ fn format_bytes(bytes: u64) -> String {
         format!("{} B", bytes)
     }
 }
-
-/// Truncate string to max length with ellipsis
-fn truncate(s: &str, max_len: usize) -> String {
-    if s.len() > max_len {
-        format!("{}...", &s[..max_len - 3])
-    } else {
-        s.to_string()
-    }
-}
@@ -14,12 +14,13 @@
 use anyhow::{Context, Result};
 use clap::{Args, Subcommand};
 
+use crate::api::server::capabilities;
 use crate::auth::{
-    get_cloud_username, oauth_login, read_token_from_stdin, refresh_oauth_token,
+    get_cloud_username, oauth_login, read_token_from_stdin, refresh_stored_token,
     validate_cloud_token, validate_token, KeyringStore, OAuthConfig, PersonalAccessToken,
     DEFAULT_CLIENT_ID, DEFAULT_CLIENT_SECRET,
 };
-use crate::config::{Config, HostConfig};
+use crate::config::{is_cloud_host, Config, HostConfig};
 use crate::interactive::{prompt_confirm_with_default, prompt_input, prompt_password};
 
 use super::GlobalOptions;
@@ -133,9 +134,10 @@ async fn login(args: &LoginArgs) -> Result<()> {
     // Determine if we're logging into Cloud or Server/DC
     // Default to Cloud unless --server/--self-hosted is specified or a non-Cloud host is provided
     let is_server = args.server
-        || args.host.as_ref().map_or(false, |h| {
-            h != "bitbucket.org" && h != "api.bitbucket.org"
-        });
+        || args
+            .host
+            .as_ref()
+            .is_some_and(|h| h != "bitbucket.org" && h != "api.bitbucket.org");
 
     if is_server {
         login_server(args).await
@@ -433,6 +435,21 @@ async fn status(args: &StatusArgs) -> Result<()> {
         if let Some(ref api_version) = host_config.api_version {
             println!("  API Version: {}", api_version);
         }
+
+        if !is_cloud_host(host) {
+            let info = match (&token, is_valid) {
+                (Some(t), true) => capabilities::server_info(host, t).await.ok(),
+                _ => capabilities::cached_server_info(host),
+            };
+            if let Some(info) = info {
+                println!(
+                    "  Server Version: {} {} (build {})",
+                    info.display_name.as_deref().unwrap_or("Bitbucket"),
+                    info.version,
+                    info.build
+                );
+            }
+        }
         println!();
     }
 
@@ -444,20 +461,11 @@ async fn refresh() -> Result<()> {
     let keyring = KeyringStore::new();
     let host = "bitbucket.org";
 
-    // Get refresh token
-    let refresh_token = keyring.get(&format!("{}.refresh", host))?.ok_or_else(|| {
+    println!("Refreshing token...");
+    let tokens = refresh_stored_token(&keyring, host).await?.ok_or_else(|| {
         anyhow::anyhow!("No refresh token found. Please re-authenticate with 'bb auth login'")
     })?;
 
-    println!("Refreshing token...");
-    let tokens = refresh_oauth_token(&refresh_token, None, None).await?;
-
-    // Store new tokens
-    keyring.store(host, &tokens.access_token)?;
-    if let Some(ref new_refresh) = tokens.refresh_token {
-        keyring.store(&format!("{}.refresh", host), new_refresh)?;
-    }
-
     println!("Token refreshed successfully");
 
     // Show expiration if available
@@ -14,10 +14,10 @@
 
 use anyhow::{bail, Result};
 use clap::Args;
-use console::style;
 
 use crate::config::Config;
 use crate::context::{ContextResolver, HostType, RepoContext};
+use crate::output::theme;
 use crate::util::open_browser;
 
 use super::GlobalOptions;
@@ -86,7 +86,7 @@ impl BrowseCommand {
             println!("{}", url);
         } else {
             let target = self.describe_target();
-            println!("{} Opening {} in browser...", style("→").cyan(), target);
+            println!("{} Opening {} in browser...", theme().info_mark(), target);
             open_browser(&url)?;
         }
 
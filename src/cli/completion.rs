@@ -9,10 +9,10 @@
 //! Shell completion commands
 
 use anyhow::Result;
-use clap::{Args, CommandFactory, Subcommand};
+use clap::{Args, Subcommand};
 use clap_complete::{generate, Shell};
 
-use super::{Cli, GlobalOptions};
+use super::{command_with_extensions, GlobalOptions};
 
 /// Generate shell completion scripts
 #[derive(Args, Debug)]
@@ -38,7 +38,7 @@ pub enum CompletionSubcommand {
 
 impl CompletionCommand {
     pub async fn run(&self, _global: &GlobalOptions) -> Result<()> {
-        let mut cmd = Cli::command();
+        let mut cmd = command_with_extensions();
         let name = "bb";
 
         match &self.command {
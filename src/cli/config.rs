@@ -16,14 +16,24 @@ use std::process::Command;
 
 use anyhow::{bail, Result};
 use clap::{Args, Subcommand};
-use console::style;
 
 use crate::config::Config;
+use crate::output::{theme, write_json};
 
 use super::GlobalOptions;
 
 /// Valid core configuration keys
-const VALID_CORE_KEYS: &[&str] = &["editor", "pager", "browser", "git_protocol", "prompt"];
+const VALID_CORE_KEYS: &[&str] = &[
+    "editor",
+    "pager",
+    "browser",
+    "git_protocol",
+    "prompt",
+    "color",
+    "theme",
+    "repo_hooks",
+    "extension_token",
+];
 
 /// Valid host configuration keys
 const VALID_HOST_KEYS: &[&str] = &[
@@ -135,7 +145,7 @@ impl ConfigCommand {
                     "value": value,
                     "host": host,
                 });
-                println!("{}", serde_json::to_string_pretty(&result)?);
+                write_json(&result)?;
             } else if let Some(v) = value {
                 println!("{}", v);
             }
@@ -148,7 +158,7 @@ impl ConfigCommand {
                     "key": args.key,
                     "value": value,
                 });
-                println!("{}", serde_json::to_string_pretty(&result)?);
+                write_json(&result)?;
             } else if let Some(v) = value {
                 println!("{}", v);
             }
@@ -183,6 +193,24 @@ impl ConfigCommand {
                 bail!("Invalid value for prompt. Valid values: enabled, disabled");
             }
 
+            if args.key == "color" && !["auto", "always", "never"].contains(&args.value.as_str()) {
+                bail!("Invalid value for color. Valid values: auto, always, never");
+            }
+
+            if args.key == "theme" && !["default", "plain"].contains(&args.value.as_str()) {
+                bail!("Invalid value for theme. Valid values: default, plain");
+            }
+
+            if args.key == "repo_hooks" && !["enabled", "disabled"].contains(&args.value.as_str()) {
+                bail!("Invalid value for repo_hooks. Valid values: enabled, disabled");
+            }
+
+            if args.key == "extension_token"
+                && !["enabled", "disabled"].contains(&args.value.as_str())
+            {
+                bail!("Invalid value for extension_token. Valid values: enabled, disabled");
+            }
+
             config.set(&args.key, args.value.clone());
         }
 
@@ -195,12 +223,12 @@ impl ConfigCommand {
                 "value": args.value,
                 "host": args.host,
             });
-            println!("{}", serde_json::to_string_pretty(&result)?);
+            write_json(&result)?;
         } else {
             println!(
                 "{} Set {} = {}",
-                style("✓").green(),
-                style(&args.key).cyan(),
+                theme().success_mark(),
+                theme().emphasis(&args.key),
                 args.value
             );
         }
@@ -223,6 +251,10 @@ impl ConfigCommand {
                 "browser" => config.core.browser = None,
                 "git_protocol" => config.core.git_protocol = "https".to_string(),
                 "prompt" => config.core.prompt = "enabled".to_string(),
+                "color" => config.core.color = None,
+                "theme" => config.core.theme = None,
+                "repo_hooks" => config.core.repo_hooks = None,
+                "extension_token" => config.core.extension_token = None,
                 _ => bail!(
                     "Unknown configuration key '{}'. Valid keys: {}",
                     args.key,
@@ -239,9 +271,13 @@ impl ConfigCommand {
                 "key": args.key,
                 "host": args.host,
             });
-            println!("{}", serde_json::to_string_pretty(&result)?);
+            write_json(&result)?;
         } else {
-            println!("{} Unset {}", style("✓").green(), style(&args.key).cyan());
+            println!(
+                "{} Unset {}",
+                theme().success_mark(),
+                theme().emphasis(&args.key)
+            );
         }
 
         Ok(())
@@ -277,7 +313,7 @@ impl ConfigCommand {
                     "aliases": config.aliases,
                 })
             };
-            println!("{}", serde_json::to_string_pretty(&result)?);
+            write_json(&result)?;
             return Ok(());
         }
 
@@ -286,7 +322,7 @@ impl ConfigCommand {
             println!();
             println!(
                 "{}",
-                style(format!("Configuration for host: {}", host)).bold()
+                theme().heading(format!("Configuration for host: {}", host))
             );
             println!("{}", "-".repeat(50));
 
@@ -301,21 +337,25 @@ impl ConfigCommand {
         } else {
             // List all config
             println!();
-            println!("{}", style("Core Configuration").bold());
+            println!("{}", theme().heading("Core Configuration"));
             println!("{}", "-".repeat(50));
             self.print_kv("editor", &config.core.editor);
             self.print_kv("pager", &config.core.pager);
             self.print_kv("browser", &config.core.browser);
             self.print_kv_value("git_protocol", &config.core.git_protocol);
             self.print_kv_value("prompt", &config.core.prompt);
+            self.print_kv("color", &config.core.color);
+            self.print_kv("theme", &config.core.theme);
+            self.print_kv("repo_hooks", &config.core.repo_hooks);
+            self.print_kv("extension_token", &config.core.extension_token);
 
             if !config.hosts.is_empty() {
                 println!();
-                println!("{}", style("Host Configuration").bold());
+                println!("{}", theme().heading("Host Configuration"));
                 println!("{}", "-".repeat(50));
                 for (host, host_config) in &config.hosts {
                     println!();
-                    println!("  {}", style(host).cyan().bold());
+                    println!("  {}", theme().emphasis(host));
                     if let Some(user) = &host_config.user {
                         println!("    user: {}", user);
                     }
@@ -333,10 +373,33 @@ impl ConfigCommand {
 
             if !config.aliases.is_empty() {
                 println!();
-                println!("{}", style("Aliases").bold());
+                println!("{}", theme().heading("Aliases"));
                 println!("{}", "-".repeat(50));
                 for (alias, command) in &config.aliases {
-                    println!("  {} = {}", style(alias).cyan(), command);
+                    println!("  {} = {}", theme().emphasis(alias), command);
+                }
+            }
+
+            if !config.colors.is_empty() {
+                println!();
+                println!("{}", theme().heading("Colors"));
+                println!("{}", "-".repeat(50));
+                for (state, color) in &config.colors {
+                    println!("  {} = {}", theme().paint_state(state, state), color);
+                }
+            }
+
+            if !config.hooks.is_empty() {
+                println!();
+                println!("{}", theme().heading("Hooks"));
+                println!("{}", "-".repeat(50));
+                for (command, hooks) in &config.hooks {
+                    for hook in &hooks.pre {
+                        println!("  {} (pre) = {}", theme().emphasis(command), hook);
+                    }
+                    for hook in &hooks.post {
+                        println!("  {} (post) = {}", theme().emphasis(command), hook);
+                    }
                 }
             }
         }
@@ -376,13 +439,13 @@ impl ConfigCommand {
                 "editor": editor,
                 "host": args.host,
             });
-            println!("{}", serde_json::to_string_pretty(&result)?);
+            write_json(&result)?;
             return Ok(());
         }
 
         println!(
             "{} Opening {} in {}...",
-            style("→").cyan(),
+            theme().info_mark(),
             config_path.display(),
             editor
         );
@@ -402,7 +465,7 @@ impl ConfigCommand {
             bail!("Editor exited with non-zero status");
         }
 
-        println!("{} Configuration saved.", style("✓").green());
+        println!("{} Configuration saved.", theme().success_mark());
         Ok(())
     }
 
@@ -415,7 +478,7 @@ impl ConfigCommand {
                 "path": config_path.display().to_string(),
                 "exists": config_path.exists(),
             });
-            println!("{}", serde_json::to_string_pretty(&result)?);
+            write_json(&result)?;
         } else {
             println!("{}", config_path.display());
         }
@@ -498,10 +561,10 @@ impl ConfigCommand {
 
     fn print_kv(&self, key: &str, value: &Option<String>) {
         let display_value = value.as_deref().unwrap_or("-");
-        println!("  {}: {}", style(key).cyan(), display_value);
+        println!("  {}: {}", theme().emphasis(key), display_value);
     }
 
     fn print_kv_value(&self, key: &str, value: &str) {
-        println!("  {}: {}", style(key).cyan(), value);
+        println!("  {}: {}", theme().emphasis(key), value);
     }
 }
@@ -29,7 +29,6 @@
 
 use anyhow::{bail, Result};
 use clap::{Args, Subcommand};
-use console::style;
 use serde::{Deserialize, Serialize};
 
 use crate::api::common::PaginatedResponse;
@@ -37,7 +36,10 @@ use crate::api::BitbucketClient;
 use crate::auth::{AuthCredential, KeyringStore};
 use crate::config::Config;
 use crate::context::{ContextResolver, HostType, RepoContext};
-use crate::output::{OutputWriter, TableOutput};
+use crate::hooks::Hooks;
+use crate::output::{
+    print_row, stdout_is_tty, theme, write_json, Color, OutputWriter, TableOutput, TableRow,
+};
 
 use super::GlobalOptions;
 
@@ -271,21 +273,45 @@ struct EnvironmentListItem {
     rank: Option<i32>,
 }
 
+impl TableRow for DeploymentListItem {
+    const COLUMNS: &'static [&'static str] = &[
+        "uuid",
+        "environment",
+        "state",
+        "release",
+        "commit",
+        "started_on",
+    ];
+    const DEFAULT_COLUMNS: &'static [&'static str] = Self::COLUMNS;
+
+    fn cell(&self, column: &str) -> String {
+        let or_dash = |value: &Option<String>| value.clone().unwrap_or_else(|| "-".to_string());
+        match column {
+            "uuid" => self.uuid.clone(),
+            "environment" => self.environment.clone(),
+            "state" => self.state.clone(),
+            "release" => or_dash(&self.release),
+            "commit" => or_dash(&self.commit),
+            "started_on" => self
+                .started_on
+                .as_deref()
+                .map(|s| s.chars().take(19).collect())
+                .unwrap_or_else(|| "-".to_string()),
+            _ => String::new(),
+        }
+    }
+
+    fn cell_color(&self, column: &str) -> Option<Color> {
+        if column != "state" {
+            return None;
+        }
+        theme().state_color(&self.state)
+    }
+}
+
 impl TableOutput for DeploymentListItem {
     fn print_table(&self, _color: bool) {
-        let state_styled = match self.state.as_str() {
-            "SUCCESSFUL" => style(&self.state).green().to_string(),
-            "FAILED" => style(&self.state).red().to_string(),
-            "IN_PROGRESS" => style(&self.state).yellow().to_string(),
-            _ => self.state.clone(),
-        };
-        println!(
-            "{:<38} {:<15} {:<12} {}",
-            &self.uuid,
-            truncate(&self.environment, 13),
-            state_styled,
-            self.release.as_deref().unwrap_or("-")
-        );
+        print_row(self);
     }
 
     fn print_markdown(&self) {
@@ -315,18 +341,18 @@ impl DeployCommand {
         resolver.resolve(global)
     }
 
-    fn get_client(&self, ctx: &RepoContext) -> Result<BitbucketClient> {
-        if !matches!(ctx.host_type, HostType::Cloud) {
-            bail!("Deployments are only available for Bitbucket Cloud.");
-        }
-
-        let client = BitbucketClient::cloud()?;
-
+    async fn get_client(&self, ctx: &RepoContext) -> Result<BitbucketClient> {
         let keyring = KeyringStore::new();
         let token = keyring
             .get(&ctx.host)?
             .ok_or_else(|| anyhow::anyhow!("Not authenticated. Run 'bb auth login' first."))?;
 
+        if !matches!(ctx.host_type, HostType::Cloud) {
+            bail!("Deployments on Bitbucket Server/DC are not supported yet; only Bitbucket Cloud deployments can be managed.");
+        }
+
+        let client = BitbucketClient::cloud()?;
+
         Ok(client.with_auth(AuthCredential::OAuth {
             access_token: token,
             refresh_token: None,
@@ -345,7 +371,7 @@ impl DeployCommand {
     /// List deployments
     async fn list(&self, args: &ListArgs, global: &GlobalOptions) -> Result<()> {
         let ctx = self.resolve_context(global)?;
-        let client = self.get_client(&ctx)?;
+        let client = self.get_client(&ctx).await?;
 
         let mut url = format!(
             "/repositories/{}/{}/deployments?pagelen={}",
@@ -386,47 +412,19 @@ impl DeployCommand {
 
         let writer = OutputWriter::new(self.get_format(global));
 
-        if !global.json {
+        let decorate = !global.json && stdout_is_tty();
+
+        if decorate {
             println!();
-            println!("{}", style("Deployments").bold());
-            println!("{}", "-".repeat(100));
-            println!(
-                "{} {} {} {} {} {}",
-                style(format!("{:<38}", "UUID")).bold(),
-                style(format!("{:<15}", "ENVIRONMENT")).bold(),
-                style(format!("{:<12}", "STATE")).bold(),
-                style(format!("{:<15}", "RELEASE")).bold(),
-                style(format!("{:<10}", "COMMIT")).bold(),
-                style("STARTED").bold()
-            );
-            println!("{}", "-".repeat(100));
-
-            for item in &items {
-                let state_styled = match item.state.as_str() {
-                    "COMPLETED" | "completed" => style(&item.state).green().to_string(),
-                    "FAILED" | "failed" => style(&item.state).red().to_string(),
-                    "IN_PROGRESS" | "in_progress" => style(&item.state).yellow().to_string(),
-                    _ => item.state.clone(),
-                };
-
-                println!(
-                    "{:<38} {:<15} {:<12} {:<15} {:<10} {}",
-                    &item.uuid,
-                    &item.environment,
-                    state_styled,
-                    item.release.as_deref().unwrap_or("-"),
-                    item.commit.as_deref().unwrap_or("-"),
-                    item.started_on
-                        .as_deref()
-                        .map(|s| &s[..std::cmp::min(19, s.len())])
-                        .unwrap_or("-")
-                );
-            }
+            println!("{}", theme().heading("Deployments"));
+            println!();
+        }
+
+        writer.write_table(&items)?;
 
+        if decorate {
             println!();
             println!("Showing {} deployment(s)", items.len());
-        } else {
-            writer.write_list(&items)?;
         }
 
         Ok(())
@@ -435,7 +433,7 @@ impl DeployCommand {
     /// View deployment details
     async fn view(&self, args: &ViewArgs, global: &GlobalOptions) -> Result<()> {
         let ctx = self.resolve_context(global)?;
-        let client = self.get_client(&ctx)?;
+        let client = self.get_client(&ctx).await?;
 
         let url = format!(
             "/repositories/{}/{}/deployments/{}",
@@ -467,7 +465,7 @@ impl DeployCommand {
     /// Promote a deployment to another environment
     async fn promote(&self, args: &PromoteArgs, global: &GlobalOptions) -> Result<()> {
         let ctx = self.resolve_context(global)?;
-        let client = self.get_client(&ctx)?;
+        let client = self.get_client(&ctx).await?;
 
         // Get current deployment
         let get_url = format!(
@@ -488,6 +486,14 @@ impl DeployCommand {
             .find(|e| e.name.to_lowercase() == args.environment.to_lowercase())
             .ok_or_else(|| anyhow::anyhow!("Environment '{}' not found", args.environment))?;
 
+        let hooks = Hooks::load("deploy promote", &ctx, global.json).await?;
+        let operation = serde_json::json!({
+            "deployment": args.uuid,
+            "environment": target_env.name,
+            "release": current.release.as_ref().map(|r| &r.name),
+        });
+        hooks.run_pre(&operation)?;
+
         // Create promotion - this creates a new deployment pointing to the same release
         let url = format!("/repositories/{}/{}/deployments", ctx.owner, ctx.repo_slug);
 
@@ -505,18 +511,23 @@ impl DeployCommand {
 
         let new_deployment: Deployment = client.post(&url, &body).await?;
 
+        hooks.run_post(
+            &operation,
+            &serde_json::json!({ "uuid": new_deployment.uuid }),
+        );
+
         if global.json {
             let result = serde_json::json!({
                 "success": true,
                 "uuid": new_deployment.uuid,
                 "environment": new_deployment.environment.name,
             });
-            println!("{}", serde_json::to_string_pretty(&result)?);
+            write_json(&result)?;
         } else {
             println!(
                 "{} Promoted deployment to {}",
-                style("✓").green(),
-                style(&args.environment).cyan()
+                theme().success_mark(),
+                theme().emphasis(&args.environment)
             );
             println!("  New deployment UUID: {}", new_deployment.uuid);
         }
@@ -538,7 +549,7 @@ impl DeployCommand {
     /// List environments
     async fn env_list(&self, global: &GlobalOptions) -> Result<()> {
         let ctx = self.resolve_context(global)?;
-        let client = self.get_client(&ctx)?;
+        let client = self.get_client(&ctx).await?;
 
         let url = format!("/repositories/{}/{}/environments", ctx.owner, ctx.repo_slug);
 
@@ -565,41 +576,19 @@ impl DeployCommand {
 
         let writer = OutputWriter::new(self.get_format(global));
 
-        if !global.json {
+        let decorate = !global.json && stdout_is_tty();
+
+        if decorate {
             println!();
-            println!("{}", style("Environments").bold());
-            println!("{}", "-".repeat(70));
-            println!(
-                "{} {} {} {}",
-                style(format!("{:<38}", "UUID")).bold(),
-                style(format!("{:<15}", "NAME")).bold(),
-                style(format!("{:<12}", "TYPE")).bold(),
-                style("RANK").bold()
-            );
-            println!("{}", "-".repeat(70));
-
-            for item in &items {
-                let type_str = item.r#type.as_deref().unwrap_or("-");
-                let type_styled = match type_str {
-                    "Production" | "production" => style(type_str).red().to_string(),
-                    "Staging" | "staging" => style(type_str).yellow().to_string(),
-                    "Test" | "test" => style(type_str).cyan().to_string(),
-                    _ => type_str.to_string(),
-                };
-
-                println!(
-                    "{:<38} {:<15} {:<12} {}",
-                    &item.uuid,
-                    &item.name,
-                    type_styled,
-                    item.rank.map_or("-".to_string(), |r| r.to_string())
-                );
-            }
+            println!("{}", theme().heading("Environments"));
+            println!();
+        }
 
+        writer.write_table(&items)?;
+
+        if decorate {
             println!();
             println!("Showing {} environment(s)", items.len());
-        } else {
-            writer.write_list(&items)?;
         }
 
         Ok(())
@@ -608,7 +597,7 @@ impl DeployCommand {
     /// View environment details
     async fn env_view(&self, args: &EnvironmentViewArgs, global: &GlobalOptions) -> Result<()> {
         let ctx = self.resolve_context(global)?;
-        let client = self.get_client(&ctx)?;
+        let client = self.get_client(&ctx).await?;
 
         let url = format!(
             "/repositories/{}/{}/environments/{}",
@@ -633,7 +622,7 @@ impl DeployCommand {
     /// Create an environment
     async fn env_create(&self, args: &EnvironmentCreateArgs, global: &GlobalOptions) -> Result<()> {
         let ctx = self.resolve_context(global)?;
-        let client = self.get_client(&ctx)?;
+        let client = self.get_client(&ctx).await?;
 
         let url = format!("/repositories/{}/{}/environments", ctx.owner, ctx.repo_slug);
 
@@ -652,12 +641,12 @@ impl DeployCommand {
                 "uuid": env.uuid,
                 "name": env.name,
             });
-            println!("{}", serde_json::to_string_pretty(&result)?);
+            write_json(&result)?;
         } else {
             println!(
                 "{} Created environment {}",
-                style("✓").green(),
-                style(&args.name).cyan()
+                theme().success_mark(),
+                theme().emphasis(&args.name)
             );
             println!("  UUID: {}", env.uuid);
             println!("  Type: {}", args.r#type);
@@ -669,7 +658,7 @@ impl DeployCommand {
     /// Edit an environment
     async fn env_edit(&self, args: &EnvironmentEditArgs, global: &GlobalOptions) -> Result<()> {
         let ctx = self.resolve_context(global)?;
-        let client = self.get_client(&ctx)?;
+        let client = self.get_client(&ctx).await?;
 
         // Get current environment
         let get_url = format!(
@@ -702,12 +691,12 @@ impl DeployCommand {
                 "uuid": env.uuid,
                 "name": env.name,
             });
-            println!("{}", serde_json::to_string_pretty(&result)?);
+            write_json(&result)?;
         } else {
             println!(
                 "{} Updated environment {}",
-                style("✓").green(),
-                style(&env.name).cyan()
+                theme().success_mark(),
+                theme().emphasis(&env.name)
             );
         }
 
@@ -717,7 +706,7 @@ impl DeployCommand {
     /// Delete an environment
     async fn env_delete(&self, args: &EnvironmentDeleteArgs, global: &GlobalOptions) -> Result<()> {
         let ctx = self.resolve_context(global)?;
-        let client = self.get_client(&ctx)?;
+        let client = self.get_client(&ctx).await?;
 
         // Confirm deletion
         if !args.confirm && !global.no_prompt {
@@ -728,7 +717,7 @@ impl DeployCommand {
                 .interact()?;
 
             if !confirmed {
-                println!("{} Cancelled.", style("!").yellow());
+                println!("{} Cancelled.", theme().warning_mark());
                 return Ok(());
             }
         }
@@ -745,12 +734,12 @@ impl DeployCommand {
                 "success": true,
                 "name": args.name,
             });
-            println!("{}", serde_json::to_string_pretty(&result)?);
+            write_json(&result)?;
         } else {
             println!(
                 "{} Deleted environment {}",
-                style("✓").green(),
-                style(&args.name).cyan()
+                theme().success_mark(),
+                theme().emphasis(&args.name)
             );
         }
 
@@ -763,22 +752,15 @@ impl DeployCommand {
 impl TableOutput for DeploymentDetail {
     fn print_table(&self, _color: bool) {
         println!();
-        println!("{}", style("Deployment Details").bold());
+        println!("{}", theme().heading("Deployment Details"));
         println!("{}", "-".repeat(60));
-        println!("UUID:                 {}", style(&self.uuid).cyan());
+        println!("UUID:                 {}", theme().emphasis(&self.uuid));
         println!("Environment:          {}", self.environment);
         println!(
             "Type:                 {}",
             self.environment_type.as_deref().unwrap_or("-")
         );
-        println!(
-            "State:                {}",
-            match self.state.as_str() {
-                "COMPLETED" | "completed" => style(&self.state).green().to_string(),
-                "FAILED" | "failed" => style(&self.state).red().to_string(),
-                _ => self.state.clone(),
-            }
-        );
+        println!("State:                {}", theme().state(&self.state));
         println!(
             "Release:              {}",
             self.release.as_deref().unwrap_or("-")
@@ -807,12 +789,39 @@ impl TableOutput for DeploymentDetail {
     }
 }
 
+impl TableRow for EnvironmentListItem {
+    const COLUMNS: &'static [&'static str] = &["uuid", "name", "type", "rank"];
+    const DEFAULT_COLUMNS: &'static [&'static str] = Self::COLUMNS;
+
+    fn cell(&self, column: &str) -> String {
+        match column {
+            "uuid" => self.uuid.clone(),
+            "name" => self.name.clone(),
+            "type" => self.r#type.clone().unwrap_or_else(|| "-".to_string()),
+            "rank" => self.rank.map_or("-".to_string(), |r| r.to_string()),
+            _ => String::new(),
+        }
+    }
+
+    fn cell_color(&self, column: &str) -> Option<Color> {
+        if column != "type" {
+            return None;
+        }
+        match self.r#type.as_deref().map(str::to_lowercase).as_deref() {
+            Some("production") => Some(Color::Red),
+            Some("staging") => Some(Color::Yellow),
+            Some("test") => Some(Color::Cyan),
+            _ => None,
+        }
+    }
+}
+
 impl TableOutput for EnvironmentListItem {
     fn print_table(&self, _color: bool) {
         println!();
-        println!("{}", style("Environment Details").bold());
+        println!("{}", theme().heading("Environment Details"));
         println!("{}", "-".repeat(60));
-        println!("UUID:                 {}", style(&self.uuid).cyan());
+        println!("UUID:                 {}", theme().emphasis(&self.uuid));
         println!("Name:                 {}", self.name);
         println!(
             "Type:                 {}",
@@ -832,12 +841,3 @@ impl TableOutput for EnvironmentListItem {
         println!("- **Type**: {}", self.r#type.as_deref().unwrap_or("-"));
     }
 }
-
-/// Truncate string to max length with ellipsis
-fn truncate(s: &str, max_len: usize) -> String {
-    if s.len() > max_len {
-        format!("{}...", &s[..max_len - 3])
-    } else {
-        s.to_string()
-    }
-}
@@ -17,6 +17,9 @@
 //! # List installed extensions
 //! bb extension list
 //!
+//! # Show an extension's manifest and install details
+//! bb extension info lint
+//!
 //! # Install an extension
 //! bb extension install owner/bb-lint
 //!
@@ -28,14 +31,26 @@
 //!
 //! # Execute an extension
 //! bb extension exec lint -- --fix
+//!
+//! # Or run it directly as a subcommand
+//! bb lint --fix
 //! ```
 
+use std::collections::BTreeMap;
+
 use anyhow::{bail, Result};
-use clap::{Args, Subcommand};
-use console::style;
+use clap::{Args, CommandFactory, Subcommand};
 use serde::Serialize;
 
-use crate::extension::ExtensionManager;
+use crate::config::{is_cloud_host, Config};
+use crate::context::{ContextResolver, HostType};
+use crate::extension::{
+    session_token, ExtensionEnv, ExtensionManager, ExtensionManifest, UpgradeOutcome,
+};
+use crate::output::{
+    print_field, print_header, print_rows, stdout_is_tty, theme, write_json, Color, OutputFormat,
+    OutputWriter, TableOutput, TableRow,
+};
 
 use super::GlobalOptions;
 
@@ -52,6 +67,9 @@ pub enum ExtensionSubcommand {
     #[command(visible_alias = "ls")]
     List,
 
+    /// Show details about an installed extension
+    Info(InfoArgs),
+
     /// Install an extension
     Install(InstallArgs),
 
@@ -71,12 +89,18 @@ pub enum ExtensionSubcommand {
     Exec(ExecArgs),
 }
 
+#[derive(Args, Debug)]
+pub struct InfoArgs {
+    /// Extension name
+    pub extension: String,
+}
+
 #[derive(Args, Debug)]
 pub struct InstallArgs {
-    /// Extension repository (e.g., owner/bb-extension)
+    /// Extension repository (e.g., owner/bb-extension, or any git URL)
     pub repo: String,
 
-    /// Pin to specific version
+    /// Pin to a branch, tag or commit; pinned extensions are not upgraded
     #[arg(long)]
     pub pin: Option<String>,
 }
@@ -122,16 +146,154 @@ pub struct ExecArgs {
 #[derive(Debug, Serialize)]
 struct ExtensionListItem {
     name: String,
+    description: Option<String>,
+    version: Option<String>,
     path: String,
     precompiled: bool,
     source: Option<String>,
     pinned_version: Option<String>,
 }
 
+impl TableRow for ExtensionListItem {
+    const COLUMNS: &'static [&'static str] =
+        &["name", "description", "path", "type", "source", "version"];
+    const DEFAULT_COLUMNS: &'static [&'static str] = &["name", "description", "type", "path"];
+
+    fn cell(&self, column: &str) -> String {
+        match column {
+            "name" => self.name.clone(),
+            "description" => self.description.clone().unwrap_or_else(|| "-".to_string()),
+            "path" => self.path.clone(),
+            "type" => if self.precompiled { "binary" } else { "script" }.to_string(),
+            "source" => self.source.clone().unwrap_or_else(|| "-".to_string()),
+            "version" => self
+                .pinned_version
+                .clone()
+                .or_else(|| self.version.clone())
+                .unwrap_or_else(|| "-".to_string()),
+            _ => String::new(),
+        }
+    }
+
+    fn cell_color(&self, column: &str) -> Option<Color> {
+        (column == "name").then_some(Color::Cyan)
+    }
+}
+
+/// Detail view for `bb extension info`
+#[derive(Debug, Serialize)]
+struct ExtensionInfo {
+    name: String,
+    description: Option<String>,
+    version: Option<String>,
+    path: String,
+    kind: String,
+    source: Option<String>,
+    #[serde(rename = "ref")]
+    git_ref: Option<String>,
+    commit: Option<String>,
+    pinned: bool,
+    min_bb_version: Option<String>,
+    compatible: bool,
+    scopes: Vec<String>,
+    commands: BTreeMap<String, String>,
+}
+
+impl TableOutput for ExtensionInfo {
+    fn print_table(&self, color: bool) {
+        print_header(&self.name);
+        println!();
+
+        if let Some(description) = &self.description {
+            println!("{}", description);
+            println!();
+        }
+
+        if let Some(version) = &self.version {
+            print_field("Version", version, color);
+        }
+        print_field("Type", &self.kind, color);
+        print_field("Path", &self.path, color);
+
+        if let Some(source) = &self.source {
+            print_field("Source", source, color);
+        }
+        if let Some(git_ref) = &self.git_ref {
+            let git_ref = if self.pinned {
+                format!("{} (pinned)", git_ref)
+            } else {
+                git_ref.clone()
+            };
+            print_field("Ref", &git_ref, color);
+        }
+        if let Some(commit) = &self.commit {
+            print_field("Commit", commit, color);
+        }
+
+        if let Some(min_version) = &self.min_bb_version {
+            let requirement = if self.compatible {
+                format!("{} {}", min_version, theme().success_mark())
+            } else {
+                format!(
+                    "{} {} (this is bb {})",
+                    min_version,
+                    theme().failure_mark(),
+                    crate::VERSION
+                )
+            };
+            print_field("Requires bb", &requirement, color);
+        }
+        if !self.scopes.is_empty() {
+            print_field("Scopes", &self.scopes.join(", "), color);
+        }
+
+        if !self.commands.is_empty() {
+            println!();
+            println!("{}", theme().heading("Commands"));
+            let width = self.commands.keys().map(|c| c.len()).max().unwrap_or(0);
+            for (command, help) in &self.commands {
+                println!("  {:width$}  {}", command, help, width = width);
+            }
+        }
+    }
+
+    fn print_markdown(&self) {
+        println!("# {}", self.name);
+        println!();
+        if let Some(description) = &self.description {
+            println!("{}", description);
+            println!();
+        }
+        if let Some(version) = &self.version {
+            println!("- **Version**: {}", version);
+        }
+        println!("- **Type**: {}", self.kind);
+        println!("- **Path**: {}", self.path);
+        if let Some(source) = &self.source {
+            println!("- **Source**: {}", source);
+        }
+        if let Some(min_version) = &self.min_bb_version {
+            println!("- **Requires bb**: {}", min_version);
+        }
+        if !self.scopes.is_empty() {
+            println!("- **Scopes**: {}", self.scopes.join(", "));
+        }
+        if !self.commands.is_empty() {
+            println!();
+            println!("## Commands");
+            println!();
+            for (command, help) in &self.commands {
+                println!("- `{}`: {}", command, help);
+            }
+        }
+    }
+}
+
 impl ExtensionCommand {
     pub async fn run(&self, global: &GlobalOptions) -> Result<()> {
         match &self.command {
             ExtensionSubcommand::List => self.list(global).await,
+            ExtensionSubcommand::Info(args) => self.info(args, global).await,
             ExtensionSubcommand::Install(args) => self.install(args, global).await,
             ExtensionSubcommand::Upgrade(args) => self.upgrade(args, global).await,
             ExtensionSubcommand::Remove(args) => self.remove(args, global).await,
@@ -161,9 +323,17 @@ impl ExtensionCommand {
             return Ok(());
         }
 
+        for ext in &extensions {
+            if let Some(error) = &ext.manifest_error {
+                eprintln!("{} {}", theme().warning_mark(), error);
+            }
+        }
+
         let items: Vec<ExtensionListItem> = extensions
             .into_iter()
             .map(|e| ExtensionListItem {
+                description: e.description().map(String::from),
+                version: e.manifest.as_ref().and_then(|m| m.version.clone()),
                 name: e.name,
                 path: e.path.display().to_string(),
                 precompiled: e.precompiled,
@@ -173,36 +343,68 @@ impl ExtensionCommand {
             .collect();
 
         if global.json {
-            println!("{}", serde_json::to_string_pretty(&items)?);
+            write_json(&items)?;
         } else {
-            println!();
-            println!("{}", style("Installed Extensions").bold());
-            println!("{}", "-".repeat(70));
-            println!(
-                "{} {} {}",
-                style(format!("{:<20}", "NAME")).bold(),
-                style(format!("{:<40}", "PATH")).bold(),
-                style("TYPE").bold()
-            );
-            println!("{}", "-".repeat(70));
-
-            for item in &items {
-                let type_str = if item.precompiled { "binary" } else { "script" };
-                println!(
-                    "{:<20} {:<40} {}",
-                    style(&item.name).cyan(),
-                    truncate(&item.path, 38),
-                    type_str
-                );
+            let decorate = stdout_is_tty();
+            if decorate {
+                println!();
+                println!("{}", theme().heading("Installed Extensions"));
+                println!();
             }
 
-            println!();
-            println!("{} extension(s) installed", items.len());
+            print_rows(&items, theme().color_enabled())?;
+
+            if decorate {
+                println!();
+                println!("{} extension(s) installed", items.len());
+            }
         }
 
         Ok(())
     }
 
+    /// Show details about an extension
+    async fn info(&self, args: &InfoArgs, global: &GlobalOptions) -> Result<()> {
+        let manager = ExtensionManager::new()?;
+
+        let ext = manager
+            .find(&args.extension)?
+            .ok_or_else(|| anyhow::anyhow!("Extension not found: {}", args.extension))?;
+
+        // Load the manifest again so that a malformed one is reported here
+        let manifest = ExtensionManifest::for_executable(&ext.path)?.unwrap_or_default();
+        let locked = manager.locked(&ext.name)?;
+
+        let kind = match &locked {
+            Some(locked) => locked.kind.to_string(),
+            None if ext.precompiled => "binary".to_string(),
+            None => "script".to_string(),
+        };
+
+        let info = ExtensionInfo {
+            compatible: manifest.check_compatible(&ext.name, crate::VERSION).is_ok(),
+            name: ext.name,
+            description: manifest.description,
+            version: manifest.version,
+            path: ext.path.display().to_string(),
+            kind,
+            source: locked.as_ref().map(|l| l.source.clone()),
+            git_ref: locked.as_ref().and_then(|l| l.git_ref.clone()),
+            commit: locked.as_ref().and_then(|l| l.commit.clone()),
+            pinned: locked.as_ref().is_some_and(|l| l.pinned),
+            min_bb_version: manifest.min_bb_version,
+            scopes: manifest.scopes,
+            commands: manifest.commands,
+        };
+
+        let output = OutputWriter::new(if global.json {
+            OutputFormat::Json
+        } else {
+            OutputFormat::Table
+        });
+        output.write(&info)
+    }
+
     /// Install an extension
     async fn install(&self, args: &InstallArgs, global: &GlobalOptions) -> Result<()> {
         let manager = ExtensionManager::new()?;
@@ -210,7 +412,7 @@ impl ExtensionCommand {
         if !global.json {
             println!(
                 "{} Installing extension from {}...",
-                style("→").cyan(),
+                theme().info_mark(),
                 args.repo
             );
             if let Some(version) = &args.pin {
@@ -218,127 +420,179 @@ impl ExtensionCommand {
             }
         }
 
-        match manager.install(&args.repo, args.pin.as_deref()) {
-            Ok(ext) => {
-                if global.json {
-                    let result = serde_json::json!({
-                        "success": true,
-                        "name": ext.name,
-                        "path": ext.path.display().to_string(),
-                    });
-                    println!("{}", serde_json::to_string_pretty(&result)?);
-                } else {
-                    println!(
-                        "{} Installed extension '{}'",
-                        style("✓").green(),
-                        style(&ext.name).cyan()
-                    );
-                    println!("  Path: {}", ext.path.display());
-                }
-                Ok(())
-            }
+        let ext = match manager.install(&args.repo, args.pin.as_deref()).await {
+            Ok(ext) => ext,
             Err(e) => {
                 if global.json {
                     let result = serde_json::json!({
                         "success": false,
                         "error": e.to_string(),
                     });
-                    println!("{}", serde_json::to_string_pretty(&result)?);
-                    Ok(())
-                } else {
-                    Err(e)
+                    write_json(&result)?;
+                    return Ok(());
                 }
+                return Err(e);
+            }
+        };
+        let locked = manager.locked(&ext.name)?;
+
+        if global.json {
+            let result = serde_json::json!({
+                "success": true,
+                "name": ext.name,
+                "path": ext.path.display().to_string(),
+                "source": locked.as_ref().map(|l| l.source.clone()),
+                "kind": locked.as_ref().map(|l| l.kind.to_string()),
+                "ref": locked.as_ref().and_then(|l| l.git_ref.clone()),
+                "commit": locked.as_ref().and_then(|l| l.commit.clone()),
+                "asset": locked.as_ref().and_then(|l| l.asset.clone()),
+                "pinned": locked.as_ref().is_some_and(|l| l.pinned),
+            });
+            write_json(&result)?;
+        } else {
+            println!(
+                "{} Installed extension '{}'",
+                theme().success_mark(),
+                theme().emphasis(&ext.name)
+            );
+            println!("  Path: {}", ext.path.display());
+            if let Some(locked) = locked {
+                let version = match (&locked.git_ref, locked.version()) {
+                    (Some(git_ref), Some(version)) if version != *git_ref => {
+                        format!("{} ({})", git_ref, version)
+                    }
+                    (_, Some(version)) => version,
+                    (Some(git_ref), None) => git_ref.clone(),
+                    (None, None) => "-".to_string(),
+                };
+                println!("  Type: {}", locked.kind);
+                println!("  Version: {}", version);
             }
         }
+
+        Ok(())
     }
 
     /// Upgrade extensions
     async fn upgrade(&self, args: &UpgradeArgs, global: &GlobalOptions) -> Result<()> {
         let manager = ExtensionManager::new()?;
 
-        if args.all {
-            // Upgrade all extensions
-            let extensions = manager.list()?;
+        let names: Vec<String> = if args.all {
+            // Only extensions installed by bb know where to upgrade from
+            manager
+                .list()?
+                .into_iter()
+                .filter(|ext| ext.source.is_some())
+                .map(|ext| ext.name)
+                .collect()
+        } else if let Some(name) = &args.extension {
+            vec![name.clone()]
+        } else {
+            bail!("Specify an extension name or use --all to upgrade all extensions");
+        };
 
-            if extensions.is_empty() {
-                if !global.json {
-                    println!("No extensions installed.");
-                }
-                return Ok(());
+        if names.is_empty() {
+            if global.json {
+                println!("[]");
+            } else {
+                println!("No extensions installed.");
             }
+            return Ok(());
+        }
 
-            if !global.json {
+        if !global.json {
+            if args.all {
                 println!(
                     "{} Upgrading {} extension(s)...",
-                    style("→").cyan(),
-                    extensions.len()
+                    theme().info_mark(),
+                    names.len()
+                );
+            } else {
+                println!(
+                    "{} Upgrading extension '{}'...",
+                    theme().info_mark(),
+                    names[0]
                 );
             }
+        }
 
-            let mut results = Vec::new();
-            for ext in &extensions {
-                let result = manager.upgrade(&ext.name);
-                results.push(serde_json::json!({
-                    "name": ext.name,
-                    "success": result.is_ok(),
-                    "error": result.err().map(|e| e.to_string()),
-                }));
-
-                if !global.json {
-                    match &results.last().unwrap()["success"].as_bool() {
-                        Some(true) => {
-                            println!("  {} Upgraded {}", style("✓").green(), ext.name);
-                        }
-                        _ => {
-                            println!(
-                                "  {} Failed to upgrade {}: {}",
-                                style("✗").red(),
-                                ext.name,
-                                results.last().unwrap()["error"]
-                                    .as_str()
-                                    .unwrap_or("Unknown error")
-                            );
-                        }
-                    }
-                }
+        let mut results = Vec::new();
+        let mut failed = 0;
+        for name in &names {
+            let result = manager.upgrade(name).await;
+            if result.is_err() {
+                failed += 1;
             }
 
+            results.push(match &result {
+                Ok(UpgradeOutcome::Upgraded { from, to }) => serde_json::json!({
+                    "name": name,
+                    "success": true,
+                    "status": "upgraded",
+                    "from": from,
+                    "to": to,
+                }),
+                Ok(UpgradeOutcome::UpToDate(version)) => serde_json::json!({
+                    "name": name,
+                    "success": true,
+                    "status": "up_to_date",
+                    "version": version,
+                }),
+                Ok(UpgradeOutcome::Pinned(version)) => serde_json::json!({
+                    "name": name,
+                    "success": true,
+                    "status": "pinned",
+                    "version": version,
+                }),
+                Err(e) => serde_json::json!({
+                    "name": name,
+                    "success": false,
+                    "error": e.to_string(),
+                }),
+            });
+
             if global.json {
-                println!("{}", serde_json::to_string_pretty(&results)?);
+                continue;
             }
-        } else if let Some(name) = &args.extension {
-            // Upgrade specific extension
-            if !global.json {
-                println!("{} Upgrading extension '{}'...", style("→").cyan(), name);
+
+            match result {
+                Ok(UpgradeOutcome::Upgraded { from, to }) => println!(
+                    "  {} Upgraded {} {} {} {}",
+                    theme().success_mark(),
+                    theme().emphasis(name),
+                    from,
+                    theme().arrow(),
+                    to
+                ),
+                Ok(UpgradeOutcome::UpToDate(version)) => println!(
+                    "  {} {} is up to date ({})",
+                    theme().success_mark(),
+                    theme().emphasis(name),
+                    version
+                ),
+                Ok(UpgradeOutcome::Pinned(version)) => println!(
+                    "  {} {} is pinned to {}; reinstall with --pin to change it",
+                    theme().warning_mark(),
+                    theme().emphasis(name),
+                    version
+                ),
+                Err(e) => println!(
+                    "  {} Failed to upgrade {}: {}",
+                    theme().failure_mark(),
+                    name,
+                    e
+                ),
             }
+        }
 
-            match manager.upgrade(name) {
-                Ok(()) => {
-                    if global.json {
-                        let result = serde_json::json!({
-                            "success": true,
-                            "name": name,
-                        });
-                        println!("{}", serde_json::to_string_pretty(&result)?);
-                    } else {
-                        println!("{} Upgraded '{}'", style("✓").green(), style(name).cyan());
-                    }
-                }
-                Err(e) => {
-                    if global.json {
-                        let result = serde_json::json!({
-                            "success": false,
-                            "name": name,
-                            "error": e.to_string(),
-                        });
-                        println!("{}", serde_json::to_string_pretty(&result)?);
-                    } else {
-                        bail!("Failed to upgrade '{}': {}", name, e);
-                    }
-                }
+        if global.json {
+            if args.all {
+                write_json(&results)?;
+            } else {
+                write_json(&results[0])?;
             }
-        } else {
-            bail!("Specify an extension name or use --all to upgrade all extensions");
+        } else if failed > 0 {
+            bail!("{} extension(s) failed to upgrade", failed);
         }
 
         Ok(())
@@ -357,7 +611,7 @@ impl ExtensionCommand {
                 .interact()?;
 
             if !confirmed {
-                println!("{} Cancelled.", style("!").yellow());
+                println!("{} Cancelled.", theme().warning_mark());
                 return Ok(());
             }
         }
@@ -365,7 +619,7 @@ impl ExtensionCommand {
         if !global.json {
             println!(
                 "{} Removing extension '{}'...",
-                style("→").cyan(),
+                theme().info_mark(),
                 args.extension
             );
         }
@@ -377,12 +631,12 @@ impl ExtensionCommand {
                         "success": true,
                         "name": args.extension,
                     });
-                    println!("{}", serde_json::to_string_pretty(&result)?);
+                    write_json(&result)?;
                 } else {
                     println!(
                         "{} Removed extension '{}'",
-                        style("✓").green(),
-                        style(&args.extension).cyan()
+                        theme().success_mark(),
+                        theme().emphasis(&args.extension)
                     );
                 }
                 Ok(())
@@ -394,7 +648,7 @@ impl ExtensionCommand {
                         "name": args.extension,
                         "error": e.to_string(),
                     });
-                    println!("{}", serde_json::to_string_pretty(&result)?);
+                    write_json(&result)?;
                     Ok(())
                 } else {
                     Err(e)
@@ -415,7 +669,7 @@ impl ExtensionCommand {
                 .unwrap_or_else(|| " (shell script)".to_string());
             println!(
                 "{} Creating extension 'bb-{}'{}...",
-                style("→").cyan(),
+                theme().info_mark(),
                 args.name,
                 lang_str
             );
@@ -430,12 +684,12 @@ impl ExtensionCommand {
                         "path": path.display().to_string(),
                         "type": args.precompiled.as_deref().unwrap_or("shell"),
                     });
-                    println!("{}", serde_json::to_string_pretty(&result)?);
+                    write_json(&result)?;
                 } else {
                     println!(
                         "{} Created extension project at {}",
-                        style("✓").green(),
-                        style(path.display().to_string()).cyan()
+                        theme().success_mark(),
+                        theme().emphasis(path.display().to_string())
                     );
                     println!();
 
@@ -473,7 +727,7 @@ impl ExtensionCommand {
                         "name": args.name,
                         "error": e.to_string(),
                     });
-                    println!("{}", serde_json::to_string_pretty(&result)?);
+                    write_json(&result)?;
                     Ok(())
                 } else {
                     Err(e)
@@ -492,16 +746,16 @@ impl ExtensionCommand {
                 "action": "browse",
                 "url": url,
             });
-            println!("{}", serde_json::to_string_pretty(&result)?);
+            write_json(&result)?;
         } else {
-            println!("{} Opening extension browser...", style("→").cyan());
+            println!("{} Opening extension browser...", theme().info_mark());
 
             // Try to open in browser
             if let Err(e) = webbrowser::open(url) {
-                println!("{} Could not open browser: {}", style("!").yellow(), e);
+                println!("{} Could not open browser: {}", theme().warning_mark(), e);
                 println!("Browse extensions at: {}", url);
             } else {
-                println!("{} Opened {} in browser", style("✓").green(), url);
+                println!("{} Opened {} in browser", theme().success_mark(), url);
             }
         }
 
@@ -524,30 +778,147 @@ impl ExtensionCommand {
                 "path": ext.path.display().to_string(),
                 "args": args.args,
             });
-            println!("{}", serde_json::to_string_pretty(&result)?);
+            write_json(&result)?;
             return Ok(());
         }
 
-        // Execute the extension
-        let exit_code = ext.execute(&args.args)?;
+        // Execute the extension and exit with its status
+        let exit_code = ext.execute(&args.args, &extension_env(global).await)?;
+        std::process::exit(exit_code);
+    }
+}
 
-        if exit_code != 0 {
-            bail!(
-                "Extension '{}' exited with code {}",
-                args.extension,
-                exit_code
-            );
-        }
+/// Runs `bb <name> [args...]` as the `bb-<name>` extension.
+///
+/// This is the fallback for subcommands clap does not know about.
+///
+/// # Parameters
+///
+/// * `args` - The external subcommand: the extension name followed by its arguments
+/// * `global` - Global options, used to build the extension's environment
+///
+/// # Returns
+///
+/// The extension's exit code, which the caller should exit with.
+///
+/// # Errors
+///
+/// Returns an error if no extension with that name is installed, or if it
+/// could not be started.
+pub async fn run_external(args: &[String], global: &GlobalOptions) -> Result<i32> {
+    let Some((name, rest)) = args.split_first() else {
+        bail!("No command given. Run 'bb --help' for usage.");
+    };
+
+    let manager = ExtensionManager::new()?;
+    let Some(ext) = manager.find(name)? else {
+        bail!(
+            "unknown command '{}' for 'bb'\n\nRun 'bb --help' for usage, or 'bb extension list' to see installed extensions.",
+            name
+        );
+    };
+
+    ext.execute(rest, &extension_env(global).await)
+}
 
-        Ok(())
+/// Builds the `BB_*` environment for an extension from the resolved context.
+///
+/// Extensions may run outside a repository, so a missing repository or
+/// credential is not an error; those variables are simply left unset.
+async fn extension_env(global: &GlobalOptions) -> ExtensionEnv {
+    let config = Config::load().unwrap_or_default();
+
+    if let Ok(context) = ContextResolver::new(config.clone()).resolve(global) {
+        return ExtensionEnv::for_context(&context, global.json, &config).await;
     }
-}
 
-/// Truncate string to max length with ellipsis
-fn truncate(s: &str, max_len: usize) -> String {
-    if s.len() > max_len {
-        format!("{}...", &s[..max_len - 3])
+    let host = global
+        .host
+        .clone()
+        .unwrap_or_else(|| "bitbucket.org".to_string());
+    let host_type = if is_cloud_host(&host) {
+        HostType::Cloud
     } else {
-        s.to_string()
+        HostType::Server
+    };
+    let token = session_token(&host, &config).await;
+
+    ExtensionEnv {
+        host: Some(host),
+        repo: None,
+        host_type: Some(host_type),
+        json: global.json,
+        token,
+    }
+}
+
+/// Adds the installed extensions to the bottom of `bb --help`.
+///
+/// Extensions are not real clap subcommands, so they are listed above the
+/// existing after-help text instead.
+pub fn with_extension_help(cmd: clap::Command) -> clap::Command {
+    let Some(extensions) = extensions_help() else {
+        return cmd;
+    };
+
+    let after_help = cmd
+        .get_after_help()
+        .map(|help| help.to_string())
+        .unwrap_or_default();
+    cmd.after_help(format!("{}\n{}", extensions, after_help))
+}
+
+/// Lists installed extensions, or `None` when there are none.
+fn extensions_help() -> Option<String> {
+    let extensions = ExtensionManager::new().and_then(|m| m.list()).ok()?;
+    if extensions.is_empty() {
+        return None;
+    }
+
+    let width = extensions.iter().map(|e| e.name.len()).max().unwrap_or(0);
+    let mut help = String::from("Extension commands:\n");
+    for ext in extensions {
+        let summary = match ext.description() {
+            Some(description) => description.to_string(),
+            None => ext.path.display().to_string(),
+        };
+        help.push_str(&format!(
+            "  {:width$}  {}\n",
+            ext.name,
+            summary,
+            width = width
+        ));
+    }
+
+    Some(help)
+}
+
+/// Returns the `bb` command definition with installed extensions added as
+/// subcommands, so that shell completions offer them.
+pub fn command_with_extensions() -> clap::Command {
+    let mut cmd = super::Cli::command();
+
+    if let Ok(extensions) = ExtensionManager::new().and_then(|m| m.list()) {
+        for ext in extensions {
+            if cmd.find_subcommand(&ext.name).is_some() {
+                continue;
+            }
+            let about = ext
+                .description()
+                .map(String::from)
+                .unwrap_or_else(|| format!("Run the {} extension", ext.name));
+            let mut ext_cmd = clap::Command::new(ext.name.clone()).about(about);
+
+            // Complete the extension's own subcommands from its manifest
+            if let Some(manifest) = &ext.manifest {
+                for (command, help) in &manifest.commands {
+                    ext_cmd = ext_cmd.subcommand(clap::Command::new(command.clone()).about(help));
+                }
+            }
+
+            cmd = cmd.subcommand(ext_cmd);
+        }
     }
+
+    cmd
 }
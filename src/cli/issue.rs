@@ -16,7 +16,6 @@ use std::fs;
 
 use anyhow::{bail, Result};
 use clap::{Args, Subcommand};
-use console::style;
 use serde::Serialize;
 
 use crate::api::client::BitbucketClient;
@@ -24,11 +23,15 @@ use crate::api::cloud::issues::{
     CreateIssueRequest, Issue, IssueComment, IssueContentInput, UserUuid,
 };
 use crate::api::common::PaginatedResponse;
+use crate::api::query::{Bbql, QueryParams};
 use crate::auth::{AuthCredential, KeyringStore};
 use crate::config::Config;
 use crate::context::{ContextResolver, HostType, RepoContext};
 use crate::interactive::{prompt_confirm_with_default, prompt_input, prompt_input_optional};
-use crate::output::{OutputFormat, OutputWriter, TableOutput};
+use crate::output::{
+    print_row, require_json_fields, stdout_is_tty, theme, write_json, Color, JsonFields,
+    OutputFormat, OutputWriter, TableOutput, TableRow,
+};
 use crate::util::open_browser;
 
 use super::GlobalOptions;
@@ -228,43 +231,45 @@ struct IssueListItem {
     votes: u32,
 }
 
-impl TableOutput for IssueListItem {
-    fn print_table(&self, color: bool) {
-        let id_display = if color {
-            style(format!("#{}", self.id)).cyan().bold().to_string()
-        } else {
-            format!("#{}", self.id)
-        };
-
-        let state_display = if color {
-            match self.state.as_str() {
-                "open" | "new" => style(&self.state).green().to_string(),
-                "resolved" | "closed" => style(&self.state).dim().to_string(),
-                "on hold" => style(&self.state).yellow().to_string(),
-                _ => style(&self.state).red().to_string(),
-            }
-        } else {
-            self.state.clone()
-        };
-
-        let priority_display = if color {
-            match self.priority.as_str() {
-                "blocker" | "critical" => style(&self.priority).red().bold().to_string(),
-                "major" => style(&self.priority).yellow().to_string(),
-                "minor" => style(&self.priority).dim().to_string(),
-                "trivial" => style(&self.priority).dim().to_string(),
-                _ => self.priority.clone(),
-            }
-        } else {
-            self.priority.clone()
-        };
+impl TableRow for IssueListItem {
+    const COLUMNS: &'static [&'static str] = &[
+        "id", "state", "priority", "kind", "reporter", "assignee", "votes", "title",
+    ];
+    const DEFAULT_COLUMNS: &'static [&'static str] =
+        &["id", "state", "priority", "assignee", "title"];
+
+    fn cell(&self, column: &str) -> String {
+        match column {
+            "id" => format!("#{}", self.id),
+            "state" => self.state.clone(),
+            "priority" => self.priority.clone(),
+            "kind" => self.kind.clone(),
+            "reporter" => self.reporter.clone(),
+            "assignee" => self.assignee.clone().unwrap_or_else(|| "-".to_string()),
+            "votes" => self.votes.to_string(),
+            "title" => self.title.clone(),
+            _ => String::new(),
+        }
+    }
 
-        let assignee = self.assignee.as_deref().unwrap_or("-");
+    fn cell_color(&self, column: &str) -> Option<Color> {
+        match column {
+            "id" => Some(Color::Cyan),
+            "state" => theme().state_color(&self.state),
+            "priority" => match self.priority.as_str() {
+                "blocker" | "critical" => Some(Color::Red),
+                "major" => Some(Color::Yellow),
+                "minor" | "trivial" => Some(Color::DarkGrey),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
 
-        println!(
-            "{:<8} {:<12} {:<10} {:<10} {}",
-            id_display, state_display, priority_display, assignee, self.title
-        );
+impl TableOutput for IssueListItem {
+    fn print_table(&self, _color: bool) {
+        print_row(self);
     }
 
     fn print_markdown(&self) {
@@ -295,17 +300,13 @@ struct IssueDetail {
 impl TableOutput for IssueDetail {
     fn print_table(&self, color: bool) {
         let title = if color {
-            style(&self.title).bold().to_string()
+            theme().heading(&self.title)
         } else {
             self.title.clone()
         };
 
         let state_display = if color {
-            match self.state.as_str() {
-                "open" | "new" => style(&self.state).green().to_string(),
-                "resolved" | "closed" => style(&self.state).dim().to_string(),
-                _ => style(&self.state).yellow().to_string(),
-            }
+            theme().state(&self.state)
         } else {
             self.state.clone()
         };
@@ -313,7 +314,7 @@ impl TableOutput for IssueDetail {
         println!(
             "{} #{}",
             if color {
-                style("Issue").cyan().bold().to_string()
+                theme().emphasis("Issue")
             } else {
                 "Issue".to_string()
             },
@@ -340,7 +341,7 @@ impl TableOutput for IssueDetail {
             println!(
                 "{}",
                 if color {
-                    style("Description").bold().to_string()
+                    theme().heading("Description")
                 } else {
                     "Description".to_string()
                 }
@@ -388,7 +389,7 @@ struct CommentDisplay {
 impl TableOutput for CommentDisplay {
     fn print_table(&self, color: bool) {
         let author = if color {
-            style(&self.author).cyan().bold().to_string()
+            theme().emphasis(&self.author)
         } else {
             self.author.clone()
         };
@@ -483,52 +484,49 @@ impl IssueCommand {
     /// List issues
     async fn list(&self, args: &ListArgs, global: &GlobalOptions) -> Result<()> {
         let ctx = self.resolve_context(global)?;
+        require_json_fields(Issue::json_fields())?;
         let client = self.get_client(&ctx)?;
 
-        // Build query parameters
-        let mut query_parts = Vec::new();
-
+        let mut q = Bbql::new();
         if let Some(state) = &args.state {
-            query_parts.push(format!("state=\"{}\"", state));
+            q.equals("state", state);
         }
-
         if let Some(priority) = &args.priority {
-            query_parts.push(format!("priority=\"{}\"", priority));
+            q.equals("priority", priority);
         }
-
         if let Some(kind) = &args.kind {
-            query_parts.push(format!("kind=\"{}\"", kind));
+            q.equals("kind", kind);
         }
-
         if let Some(assignee) = &args.assignee {
-            query_parts.push(format!("assignee.username=\"{}\"", assignee));
+            q.equals("assignee.username", assignee);
         }
-
         if let Some(reporter) = &args.reporter {
-            query_parts.push(format!("reporter.username=\"{}\"", reporter));
+            q.equals("reporter.username", reporter);
         }
-
         if let Some(search) = &args.search {
-            query_parts.push(format!("title~\"{}\"", search));
+            q.contains("title", search);
         }
 
-        let mut url = format!(
-            "/repositories/{}/{}/issues?pagelen={}",
-            ctx.owner, ctx.repo_slug, args.limit
-        );
+        let mut query = QueryParams::new();
+        query.push("pagelen", args.limit).push_opt("q", q.build());
 
-        if !query_parts.is_empty() {
-            url = format!("{}&q={}", url, query_parts.join(" AND "));
-        }
+        let url = format!(
+            "/repositories/{}/{}/issues?{}",
+            ctx.owner, ctx.repo_slug, query
+        );
 
         let response: PaginatedResponse<Issue> = client.get(&url).await?;
 
+        if global.json {
+            return write_json(&response.values);
+        }
+
         let items: Vec<IssueListItem> = response
             .values
             .into_iter()
             .map(|issue| IssueListItem {
                 id: issue.id,
-                title: truncate(&issue.title, 50),
+                title: issue.title,
                 state: issue.state,
                 priority: issue.priority,
                 kind: issue.kind,
@@ -545,22 +543,15 @@ impl IssueCommand {
 
         let writer = OutputWriter::new(self.get_format(global));
 
-        if !global.json {
+        let decorate = !global.json && stdout_is_tty();
+
+        if decorate {
             println!();
-            println!(
-                "{} {} {} {} {}",
-                style(format!("{:<8}", "ID")).bold(),
-                style(format!("{:<12}", "STATE")).bold(),
-                style(format!("{:<10}", "PRIORITY")).bold(),
-                style(format!("{:<10}", "ASSIGNEE")).bold(),
-                style("TITLE").bold()
-            );
-            println!("{}", "-".repeat(80));
         }
 
-        writer.write_list(&items)?;
+        writer.write_table(&items)?;
 
-        if !global.json {
+        if decorate {
             println!();
             println!("Showing {} issue(s)", items.len());
         }
@@ -571,6 +562,7 @@ impl IssueCommand {
     /// View an issue
     async fn view(&self, args: &ViewArgs, global: &GlobalOptions) -> Result<()> {
         let ctx = self.resolve_context(global)?;
+        require_json_fields(Issue::json_fields())?;
 
         let url = format!(
             "https://bitbucket.org/{}/{}/issues/{}",
@@ -580,7 +572,7 @@ impl IssueCommand {
         if args.web {
             println!(
                 "{} Opening issue #{} in browser...",
-                style("→").cyan(),
+                theme().info_mark(),
                 args.id
             );
             open_browser(&url)?;
@@ -596,6 +588,10 @@ impl IssueCommand {
 
         let issue: Issue = client.get(&api_url).await?;
 
+        if global.json {
+            return write_json(&issue);
+        }
+
         let detail = IssueDetail {
             id: issue.id,
             title: issue.title,
@@ -626,7 +622,7 @@ impl IssueCommand {
 
             if !comments_response.values.is_empty() {
                 println!();
-                println!("{}", style("Comments").bold());
+                println!("{}", theme().heading("Comments"));
                 println!("{}", "-".repeat(60));
 
                 for comment in comments_response.values {
@@ -695,13 +691,13 @@ impl IssueCommand {
                 "state": issue.state,
                 "url": issue_url,
             });
-            println!("{}", serde_json::to_string_pretty(&result)?);
+            write_json(&result)?;
         } else {
             println!();
             println!(
                 "{} Created issue #{}",
-                style("✓").green(),
-                style(issue.id).cyan().bold()
+                theme().success_mark(),
+                theme().emphasis(issue.id)
             );
             println!();
             println!("  Title: {}", issue.title);
@@ -745,12 +741,12 @@ impl IssueCommand {
                 "title": issue.title,
                 "state": issue.state,
             });
-            println!("{}", serde_json::to_string_pretty(&result)?);
+            write_json(&result)?;
         } else {
             println!(
                 "{} Updated issue #{}",
-                style("✓").green(),
-                style(issue.id).cyan().bold()
+                theme().success_mark(),
+                theme().emphasis(issue.id)
             );
         }
 
@@ -798,12 +794,12 @@ impl IssueCommand {
                 "id": issue.id,
                 "state": issue.state,
             });
-            println!("{}", serde_json::to_string_pretty(&result)?);
+            write_json(&result)?;
         } else {
             println!(
                 "{} Closed issue #{}",
-                style("✓").green(),
-                style(issue.id).cyan().bold()
+                theme().success_mark(),
+                theme().emphasis(issue.id)
             );
         }
 
@@ -837,12 +833,12 @@ impl IssueCommand {
                 "id": issue.id,
                 "state": issue.state,
             });
-            println!("{}", serde_json::to_string_pretty(&result)?);
+            write_json(&result)?;
         } else {
             println!(
                 "{} Reopened issue #{}",
-                style("✓").green(),
-                style(issue.id).cyan().bold()
+                theme().success_mark(),
+                theme().emphasis(issue.id)
             );
         }
 
@@ -882,12 +878,12 @@ impl IssueCommand {
                 "issue_id": args.id,
                 "comment_id": comment.id,
             });
-            println!("{}", serde_json::to_string_pretty(&result)?);
+            write_json(&result)?;
         } else {
             println!(
                 "{} Added comment to issue #{}",
-                style("✓").green(),
-                style(args.id).cyan().bold()
+                theme().success_mark(),
+                theme().emphasis(args.id)
             );
         }
 
@@ -924,12 +920,12 @@ impl IssueCommand {
                 "id": args.id,
                 "deleted": true,
             });
-            println!("{}", serde_json::to_string_pretty(&result)?);
+            write_json(&result)?;
         } else {
             println!(
                 "{} Deleted issue #{}",
-                style("✓").green(),
-                style(args.id).cyan().bold()
+                theme().success_mark(),
+                theme().emphasis(args.id)
             );
         }
 
@@ -939,14 +935,6 @@ impl IssueCommand {
 
 // Helper functions
 
-fn truncate(s: &str, max_len: usize) -> String {
-    if s.len() <= max_len {
-        s.to_string()
-    } else {
-        format!("{}...", &s[..max_len - 3])
-    }
-}
-
 fn format_timestamp(ts: &str) -> String {
     // Parse ISO 8601 and format nicely
     chrono::DateTime::parse_from_rfc3339(ts)
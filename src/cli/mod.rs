@@ -35,7 +35,7 @@ pub use browse::BrowseCommand;
 pub use completion::CompletionCommand;
 pub use config::ConfigCommand;
 pub use deploy::DeployCommand;
-pub use extension::ExtensionCommand;
+pub use extension::{command_with_extensions, run_external, with_extension_help, ExtensionCommand};
 pub use issue::IssueCommand;
 pub use pipeline::PipelineCommand;
 pub use pr::PrCommand;
@@ -46,6 +46,8 @@ pub use ssh_key::SshKeyCommand;
 pub use webhook::WebhookCommand;
 pub use workspace::WorkspaceCommand;
 
+use std::ffi::OsString;
+
 use clap::{Parser, Subcommand};
 
 /// Bitbucket CLI - Work with Bitbucket from the command line
@@ -74,25 +76,73 @@ pub struct GlobalOptions {
     #[arg(long, short = 'R', global = true, env = "BB_REPO")]
     pub repo: Option<String>,
 
-    /// Workspace for the operation (Cloud)
-    #[arg(long, short = 'w', global = true, env = "BB_WORKSPACE")]
+    /// Workspace for the operation (Cloud); also `-w` unless the command has its own `-w`
+    #[arg(long, global = true, env = "BB_WORKSPACE")]
     pub workspace: Option<String>,
 
-    /// Project key for the operation (Server/DC)
-    #[arg(long, short = 'p', global = true, env = "BB_PROJECT")]
+    /// Project key for the operation (Server/DC); also `-p` unless the command has its own `-p`
+    #[arg(long, global = true, env = "BB_PROJECT")]
     pub project: Option<String>,
 
     /// Bitbucket host (for Server/DC)
     #[arg(long, global = true, env = "BB_HOST")]
     pub host: Option<String>,
 
-    /// Output format as JSON
-    #[arg(long, global = true)]
+    /// Output JSON, optionally limited to comma-separated FIELDS
+    #[arg(
+        long = "json",
+        global = true,
+        value_name = "FIELDS",
+        num_args = 0..=1,
+        default_missing_value = ""
+    )]
+    pub json_fields: Option<String>,
+
+    /// Whether JSON output was requested (set from `--json` or `--jq`)
+    #[arg(skip)]
     pub json: bool,
 
+    /// Filter JSON output with a jq expression (implies --json)
+    #[arg(long, global = true, value_name = "EXPR")]
+    pub jq: Option<String>,
+
+    /// Format JSON output with a Go-style template (implies --json)
+    #[arg(long, global = true, value_name = "TEMPLATE", conflicts_with = "jq")]
+    pub template: Option<String>,
+
+    /// Output format for data: json, csv, tsv, yaml or ndjson
+    #[arg(
+        long,
+        global = true,
+        value_name = "FORMAT",
+        value_parser = ["json", "csv", "tsv", "yaml", "ndjson"],
+        conflicts_with_all = ["jq", "template"]
+    )]
+    pub format: Option<String>,
+
+    /// Table columns to show, comma-separated (list commands)
+    #[arg(long, global = true, value_name = "COLUMNS")]
+    pub columns: Option<String>,
+
+    /// Sort table rows by a column (list commands)
+    #[arg(long, global = true, value_name = "COLUMN")]
+    pub sort: Option<String>,
+
+    /// Reverse the order of table rows (list commands)
+    #[arg(long, global = true)]
+    pub reverse: bool,
+
     /// Disable interactive prompts
     #[arg(long, global = true, env = "BB_NO_PROMPT")]
     pub no_prompt: bool,
+
+    /// When to use color: auto, always or never
+    #[arg(long, global = true, value_name = "WHEN", value_parser = ["auto", "always", "never"])]
+    pub color: Option<String>,
+
+    /// Print long output directly instead of through a pager
+    #[arg(long, global = true, env = "BB_NO_PAGER")]
+    pub no_pager: bool,
 }
 
 /// Top-level commands
@@ -160,4 +210,102 @@ pub enum Commands {
 
     /// Print version information
     Version,
+
+    /// Run an installed `bb-<name>` extension
+    #[command(external_subcommand)]
+    External(Vec<String>),
+}
+
+/// Global options that also have a short form, as `(short, long)`.
+///
+/// Several subcommands use `-w` and `-p` for their own flags, such as
+/// `pr view -w` (`--web`) and `issue list -p` (`--priority`). Clap does not
+/// allow a global short flag to clash with those, so the short forms are
+/// not declared; [`expand_global_shorts`] maps them to the long option for
+/// every other command.
+const GLOBAL_SHORTS: &[(char, &str)] = &[('w', "--workspace"), ('p', "--project")];
+
+/// Rewrites `-w` and `-p` to `--workspace` and `--project`, unless the
+/// command being run defines its own `-w` or `-p`.
+///
+/// # Parameters
+///
+/// * `command` - The `bb` command definition
+/// * `args` - The full command line, including the program name
+///
+/// # Returns
+///
+/// The command line with global short flags spelled out. Arguments after
+/// `--` are left alone.
+pub fn expand_global_shorts(command: &clap::Command, args: Vec<OsString>) -> Vec<OsString> {
+    // Flags belong to the command named last before them, so follow the
+    // subcommand names while rewriting
+    let mut current = command;
+    let mut after_separator = false;
+
+    args.into_iter()
+        .enumerate()
+        .map(|(i, arg)| {
+            let Some(text) = arg.to_str().filter(|_| i > 0 && !after_separator) else {
+                return arg;
+            };
+            if text == "--" {
+                after_separator = true;
+                return arg;
+            }
+            if let Some(sub) = current.find_subcommand(text) {
+                current = sub;
+                return arg;
+            }
+
+            for (short, long) in GLOBAL_SHORTS {
+                let Some(rest) = text.strip_prefix(&format!("-{}", short)) else {
+                    continue;
+                };
+                if current
+                    .get_arguments()
+                    .any(|a| a.get_short() == Some(*short))
+                {
+                    break;
+                }
+                return match rest.strip_prefix('=').unwrap_or(rest) {
+                    "" => OsString::from(*long),
+                    value => OsString::from(format!("{}={}", long, value)),
+                };
+            }
+            arg
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn test_cli_definition_is_valid() {
+        Cli::command().debug_assert();
+    }
+
+    fn parse(args: &[&str]) -> Cli {
+        let args = args.iter().map(OsString::from).collect();
+        Cli::try_parse_from(expand_global_shorts(&Cli::command(), args)).unwrap()
+    }
+
+    #[test]
+    fn test_global_short_flags_yield_to_command_flags() {
+        let cli = parse(&["bb", "-w", "acme", "pr", "list"]);
+        assert_eq!(cli.global.workspace.as_deref(), Some("acme"));
+
+        let cli = parse(&["bb", "pipeline", "list", "-wacme", "-p", "PROJ"]);
+        assert_eq!(cli.global.workspace.as_deref(), Some("acme"));
+        assert_eq!(cli.global.project.as_deref(), Some("PROJ"));
+
+        // `pr view -w` is `--web`, and `issue list -p` is `--priority`
+        let cli = parse(&["bb", "pr", "view", "42", "-w"]);
+        assert_eq!(cli.global.workspace, None);
+        let cli = parse(&["bb", "issue", "list", "-p", "major"]);
+        assert_eq!(cli.global.project, None);
+    }
 }
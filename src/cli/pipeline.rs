@@ -18,7 +18,6 @@ use std::time::Duration;
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
 use clap::{Args, Subcommand};
-use console::style;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 
@@ -28,13 +27,26 @@ use crate::api::cloud::pipelines::{
 };
 use crate::api::common::PaginatedResponse;
 use crate::api::format_api_error;
+use crate::api::replay::ReplayExt;
 use crate::auth::KeyringStore;
 use crate::config::Config;
 use crate::context::{ContextResolver, HostType, RepoContext};
-use crate::output::{OutputFormat, OutputWriter, TableOutput};
+use crate::hooks::Hooks;
+use crate::output::{
+    print_row, require_json_fields, theme, write_json, write_json_compact, Color, JsonFields,
+    ListPages, OutputFormat, OutputWriter, TableOutput, TableRow,
+};
+use crate::util::{page_output, pager_enabled, print_unpaged};
 
 use super::GlobalOptions;
 
+/// The largest page Cloud returns for pipeline lists
+const CLOUD_PIPELINE_PAGELEN: u32 = 100;
+
+/// How many pages `pipeline list` reads looking for runs that match its
+/// filters
+const MAX_PIPELINE_PAGES: usize = 10;
+
 /// Manage pipelines (Cloud only).
 ///
 /// Bitbucket Pipelines is a CI/CD service built into Bitbucket Cloud.
@@ -274,39 +286,39 @@ struct PipelineListItem {
     duration_seconds: Option<u64>,
 }
 
-impl TableOutput for PipelineListItem {
-    fn print_table(&self, color: bool) {
-        let status = if let Some(ref result) = self.result {
-            let status_str = format!("{} ({})", self.state, result);
-            if color {
-                match result.to_uppercase().as_str() {
-                    "SUCCESSFUL" => style(status_str).green().to_string(),
-                    "FAILED" => style(status_str).red().to_string(),
-                    "STOPPED" => style(status_str).yellow().to_string(),
-                    _ => status_str,
-                }
-            } else {
-                status_str
-            }
-        } else if color && self.state.to_uppercase() == "IN_PROGRESS" {
-            style(&self.state).cyan().to_string()
-        } else {
-            self.state.clone()
-        };
+impl TableRow for PipelineListItem {
+    const COLUMNS: &'static [&'static str] =
+        &["number", "status", "branch", "created_on", "duration"];
+    const DEFAULT_COLUMNS: &'static [&'static str] = Self::COLUMNS;
+
+    fn cell(&self, column: &str) -> String {
+        match column {
+            "number" => format!("#{}", self.build_number),
+            "status" => match self.result {
+                Some(ref result) => format!("{} ({})", self.state, result),
+                None => self.state.clone(),
+            },
+            "branch" => self.branch.clone(),
+            "created_on" => format_timestamp(&self.created_on),
+            "duration" => self
+                .duration_seconds
+                .map(format_duration)
+                .unwrap_or_else(|| "-".to_string()),
+            _ => String::new(),
+        }
+    }
 
-        let duration = self
-            .duration_seconds
-            .map(format_duration)
-            .unwrap_or_else(|| "-".to_string());
+    fn cell_color(&self, column: &str) -> Option<Color> {
+        if column != "status" {
+            return None;
+        }
+        theme().state_color(self.result.as_deref().unwrap_or(&self.state))
+    }
+}
 
-        println!(
-            "#{:<6} {:20} {:20} {:15} {}",
-            self.build_number,
-            status,
-            self.branch,
-            format_timestamp(&self.created_on),
-            duration
-        );
+impl TableOutput for PipelineListItem {
+    fn print_table(&self, _color: bool) {
+        print_row(self);
     }
 
     fn print_markdown(&self) {
@@ -334,19 +346,31 @@ struct PipelineCache {
     file_size_bytes: Option<u64>,
 }
 
-impl TableOutput for PipelineCache {
-    fn print_table(&self, _color: bool) {
-        println!(
-            "{:30} {:15} {}",
-            self.name,
-            self.file_size_bytes
+impl TableRow for PipelineCache {
+    const COLUMNS: &'static [&'static str] = &["uuid", "name", "size", "created_on"];
+    const DEFAULT_COLUMNS: &'static [&'static str] = &["name", "size", "created_on"];
+
+    fn cell(&self, column: &str) -> String {
+        match column {
+            "uuid" => self.uuid.clone(),
+            "name" => self.name.clone(),
+            "size" => self
+                .file_size_bytes
                 .map(format_bytes)
                 .unwrap_or_else(|| "-".to_string()),
-            self.created_on
+            "created_on" => self
+                .created_on
                 .as_ref()
                 .map(|c| format_timestamp(c))
-                .unwrap_or_else(|| "-".to_string())
-        );
+                .unwrap_or_else(|| "-".to_string()),
+            _ => String::new(),
+        }
+    }
+}
+
+impl TableOutput for PipelineCache {
+    fn print_table(&self, _color: bool) {
+        print_row(self);
     }
 
     fn print_markdown(&self) {
@@ -391,35 +415,49 @@ struct ScheduleSelector {
     pattern: Option<String>,
 }
 
-impl TableOutput for PipelineSchedule {
-    fn print_table(&self, color: bool) {
-        let enabled = if self.enabled {
-            if color {
-                style("Yes").green().to_string()
-            } else {
-                "Yes".to_string()
-            }
-        } else if color {
-            style("No").dim().to_string()
-        } else {
-            "No".to_string()
-        };
+impl TableRow for PipelineSchedule {
+    const COLUMNS: &'static [&'static str] =
+        &["id", "enabled", "cron", "branch", "pipeline", "created_on"];
+    const DEFAULT_COLUMNS: &'static [&'static str] =
+        &["id", "enabled", "cron", "branch", "pipeline"];
+
+    fn cell(&self, column: &str) -> String {
+        match column {
+            "id" => truncate_uuid(&self.uuid),
+            "enabled" => if self.enabled { "Yes" } else { "No" }.to_string(),
+            "cron" => self.cron_pattern.clone(),
+            "branch" => self
+                .target
+                .ref_name
+                .clone()
+                .unwrap_or_else(|| "-".to_string()),
+            "pipeline" => self
+                .target
+                .selector
+                .as_ref()
+                .and_then(|s| s.pattern.clone())
+                .unwrap_or_else(|| "default".to_string()),
+            "created_on" => self
+                .created_on
+                .as_ref()
+                .map(|c| format_timestamp(c))
+                .unwrap_or_else(|| "-".to_string()),
+            _ => String::new(),
+        }
+    }
 
-        let pipeline = self
-            .target
-            .selector
-            .as_ref()
-            .and_then(|s| s.pattern.clone())
-            .unwrap_or_else(|| "default".to_string());
+    fn cell_color(&self, column: &str) -> Option<Color> {
+        match column {
+            "enabled" if self.enabled => Some(Color::Green),
+            "enabled" => Some(Color::DarkGrey),
+            _ => None,
+        }
+    }
+}
 
-        println!(
-            "{:12} {:8} {:20} {:20} {}",
-            truncate_uuid(&self.uuid),
-            enabled,
-            self.cron_pattern,
-            self.target.ref_name.as_deref().unwrap_or("-"),
-            pipeline
-        );
+impl TableOutput for PipelineSchedule {
+    fn print_table(&self, _color: bool) {
+        print_row(self);
     }
 
     fn print_markdown(&self) {
@@ -457,31 +495,37 @@ struct RunnerState {
     status: String,
 }
 
-impl TableOutput for PipelineRunner {
-    fn print_table(&self, color: bool) {
-        let status = self
-            .state
-            .as_ref()
-            .map(|s| s.status.clone())
-            .unwrap_or_else(|| "UNKNOWN".to_string());
+impl TableRow for PipelineRunner {
+    const COLUMNS: &'static [&'static str] = &["id", "name", "status", "labels"];
+    const DEFAULT_COLUMNS: &'static [&'static str] = Self::COLUMNS;
 
-        let status_display = if color {
-            match status.to_uppercase().as_str() {
-                "ONLINE" => style(&status).green().to_string(),
-                "OFFLINE" => style(&status).red().to_string(),
-                _ => status.clone(),
-            }
-        } else {
-            status.clone()
-        };
+    fn cell(&self, column: &str) -> String {
+        match column {
+            "id" => truncate_uuid(&self.uuid),
+            "name" => self.name.clone(),
+            "status" => self
+                .state
+                .as_ref()
+                .map(|s| s.status.clone())
+                .unwrap_or_else(|| "UNKNOWN".to_string()),
+            "labels" => self.labels.join(", "),
+            _ => String::new(),
+        }
+    }
 
-        println!(
-            "{:12} {:30} {:15} {}",
-            truncate_uuid(&self.uuid),
-            self.name,
-            status_display,
-            self.labels.join(", ")
-        );
+    fn cell_color(&self, column: &str) -> Option<Color> {
+        if column != "status" {
+            return None;
+        }
+        self.state
+            .as_ref()
+            .and_then(|s| theme().state_color(&s.status))
+    }
+}
+
+impl TableOutput for PipelineRunner {
+    fn print_table(&self, _color: bool) {
+        print_row(self);
     }
 
     fn print_markdown(&self) {
@@ -607,78 +651,103 @@ impl PipelineCommand {
     /// List pipeline runs
     async fn list(&self, args: &ListArgs, global: &GlobalOptions) -> Result<()> {
         let context = self.get_cloud_context(global)?;
+        require_json_fields(Pipeline::json_fields())?;
         let token = self.get_token(&context)?;
         let client = self.create_client()?;
 
-        // Build URL with query parameters
-        let url = format!(
+        let matches = |p: &Pipeline| {
+            // Filter by branch
+            if let Some(ref branch) = args.branch {
+                if let Some(ref ref_name) = p.target.ref_name {
+                    if ref_name != branch {
+                        return false;
+                    }
+                }
+            }
+            // Filter by status
+            if let Some(ref status) = args.status {
+                let matches = match status.as_str() {
+                    "pending" => p.state.state_type == "pipeline_state_pending",
+                    "in_progress" => p.state.state_type == "pipeline_state_in_progress",
+                    "successful" => p
+                        .state
+                        .result
+                        .as_ref()
+                        .map(|r| r.result_type == "pipeline_state_completed_successful")
+                        .unwrap_or(false),
+                    "failed" => p
+                        .state
+                        .result
+                        .as_ref()
+                        .map(|r| r.result_type == "pipeline_state_completed_failed")
+                        .unwrap_or(false),
+                    "stopped" => p
+                        .state
+                        .result
+                        .as_ref()
+                        .map(|r| r.result_type == "pipeline_state_completed_stopped")
+                        .unwrap_or(false),
+                    _ => true,
+                };
+                if !matches {
+                    return false;
+                }
+            }
+            true
+        };
+
+        // Pipelines are filtered locally, so page on until `--limit` match
+        let mut pipelines = ListPages::new(global.json);
+        let mut pages = 0;
+        let mut next = Some(format!(
             "https://api.bitbucket.org/2.0/repositories/{}/{}/pipelines/?sort=-created_on&pagelen={}",
-            context.owner, context.repo_slug, args.limit
-        );
+            context.owner,
+            context.repo_slug,
+            args.limit.min(CLOUD_PIPELINE_PAGELEN)
+        ));
+        while let Some(url) = next.take() {
+            let response = client
+                .get(&url)
+                .bearer_auth(&token)
+                .send_replayable()
+                .await
+                .context("Failed to fetch pipelines")?;
 
-        let response = client
-            .get(&url)
-            .bearer_auth(&token)
-            .send()
-            .await
-            .context("Failed to fetch pipelines")?;
+            if !response.status().is_success() {
+                let status = response.status();
+                let body = response.text().await.unwrap_or_default();
+                return Err(format_api_error(status, &body));
+            }
 
-        if !response.status().is_success() {
-            let status = response.status();
-            let body = response.text().await.unwrap_or_default();
-            return Err(format_api_error(status, &body));
+            let paginated: PaginatedResponse<Pipeline> = response
+                .json()
+                .await
+                .context("Failed to parse pipelines response")?;
+
+            let mut page: Vec<Pipeline> = paginated.values.into_iter().filter(matches).collect();
+            page.truncate(args.limit as usize - pipelines.count());
+            pipelines.push(page)?;
+            pages += 1;
+            if pipelines.count() < args.limit as usize {
+                if pages == MAX_PIPELINE_PAGES && paginated.next.is_some() {
+                    eprintln!(
+                        "{} Stopping after {} pages of pipelines",
+                        theme().warning_mark(),
+                        MAX_PIPELINE_PAGES
+                    );
+                } else {
+                    next = paginated.next;
+                }
+            }
         }
 
-        let paginated: PaginatedResponse<Pipeline> = response
-            .json()
-            .await
-            .context("Failed to parse pipelines response")?;
+        if global.json {
+            return pipelines.finish();
+        }
 
-        // Filter results
-        let items: Vec<PipelineListItem> = paginated
-            .values
+        let items: Vec<PipelineListItem> = pipelines
+            .into_items()
             .into_iter()
-            .filter(|p| {
-                // Filter by branch
-                if let Some(ref branch) = args.branch {
-                    if let Some(ref ref_name) = p.target.ref_name {
-                        if ref_name != branch {
-                            return false;
-                        }
-                    }
-                }
-                // Filter by status
-                if let Some(ref status) = args.status {
-                    let matches = match status.as_str() {
-                        "pending" => p.state.state_type == "pipeline_state_pending",
-                        "in_progress" => p.state.state_type == "pipeline_state_in_progress",
-                        "successful" => p
-                            .state
-                            .result
-                            .as_ref()
-                            .map(|r| r.result_type == "pipeline_state_completed_successful")
-                            .unwrap_or(false),
-                        "failed" => p
-                            .state
-                            .result
-                            .as_ref()
-                            .map(|r| r.result_type == "pipeline_state_completed_failed")
-                            .unwrap_or(false),
-                        "stopped" => p
-                            .state
-                            .result
-                            .as_ref()
-                            .map(|r| r.result_type == "pipeline_state_completed_stopped")
-                            .unwrap_or(false),
-                        _ => true,
-                    };
-                    if !matches {
-                        return false;
-                    }
-                }
-                true
-            })
-            .take(args.limit as usize)
             .map(|p| PipelineListItem {
                 build_number: p.build_number,
                 state: p.state.name.clone(),
@@ -697,15 +766,7 @@ impl PipelineCommand {
         let format = self.get_format(global);
         let writer = OutputWriter::new(format);
 
-        if !global.json {
-            println!(
-                "{:<7} {:20} {:20} {:15} DURATION",
-                "#", "STATUS", "BRANCH", "CREATED"
-            );
-            println!("{}", "-".repeat(80));
-        }
-
-        writer.write_list(&items)?;
+        writer.write_table(&items)?;
 
         Ok(())
     }
@@ -713,6 +774,9 @@ impl PipelineCommand {
     /// View pipeline details
     async fn view(&self, args: &ViewArgs, global: &GlobalOptions) -> Result<()> {
         let context = self.get_cloud_context(global)?;
+        let mut fields = Pipeline::json_fields().to_vec();
+        fields.push("steps");
+        require_json_fields(&fields)?;
         let token = self.get_token(&context)?;
         let client = self.create_client()?;
 
@@ -738,7 +802,7 @@ impl PipelineCommand {
         let response = client
             .get(&pipeline_url)
             .bearer_auth(&token)
-            .send()
+            .send_replayable()
             .await
             .context("Failed to fetch pipeline")?;
 
@@ -761,7 +825,11 @@ impl PipelineCommand {
             format_pipeline_id(&args.id)
         );
 
-        let steps: Vec<PipelineStep> = match client.get(&steps_url).bearer_auth(&token).send().await
+        let steps: Vec<PipelineStep> = match client
+            .get(&steps_url)
+            .bearer_auth(&token)
+            .send_replayable()
+            .await
         {
             Ok(resp) if resp.status().is_success() => {
                 let paginated: PaginatedResponse<PipelineStep> =
@@ -779,29 +847,9 @@ impl PipelineCommand {
         };
 
         if global.json {
-            let output = serde_json::json!({
-                "uuid": pipeline.uuid,
-                "build_number": pipeline.build_number,
-                "state": pipeline.state.name,
-                "result": pipeline.state.result.as_ref().map(|r| &r.name),
-                "target": {
-                    "ref_type": pipeline.target.ref_type,
-                    "ref_name": pipeline.target.ref_name,
-                },
-                "created_on": pipeline.created_on,
-                "completed_on": pipeline.completed_on,
-                "duration_seconds": pipeline.duration_in_seconds,
-                "steps": steps.iter().map(|s| {
-                    serde_json::json!({
-                        "uuid": s.uuid,
-                        "name": s.name,
-                        "state": s.state.name,
-                        "result": s.state.result.as_ref().map(|r| &r.name),
-                        "duration_seconds": s.duration_in_seconds,
-                    })
-                }).collect::<Vec<_>>(),
-            });
-            println!("{}", serde_json::to_string_pretty(&output)?);
+            let mut output = serde_json::to_value(&pipeline)?;
+            output["steps"] = serde_json::to_value(&steps)?;
+            write_json(&output)?;
         } else {
             // Display pipeline info
             let status = if let Some(ref result) = pipeline.state.result {
@@ -833,12 +881,7 @@ impl PipelineCommand {
             println!("\nSteps:");
             for step in &steps {
                 let step_status = if let Some(ref result) = step.state.result {
-                    let symbol = match result.result_type.as_str() {
-                        "pipeline_state_completed_successful" => style("[OK]").green(),
-                        "pipeline_state_completed_failed" => style("[FAILED]").red(),
-                        "pipeline_state_completed_stopped" => style("[STOPPED]").yellow(),
-                        _ => style("[?]").dim(),
-                    };
+                    let symbol = step_result_symbol(Some(&result.result_type));
                     format!("{} {}", symbol, result.name)
                 } else {
                     format!("[{}]", step.state.name)
@@ -908,6 +951,14 @@ impl PipelineCommand {
             variables,
         };
 
+        let hooks = Hooks::load("pipeline run", &context, global.json).await?;
+        let operation = serde_json::json!({
+            "branch": branch,
+            "custom": args.custom,
+            "variables": request.variables.iter().map(|v| &v.key).collect::<Vec<_>>(),
+        });
+        hooks.run_pre(&operation)?;
+
         let url = format!(
             "https://api.bitbucket.org/2.0/repositories/{}/{}/pipelines/",
             context.owner, context.repo_slug
@@ -917,7 +968,7 @@ impl PipelineCommand {
             .post(&url)
             .bearer_auth(&token)
             .json(&request)
-            .send()
+            .send_replayable()
             .await
             .context("Failed to trigger pipeline")?;
 
@@ -932,13 +983,21 @@ impl PipelineCommand {
             .await
             .context("Failed to parse pipeline response")?;
 
+        hooks.run_post(
+            &operation,
+            &serde_json::json!({
+                "build_number": pipeline.build_number,
+                "uuid": pipeline.uuid,
+            }),
+        );
+
         if global.json {
             let output = serde_json::json!({
                 "build_number": pipeline.build_number,
                 "uuid": pipeline.uuid,
                 "branch": branch,
             });
-            println!("{}", serde_json::to_string_pretty(&output)?);
+            write_json(&output)?;
         } else {
             println!(
                 "Triggered pipeline #{} on branch '{}'",
@@ -976,7 +1035,7 @@ impl PipelineCommand {
         let response = client
             .post(&url)
             .bearer_auth(&token)
-            .send()
+            .send_replayable()
             .await
             .context("Failed to stop pipeline")?;
 
@@ -1012,7 +1071,7 @@ impl PipelineCommand {
         let response = client
             .get(&pipeline_url)
             .bearer_auth(&token)
-            .send()
+            .send_replayable()
             .await
             .context("Failed to fetch pipeline")?;
 
@@ -1061,7 +1120,7 @@ impl PipelineCommand {
             .post(&url)
             .bearer_auth(&token)
             .json(&request)
-            .send()
+            .send_replayable()
             .await
             .context("Failed to trigger pipeline rerun")?;
 
@@ -1082,7 +1141,7 @@ impl PipelineCommand {
                 "new_build_number": new_pipeline.build_number,
                 "uuid": new_pipeline.uuid,
             });
-            println!("{}", serde_json::to_string_pretty(&output)?);
+            write_json(&output)?;
         } else {
             println!(
                 "Rerunning pipeline {} as #{}",
@@ -1114,7 +1173,7 @@ impl PipelineCommand {
         let response = client
             .get(&steps_url)
             .bearer_auth(&token)
-            .send()
+            .send_replayable()
             .await
             .context("Failed to fetch pipeline steps")?;
 
@@ -1155,6 +1214,19 @@ impl PipelineCommand {
             return Ok(());
         }
 
+        // Logs are paged as a whole on a terminal; otherwise each step is
+        // printed as soon as its log arrives
+        let paged = !global.json && pager_enabled();
+        let mut content = String::new();
+        let mut emit = |text: &str| -> Result<()> {
+            if paged {
+                content.push_str(text);
+                Ok(())
+            } else {
+                print_unpaged(text)
+            }
+        };
+
         // Fetch logs for each step
         for step in &steps {
             let log_url = format!(
@@ -1166,10 +1238,15 @@ impl PipelineCommand {
             );
 
             if !global.json {
-                println!("\n=== Step: {} ===\n", step.name);
+                emit(&format!("\n=== Step: {} ===\n\n", step.name))?;
             }
 
-            match client.get(&log_url).bearer_auth(&token).send().await {
+            match client
+                .get(&log_url)
+                .bearer_auth(&token)
+                .send_replayable()
+                .await
+            {
                 Ok(resp) if resp.status().is_success() => {
                     let log_text = resp
                         .text()
@@ -1180,24 +1257,29 @@ impl PipelineCommand {
                             "step": step.name,
                             "log": log_text,
                         });
-                        println!("{}", serde_json::to_string(&output)?);
+                        write_json_compact(&output)?;
                     } else {
-                        println!("{}", log_text);
+                        emit(&log_text)?;
+                        emit("\n")?;
                     }
                 }
                 Ok(resp) => {
                     if !global.json {
-                        println!("(logs not available: {})", resp.status());
+                        emit(&format!("(logs not available: {})\n", resp.status()))?;
                     }
                 }
                 Err(e) => {
                     if !global.json {
-                        println!("(failed to fetch logs: {})", e);
+                        emit(&format!("(failed to fetch logs: {})\n", e))?;
                     }
                 }
             }
         }
 
+        if paged {
+            page_output(&content)?;
+        }
+
         Ok(())
     }
 
@@ -1235,7 +1317,7 @@ impl PipelineCommand {
             let response = client
                 .get(&pipeline_url)
                 .bearer_auth(token)
-                .send()
+                .send_replayable()
                 .await
                 .context("Failed to fetch pipeline")?;
 
@@ -1258,22 +1340,26 @@ impl PipelineCommand {
                 format_pipeline_id(id)
             );
 
-            let steps: Vec<PipelineStep> =
-                match client.get(&steps_url).bearer_auth(token).send().await {
-                    Ok(resp) if resp.status().is_success() => {
-                        let paginated: PaginatedResponse<PipelineStep> =
-                            resp.json().await.unwrap_or_else(|_| PaginatedResponse {
-                                values: vec![],
-                                next: None,
-                                previous: None,
-                                size: None,
-                                page: None,
-                                pagelen: None,
-                            });
-                        paginated.values
-                    }
-                    _ => vec![],
-                };
+            let steps: Vec<PipelineStep> = match client
+                .get(&steps_url)
+                .bearer_auth(token)
+                .send_replayable()
+                .await
+            {
+                Ok(resp) if resp.status().is_success() => {
+                    let paginated: PaginatedResponse<PipelineStep> =
+                        resp.json().await.unwrap_or_else(|_| PaginatedResponse {
+                            values: vec![],
+                            next: None,
+                            previous: None,
+                            size: None,
+                            page: None,
+                            pagelen: None,
+                        });
+                    paginated.values
+                }
+                _ => vec![],
+            };
 
             // Display status
             let status = if let Some(ref result) = pipeline.state.result {
@@ -1295,17 +1381,12 @@ impl PipelineCommand {
 
             for step in &steps {
                 let symbol = match step.state.state_type.as_str() {
-                    "pipeline_state_pending" => style("[ ]").dim(),
-                    "pipeline_state_in_progress" => style("[*]").cyan(),
-                    "pipeline_state_completed" => {
-                        match step.state.result.as_ref().map(|r| r.result_type.as_str()) {
-                            Some("pipeline_state_completed_successful") => style("[OK]").green(),
-                            Some("pipeline_state_completed_failed") => style("[FAILED]").red(),
-                            Some("pipeline_state_completed_stopped") => style("[STOPPED]").yellow(),
-                            _ => style("[?]").dim(),
-                        }
-                    }
-                    _ => style("[?]").dim(),
+                    "pipeline_state_pending" => theme().paint_state("[ ]", "PENDING"),
+                    "pipeline_state_in_progress" => theme().paint_state("[*]", "IN_PROGRESS"),
+                    "pipeline_state_completed" => step_result_symbol(
+                        step.state.result.as_ref().map(|r| r.result_type.as_str()),
+                    ),
+                    _ => step_result_symbol(None),
                 };
 
                 let duration = step
@@ -1361,7 +1442,7 @@ impl PipelineCommand {
             .put(&url)
             .bearer_auth(&token)
             .json(&UpdatePipelinesConfig { enabled: true })
-            .send()
+            .send_replayable()
             .await
             .context("Failed to enable pipelines")?;
 
@@ -1401,7 +1482,7 @@ impl PipelineCommand {
             .put(&url)
             .bearer_auth(&token)
             .json(&UpdatePipelinesConfig { enabled: false })
-            .send()
+            .send_replayable()
             .await
             .context("Failed to disable pipelines")?;
 
@@ -1483,7 +1564,7 @@ impl PipelineCommand {
             let response = client
                 .get(&url)
                 .bearer_auth(&token)
-                .send()
+                .send_replayable()
                 .await
                 .context("Failed to fetch pipelines config")?;
 
@@ -1504,7 +1585,7 @@ impl PipelineCommand {
                     "enabled": enabled,
                     "local_config_exists": local_exists,
                 });
-                println!("{}", serde_json::to_string_pretty(&output)?);
+                write_json(&output)?;
             } else {
                 println!(
                     "Pipelines configuration for {}/{}:",
@@ -1542,7 +1623,7 @@ impl PipelineCommand {
                 let response = client
                     .get(&url)
                     .bearer_auth(&token)
-                    .send()
+                    .send_replayable()
                     .await
                     .context("Failed to fetch caches")?;
 
@@ -1565,12 +1646,7 @@ impl PipelineCommand {
                 let format = self.get_format(global);
                 let writer = OutputWriter::new(format);
 
-                if !global.json {
-                    println!("{:30} {:15} CREATED", "NAME", "SIZE");
-                    println!("{}", "-".repeat(60));
-                }
-
-                writer.write_list(&paginated.values)?;
+                writer.write_table(&paginated.values)?;
             }
 
             CacheSubcommand::Delete { name } => {
@@ -1582,7 +1658,7 @@ impl PipelineCommand {
                 let response = client
                     .delete(&url)
                     .bearer_auth(&token)
-                    .send()
+                    .send_replayable()
                     .await
                     .context("Failed to delete cache")?;
 
@@ -1609,7 +1685,7 @@ impl PipelineCommand {
                 let response = client
                     .get(&url)
                     .bearer_auth(&token)
-                    .send()
+                    .send_replayable()
                     .await
                     .context("Failed to fetch caches")?;
 
@@ -1640,7 +1716,12 @@ impl PipelineCommand {
                         context.owner, context.repo_slug, cache.name
                     );
 
-                    if let Ok(resp) = client.delete(&delete_url).bearer_auth(&token).send().await {
+                    if let Ok(resp) = client
+                        .delete(&delete_url)
+                        .bearer_auth(&token)
+                        .send_replayable()
+                        .await
+                    {
                         if resp.status().is_success() {
                             deleted += 1;
                         }
@@ -1674,7 +1755,7 @@ impl PipelineCommand {
                 let response = client
                     .get(&url)
                     .bearer_auth(&token)
-                    .send()
+                    .send_replayable()
                     .await
                     .context("Failed to fetch schedules")?;
 
@@ -1697,15 +1778,7 @@ impl PipelineCommand {
                 let format = self.get_format(global);
                 let writer = OutputWriter::new(format);
 
-                if !global.json {
-                    println!(
-                        "{:12} {:8} {:20} {:20} PIPELINE",
-                        "ID", "ENABLED", "CRON", "BRANCH"
-                    );
-                    println!("{}", "-".repeat(80));
-                }
-
-                writer.write_list(&paginated.values)?;
+                writer.write_table(&paginated.values)?;
             }
 
             ScheduleSubcommand::Create(args) => {
@@ -1735,7 +1808,7 @@ impl PipelineCommand {
                     .post(&url)
                     .bearer_auth(&token)
                     .json(&request)
-                    .send()
+                    .send_replayable()
                     .await
                     .context("Failed to create schedule")?;
 
@@ -1751,7 +1824,7 @@ impl PipelineCommand {
                     .context("Failed to parse schedule response")?;
 
                 if global.json {
-                    println!("{}", serde_json::to_string_pretty(&schedule)?);
+                    write_json(&schedule)?;
                 } else {
                     println!("Created schedule: {}", truncate_uuid(&schedule.uuid));
                     println!("  Cron: {}", args.cron);
@@ -1771,7 +1844,7 @@ impl PipelineCommand {
                 let response = client
                     .delete(&url)
                     .bearer_auth(&token)
-                    .send()
+                    .send_replayable()
                     .await
                     .context("Failed to delete schedule")?;
 
@@ -1798,7 +1871,7 @@ impl PipelineCommand {
                     .put(&url)
                     .bearer_auth(&token)
                     .json(&serde_json::json!({ "enabled": false }))
-                    .send()
+                    .send_replayable()
                     .await
                     .context("Failed to pause schedule")?;
 
@@ -1825,7 +1898,7 @@ impl PipelineCommand {
                     .put(&url)
                     .bearer_auth(&token)
                     .json(&serde_json::json!({ "enabled": true }))
-                    .send()
+                    .send_replayable()
                     .await
                     .context("Failed to resume schedule")?;
 
@@ -1863,7 +1936,7 @@ impl PipelineCommand {
                 let response = client
                     .get(&url)
                     .bearer_auth(&token)
-                    .send()
+                    .send_replayable()
                     .await
                     .context("Failed to fetch runners")?;
 
@@ -1886,12 +1959,7 @@ impl PipelineCommand {
                 let format = self.get_format(global);
                 let writer = OutputWriter::new(format);
 
-                if !global.json {
-                    println!("{:12} {:30} {:15} LABELS", "ID", "NAME", "STATUS");
-                    println!("{}", "-".repeat(80));
-                }
-
-                writer.write_list(&paginated.values)?;
+                writer.write_table(&paginated.values)?;
             }
 
             RunnerSubcommand::Register => {
@@ -1900,7 +1968,7 @@ impl PipelineCommand {
                         "message": "Manual registration required",
                         "documentation_url": "https://support.atlassian.com/bitbucket-cloud/docs/runners/"
                     });
-                    println!("{}", serde_json::to_string_pretty(&output)?);
+                    write_json(&output)?;
                 } else {
                     println!("To register a self-hosted runner:");
                     println!();
@@ -1923,7 +1991,7 @@ impl PipelineCommand {
                 let response = client
                     .delete(&url)
                     .bearer_auth(&token)
-                    .send()
+                    .send_replayable()
                     .await
                     .context("Failed to remove runner")?;
 
@@ -1991,6 +2059,16 @@ fn format_timestamp(ts: &str) -> String {
     }
 }
 
+/// Show a completed step's result as `[OK]`, `[FAILED]` or `[STOPPED]` in its theme color
+fn step_result_symbol(result_type: Option<&str>) -> String {
+    match result_type {
+        Some("pipeline_state_completed_successful") => theme().paint_state("[OK]", "SUCCESSFUL"),
+        Some("pipeline_state_completed_failed") => theme().paint_state("[FAILED]", "FAILED"),
+        Some("pipeline_state_completed_stopped") => theme().paint_state("[STOPPED]", "STOPPED"),
+        _ => theme().muted("[?]"),
+    }
+}
+
 fn format_duration(seconds: u64) -> String {
     if seconds < 60 {
         format!("{}s", seconds)
@@ -8,20 +8,40 @@
 
 use anyhow::{Context, Result};
 use clap::{Args, Subcommand};
-use console::style;
+use console::Term;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::process::Command;
 
 use super::GlobalOptions;
+use crate::api::cloud::pipelines::PipelineStep;
 use crate::api::cloud::pullrequests as cloud_prs;
+use crate::api::cloud::repositories::{BranchRestriction, BranchingModel};
 use crate::api::common::{PaginatedResponse, ServerPaginatedResponse};
+use crate::api::diff::{self, DiffFile, LineKind, Side};
 use crate::api::format_api_error;
+use crate::api::query::{Bbql, QueryParams};
+use crate::api::replay::ReplayExt;
+use crate::api::server::builds as server_builds;
+use crate::api::server::capabilities::{self, Capability};
 use crate::api::server::pullrequests as server_prs;
 use crate::auth::KeyringStore;
 use crate::config::Config;
 use crate::context::{ContextResolver, HostType, RepoContext};
-use crate::output::{print_field, print_header, OutputFormat, OutputWriter, TableOutput};
+use crate::exit_codes::{self, ExitError};
+use crate::hooks::Hooks;
+use crate::interactive::{
+    fuzzy_select, is_interactive, prompt_editor, prompt_input_optional, select,
+};
+use crate::output::{
+    format_field, format_header, print_row, render_rows, require_json_fields, stdout_is_tty,
+    template, theme, write_json, Color, JsonFields, ListPages, OutputFormat, OutputWriter,
+    TableOutput, TableRow, Theme,
+};
+use crate::review::{PendingComment, PendingReview};
+use crate::users::{ResolvedUser, UserResolver};
+use crate::util::{format_duration, page_output, parse_date, parse_duration};
 
 /// Manage pull requests
 #[derive(Args, Debug)]
@@ -71,33 +91,39 @@ pub enum PrSubcommand {
     /// Submit a review
     Review(ReviewArgs),
 
-    /// Add a comment to a pull request
+    /// Add, edit, delete or resolve pull request comments
     Comment(CommentArgs),
 
     /// List comments on a pull request
     Comments(CommentsArgs),
 
+    /// List, create, resolve or delete pull request tasks
+    Task(TaskCommand),
+
     /// Edit a pull request
     Edit(EditArgs),
 
-    /// Mark a pull request as ready for review
+    /// Mark a draft pull request as ready for review, or back to draft
     Ready(ReadyArgs),
 
+    /// Explain whether a pull request can be merged
+    Mergeability(MergeabilityArgs),
+
     /// View build status and checks
     Checks(ChecksArgs),
 }
 
-#[derive(Args, Debug)]
+#[derive(Args, Debug, Clone)]
 pub struct ListArgs {
     /// Filter by state
     #[arg(long, short = 's', value_parser = ["open", "merged", "declined", "superseded"])]
     pub state: Option<String>,
 
-    /// Filter by author
+    /// Filter by author: @username, email or display name
     #[arg(long, short = 'a')]
     pub author: Option<String>,
 
-    /// Filter by reviewer
+    /// Filter by reviewer: @username, email or display name
     #[arg(long)]
     pub reviewer: Option<String>,
 
@@ -124,6 +150,22 @@ pub struct ListArgs {
     /// Search in title and description
     #[arg(long, short = 'S')]
     pub search: Option<String>,
+
+    /// Only PRs created on or after this date (YYYY-MM-DD or RFC 3339)
+    #[arg(long, value_name = "DATE")]
+    pub created_after: Option<String>,
+
+    /// Only PRs updated on or after this date (YYYY-MM-DD or RFC 3339)
+    #[arg(long, value_name = "DATE")]
+    pub updated_after: Option<String>,
+
+    /// Only draft PRs
+    #[arg(long, conflicts_with = "no_draft")]
+    pub draft: bool,
+
+    /// Only PRs that are ready for review
+    #[arg(long)]
+    pub no_draft: bool,
 }
 
 #[derive(Args, Debug)]
@@ -166,7 +208,7 @@ pub struct CreateArgs {
     #[arg(long, short = 'd')]
     pub draft: bool,
 
-    /// Add reviewers
+    /// Add reviewers: @username, email, display name or a Server reviewer group
     #[arg(long, short = 'r', action = clap::ArgAction::Append)]
     pub reviewer: Vec<String>,
 
@@ -228,13 +270,24 @@ pub struct MergeArgs {
     #[arg(long, short = 'd')]
     pub delete_branch: bool,
 
-    /// Enable auto-merge when requirements are met
+    /// Merge once the merge requirements are met (native auto-merge on
+    /// Server/DC 8.15+; waits and polls on Cloud)
     #[arg(long)]
     pub auto: bool,
 
+    /// How long --auto waits before giving up, e.g. 30m or 2h (default:
+    /// 1h). Cloud only; Server's native auto-merge has no timeout
+    #[arg(long, requires = "auto")]
+    pub timeout: Option<String>,
+
     /// Custom merge commit message
     #[arg(long, short = 'M')]
     pub message: Option<String>,
+
+    /// Merge even if the pull request has unresolved tasks or fails the
+    /// merge preflight
+    #[arg(long, short = 'f')]
+    pub force: bool,
 }
 
 #[derive(Args, Debug)]
@@ -291,10 +344,22 @@ pub struct ReviewArgs {
     /// Review body
     #[arg(long, short = 'b')]
     pub body: Option<String>,
+
+    /// Walk the diff hunk by hunk, adding inline comments and tasks
+    #[arg(long, short = 'i')]
+    pub interactive: bool,
+
+    /// Delete your pending review without posting it
+    #[arg(long, conflicts_with_all = ["interactive", "approve", "request_changes", "comment", "body"])]
+    pub discard: bool,
 }
 
 #[derive(Args, Debug)]
+#[command(args_conflicts_with_subcommands = true)]
 pub struct CommentArgs {
+    #[command(subcommand)]
+    pub command: Option<CommentSubcommand>,
+
     /// Pull request number
     pub number: Option<u32>,
 
@@ -307,12 +372,141 @@ pub struct CommentArgs {
     pub body_file: Option<String>,
 
     /// Edit your last comment
-    #[arg(long)]
+    #[arg(long, conflicts_with_all = ["edit", "reply_to", "file"])]
     pub edit_last: bool,
 
+    /// Edit a comment by ID
+    #[arg(long, value_name = "ID", conflicts_with_all = ["reply_to", "file"])]
+    pub edit: Option<u64>,
+
     /// Reply to a specific comment
+    #[arg(long, value_name = "ID", conflicts_with = "file")]
+    pub reply_to: Option<u64>,
+
+    /// Attach the comment to a file in the diff
+    #[arg(long, value_name = "PATH")]
+    pub file: Option<String>,
+
+    /// Line of the file to comment on
+    #[arg(long, requires = "file")]
+    pub line: Option<u32>,
+
+    /// Side of the diff the line numbers refer to [default: new]
+    #[arg(long, requires = "file", value_parser = ["old", "new"])]
+    pub side: Option<String>,
+
+    /// Comment on a range of lines, e.g. 110-120
+    #[arg(long, requires = "file", value_name = "START-END")]
+    pub range: Option<String>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum CommentSubcommand {
+    /// Delete a comment
+    Delete(CommentDeleteArgs),
+
+    /// Mark a comment thread as resolved
+    Resolve(CommentThreadArgs),
+
+    /// Reopen a resolved comment thread
+    Reopen(CommentThreadArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct CommentDeleteArgs {
+    /// Comment ID
+    pub id: u64,
+
+    /// Pull request number (defaults to the PR for the current branch)
+    #[arg(long)]
+    pub pr: Option<u32>,
+
+    /// Skip confirmation
+    #[arg(long, short = 'y')]
+    pub confirm: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct CommentThreadArgs {
+    /// ID of the thread's first comment
+    pub id: u64,
+
+    /// Pull request number (defaults to the PR for the current branch)
+    #[arg(long)]
+    pub pr: Option<u32>,
+}
+
+#[derive(Args, Debug)]
+pub struct TaskCommand {
+    #[command(subcommand)]
+    pub command: TaskSubcommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum TaskSubcommand {
+    /// List tasks on a pull request
+    #[command(visible_alias = "ls")]
+    List(TaskListArgs),
+
+    /// Create a task
+    Create(TaskCreateArgs),
+
+    /// Mark a task as done
+    Resolve(TaskIdArgs),
+
+    /// Reopen a resolved task
+    Reopen(TaskIdArgs),
+
+    /// Delete a task
+    Delete(TaskDeleteArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct TaskListArgs {
+    /// Pull request number
+    pub number: Option<u32>,
+
+    /// Filter by state
+    #[arg(long, short = 's', value_parser = ["open", "resolved"])]
+    pub state: Option<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct TaskCreateArgs {
+    /// Pull request number
+    pub number: Option<u32>,
+
+    /// Task description
+    #[arg(long, short = 'b')]
+    pub body: String,
+
+    /// Attach the task to a comment
+    #[arg(long)]
+    pub comment: Option<u64>,
+}
+
+#[derive(Args, Debug)]
+pub struct TaskIdArgs {
+    /// Task ID
+    pub id: u64,
+
+    /// Pull request number (defaults to the PR for the current branch)
+    #[arg(long)]
+    pub pr: Option<u32>,
+}
+
+#[derive(Args, Debug)]
+pub struct TaskDeleteArgs {
+    /// Task ID
+    pub id: u64,
+
+    /// Pull request number (defaults to the PR for the current branch)
     #[arg(long)]
-    pub reply_to: Option<u32>,
+    pub pr: Option<u32>,
+
+    /// Skip confirmation
+    #[arg(long, short = 'y')]
+    pub confirm: bool,
 }
 
 #[derive(Args, Debug)]
@@ -338,15 +532,29 @@ pub struct EditArgs {
     #[arg(long, short = 'B')]
     pub base: Option<String>,
 
-    /// Add reviewer
-    #[arg(long)]
-    pub add_reviewer: Option<String>,
+    /// Add reviewers: @username, email, display name or a Server reviewer group
+    #[arg(long, action = clap::ArgAction::Append)]
+    pub add_reviewer: Vec<String>,
+
+    /// Remove reviewers, named the same way as --add-reviewer
+    #[arg(long, action = clap::ArgAction::Append)]
+    pub remove_reviewer: Vec<String>,
 }
 
 #[derive(Args, Debug)]
 pub struct ReadyArgs {
     /// Pull request number
     pub number: Option<u32>,
+
+    /// Convert the pull request back to a draft
+    #[arg(long)]
+    pub undo: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct MergeabilityArgs {
+    /// Pull request number
+    pub number: Option<u32>,
 }
 
 #[derive(Args, Debug)]
@@ -357,6 +565,27 @@ pub struct ChecksArgs {
     /// Watch until all checks complete
     #[arg(long, short = 'w')]
     pub watch: bool,
+
+    /// How often --watch polls, e.g. 10s or 1m
+    #[arg(long, default_value = "10s")]
+    pub interval: String,
+
+    /// Give up watching after this long, e.g. 30m
+    #[arg(long, requires = "watch")]
+    pub timeout: Option<String>,
+
+    /// Stop watching as soon as a check fails
+    #[arg(long, requires = "watch")]
+    pub fail_fast: bool,
+
+    /// Stop watching if the pull request has no checks, instead of waiting
+    /// for one to be reported
+    #[arg(long, requires = "watch")]
+    pub allow_empty: bool,
+
+    /// Show step progress for checks run by Bitbucket Pipelines (Cloud)
+    #[arg(long)]
+    pub steps: bool,
 }
 
 /// Display format for PR in list output
@@ -368,45 +597,57 @@ struct PrListItem {
     author: String,
     source_branch: String,
     destination_branch: String,
+    created_on: String,
     updated_on: String,
 }
 
-impl TableOutput for PrListItem {
-    fn print_table(&self, color: bool) {
-        let state_styled = if color {
-            match self.state.as_str() {
-                "OPEN" => style("OPEN").green().to_string(),
-                "MERGED" => style("MERGED").magenta().to_string(),
-                "DECLINED" => style("DECLINED").red().to_string(),
-                "SUPERSEDED" => style("SUPERSEDED").yellow().to_string(),
-                _ => self.state.clone(),
-            }
-        } else {
-            self.state.clone()
-        };
-
-        // Truncate title smartly
-        let title_truncated = truncate_string(&self.title, 45);
-
-        // Truncate author name
-        let author_truncated = truncate_string(&self.author, 18);
-
-        // Truncate branch names and format with arrow
-        let source = truncate_string(&self.source_branch, 20);
-        let dest = truncate_string(&self.destination_branch, 15);
-        let branches = format!("{} → {}", source, dest);
+impl TableRow for PrListItem {
+    const COLUMNS: &'static [&'static str] = &[
+        "id",
+        "state",
+        "title",
+        "author",
+        "branches",
+        "source_branch",
+        "destination_branch",
+        "created_on",
+        "updated_on",
+    ];
+    const DEFAULT_COLUMNS: &'static [&'static str] =
+        &["id", "state", "title", "author", "branches"];
+
+    fn cell(&self, column: &str) -> String {
+        match column {
+            "id" => format!("#{}", self.id),
+            "state" => self.state.clone(),
+            "title" => self.title.clone(),
+            "author" => self.author.clone(),
+            "branches" => format!(
+                "{} {} {}",
+                self.source_branch,
+                theme().arrow(),
+                self.destination_branch
+            ),
+            "source_branch" => self.source_branch.clone(),
+            "destination_branch" => self.destination_branch.clone(),
+            "created_on" => self.created_on.clone(),
+            "updated_on" => self.updated_on.clone(),
+            _ => String::new(),
+        }
+    }
 
-        // Use bold for PR ID
-        let id_styled = if color {
-            style(format!("#{}", self.id)).cyan().to_string()
-        } else {
-            format!("#{}", self.id)
-        };
+    fn cell_color(&self, column: &str) -> Option<Color> {
+        match column {
+            "id" => Some(Color::Cyan),
+            "state" => theme().state_color(&self.state),
+            _ => None,
+        }
+    }
+}
 
-        println!(
-            "{:<7} {:<10} {:<45}  {:<18}  {}",
-            id_styled, state_styled, title_truncated, author_truncated, branches
-        );
+impl TableOutput for PrListItem {
+    fn print_table(&self, _color: bool) {
+        print_row(self);
     }
 
     fn print_markdown(&self) {
@@ -422,16 +663,6 @@ impl TableOutput for PrListItem {
     }
 }
 
-/// Truncate a string to max length, adding "…" if truncated
-fn truncate_string(s: &str, max_len: usize) -> String {
-    if s.chars().count() > max_len {
-        let truncated: String = s.chars().take(max_len - 1).collect();
-        format!("{}…", truncated)
-    } else {
-        s.to_string()
-    }
-}
-
 /// Display format for PR detail view
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct PrDetail {
@@ -445,54 +676,78 @@ struct PrDetail {
     reviewers: Vec<String>,
     approvals: u32,
     comment_count: u32,
+    open_tasks: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    mergeable: Option<String>,
     created_on: String,
     updated_on: String,
     web_url: String,
 }
 
-impl TableOutput for PrDetail {
-    fn print_table(&self, color: bool) {
+impl PrDetail {
+    /// Renders the detail view as text, as printed by `print_table`
+    fn render(&self, color: bool) -> String {
         let state_styled = if color {
-            match self.state.as_str() {
-                "OPEN" => style(&self.state).green().bold().to_string(),
-                "MERGED" => style(&self.state).magenta().bold().to_string(),
-                "DECLINED" => style(&self.state).red().bold().to_string(),
-                "SUPERSEDED" => style(&self.state).yellow().bold().to_string(),
-                _ => self.state.clone(),
-            }
+            theme().state(&self.state)
         } else {
             self.state.clone()
         };
 
-        print_header(&format!("PR #{}: {}", self.id, self.title));
-        println!();
-
-        print_field("State", &state_styled, color);
-        print_field("Author", &self.author, color);
-        println!();
-
-        print_field("Source", &self.source_branch, color);
-        print_field("Destination", &self.destination_branch, color);
-        println!();
+        let mut lines = vec![
+            format_header(&format!("PR #{}: {}", self.id, self.title)),
+            String::new(),
+            format_field("State", &state_styled, color),
+            format_field("Author", &self.author, color),
+            String::new(),
+            format_field("Source", &self.source_branch, color),
+            format_field("Destination", &self.destination_branch, color),
+            String::new(),
+        ];
 
         if !self.reviewers.is_empty() {
-            print_field("Reviewers", &self.reviewers.join(", "), color);
+            lines.push(format_field("Reviewers", &self.reviewers.join(", "), color));
         }
-        print_field("Approvals", &self.approvals.to_string(), color);
-        print_field("Comments", &self.comment_count.to_string(), color);
-        println!();
+        lines.push(format_field(
+            "Approvals",
+            &self.approvals.to_string(),
+            color,
+        ));
+        lines.push(format_field(
+            "Comments",
+            &self.comment_count.to_string(),
+            color,
+        ));
+        lines.push(format_field(
+            "Open tasks",
+            &self.open_tasks.to_string(),
+            color,
+        ));
+        if let Some(mergeable) = &self.mergeable {
+            lines.push(format_field("Mergeable", mergeable, color));
+        }
+        lines.push(String::new());
 
         if let Some(desc) = &self.description {
             if !desc.is_empty() {
-                println!("Description:");
-                println!("{}", desc);
-                println!();
+                lines.push("Description:".to_string());
+                lines.push(desc.clone());
+                lines.push(String::new());
             }
         }
 
-        print_field("Created", &self.created_on, color);
-        print_field("Updated", &self.updated_on, color);
-        print_field("URL", &self.web_url, color);
+        lines.push(format_field("Created", &self.created_on, color));
+        lines.push(format_field("Updated", &self.updated_on, color));
+        lines.push(format_field("URL", &self.web_url, color));
+
+        let mut out = lines.join("\n");
+        out.push('\n');
+        out
+    }
+}
+
+impl TableOutput for PrDetail {
+    fn print_table(&self, color: bool) {
+        print!("{}", self.render(color));
     }
 
     fn print_markdown(&self) {
@@ -507,9 +762,13 @@ impl TableOutput for PrDetail {
             println!("**Reviewers**: {}", self.reviewers.join(", "));
         }
         println!(
-            "**Approvals**: {} | **Comments**: {}",
-            self.approvals, self.comment_count
+            "**Approvals**: {} | **Comments**: {} | **Open tasks**: {}",
+            self.approvals, self.comment_count, self.open_tasks
         );
+        if let Some(mergeable) = &self.mergeable {
+            println!();
+            println!("**Mergeable**: {}", mergeable);
+        }
         println!();
 
         if let Some(desc) = &self.description {
@@ -532,29 +791,186 @@ struct PrComment {
     author: String,
     content: String,
     created_on: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    inline: Option<InlineAnchor>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    parent: Option<u64>,
+    #[serde(default)]
+    resolved: bool,
+    /// Stable identity of the author (Cloud UUID, Server username), used to
+    /// find the user's own comments
+    #[serde(skip)]
+    author_key: String,
 }
 
-impl TableOutput for PrComment {
-    fn print_table(&self, color: bool) {
-        let author_styled = if color {
-            style(&self.author).cyan().to_string()
+/// Where an inline comment is attached in the diff
+///
+/// `line` is `None` for comments on a whole file. `start_line` is set for
+/// comments that span a range of lines ending at `line`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct InlineAnchor {
+    path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    line: Option<u32>,
+    side: Side,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    start_line: Option<u32>,
+}
+
+/// An inline anchor that has been checked against the PR diff
+///
+/// Carries what Server needs beyond the anchor itself: the kind of each
+/// anchored line and the file's previous path if it was renamed.
+#[derive(Debug)]
+struct CheckedAnchor {
+    anchor: InlineAnchor,
+    src_path: Option<String>,
+    line_kind: LineKind,
+    start_kind: Option<LineKind>,
+}
+
+/// How a review is submitted
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ReviewVerdict {
+    Comment,
+    Approve,
+    RequestChanges,
+}
+
+/// A comment's current text, and on Server the version needed to change it
+struct CommentRevision {
+    text: String,
+    version: Option<u32>,
+}
+
+impl PrComment {
+    /// Renders the comment as text, indented by `depth` levels for replies
+    fn render(&self, color: bool, depth: usize) -> String {
+        let indent = "    ".repeat(depth);
+        let (author_styled, resolved) = if color {
+            (theme().emphasis(&self.author), theme().muted("[resolved]"))
         } else {
-            self.author.clone()
+            (self.author.clone(), "[resolved]".to_string())
         };
 
-        println!("@{} ({}):", author_styled, self.created_on);
-        println!("  {}", self.content);
-        println!();
+        let mut text = format!(
+            "{}#{} @{} ({})",
+            indent, self.id, author_styled, self.created_on
+        );
+        if self.resolved {
+            text.push(' ');
+            text.push_str(&resolved);
+        }
+        text.push_str(":\n");
+        for line in self.content.lines() {
+            text.push_str(&format!("{}  {}\n", indent, line));
+        }
+        text.push('\n');
+        text
+    }
+}
+
+impl TableOutput for PrComment {
+    fn print_table(&self, color: bool) {
+        print!("{}", self.render(color, 0));
     }
 
     fn print_markdown(&self) {
-        println!("**@{}** - {}", self.author, self.created_on);
+        match &self.inline {
+            Some(anchor) => println!(
+                "**@{}** on `{}` - {}",
+                self.author,
+                anchor.location(),
+                self.created_on
+            ),
+            None => println!("**@{}** - {}", self.author, self.created_on),
+        }
         println!();
         println!("> {}", self.content);
         println!();
     }
 }
 
+/// Display format for a PR task
+///
+/// Cloud tasks and Server blocker comments share this shape. `state` is
+/// normalized to `OPEN` or `RESOLVED`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct PrTask {
+    id: u64,
+    state: String,
+    content: String,
+    author: String,
+    created_on: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    comment: Option<u64>,
+}
+
+impl PrTask {
+    fn is_open(&self) -> bool {
+        self.state != "RESOLVED"
+    }
+}
+
+impl TableRow for PrTask {
+    const COLUMNS: &'static [&'static str] =
+        &["id", "state", "content", "author", "comment", "created_on"];
+    const DEFAULT_COLUMNS: &'static [&'static str] = &["id", "state", "content", "author"];
+
+    fn cell(&self, column: &str) -> String {
+        match column {
+            "id" => format!("#{}", self.id),
+            "state" => self.state.clone(),
+            "content" => self.content.clone(),
+            "author" => self.author.clone(),
+            "comment" => self
+                .comment
+                .map(|id| format!("#{}", id))
+                .unwrap_or_default(),
+            "created_on" => self.created_on.clone(),
+            _ => String::new(),
+        }
+    }
+
+    fn cell_color(&self, column: &str) -> Option<Color> {
+        match column {
+            "id" => Some(Color::Cyan),
+            "state" => theme().state_color(&self.state),
+            _ => None,
+        }
+    }
+}
+
+impl TableOutput for PrTask {
+    fn print_table(&self, _color: bool) {
+        print_row(self);
+    }
+
+    fn print_markdown(&self) {
+        let mark = if self.is_open() { " " } else { "x" };
+        println!(
+            "- [{}] {} (#{}, @{})",
+            mark, self.content, self.id, self.author
+        );
+    }
+}
+
+impl InlineAnchor {
+    /// Formats the position as `path:line`, `path:start-end` or `path`,
+    /// with `(old)` appended for lines on the old side
+    fn location(&self) -> String {
+        let mut location = match (self.start_line, self.line) {
+            (Some(start), Some(line)) => format!("{}:{}-{}", self.path, start, line),
+            (None, Some(line)) => format!("{}:{}", self.path, line),
+            _ => self.path.clone(),
+        };
+        if self.line.is_some() && self.side == Side::Old {
+            location.push_str(" (old)");
+        }
+        location
+    }
+}
+
 impl PrCommand {
     pub async fn run(&self, global: &GlobalOptions) -> Result<()> {
         match &self.command {
@@ -572,8 +988,10 @@ impl PrCommand {
             PrSubcommand::Review(args) => self.review(args, global).await,
             PrSubcommand::Comment(args) => self.comment(args, global).await,
             PrSubcommand::Comments(args) => self.comments(args, global).await,
+            PrSubcommand::Task(args) => self.task(&args.command, global).await,
             PrSubcommand::Edit(args) => self.edit(args, global).await,
             PrSubcommand::Ready(args) => self.ready(args, global).await,
+            PrSubcommand::Mergeability(args) => self.mergeability(args, global).await,
             PrSubcommand::Checks(args) => self.checks(args, global).await,
         }
     }
@@ -586,6 +1004,12 @@ impl PrCommand {
 
         let context = resolver.resolve(global)?;
 
+        if context.host_type == HostType::Cloud {
+            require_json_fields(cloud_prs::PullRequest::json_fields())?;
+        } else {
+            require_json_fields(server_prs::PullRequest::json_fields())?;
+        }
+
         let token = keyring.get(&context.host)?.ok_or_else(|| {
             anyhow::anyhow!(
                 "Not authenticated for {}. Run 'bb auth login' first.",
@@ -593,6 +1017,34 @@ impl PrCommand {
             )
         })?;
 
+        if context.host_type == HostType::Server && draft_filter(args).is_some() {
+            capabilities::detect(&context.host, &token)
+                .await?
+                .require(Capability::DraftPullRequests)?;
+        }
+
+        // Filters take Cloud account UUIDs and Server/DC usernames
+        let resolved;
+        let args = if args.author.is_some() || args.reviewer.is_some() {
+            let users = UserResolver::new(&context, &token)?;
+            let mut filters = args.clone();
+            for user in [&mut filters.author, &mut filters.reviewer]
+                .into_iter()
+                .flatten()
+            {
+                let found = users.resolve_one(user).await?;
+                *user = if context.host_type == HostType::Cloud {
+                    found.id
+                } else {
+                    found.name().to_string()
+                };
+            }
+            resolved = filters;
+            &resolved
+        } else {
+            args
+        };
+
         let client = Client::builder()
             .user_agent(format!("bb/{}", crate::VERSION))
             .build()?;
@@ -605,63 +1057,50 @@ impl PrCommand {
 
         if context.host_type == HostType::Cloud {
             // Bitbucket Cloud
-            let mut url = format!(
-                "https://api.bitbucket.org/2.0/repositories/{}/{}/pullrequests?pagelen={}",
-                context.owner, context.repo_slug, args.limit
-            );
-
-            // State filter
-            let state = args.state.as_deref().unwrap_or("OPEN").to_uppercase();
-            url.push_str(&format!("&state={}", state));
-
-            // Build query parameters
-            let mut q_parts: Vec<String> = Vec::new();
-
-            if let Some(author) = &args.author {
-                q_parts.push(format!("author.username=\"{}\"", author));
-            }
-
-            if let Some(base) = &args.base {
-                q_parts.push(format!("destination.branch.name=\"{}\"", base));
-            }
-
-            if let Some(head) = &args.head {
-                q_parts.push(format!("source.branch.name=\"{}\"", head));
-            }
+            let query = cloud_list_query(args, global.sort.as_deref(), global.reverse)?;
+            let mut next = Some(format!(
+                "https://api.bitbucket.org/2.0/repositories/{}/{}/pullrequests?{}",
+                context.owner, context.repo_slug, query
+            ));
 
-            if let Some(search) = &args.search {
-                q_parts.push(format!("title~\"{}\"", search));
-            }
+            let mut prs = ListPages::new(global.json);
+            while let Some(url) = next.take() {
+                let response = client
+                    .get(&url)
+                    .bearer_auth(&token)
+                    .send_replayable()
+                    .await
+                    .context("Failed to connect to Bitbucket Cloud")?;
+
+                if !response.status().is_success() {
+                    let status = response.status();
+                    let text = response.text().await.unwrap_or_default();
+                    return Err(format_api_error(status, &text));
+                }
 
-            if !q_parts.is_empty() {
-                url.push_str(&format!("&q={}", q_parts.join(" AND ")));
+                let mut page: PaginatedResponse<cloud_prs::PullRequest> = response.json().await?;
+                page.values.truncate(args.limit as usize - prs.count());
+                prs.push(page.values)?;
+                if prs.count() < args.limit as usize {
+                    next = page.next;
+                }
             }
 
-            let response = client
-                .get(&url)
-                .bearer_auth(&token)
-                .send()
-                .await
-                .context("Failed to connect to Bitbucket Cloud")?;
-
-            if !response.status().is_success() {
-                let status = response.status();
-                let text = response.text().await.unwrap_or_default();
-                return Err(format_api_error(status, &text));
+            if global.json {
+                return prs.finish();
             }
 
-            let prs: PaginatedResponse<cloud_prs::PullRequest> = response.json().await?;
-
             let items: Vec<PrListItem> = prs
-                .values
+                .into_items()
                 .into_iter()
                 .map(|pr| PrListItem {
                     id: pr.id,
                     title: pr.title,
-                    state: pr.state,
+                    state: display_state(pr.state, pr.draft),
                     author: pr.author.username.unwrap_or(pr.author.name),
                     source_branch: pr.source.branch.name,
                     destination_branch: pr.destination.branch.name,
+                    created_on: pr.created_on,
                     updated_on: pr.updated_on,
                 })
                 .collect();
@@ -669,66 +1108,112 @@ impl PrCommand {
             if items.is_empty() {
                 println!("No pull requests found");
             } else {
-                if !global.json {
+                if stdout_is_tty() {
                     println!(
                         "Pull requests in {}/{}:\n",
                         context.owner, context.repo_slug
                     );
-                    println!(
-                        "{:<7} {:<10} {:<45}  {:<18}  {}",
-                        style("ID").bold(),
-                        style("STATE").bold(),
-                        style("TITLE").bold(),
-                        style("AUTHOR").bold(),
-                        style("BRANCHES").bold()
-                    );
-                    println!("{}", style("─".repeat(110)).dim());
                 }
-                output.write_list(&items)?;
+                output.write_table(&items)?;
             }
         } else {
             // Bitbucket Server/DC
-            let state = match args.state.as_deref() {
-                Some("open") | None => "OPEN",
-                Some("merged") => "MERGED",
-                Some("declined") => "DECLINED",
-                _ => "ALL",
+            let query = server_list_query(args, global.sort.as_deref(), global.reverse)?;
+
+            // Server has no date or second branch filter; apply them here,
+            // paging on until `--limit` PRs match
+            let created_after = args.created_after.as_deref().map(parse_date).transpose()?;
+            let updated_after = args.updated_after.as_deref().map(parse_date).transpose()?;
+            let draft = draft_filter(args);
+            let matches = |pr: &server_prs::PullRequest| {
+                draft.is_none_or(|draft| pr.draft == draft)
+                    && created_after.is_none_or(|t| pr.created_date as i64 >= t.timestamp_millis())
+                    && updated_after.is_none_or(|t| pr.updated_date as i64 >= t.timestamp_millis())
+                    && (args.base.is_none()
+                        || args.head.is_none()
+                        || args.head.as_deref() == Some(pr.from_ref.display_id.as_str()))
             };
 
-            let mut url = format!(
-                "https://{}/rest/api/1.0/projects/{}/repos/{}/pull-requests?limit={}&state={}",
-                context.host, context.owner, context.repo_slug, args.limit, state
-            );
+            let newest_first = query.get("order").is_none_or(|order| order == "NEWEST");
 
-            if let Some(base) = &args.base {
-                url.push_str(&format!("&at=refs/heads/{}", base));
-            }
+            let mut prs = ListPages::new(global.json);
+            let mut start = 0;
+            let mut pages = 0;
+            loop {
+                let mut page_query = query.clone();
+                if start > 0 {
+                    page_query.push("start", start);
+                }
+                let url = format!(
+                    "https://{}/rest/api/1.0/projects/{}/repos/{}/pull-requests?{}",
+                    context.host, context.owner, context.repo_slug, page_query
+                );
 
-            let response = client
-                .get(&url)
-                .bearer_auth(&token)
-                .send()
-                .await
-                .context("Failed to connect to Bitbucket Server")?;
+                let response = client
+                    .get(&url)
+                    .bearer_auth(&token)
+                    .send_replayable()
+                    .await
+                    .context("Failed to connect to Bitbucket Server")?;
+
+                if !response.status().is_success() {
+                    let status = response.status();
+                    let text = response.text().await.unwrap_or_default();
+                    return Err(format_api_error(status, &text));
+                }
 
-            if !response.status().is_success() {
-                let status = response.status();
-                let text = response.text().await.unwrap_or_default();
-                return Err(format_api_error(status, &text));
+                let page: ServerPaginatedResponse<server_prs::PullRequest> =
+                    response.json().await?;
+                pages += 1;
+                // Newest first, every later PR was updated before the last
+                // one here, and a PR is never created after its last update
+                let past_dates = newest_first
+                    && page.values.last().is_some_and(|pr| {
+                        [created_after, updated_after]
+                            .into_iter()
+                            .flatten()
+                            .any(|t| (pr.updated_date as i64) < t.timestamp_millis())
+                    });
+                let mut matched: Vec<_> =
+                    page.values.into_iter().filter(|pr| matches(pr)).collect();
+                matched.truncate(args.limit as usize - prs.count());
+                prs.push(matched)?;
+
+                match page.next_page_start {
+                    Some(next)
+                        if !page.is_last_page
+                            && !past_dates
+                            && prs.count() < args.limit as usize =>
+                    {
+                        if pages == MAX_SERVER_PR_PAGES {
+                            eprintln!(
+                                "{} Stopping after {} pages of pull requests",
+                                theme().warning_mark(),
+                                MAX_SERVER_PR_PAGES
+                            );
+                            break;
+                        }
+                        start = next;
+                    }
+                    _ => break,
+                }
             }
 
-            let prs: ServerPaginatedResponse<server_prs::PullRequest> = response.json().await?;
+            if global.json {
+                return prs.finish();
+            }
 
             let items: Vec<PrListItem> = prs
-                .values
+                .into_items()
                 .into_iter()
                 .map(|pr| PrListItem {
                     id: pr.id,
                     title: pr.title,
-                    state: pr.state,
+                    state: display_state(pr.state, pr.draft),
                     author: pr.author.user.display_name,
                     source_branch: pr.from_ref.display_id,
                     destination_branch: pr.to_ref.display_id,
+                    created_on: format_server_timestamp(pr.created_date),
                     updated_on: format_server_timestamp(pr.updated_date),
                 })
                 .collect();
@@ -736,22 +1221,13 @@ impl PrCommand {
             if items.is_empty() {
                 println!("No pull requests found");
             } else {
-                if !global.json {
+                if stdout_is_tty() {
                     println!(
                         "Pull requests in {}/{}:\n",
                         context.owner, context.repo_slug
                     );
-                    println!(
-                        "{:<7} {:<10} {:<45}  {:<18}  {}",
-                        style("ID").bold(),
-                        style("STATE").bold(),
-                        style("TITLE").bold(),
-                        style("AUTHOR").bold(),
-                        style("BRANCHES").bold()
-                    );
-                    println!("{}", style("─".repeat(110)).dim());
                 }
-                output.write_list(&items)?;
+                output.write_table(&items)?;
             }
         }
 
@@ -766,6 +1242,12 @@ impl PrCommand {
 
         let context = resolver.resolve(global)?;
 
+        if context.host_type == HostType::Cloud {
+            require_json_fields(cloud_prs::PullRequest::json_fields())?;
+        } else {
+            require_json_fields(server_prs::PullRequest::json_fields())?;
+        }
+
         // Get PR number from arg or current branch
         let pr_number = if let Some(num) = args.number {
             num
@@ -818,7 +1300,7 @@ impl PrCommand {
             let response = client
                 .get(&url)
                 .bearer_auth(&token)
-                .send()
+                .send_replayable()
                 .await
                 .context("Failed to connect to Bitbucket Cloud")?;
 
@@ -830,6 +1312,10 @@ impl PrCommand {
 
             let pr: cloud_prs::PullRequest = response.json().await?;
 
+            if global.json {
+                return write_json(&pr);
+            }
+
             let approvals = pr.participants.iter().filter(|p| p.approved).count() as u32;
             let reviewers: Vec<String> = pr
                 .reviewers
@@ -837,17 +1323,34 @@ impl PrCommand {
                 .map(|r| r.username.clone().unwrap_or_else(|| r.name.clone()))
                 .collect();
 
+            let mergeable = if pr.state == "OPEN" {
+                let mergeability = async {
+                    let statuses = self
+                        .fetch_commit_statuses(&client, &context, &token, pr_number)
+                        .await?;
+                    let rules = self.fetch_merge_checks(&client, &context, &token).await?;
+                    let checks = cloud_merge_checks(&pr, &statuses, rules.as_ref(), false);
+                    anyhow::Ok(Mergeability::new(pr.id, checks, Vec::new()))
+                }
+                .await;
+                Some(mergeability_summary(mergeability))
+            } else {
+                None
+            };
+
             PrDetail {
                 id: pr.id,
                 title: pr.title,
                 description: pr.description,
-                state: pr.state,
+                state: display_state(pr.state, pr.draft),
                 author: pr.author.username.unwrap_or(pr.author.name),
                 source_branch: pr.source.branch.name,
                 destination_branch: pr.destination.branch.name,
                 reviewers,
                 approvals,
                 comment_count: pr.comment_count,
+                open_tasks: pr.task_count,
+                mergeable,
                 created_on: pr.created_on,
                 updated_on: pr.updated_on,
                 web_url: format!(
@@ -864,7 +1367,7 @@ impl PrCommand {
             let response = client
                 .get(&url)
                 .bearer_auth(&token)
-                .send()
+                .send_replayable()
                 .await
                 .context("Failed to connect to Bitbucket Server")?;
 
@@ -876,24 +1379,39 @@ impl PrCommand {
 
             let pr: server_prs::PullRequest = response.json().await?;
 
-            let approvals = pr.reviewers.iter().filter(|r| r.approved).count() as u32;
+            if global.json {
+                return write_json(&pr);
+            }
+
+            let approvals = pr.reviewers.iter().filter(|r| r.approved).count() as u32;
             let reviewers: Vec<String> = pr
                 .reviewers
                 .iter()
                 .map(|r| r.user.display_name.clone())
                 .collect();
 
+            let mergeable = if pr.state == "OPEN" {
+                let mergeability = self
+                    .check_mergeability(&client, &context, &token, pr_number, false)
+                    .await;
+                Some(mergeability_summary(mergeability))
+            } else {
+                None
+            };
+
             PrDetail {
                 id: pr.id,
                 title: pr.title,
                 description: pr.description,
-                state: pr.state,
+                state: display_state(pr.state, pr.draft),
                 author: pr.author.user.display_name,
                 source_branch: pr.from_ref.display_id,
                 destination_branch: pr.to_ref.display_id,
                 reviewers,
                 approvals,
-                comment_count: 0, // Server doesn't return this in PR response
+                comment_count: pr.properties.comment_count,
+                open_tasks: pr.properties.open_task_count,
+                mergeable,
                 created_on: format_server_timestamp(pr.created_date),
                 updated_on: format_server_timestamp(pr.updated_date),
                 web_url: format!(
@@ -903,13 +1421,33 @@ impl PrCommand {
             }
         };
 
-        output.write(&detail)?;
-
         // Show comments if requested
         if args.comments {
-            println!("\n--- Comments ---\n");
-            self.fetch_and_display_comments(&context, &token, pr_number, global)
-                .await?;
+            if global.json || template().is_some() {
+                output.write(&detail)?;
+                println!("\n--- Comments ---\n");
+                self.fetch_and_display_comments(&context, &token, pr_number, global)
+                    .await?;
+            } else {
+                // The comment thread can be long, so send it all through the pager
+                let color = theme().color_enabled();
+                let comments = self.fetch_comments(&context, &token, pr_number).await?;
+
+                let mut content = detail.render(color);
+                content.push_str("\n--- Comments ---\n\n");
+                if comments.is_empty() {
+                    content.push_str(&format!("No comments on PR #{}\n", pr_number));
+                } else {
+                    content.push_str(
+                        &self
+                            .render_comment_list(&context, &token, pr_number, &comments)
+                            .await?,
+                    );
+                }
+                page_output(&content)?;
+            }
+        } else {
+            output.write(&detail)?;
         }
 
         Ok(())
@@ -973,6 +1511,28 @@ impl PrCommand {
             None
         };
 
+        let hooks = Hooks::load("pr create", &context, global.json).await?;
+        let operation = serde_json::json!({
+            "title": title,
+            "description": description,
+            "source_branch": source_branch,
+            "destination_branch": dest_branch,
+            "reviewers": args.reviewer,
+            "draft": args.draft,
+        });
+
+        // Check before the pre hooks run, as they may have side effects
+        if args.draft && context.host_type == HostType::Server {
+            capabilities::detect(&context.host, &token)
+                .await?
+                .require_verified(Capability::DraftPullRequests)?;
+        }
+        let reviewers = UserResolver::new(&context, &token)?
+            .resolve_all(&args.reviewer)
+            .await?;
+
+        hooks.run_pre(&operation)?;
+
         let client = Client::builder()
             .user_agent(format!("bb/{}", crate::VERSION))
             .build()?;
@@ -989,10 +1549,9 @@ impl PrCommand {
                 context.owner, context.repo_slug
             );
 
-            let reviewers: Vec<cloud_prs::UserUuid> = args
-                .reviewer
+            let reviewers: Vec<cloud_prs::UserUuid> = reviewers
                 .iter()
-                .map(|r| cloud_prs::UserUuid { uuid: r.clone() })
+                .map(|r| cloud_prs::UserUuid { uuid: r.id.clone() })
                 .collect();
 
             let body = cloud_prs::CreatePullRequestRequest {
@@ -1010,13 +1569,14 @@ impl PrCommand {
                 },
                 reviewers,
                 close_source_branch: Some(true),
+                draft: args.draft.then_some(true),
             };
 
             let response = client
                 .post(&url)
                 .bearer_auth(&token)
                 .json(&body)
-                .send()
+                .send_replayable()
                 .await
                 .context("Failed to connect to Bitbucket Cloud")?;
 
@@ -1039,6 +1599,11 @@ impl PrCommand {
             );
             println!("View at: {}", pr_url);
 
+            hooks.run_post(
+                &operation,
+                &serde_json::json!({ "id": pr.id, "url": pr_url }),
+            );
+
             if args.web {
                 webbrowser::open(&pr_url)?;
             }
@@ -1048,11 +1613,12 @@ impl PrCommand {
                 context.host, context.owner, context.repo_slug
             );
 
-            let reviewers: Vec<server_prs::UserRef> = args
-                .reviewer
+            let reviewers: Vec<server_prs::UserRef> = reviewers
                 .iter()
                 .map(|r| server_prs::UserRef {
-                    user: server_prs::UserName { name: r.clone() },
+                    user: server_prs::UserName {
+                        name: r.name().to_string(),
+                    },
                 })
                 .collect();
 
@@ -1078,13 +1644,14 @@ impl PrCommand {
                     },
                 },
                 reviewers,
+                draft: args.draft.then_some(true),
             };
 
             let response = client
                 .post(&url)
                 .bearer_auth(&token)
                 .json(&body)
-                .send()
+                .send_replayable()
                 .await
                 .context("Failed to connect to Bitbucket Server")?;
 
@@ -1107,6 +1674,11 @@ impl PrCommand {
             );
             println!("View at: {}", pr_url);
 
+            hooks.run_post(
+                &operation,
+                &serde_json::json!({ "id": pr.id, "url": pr_url }),
+            );
+
             if args.web {
                 webbrowser::open(&pr_url)?;
             }
@@ -1141,7 +1713,11 @@ impl PrCommand {
                 context.owner, context.repo_slug, args.number
             );
 
-            let response = client.get(&url).bearer_auth(&token).send().await?;
+            let response = client
+                .get(&url)
+                .bearer_auth(&token)
+                .send_replayable()
+                .await?;
 
             if !response.status().is_success() {
                 let status = response.status();
@@ -1157,7 +1733,11 @@ impl PrCommand {
                 context.host, context.owner, context.repo_slug, args.number
             );
 
-            let response = client.get(&url).bearer_auth(&token).send().await?;
+            let response = client
+                .get(&url)
+                .bearer_auth(&token)
+                .send_replayable()
+                .await?;
 
             if !response.status().is_success() {
                 let status = response.status();
@@ -1228,52 +1808,12 @@ impl PrCommand {
             )
         })?;
 
-        let client = Client::builder()
-            .user_agent(format!("bb/{}", crate::VERSION))
-            .build()?;
-
-        if context.host_type == HostType::Cloud {
-            let url = format!(
-                "https://api.bitbucket.org/2.0/repositories/{}/{}/pullrequests/{}/diff",
-                context.owner, context.repo_slug, pr_number
-            );
-
-            let response = client.get(&url).bearer_auth(&token).send().await?;
-
-            if !response.status().is_success() {
-                let status = response.status();
-                let text = response.text().await.unwrap_or_default();
-                return Err(format_api_error(status, &text));
-            }
-
-            let diff = response.text().await?;
+        let diff = self.fetch_diff(&context, &token, pr_number).await?;
 
-            if args.stat {
-                self.print_diff_stats(&diff);
-            } else {
-                println!("{}", diff);
-            }
+        if args.stat {
+            self.print_diff_stats(&diff);
         } else {
-            let url = format!(
-                "https://{}/rest/api/1.0/projects/{}/repos/{}/pull-requests/{}/diff",
-                context.host, context.owner, context.repo_slug, pr_number
-            );
-
-            let response = client.get(&url).bearer_auth(&token).send().await?;
-
-            if !response.status().is_success() {
-                let status = response.status();
-                let text = response.text().await.unwrap_or_default();
-                return Err(format_api_error(status, &text));
-            }
-
-            let diff = response.text().await?;
-
-            if args.stat {
-                self.print_diff_stats(&diff);
-            } else {
-                println!("{}", diff);
-            }
+            page_output(&format!("{}\n", colorize_diff(&diff, theme())))?;
         }
 
         Ok(())
@@ -1287,6 +1827,13 @@ impl PrCommand {
 
         let context = resolver.resolve(global)?;
 
+        // Server merges natively once the checks pass, so there is nothing to wait on
+        if args.timeout.is_some() && context.host_type == HostType::Server {
+            anyhow::bail!(
+                "--timeout only applies to --auto on Bitbucket Cloud; Bitbucket Server merges on its own once the merge checks pass"
+            );
+        }
+
         let pr_number = if let Some(num) = args.number {
             num
         } else {
@@ -1319,7 +1866,97 @@ impl PrCommand {
             "merge_commit"
         };
 
+        // --auto waits for open tasks to be resolved instead. The task lookup
+        // only guards against surprises, so a server without tasks or a
+        // failed lookup leaves the decision to the merge itself.
+        if !args.force && !args.auto {
+            let open = match self
+                .fetch_open_task_count(&context, &token, pr_number)
+                .await
+            {
+                Ok(open) => open,
+                Err(e) => {
+                    eprintln!(
+                        "{} Could not check the PR for open tasks: {:#}",
+                        theme().warning_mark(),
+                        e
+                    );
+                    0
+                }
+            };
+
+            if open > 0 {
+                let summary = format!(
+                    "PR #{} has {} unresolved task{}",
+                    pr_number,
+                    open,
+                    if open == 1 { "" } else { "s" }
+                );
+
+                if global.no_prompt || !stdout_is_tty() {
+                    anyhow::bail!("{}. Resolve them or use --force to merge anyway.", summary);
+                }
+
+                use dialoguer::Confirm;
+                let confirmed = Confirm::new()
+                    .with_prompt(format!("{}. Merge anyway?", summary))
+                    .default(false)
+                    .interact()?;
+
+                if !confirmed {
+                    println!("{} Cancelled.", theme().warning_mark());
+                    return Ok(());
+                }
+            }
+        }
+
+        // Explain up front why the merge would be refused; --auto waits instead.
+        // Open tasks were dealt with above.
+        if !args.force && !args.auto {
+            let mergeability = self
+                .check_mergeability(&client, &context, &token, pr_number, true)
+                .await?;
+            if !mergeability.can_merge {
+                let reasons: Vec<String> = mergeability
+                    .checks
+                    .iter()
+                    .filter(|c| c.state.blocks())
+                    .map(|c| format!("  {}: {}", c.name, c.detail))
+                    .collect();
+                anyhow::bail!(
+                    "PR #{} cannot be merged:\n{}\nRun 'bb pr mergeability {}' for the full checklist, or use --force to try anyway.",
+                    pr_number,
+                    reasons.join("\n"),
+                    pr_number
+                );
+            }
+        }
+
+        let hooks = Hooks::load("pr merge", &context, global.json).await?;
+        let operation = serde_json::json!({
+            "id": pr_number,
+            "strategy": strategy,
+            "message": args.message,
+            "delete_branch": args.delete_branch,
+            "auto": args.auto,
+        });
+        hooks.run_pre(&operation)?;
+
         if context.host_type == HostType::Cloud {
+            if args.auto {
+                let timeout = match &args.timeout {
+                    Some(t) => parse_duration(t).context("Invalid --timeout")?,
+                    None => AUTO_MERGE_TIMEOUT,
+                };
+                let still_open = self
+                    .wait_until_mergeable(&client, &context, &token, pr_number, args.force, timeout)
+                    .await?;
+                if !still_open {
+                    output.write_success(&format!("PR #{} has already been merged", pr_number));
+                    return Ok(());
+                }
+            }
+
             let url = format!(
                 "https://api.bitbucket.org/2.0/repositories/{}/{}/pullrequests/{}/merge",
                 context.owner, context.repo_slug, pr_number
@@ -1335,7 +1972,7 @@ impl PrCommand {
                 .post(&url)
                 .bearer_auth(&token)
                 .json(&body)
-                .send()
+                .send_replayable()
                 .await
                 .context("Failed to connect to Bitbucket Cloud")?;
 
@@ -1346,6 +1983,57 @@ impl PrCommand {
             }
 
             output.write_success(&format!("Merged PR #{}", pr_number));
+        } else if args.auto {
+            capabilities::detect(&context.host, &token)
+                .await?
+                .require_verified(Capability::AutoMerge)?;
+
+            let url = format!(
+                "https://{}/rest/api/1.0/projects/{}/repos/{}/pull-requests/{}/auto-merge",
+                context.host, context.owner, context.repo_slug, pr_number
+            );
+
+            // Without a strategy flag the repository's default strategy applies
+            let strategy_id = if args.squash {
+                Some("squash")
+            } else if args.rebase {
+                Some("rebase-no-ff")
+            } else if args.merge {
+                Some("no-ff")
+            } else {
+                None
+            };
+
+            let body = server_prs::AutoMergeRequest {
+                strategy_id: strategy_id.map(str::to_string),
+                message: args.message.clone(),
+            };
+
+            let response = client
+                .post(&url)
+                .bearer_auth(&token)
+                .json(&body)
+                .send_replayable()
+                .await
+                .context("Failed to connect to Bitbucket Server")?;
+
+            if !response.status().is_success() {
+                let status = response.status();
+                let text = response.text().await.unwrap_or_default();
+                return Err(format_api_error(status, &text))
+                    .context(format!("Failed to enable auto-merge for PR #{}", pr_number));
+            }
+
+            output.write_success(&format!(
+                "Auto-merge enabled for PR #{}. It will be merged once all merge checks pass.",
+                pr_number
+            ));
+
+            hooks.run_post(
+                &operation,
+                &serde_json::json!({ "merged": false, "auto_merge": true }),
+            );
+            return Ok(());
         } else {
             let url = format!(
                 "https://{}/rest/api/1.0/projects/{}/repos/{}/pull-requests/{}/merge",
@@ -1361,7 +2049,7 @@ impl PrCommand {
                 .post(&url)
                 .bearer_auth(&token)
                 .json(&body)
-                .send()
+                .send_replayable()
                 .await
                 .context("Failed to connect to Bitbucket Server")?;
 
@@ -1380,11 +2068,144 @@ impl PrCommand {
             }
         }
 
+        hooks.run_post(&operation, &serde_json::json!({ "merged": true }));
+
         Ok(())
     }
 
-    /// Close/decline a pull request
-    async fn close(&self, args: &CloseArgs, global: &GlobalOptions) -> Result<()> {
+    /// Polls a Cloud pull request until it meets its merge requirements
+    ///
+    /// Prints progress to stderr whenever what the pull request is waiting
+    /// for changes. Returns `false` if the pull request was merged by
+    /// someone else in the meantime.
+    ///
+    /// # Errors
+    ///
+    /// Fails with an [`ExitError`] carrying [`exit_codes::CHECKS_FAILED`]
+    /// when a build the merge checks require fails or is stopped (builds no
+    /// check requires are ignored), [`exit_codes::PR_CHANGED`] when the
+    /// pull request is declined or gets new commits, and
+    /// [`exit_codes::TIMEOUT`] when `timeout` elapses.
+    async fn wait_until_mergeable(
+        &self,
+        client: &Client,
+        context: &RepoContext,
+        token: &str,
+        pr_number: u32,
+        force: bool,
+        timeout: std::time::Duration,
+    ) -> Result<bool> {
+        let started = std::time::Instant::now();
+        let rules = self.fetch_merge_checks(client, context, token).await?;
+        if rules.is_none() {
+            eprintln!("{} {}", theme().warning_mark(), MERGE_CHECKS_UNREADABLE);
+        }
+        let mut head: Option<String> = None;
+        let mut last_progress = String::new();
+
+        loop {
+            let pr = self
+                .fetch_cloud_pr(client, context, token, pr_number)
+                .await?;
+
+            match pr.state.as_str() {
+                "OPEN" => {}
+                "MERGED" => return Ok(false),
+                state => {
+                    return Err(ExitError::new(
+                        exit_codes::PR_CHANGED,
+                        format!(
+                            "PR #{} is {}; auto-merge cancelled",
+                            pr_number,
+                            state.to_lowercase()
+                        ),
+                    )
+                    .into())
+                }
+            }
+
+            let commit = pr.source.commit.as_ref().map(|c| c.hash.clone());
+            match &head {
+                None => head = commit,
+                Some(first) if commit.as_ref() != Some(first) => {
+                    return Err(ExitError::new(
+                        exit_codes::PR_CHANGED,
+                        format!(
+                            "PR #{} was updated with new commits; auto-merge cancelled",
+                            pr_number
+                        ),
+                    )
+                    .into())
+                }
+                Some(_) => {}
+            }
+
+            let statuses = self
+                .fetch_commit_statuses(client, context, token, pr_number)
+                .await?;
+
+            let checks = cloud_merge_checks(&pr, &statuses, rules.as_ref(), force);
+
+            // A failed build only ends the wait when a merge check requires it
+            let builds_block = checks
+                .iter()
+                .any(|c| c.name == "Builds" && c.state == CheckState::Failed);
+            if let Some(failed) = statuses
+                .iter()
+                .find(|s| s.state == "FAILED" || s.state == "STOPPED")
+                .filter(|_| builds_block)
+            {
+                return Err(ExitError::new(
+                    exit_codes::CHECKS_FAILED,
+                    format!(
+                        "Check '{}' {} on PR #{}; auto-merge cancelled",
+                        failed.name,
+                        if failed.state == "FAILED" {
+                            "failed"
+                        } else {
+                            "was stopped"
+                        },
+                        pr_number
+                    ),
+                )
+                .into());
+            }
+
+            let mergeability = Mergeability::new(pr.id, checks, Vec::new());
+            if mergeability.can_merge {
+                return Ok(true);
+            }
+
+            let progress = mergeability.blockers().join(", ");
+            let elapsed = started.elapsed();
+            if elapsed >= timeout {
+                return Err(ExitError::new(
+                    exit_codes::TIMEOUT,
+                    format!(
+                        "Timed out after {} waiting for PR #{} ({})",
+                        format_duration(timeout),
+                        pr_number,
+                        progress
+                    ),
+                )
+                .into());
+            }
+            if progress != last_progress {
+                eprintln!(
+                    "{} Waiting to merge PR #{}: {}",
+                    theme().pending_mark(),
+                    pr_number,
+                    progress
+                );
+                last_progress = progress;
+            }
+
+            tokio::time::sleep(AUTO_MERGE_POLL_INTERVAL.min(timeout - elapsed)).await;
+        }
+    }
+
+    /// Explain whether a pull request can be merged
+    async fn mergeability(&self, args: &MergeabilityArgs, global: &GlobalOptions) -> Result<()> {
         let config = Config::load().unwrap_or_default();
         let resolver = ContextResolver::new(config);
         let keyring = KeyringStore::new();
@@ -1414,57 +2235,214 @@ impl PrCommand {
             OutputFormat::Table
         });
 
+        let mergeability = self
+            .check_mergeability(&client, &context, &token, pr_number, false)
+            .await?;
+        output.write(&mergeability)?;
+
+        Ok(())
+    }
+
+    /// Works out whether a pull request can be merged
+    ///
+    /// Server reports this itself through the `/merge` resource. On Cloud
+    /// the checklist is computed from the pull request, its build statuses
+    /// and the merge checks on the destination branch; `force` lets open
+    /// tasks through unless a merge check requires them to be resolved.
+    async fn check_mergeability(
+        &self,
+        client: &Client,
+        context: &RepoContext,
+        token: &str,
+        pr_number: u32,
+        force: bool,
+    ) -> Result<Mergeability> {
         if context.host_type == HostType::Cloud {
+            let pr = self
+                .fetch_cloud_pr(client, context, token, pr_number)
+                .await?;
+            let statuses = self
+                .fetch_commit_statuses(client, context, token, pr_number)
+                .await?;
+            let rules = self.fetch_merge_checks(client, context, token).await?;
+
+            let notes = if rules.is_none() {
+                vec![MERGE_CHECKS_UNREADABLE.to_string()]
+            } else {
+                Vec::new()
+            };
+            let checks = cloud_merge_checks(&pr, &statuses, rules.as_ref(), force);
+            Ok(Mergeability::new(pr.id, checks, notes))
+        } else {
             let url = format!(
-                "https://api.bitbucket.org/2.0/repositories/{}/{}/pullrequests/{}/decline",
-                context.owner, context.repo_slug, pr_number
+                "https://{}/rest/api/1.0/projects/{}/repos/{}/pull-requests/{}/merge",
+                context.host, context.owner, context.repo_slug, pr_number
             );
 
             let response = client
-                .post(&url)
-                .bearer_auth(&token)
-                .send()
+                .get(&url)
+                .bearer_auth(token)
+                .send_replayable()
                 .await
-                .context("Failed to connect to Bitbucket Cloud")?;
+                .context("Failed to connect to Bitbucket Server")?;
 
             if !response.status().is_success() {
                 let status = response.status();
                 let text = response.text().await.unwrap_or_default();
-                anyhow::bail!("Failed to decline PR ({}): {}", status, text);
+                return Err(format_api_error(status, &text));
             }
-        } else {
+
+            let status: server_prs::MergeStatus = response.json().await?;
+            Ok(Mergeability::new(
+                pr_number as u64,
+                server_merge_checks(&status),
+                Vec::new(),
+            ))
+        }
+    }
+
+    /// Fetches a Cloud pull request
+    async fn fetch_cloud_pr(
+        &self,
+        client: &Client,
+        context: &RepoContext,
+        token: &str,
+        pr_number: u32,
+    ) -> Result<cloud_prs::PullRequest> {
+        let url = format!(
+            "https://api.bitbucket.org/2.0/repositories/{}/{}/pullrequests/{}",
+            context.owner, context.repo_slug, pr_number
+        );
+
+        let response = client
+            .get(&url)
+            .bearer_auth(token)
+            .send_replayable()
+            .await
+            .context("Failed to connect to Bitbucket Cloud")?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            return Err(format_api_error(status, &text));
+        }
+
+        Ok(response.json().await?)
+    }
+
+    /// Fetches the build statuses of a Cloud pull request
+    async fn fetch_commit_statuses(
+        &self,
+        client: &Client,
+        context: &RepoContext,
+        token: &str,
+        pr_number: u32,
+    ) -> Result<Vec<CommitStatus>> {
+        let url = format!(
+            "https://api.bitbucket.org/2.0/repositories/{}/{}/pullrequests/{}/statuses?pagelen=100",
+            context.owner, context.repo_slug, pr_number
+        );
+
+        let response = client
+            .get(&url)
+            .bearer_auth(token)
+            .send_replayable()
+            .await
+            .context("Failed to connect to Bitbucket Cloud")?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            return Err(format_api_error(status, &text));
+        }
+
+        let statuses: PaginatedResponse<CommitStatus> = response.json().await?;
+        Ok(statuses.values)
+    }
+
+    /// Fetches a Cloud repository's branch restrictions, which hold its
+    /// merge checks, and its branching model when a merge check selects
+    /// branches by type
+    ///
+    /// Reading branch restrictions needs repository admin access; without
+    /// it `None` is returned.
+    async fn fetch_merge_checks(
+        &self,
+        client: &Client,
+        context: &RepoContext,
+        token: &str,
+    ) -> Result<Option<MergeRules>> {
+        let url = format!(
+            "https://api.bitbucket.org/2.0/repositories/{}/{}/branch-restrictions?pagelen=100",
+            context.owner, context.repo_slug
+        );
+
+        let response = client
+            .get(&url)
+            .bearer_auth(token)
+            .send_replayable()
+            .await
+            .context("Failed to connect to Bitbucket Cloud")?;
+
+        let status = response.status();
+        if status == reqwest::StatusCode::FORBIDDEN {
+            return Ok(None);
+        }
+        if !status.is_success() {
+            let text = response.text().await.unwrap_or_default();
+            return Err(format_api_error(status, &text));
+        }
+
+        let restrictions: PaginatedResponse<BranchRestriction> = response.json().await?;
+        let mut rules = MergeRules {
+            restrictions: restrictions.values,
+            model: BranchingModel::default(),
+        };
+
+        if rules
+            .restrictions
+            .iter()
+            .any(|r| r.branch_match_kind == "branching_model")
+        {
             let url = format!(
-                "https://{}/rest/api/1.0/projects/{}/repos/{}/pull-requests/{}/decline",
-                context.host, context.owner, context.repo_slug, pr_number
+                "https://api.bitbucket.org/2.0/repositories/{}/{}/branching-model",
+                context.owner, context.repo_slug
             );
 
             let response = client
-                .post(&url)
-                .bearer_auth(&token)
-                .send()
+                .get(&url)
+                .bearer_auth(token)
+                .send_replayable()
                 .await
-                .context("Failed to connect to Bitbucket Server")?;
+                .context("Failed to connect to Bitbucket Cloud")?;
 
             if !response.status().is_success() {
                 let status = response.status();
                 let text = response.text().await.unwrap_or_default();
-                anyhow::bail!("Failed to decline PR ({}): {}", status, text);
+                return Err(format_api_error(status, &text))
+                    .context("Failed to read the branching model the merge checks refer to");
             }
-        }
 
-        output.write_success(&format!("Declined PR #{}", pr_number));
+            rules.model = response.json().await?;
+        }
 
-        Ok(())
+        Ok(Some(rules))
     }
 
-    /// Reopen a declined PR
-    async fn reopen(&self, args: &ReopenArgs, global: &GlobalOptions) -> Result<()> {
+    /// Close/decline a pull request
+    async fn close(&self, args: &CloseArgs, global: &GlobalOptions) -> Result<()> {
         let config = Config::load().unwrap_or_default();
         let resolver = ContextResolver::new(config);
         let keyring = KeyringStore::new();
 
         let context = resolver.resolve(global)?;
 
+        let pr_number = if let Some(num) = args.number {
+            num
+        } else {
+            self.find_pr_for_current_branch(&context, &keyring).await?
+        };
+
         let token = keyring.get(&context.host)?.ok_or_else(|| {
             anyhow::anyhow!(
                 "Not authenticated for {}. Run 'bb auth login' first.",
@@ -1483,13 +2461,81 @@ impl PrCommand {
         });
 
         if context.host_type == HostType::Cloud {
-            // Cloud: update PR state
             let url = format!(
-                "https://api.bitbucket.org/2.0/repositories/{}/{}/pullrequests/{}",
-                context.owner, context.repo_slug, args.number
+                "https://api.bitbucket.org/2.0/repositories/{}/{}/pullrequests/{}/decline",
+                context.owner, context.repo_slug, pr_number
             );
 
-            #[derive(Serialize)]
+            let response = client
+                .post(&url)
+                .bearer_auth(&token)
+                .send_replayable()
+                .await
+                .context("Failed to connect to Bitbucket Cloud")?;
+
+            if !response.status().is_success() {
+                let status = response.status();
+                let text = response.text().await.unwrap_or_default();
+                anyhow::bail!("Failed to decline PR ({}): {}", status, text);
+            }
+        } else {
+            let url = format!(
+                "https://{}/rest/api/1.0/projects/{}/repos/{}/pull-requests/{}/decline",
+                context.host, context.owner, context.repo_slug, pr_number
+            );
+
+            let response = client
+                .post(&url)
+                .bearer_auth(&token)
+                .send_replayable()
+                .await
+                .context("Failed to connect to Bitbucket Server")?;
+
+            if !response.status().is_success() {
+                let status = response.status();
+                let text = response.text().await.unwrap_or_default();
+                anyhow::bail!("Failed to decline PR ({}): {}", status, text);
+            }
+        }
+
+        output.write_success(&format!("Declined PR #{}", pr_number));
+
+        Ok(())
+    }
+
+    /// Reopen a declined PR
+    async fn reopen(&self, args: &ReopenArgs, global: &GlobalOptions) -> Result<()> {
+        let config = Config::load().unwrap_or_default();
+        let resolver = ContextResolver::new(config);
+        let keyring = KeyringStore::new();
+
+        let context = resolver.resolve(global)?;
+
+        let token = keyring.get(&context.host)?.ok_or_else(|| {
+            anyhow::anyhow!(
+                "Not authenticated for {}. Run 'bb auth login' first.",
+                context.host
+            )
+        })?;
+
+        let client = Client::builder()
+            .user_agent(format!("bb/{}", crate::VERSION))
+            .build()?;
+
+        let output = OutputWriter::new(if global.json {
+            OutputFormat::Json
+        } else {
+            OutputFormat::Table
+        });
+
+        if context.host_type == HostType::Cloud {
+            // Cloud: update PR state
+            let url = format!(
+                "https://api.bitbucket.org/2.0/repositories/{}/{}/pullrequests/{}",
+                context.owner, context.repo_slug, args.number
+            );
+
+            #[derive(Serialize)]
             struct ReopenRequest {
                 state: String,
             }
@@ -1502,7 +2548,7 @@ impl PrCommand {
                 .put(&url)
                 .bearer_auth(&token)
                 .json(&body)
-                .send()
+                .send_replayable()
                 .await?;
 
             if !response.status().is_success() {
@@ -1516,7 +2562,11 @@ impl PrCommand {
                 context.host, context.owner, context.repo_slug, args.number
             );
 
-            let response = client.post(&url).bearer_auth(&token).send().await?;
+            let response = client
+                .post(&url)
+                .bearer_auth(&token)
+                .send_replayable()
+                .await?;
 
             if !response.status().is_success() {
                 let status = response.status();
@@ -1567,7 +2617,11 @@ impl PrCommand {
                 context.owner, context.repo_slug, pr_number
             );
 
-            let response = client.post(&url).bearer_auth(&token).send().await?;
+            let response = client
+                .post(&url)
+                .bearer_auth(&token)
+                .send_replayable()
+                .await?;
 
             if !response.status().is_success() {
                 let status = response.status();
@@ -1580,7 +2634,11 @@ impl PrCommand {
                 context.host, context.owner, context.repo_slug, pr_number
             );
 
-            let response = client.post(&url).bearer_auth(&token).send().await?;
+            let response = client
+                .post(&url)
+                .bearer_auth(&token)
+                .send_replayable()
+                .await?;
 
             if !response.status().is_success() {
                 let status = response.status();
@@ -1635,7 +2693,11 @@ impl PrCommand {
                 context.owner, context.repo_slug, pr_number
             );
 
-            let response = client.post(&url).bearer_auth(&token).send().await?;
+            let response = client
+                .post(&url)
+                .bearer_auth(&token)
+                .send_replayable()
+                .await?;
 
             if !response.status().is_success() {
                 let status = response.status();
@@ -1645,13 +2707,14 @@ impl PrCommand {
 
             // Add comment if provided
             if let Some(body) = &args.body {
-                self.add_comment(&context, &token, pr_number, body).await?;
+                self.add_comment(&context, &token, pr_number, body, None, None)
+                    .await?;
             }
         } else {
             // Server uses needs_work status
             // First add comment if provided, then set status
             if let Some(comment_body) = &args.body {
-                self.add_comment(&context, &token, pr_number, comment_body)
+                self.add_comment(&context, &token, pr_number, comment_body, None, None)
                     .await?;
             }
 
@@ -1702,7 +2765,11 @@ impl PrCommand {
                 context.owner, context.repo_slug, pr_number
             );
 
-            let response = client.delete(&url).bearer_auth(&token).send().await?;
+            let response = client
+                .delete(&url)
+                .bearer_auth(&token)
+                .send_replayable()
+                .await?;
 
             if !response.status().is_success() {
                 let status = response.status();
@@ -1716,7 +2783,11 @@ impl PrCommand {
                 context.host, context.owner, context.repo_slug, pr_number
             );
 
-            let response = client.delete(&url).bearer_auth(&token).send().await?;
+            let response = client
+                .delete(&url)
+                .bearer_auth(&token)
+                .send_replayable()
+                .await?;
 
             if !response.status().is_success() {
                 let status = response.status();
@@ -1757,38 +2828,334 @@ impl PrCommand {
             OutputFormat::Table
         });
 
-        // Add comment if body provided
-        if let Some(body) = &args.body {
-            self.add_comment(&context, &token, pr_number, body).await?;
+        let pending = PendingReview::open(&context, pr_number)?;
+
+        if args.discard {
+            let count = pending.comments.len();
+            pending.discard()?;
+            output.write_success(&format!(
+                "Discarded pending review of PR #{} ({} comment{})",
+                pr_number,
+                count,
+                if count == 1 { "" } else { "s" }
+            ));
+            return Ok(());
         }
 
-        // Perform review action
-        if args.approve {
-            self.approve(
-                &ApproveArgs {
-                    number: Some(pr_number),
-                },
-                global,
-            )
-            .await?;
+        let verdict = if args.approve {
+            Some(ReviewVerdict::Approve)
         } else if args.request_changes {
-            self.request_changes(
-                &RequestChangesArgs {
-                    number: Some(pr_number),
-                    body: None,
-                },
-                global,
-            )
-            .await?;
-        } else if args.comment && args.body.is_some() {
-            output.write_success(&format!("Commented on PR #{}", pr_number));
+            Some(ReviewVerdict::RequestChanges)
+        } else if args.comment {
+            Some(ReviewVerdict::Comment)
+        } else {
+            None
+        };
+
+        if args.interactive {
+            return self
+                .review_interactive(&context, &token, pr_number, pending, verdict, args, global)
+                .await;
+        }
+
+        self.submit_review(
+            &context,
+            &token,
+            pr_number,
+            pending,
+            verdict,
+            args.body.as_deref(),
+            global,
+        )
+        .await
+    }
+
+    /// Walk the PR diff in the terminal, queueing comments in the pending
+    /// review, then submit it
+    ///
+    /// Every comment is saved as soon as it is written, so quitting (or a
+    /// crash) keeps the review for the next session.
+    #[allow(clippy::too_many_arguments)]
+    async fn review_interactive(
+        &self,
+        context: &RepoContext,
+        token: &str,
+        pr_number: u32,
+        mut pending: PendingReview,
+        verdict: Option<ReviewVerdict>,
+        args: &ReviewArgs,
+        global: &GlobalOptions,
+    ) -> Result<()> {
+        if global.no_prompt || !is_interactive() {
+            anyhow::bail!("--interactive needs a terminal. Use --body to review from a script.");
+        }
+
+        let files = diff::parse(&self.fetch_diff(context, token, pr_number).await?);
+        if files.is_empty() {
+            anyhow::bail!("PR #{} has no changes to review", pr_number);
+        }
+
+        let theme = theme();
+        if !pending.is_empty() {
+            println!(
+                "Resuming your pending review of PR #{} ({} comment{})\n",
+                pr_number,
+                pending.comments.len(),
+                if pending.comments.len() == 1 { "" } else { "s" }
+            );
+        }
+
+        const ACTIONS: &[&str] = &[
+            "Next hunk",
+            "Comment on a line",
+            "Add a task on a line",
+            "Skip to the next file",
+            "Finish review",
+            "Quit and keep the pending review",
+        ];
+
+        'files: for (i, file) in files.iter().enumerate() {
+            for (j, hunk) in file.hunks.iter().enumerate() {
+                println!(
+                    "{}",
+                    theme.heading(format!(
+                        "{} (file {}/{}, hunk {}/{})",
+                        file.path(),
+                        i + 1,
+                        files.len(),
+                        j + 1,
+                        file.hunks.len()
+                    ))
+                );
+                println!(
+                    "{}",
+                    render_hunk(hunk, &pending.comments, file.path(), theme)
+                );
+
+                loop {
+                    match select("What next?", ACTIONS)? {
+                        0 => break,
+                        action @ (1 | 2) => {
+                            let choices = hunk_line_choices(hunk);
+                            let labels: Vec<&String> = choices.iter().map(|c| &c.2).collect();
+                            let (side, line, _) = &choices[fuzzy_select("Line", &labels)?];
+
+                            let Some(body) = prompt_editor(None)?
+                                .map(|body| body.trim().to_string())
+                                .filter(|body| !body.is_empty())
+                            else {
+                                println!("{} Empty comment discarded.", theme.warning_mark());
+                                continue;
+                            };
+
+                            let comment = PendingComment {
+                                path: file.path().to_string(),
+                                line: *line,
+                                side: *side,
+                                body,
+                                task: action == 2,
+                                posted_id: None,
+                            };
+                            println!(
+                                "{} Added pending {} on {}",
+                                theme.success_mark(),
+                                if comment.task { "task" } else { "comment" },
+                                comment.location()
+                            );
+                            pending.push(comment)?;
+                        }
+                        3 => continue 'files,
+                        4 => break 'files,
+                        _ => {
+                            println!(
+                                "Saved {} pending comment{} to {}. Run 'bb pr review {} --interactive' to continue.",
+                                pending.comments.len(),
+                                if pending.comments.len() == 1 { "" } else { "s" },
+                                pending.path().display(),
+                                pr_number
+                            );
+                            return Ok(());
+                        }
+                    }
+                }
+            }
+        }
+
+        let verdict = match verdict {
+            Some(verdict) => verdict,
+            None => {
+                const VERDICTS: &[&str] = &[
+                    "Comment",
+                    "Approve",
+                    "Request changes",
+                    "Quit and keep the pending review",
+                ];
+                match select(
+                    &format!(
+                        "Submit review with {} comment{} as",
+                        pending.comments.len(),
+                        if pending.comments.len() == 1 { "" } else { "s" }
+                    ),
+                    VERDICTS,
+                )? {
+                    0 => ReviewVerdict::Comment,
+                    1 => ReviewVerdict::Approve,
+                    2 => ReviewVerdict::RequestChanges,
+                    _ => {
+                        println!("Saved pending review to {}", pending.path().display());
+                        return Ok(());
+                    }
+                }
+            }
+        };
+
+        let body = match &args.body {
+            Some(body) => Some(body.clone()),
+            None => prompt_input_optional("Summary comment (optional)")?,
+        };
+
+        self.submit_review(
+            context,
+            token,
+            pr_number,
+            pending,
+            Some(verdict),
+            body.as_deref(),
+            global,
+        )
+        .await
+    }
+
+    /// Post the pending review's comments, then the summary body and the
+    /// verdict
+    ///
+    /// The anchors are all checked against the current diff before anything
+    /// is posted. Each comment is removed from the pending review once it and
+    /// its task have been posted, and a comment whose task failed keeps the
+    /// posted comment's ID, so a failure part way through can be retried
+    /// without posting duplicates.
+    #[allow(clippy::too_many_arguments)]
+    async fn submit_review(
+        &self,
+        context: &RepoContext,
+        token: &str,
+        pr_number: u32,
+        mut pending: PendingReview,
+        verdict: Option<ReviewVerdict>,
+        body: Option<&str>,
+        global: &GlobalOptions,
+    ) -> Result<()> {
+        let output = OutputWriter::new(if global.json {
+            OutputFormat::Json
+        } else {
+            OutputFormat::Table
+        });
+
+        let posted = pending.comments.len();
+        if posted > 0 {
+            let files = diff::parse(&self.fetch_diff(context, token, pr_number).await?);
+            // Comments already posted only wait for their task
+            let checked = pending
+                .comments
+                .iter()
+                .filter(|comment| comment.posted_id.is_none())
+                .map(|comment| {
+                    check_anchor(
+                        &files,
+                        &InlineAnchor {
+                            path: comment.path.clone(),
+                            line: Some(comment.line),
+                            side: comment.side,
+                            start_line: None,
+                        },
+                    )
+                    .with_context(|| {
+                        format!(
+                            "Pending comment on {} no longer matches the diff. Edit or remove it in {}",
+                            comment.location(),
+                            pending.path().display()
+                        )
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?;
+            let mut checked = checked.iter();
+
+            while let Some(comment) = pending.comments.first().cloned() {
+                let id = match comment.posted_id {
+                    Some(id) => id,
+                    None => {
+                        let id = self
+                            .add_comment(
+                                context,
+                                token,
+                                pr_number,
+                                &comment.body,
+                                checked.next(),
+                                None,
+                            )
+                            .await?;
+                        pending.mark_posted(id)?;
+                        id
+                    }
+                };
+                if comment.task {
+                    self.create_task(context, token, pr_number, &comment.body, Some(id))
+                        .await?;
+                }
+                pending.pop_front()?;
+            }
+        }
+
+        if let Some(body) = body {
+            self.add_comment(context, token, pr_number, body, None, None)
+                .await?;
+        }
+        pending.discard()?;
+
+        if posted > 0 {
+            output.write_success(&format!(
+                "Posted {} review comment{} on PR #{}",
+                posted,
+                if posted == 1 { "" } else { "s" },
+                pr_number
+            ));
+        }
+
+        match verdict {
+            Some(ReviewVerdict::Approve) => {
+                self.approve(
+                    &ApproveArgs {
+                        number: Some(pr_number),
+                    },
+                    global,
+                )
+                .await?;
+            }
+            Some(ReviewVerdict::RequestChanges) => {
+                self.request_changes(
+                    &RequestChangesArgs {
+                        number: Some(pr_number),
+                        body: None,
+                    },
+                    global,
+                )
+                .await?;
+            }
+            Some(ReviewVerdict::Comment) if body.is_some() => {
+                output.write_success(&format!("Commented on PR #{}", pr_number));
+            }
+            _ => {}
         }
 
         Ok(())
     }
 
-    /// Add a comment to a PR
+    /// Add, reply to or edit a comment on a PR
     async fn comment(&self, args: &CommentArgs, global: &GlobalOptions) -> Result<()> {
+        if let Some(command) = &args.command {
+            return self.comment_action(command, global).await;
+        }
+
         let config = Config::load().unwrap_or_default();
         let resolver = ContextResolver::new(config);
         let keyring = KeyringStore::new();
@@ -1801,17 +3168,7 @@ impl PrCommand {
             self.find_pr_for_current_branch(&context, &keyring).await?
         };
 
-        // Get comment body
-        let body = if let Some(b) = &args.body {
-            b.clone()
-        } else if let Some(file) = &args.body_file {
-            std::fs::read_to_string(file)?
-        } else {
-            use dialoguer::Editor;
-            Editor::new()
-                .edit("Enter your comment")?
-                .ok_or_else(|| anyhow::anyhow!("Comment cannot be empty"))?
-        };
+        let anchor = inline_anchor(args)?;
 
         let token = keyring.get(&context.host)?.ok_or_else(|| {
             anyhow::anyhow!(
@@ -1820,27 +3177,95 @@ impl PrCommand {
             )
         })?;
 
-        self.add_comment(&context, &token, pr_number, &body).await?;
-
         let output = OutputWriter::new(if global.json {
             OutputFormat::Json
         } else {
             OutputFormat::Table
         });
-        output.write_success(&format!("Added comment to PR #{}", pr_number));
+
+        let edit_id = if args.edit_last {
+            Some(self.last_own_comment(&context, &token, pr_number).await?)
+        } else {
+            args.edit
+        };
+
+        if let Some(id) = edit_id {
+            let existing = self.fetch_comment(&context, &token, pr_number, id).await?;
+            let body = match comment_body(args)? {
+                Some(body) => body,
+                None => dialoguer::Editor::new()
+                    .edit(&existing.text)?
+                    .ok_or_else(|| anyhow::anyhow!("Comment cannot be empty"))?,
+            };
+
+            self.update_comment(&context, &token, pr_number, id, &body, existing.version)
+                .await?;
+            output.write_success(&format!("Updated comment #{} on PR #{}", id, pr_number));
+            return Ok(());
+        }
+
+        // Check the position before asking for a body that would be rejected
+        let checked = match &anchor {
+            Some(anchor) => {
+                let files = diff::parse(&self.fetch_diff(&context, &token, pr_number).await?);
+                Some(check_anchor(&files, anchor)?)
+            }
+            None => None,
+        };
+
+        let body = match comment_body(args)? {
+            Some(body) => body,
+            None => dialoguer::Editor::new()
+                .edit("Enter your comment")?
+                .ok_or_else(|| anyhow::anyhow!("Comment cannot be empty"))?,
+        };
+
+        self.add_comment(
+            &context,
+            &token,
+            pr_number,
+            &body,
+            checked.as_ref(),
+            args.reply_to,
+        )
+        .await?;
+
+        match (&checked, args.reply_to) {
+            (_, Some(parent)) => output.write_success(&format!(
+                "Replied to comment #{} on PR #{}",
+                parent, pr_number
+            )),
+            (Some(checked), None) => output.write_success(&format!(
+                "Added comment on {} to PR #{}",
+                checked.anchor.location(),
+                pr_number
+            )),
+            (None, None) => output.write_success(&format!("Added comment to PR #{}", pr_number)),
+        }
 
         Ok(())
     }
 
-    /// List comments on a PR
-    async fn comments(&self, args: &CommentsArgs, global: &GlobalOptions) -> Result<()> {
+    /// Delete, resolve or reopen a comment
+    async fn comment_action(
+        &self,
+        command: &CommentSubcommand,
+        global: &GlobalOptions,
+    ) -> Result<()> {
         let config = Config::load().unwrap_or_default();
         let resolver = ContextResolver::new(config);
         let keyring = KeyringStore::new();
 
         let context = resolver.resolve(global)?;
 
-        let pr_number = if let Some(num) = args.number {
+        let (id, pr) = match command {
+            CommentSubcommand::Delete(args) => (args.id, args.pr),
+            CommentSubcommand::Resolve(args) | CommentSubcommand::Reopen(args) => {
+                (args.id, args.pr)
+            }
+        };
+
+        let pr_number = if let Some(num) = pr {
             num
         } else {
             self.find_pr_for_current_branch(&context, &keyring).await?
@@ -1853,19 +3278,61 @@ impl PrCommand {
             )
         })?;
 
-        self.fetch_and_display_comments(&context, &token, pr_number, global)
-            .await
+        let output = OutputWriter::new(if global.json {
+            OutputFormat::Json
+        } else {
+            OutputFormat::Table
+        });
+
+        match command {
+            CommentSubcommand::Delete(args) => {
+                if !args.confirm && !global.no_prompt {
+                    use dialoguer::Confirm;
+                    let confirmed = Confirm::new()
+                        .with_prompt(format!("Delete comment #{} on PR #{}?", id, pr_number))
+                        .default(false)
+                        .interact()?;
+
+                    if !confirmed {
+                        println!("{} Cancelled.", theme().warning_mark());
+                        return Ok(());
+                    }
+                }
+
+                self.delete_comment(&context, &token, pr_number, id).await?;
+                output.write_success(&format!("Deleted comment #{} from PR #{}", id, pr_number));
+            }
+            CommentSubcommand::Resolve(_) => {
+                self.set_thread_resolved(&context, &token, pr_number, id, true)
+                    .await?;
+                output.write_success(&format!("Resolved thread #{} on PR #{}", id, pr_number));
+            }
+            CommentSubcommand::Reopen(_) => {
+                self.set_thread_resolved(&context, &token, pr_number, id, false)
+                    .await?;
+                output.write_success(&format!("Reopened thread #{} on PR #{}", id, pr_number));
+            }
+        }
+
+        Ok(())
     }
 
-    /// Edit a PR
-    async fn edit(&self, args: &EditArgs, global: &GlobalOptions) -> Result<()> {
+    /// List, create, resolve, reopen or delete tasks
+    async fn task(&self, command: &TaskSubcommand, global: &GlobalOptions) -> Result<()> {
         let config = Config::load().unwrap_or_default();
         let resolver = ContextResolver::new(config);
         let keyring = KeyringStore::new();
 
         let context = resolver.resolve(global)?;
 
-        let pr_number = if let Some(num) = args.number {
+        let pr = match command {
+            TaskSubcommand::List(args) => args.number,
+            TaskSubcommand::Create(args) => args.number,
+            TaskSubcommand::Resolve(args) | TaskSubcommand::Reopen(args) => args.pr,
+            TaskSubcommand::Delete(args) => args.pr,
+        };
+
+        let pr_number = if let Some(num) = pr {
             num
         } else {
             self.find_pr_for_current_branch(&context, &keyring).await?
@@ -1878,9 +3345,11 @@ impl PrCommand {
             )
         })?;
 
-        let client = Client::builder()
-            .user_agent(format!("bb/{}", crate::VERSION))
-            .build()?;
+        if context.host_type == HostType::Server {
+            capabilities::detect(&context.host, &token)
+                .await?
+                .require(Capability::BlockerComments)?;
+        }
 
         let output = OutputWriter::new(if global.json {
             OutputFormat::Json
@@ -1888,18 +3357,151 @@ impl PrCommand {
             OutputFormat::Table
         });
 
-        #[derive(Serialize, Default)]
-        struct UpdateRequest {
-            #[serde(skip_serializing_if = "Option::is_none")]
-            title: Option<String>,
-            #[serde(skip_serializing_if = "Option::is_none")]
-            description: Option<String>,
-        }
-
-        let body = UpdateRequest {
-            title: args.title.clone(),
-            description: args.body.clone(),
-        };
+        match command {
+            TaskSubcommand::List(args) => {
+                let mut tasks = self.fetch_tasks(&context, &token, pr_number).await?;
+                if let Some(state) = &args.state {
+                    let state = state.to_uppercase();
+                    tasks.retain(|t| t.state == state);
+                }
+
+                if tasks.is_empty() && !global.json {
+                    println!("No tasks found");
+                } else {
+                    output.write_table(&tasks)?;
+                }
+            }
+            TaskSubcommand::Create(args) => {
+                let id = self
+                    .create_task(&context, &token, pr_number, &args.body, args.comment)
+                    .await?;
+                output.write_success(&format!("Created task #{} on PR #{}", id, pr_number));
+            }
+            TaskSubcommand::Resolve(args) => {
+                self.set_task_resolved(&context, &token, pr_number, args.id, true)
+                    .await?;
+                output.write_success(&format!("Resolved task #{} on PR #{}", args.id, pr_number));
+            }
+            TaskSubcommand::Reopen(args) => {
+                self.set_task_resolved(&context, &token, pr_number, args.id, false)
+                    .await?;
+                output.write_success(&format!("Reopened task #{} on PR #{}", args.id, pr_number));
+            }
+            TaskSubcommand::Delete(args) => {
+                if !args.confirm && !global.no_prompt {
+                    use dialoguer::Confirm;
+                    let confirmed = Confirm::new()
+                        .with_prompt(format!("Delete task #{} on PR #{}?", args.id, pr_number))
+                        .default(false)
+                        .interact()?;
+
+                    if !confirmed {
+                        println!("{} Cancelled.", theme().warning_mark());
+                        return Ok(());
+                    }
+                }
+
+                self.delete_task(&context, &token, pr_number, args.id)
+                    .await?;
+                output.write_success(&format!("Deleted task #{} from PR #{}", args.id, pr_number));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// List comments on a PR
+    async fn comments(&self, args: &CommentsArgs, global: &GlobalOptions) -> Result<()> {
+        let config = Config::load().unwrap_or_default();
+        let resolver = ContextResolver::new(config);
+        let keyring = KeyringStore::new();
+
+        let context = resolver.resolve(global)?;
+
+        let pr_number = if let Some(num) = args.number {
+            num
+        } else {
+            self.find_pr_for_current_branch(&context, &keyring).await?
+        };
+
+        let token = keyring.get(&context.host)?.ok_or_else(|| {
+            anyhow::anyhow!(
+                "Not authenticated for {}. Run 'bb auth login' first.",
+                context.host
+            )
+        })?;
+
+        self.fetch_and_display_comments(&context, &token, pr_number, global)
+            .await
+    }
+
+    /// Edit a PR
+    async fn edit(&self, args: &EditArgs, global: &GlobalOptions) -> Result<()> {
+        let config = Config::load().unwrap_or_default();
+        let resolver = ContextResolver::new(config);
+        let keyring = KeyringStore::new();
+
+        let context = resolver.resolve(global)?;
+
+        let pr_number = if let Some(num) = args.number {
+            num
+        } else {
+            self.find_pr_for_current_branch(&context, &keyring).await?
+        };
+
+        let token = keyring.get(&context.host)?.ok_or_else(|| {
+            anyhow::anyhow!(
+                "Not authenticated for {}. Run 'bb auth login' first.",
+                context.host
+            )
+        })?;
+
+        let client = Client::builder()
+            .user_agent(format!("bb/{}", crate::VERSION))
+            .build()?;
+
+        let output = OutputWriter::new(if global.json {
+            OutputFormat::Json
+        } else {
+            OutputFormat::Table
+        });
+
+        #[derive(Serialize, Default)]
+        struct UpdateRequest {
+            #[serde(skip_serializing_if = "Option::is_none")]
+            title: Option<String>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            description: Option<String>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            reviewers: Option<Vec<cloud_prs::UserUuid>>,
+        }
+
+        let users = UserResolver::new(&context, &token)?;
+        let added = users.resolve_all(&args.add_reviewer).await?;
+        let removed = users.resolve_all(&args.remove_reviewer).await?;
+
+        // Cloud replaces the reviewers as a whole, so start from the current ones
+        let reviewers =
+            if context.host_type == HostType::Cloud && !(added.is_empty() && removed.is_empty()) {
+                let pr = self
+                    .fetch_cloud_pr(&client, &context, &token, pr_number)
+                    .await?;
+                let current = pr.reviewers.into_iter().filter_map(|r| r.uuid).collect();
+                Some(
+                    updated_reviewers(current, &added, &removed)
+                        .into_iter()
+                        .map(|uuid| cloud_prs::UserUuid { uuid })
+                        .collect(),
+                )
+            } else {
+                None
+            };
+
+        let body = UpdateRequest {
+            title: args.title.clone(),
+            description: args.body.clone(),
+            reviewers,
+        };
 
         if context.host_type == HostType::Cloud {
             let url = format!(
@@ -1911,7 +3513,7 @@ impl PrCommand {
                 .put(&url)
                 .bearer_auth(&token)
                 .json(&body)
-                .send()
+                .send_replayable()
                 .await?;
 
             if !response.status().is_success() {
@@ -1919,17 +3521,38 @@ impl PrCommand {
                 let text = response.text().await.unwrap_or_default();
                 anyhow::bail!("Failed to update PR ({}): {}", status, text);
             }
-        } else {
+        } else if args.title.is_some() || args.body.is_some() {
             let url = format!(
                 "https://{}/rest/api/1.0/projects/{}/repos/{}/pull-requests/{}",
                 context.host, context.owner, context.repo_slug, pr_number
             );
 
+            let response = client
+                .get(&url)
+                .bearer_auth(&token)
+                .send_replayable()
+                .await?;
+
+            if !response.status().is_success() {
+                let status = response.status();
+                let text = response.text().await.unwrap_or_default();
+                return Err(format_api_error(status, &text));
+            }
+
+            let pr: server_prs::PullRequest = response.json().await?;
+            let mut update = server_pr_update(&pr);
+            if let Some(title) = body.title {
+                update["title"] = title.into();
+            }
+            if let Some(description) = body.description {
+                update["description"] = description.into();
+            }
+
             let response = client
                 .put(&url)
                 .bearer_auth(&token)
-                .json(&body)
-                .send()
+                .json(&update)
+                .send_replayable()
                 .await?;
 
             if !response.status().is_success() {
@@ -1939,10 +3562,15 @@ impl PrCommand {
             }
         }
 
-        // Add reviewer if specified
-        if let Some(reviewer) = &args.add_reviewer {
-            self.add_reviewer(&context, &token, pr_number, reviewer)
-                .await?;
+        if context.host_type == HostType::Server {
+            for reviewer in &added {
+                self.add_reviewer(&context, &token, pr_number, reviewer)
+                    .await?;
+            }
+            for reviewer in &removed {
+                self.remove_reviewer(&context, &token, pr_number, reviewer)
+                    .await?;
+            }
         }
 
         output.write_success(&format!("Updated PR #{}", pr_number));
@@ -1950,7 +3578,8 @@ impl PrCommand {
         Ok(())
     }
 
-    /// Mark PR as ready for review
+    /// Mark a draft PR as ready for review, or with `--undo` turn it back
+    /// into a draft
     async fn ready(&self, args: &ReadyArgs, global: &GlobalOptions) -> Result<()> {
         let config = Config::load().unwrap_or_default();
         let resolver = ContextResolver::new(config);
@@ -1964,15 +3593,98 @@ impl PrCommand {
             self.find_pr_for_current_branch(&context, &keyring).await?
         };
 
+        let token = keyring.get(&context.host)?.ok_or_else(|| {
+            anyhow::anyhow!(
+                "Not authenticated for {}. Run 'bb auth login' first.",
+                context.host
+            )
+        })?;
+
+        if context.host_type == HostType::Server {
+            capabilities::detect(&context.host, &token)
+                .await?
+                .require(Capability::DraftPullRequests)?;
+        }
+
+        let client = Client::builder()
+            .user_agent(format!("bb/{}", crate::VERSION))
+            .build()?;
+
         let output = OutputWriter::new(if global.json {
             OutputFormat::Json
         } else {
             OutputFormat::Table
         });
 
-        // Bitbucket doesn't have native draft PRs like GitHub
-        println!("Note: Bitbucket doesn't have draft PRs. Your PR is already visible and ready for review.");
-        output.write_success(&format!("PR #{} is ready for review", pr_number));
+        let draft = args.undo;
+        let (done, unchanged) = if draft {
+            ("is now a draft", "is already a draft")
+        } else {
+            ("is ready for review", "is already ready for review")
+        };
+
+        let url = if context.host_type == HostType::Cloud {
+            format!(
+                "https://api.bitbucket.org/2.0/repositories/{}/{}/pullrequests/{}",
+                context.owner, context.repo_slug, pr_number
+            )
+        } else {
+            format!(
+                "https://{}/rest/api/1.0/projects/{}/repos/{}/pull-requests/{}",
+                context.host, context.owner, context.repo_slug, pr_number
+            )
+        };
+
+        let response = client
+            .get(&url)
+            .bearer_auth(&token)
+            .send_replayable()
+            .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            return Err(format_api_error(status, &text));
+        }
+
+        let body = if context.host_type == HostType::Cloud {
+            let pr: cloud_prs::PullRequest = response.json().await?;
+            if pr.state != "OPEN" {
+                anyhow::bail!("PR #{} is {}", pr_number, pr.state.to_lowercase());
+            }
+            if pr.draft == draft {
+                println!("PR #{} {}", pr_number, unchanged);
+                return Ok(());
+            }
+            serde_json::json!({ "draft": draft })
+        } else {
+            let pr: server_prs::PullRequest = response.json().await?;
+            if pr.state != "OPEN" {
+                anyhow::bail!("PR #{} is {}", pr_number, pr.state.to_lowercase());
+            }
+            if pr.draft == draft {
+                println!("PR #{} {}", pr_number, unchanged);
+                return Ok(());
+            }
+            let mut update = server_pr_update(&pr);
+            update["draft"] = draft.into();
+            update
+        };
+
+        let response = client
+            .put(&url)
+            .bearer_auth(&token)
+            .json(&body)
+            .send_replayable()
+            .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            return Err(format_api_error(status, &text));
+        }
+
+        output.write_success(&format!("PR #{} {}", pr_number, done));
 
         Ok(())
     }
@@ -2002,133 +3714,412 @@ impl PrCommand {
             .user_agent(format!("bb/{}", crate::VERSION))
             .build()?;
 
-        if context.host_type == HostType::Cloud {
-            let url = format!(
-                "https://api.bitbucket.org/2.0/repositories/{}/{}/pullrequests/{}/statuses",
-                context.owner, context.repo_slug, pr_number
-            );
-
-            let response = client.get(&url).bearer_auth(&token).send().await?;
+        let output = OutputWriter::new(if global.json {
+            OutputFormat::Json
+        } else {
+            OutputFormat::Table
+        });
 
-            if !response.status().is_success() {
-                let status = response.status();
-                let text = response.text().await.unwrap_or_default();
-                return Err(format_api_error(status, &text));
+        let interval = parse_duration(&args.interval).context("Invalid --interval")?;
+        if interval.is_zero() {
+            anyhow::bail!("--interval must be at least 1s");
+        }
+        let timeout = args
+            .timeout
+            .as_deref()
+            .map(parse_duration)
+            .transpose()
+            .context("Invalid --timeout")?;
+
+        // JSON and templates print once, when watching ends; a terminal gets
+        // the list redrawn in place and piped output each changed list
+        let structured = global.json || template().is_some();
+        let redraw = !structured && stdout_is_tty();
+        let started = std::time::Instant::now();
+        let mut last = String::new();
+
+        let checks = loop {
+            let mut checks = self
+                .fetch_build_checks(&client, &context, &token, pr_number)
+                .await?;
+            if args.steps && context.host_type == HostType::Cloud {
+                self.add_step_progress(&client, &context, &token, &mut checks)
+                    .await?;
             }
 
-            #[derive(Deserialize)]
-            struct StatusResponse {
-                values: Vec<BuildStatus>,
+            let failed = checks.iter().any(BuildCheck::is_failed);
+            // No checks yet usually means CI has not picked up the commit
+            let done = if checks.is_empty() {
+                args.allow_empty
+            } else {
+                checks.iter().all(BuildCheck::is_finished)
+            };
+            let stop = !args.watch || done || (args.fail_fast && failed);
+
+            if !structured {
+                let content = if checks.is_empty() {
+                    format!("No build checks found for PR #{}\n", pr_number)
+                } else {
+                    render_rows(&checks, theme().color_enabled())?
+                };
+                if content != last || redraw {
+                    if redraw && !last.is_empty() {
+                        Term::stdout().clear_last_lines(last.lines().count())?;
+                    }
+                    print!("{}", content);
+                    last = content;
+                }
             }
 
-            #[derive(Deserialize)]
-            struct BuildStatus {
-                state: String,
-                name: String,
-                description: Option<String>,
+            if stop {
+                break checks;
             }
 
-            let statuses: StatusResponse = response.json().await?;
-
-            if statuses.values.is_empty() {
-                println!("No build checks found for PR #{}", pr_number);
-            } else {
-                println!("Build checks for PR #{}:\n", pr_number);
-                for status in &statuses.values {
-                    let state_icon = match status.state.as_str() {
-                        "SUCCESSFUL" => style("✓").green(),
-                        "FAILED" => style("✗").red(),
-                        "INPROGRESS" => style("●").yellow(),
-                        _ => style("?").dim(),
+            let elapsed = started.elapsed();
+            if let Some(timeout) = timeout {
+                if elapsed >= timeout {
+                    if structured {
+                        output.write_table(&checks)?;
+                    }
+                    let running = match checks.iter().filter(|c| !c.is_finished()).count() {
+                        0 => "no checks reported".to_string(),
+                        n => format!("{} still running", n),
                     };
-                    println!(
-                        "{} {} - {}",
-                        state_icon,
-                        status.name,
-                        status.description.as_deref().unwrap_or("")
-                    );
+                    return Err(ExitError::new(
+                        exit_codes::TIMEOUT,
+                        format!(
+                            "Timed out after {} waiting for checks on PR #{} ({})",
+                            format_duration(timeout),
+                            pr_number,
+                            running
+                        ),
+                    )
+                    .into());
                 }
             }
 
-            // Watch mode
-            if args.watch {
-                println!("\nWatching for changes... (Ctrl+C to exit)");
-                loop {
-                    tokio::time::sleep(tokio::time::Duration::from_secs(10)).await;
-
-                    let response = client.get(&url).bearer_auth(&token).send().await?;
-
-                    let statuses: StatusResponse = response.json().await?;
+            let wait = match timeout {
+                Some(timeout) => interval.min(timeout - elapsed),
+                None => interval,
+            };
+            tokio::time::sleep(wait).await;
+        };
 
-                    let all_done = statuses
-                        .values
-                        .iter()
-                        .all(|s| s.state == "SUCCESSFUL" || s.state == "FAILED");
+        if structured {
+            output.write_table(&checks)?;
+        }
 
-                    if all_done {
-                        println!("\nAll checks completed!");
-                        break;
-                    }
-                }
-            }
-        } else {
-            // Server build status
-            println!("Build checks for Server/DC require checking commit statuses.");
-            println!("Use the web interface to view build status at:");
-            println!(
-                "  https://{}/projects/{}/repos/{}/pull-requests/{}",
-                context.host, context.owner, context.repo_slug, pr_number
-            );
+        let failed: Vec<&str> = checks
+            .iter()
+            .filter(|c| c.is_failed())
+            .map(|c| c.name.as_str())
+            .collect();
+        if !failed.is_empty() {
+            return Err(ExitError::new(
+                exit_codes::CHECKS_FAILED,
+                format!(
+                    "{} check{} failed on PR #{}: {}",
+                    failed.len(),
+                    if failed.len() == 1 { "" } else { "s" },
+                    pr_number,
+                    failed.join(", ")
+                ),
+            )
+            .into());
         }
 
         Ok(())
     }
 
-    // Helper methods
-
-    /// Get current branch name
-    fn get_current_branch(&self) -> Result<String> {
-        let output = Command::new("git")
-            .args(["rev-parse", "--abbrev-ref", "HEAD"])
-            .output()
-            .context("Failed to get current branch")?;
+    /// Adds Pipelines step progress to running Cloud checks that were
+    /// started by Bitbucket Pipelines
+    async fn add_step_progress(
+        &self,
+        client: &Client,
+        context: &RepoContext,
+        token: &str,
+        checks: &mut [BuildCheck],
+    ) -> Result<()> {
+        for check in checks.iter_mut().filter(|c| !c.is_finished()) {
+            let Some(build_number) = check.url.as_deref().and_then(pipeline_build_number) else {
+                continue;
+            };
 
-        if !output.status.success() {
-            anyhow::bail!("Not in a git repository");
-        }
+            let url = format!(
+                "https://api.bitbucket.org/2.0/repositories/{}/{}/pipelines/{}/steps/",
+                context.owner, context.repo_slug, build_number
+            );
 
-        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
-    }
+            let response = client
+                .get(&url)
+                .bearer_auth(token)
+                .send_replayable()
+                .await
+                .context("Failed to connect to Bitbucket Cloud")?;
 
-    /// Get first commit message for auto-fill
-    fn get_first_commit_message(&self, source: &str, dest: &str) -> Result<String> {
-        let output = Command::new("git")
-            .args(["log", "--format=%s", "-1", &format!("{}..{}", dest, source)])
-            .output()?;
+            if !response.status().is_success() {
+                let status = response.status();
+                let text = response.text().await.unwrap_or_default();
+                return Err(format_api_error(status, &text));
+            }
 
-        if output.status.success() && !output.stdout.is_empty() {
-            Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
-        } else {
-            // Fallback to current branch name
-            Ok(source.to_string())
+            let steps: PaginatedResponse<PipelineStep> = response.json().await?;
+            check.progress = step_progress(&steps.values);
         }
-    }
-
-    /// Get commit descriptions for auto-fill
-    fn get_commit_descriptions(&self, source: &str, dest: &str) -> Result<String> {
-        let output = Command::new("git")
-            .args(["log", "--format=%B", &format!("{}..{}", dest, source)])
-            .output()?;
 
-        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+        Ok(())
     }
 
-    /// Find PR for current branch
-    async fn find_pr_for_current_branch(
+    /// Fetches the builds reported on a pull request
+    ///
+    /// Cloud lists them per pull request. Server reports them per commit,
+    /// so the statuses of the pull request's latest source commit are read,
+    /// and builds required by a required-builds merge check on the target
+    /// branch are marked (Data Center 7.14+).
+    async fn fetch_build_checks(
         &self,
+        client: &Client,
         context: &RepoContext,
-        keyring: &KeyringStore,
-    ) -> Result<u32> {
+        token: &str,
+        pr_number: u32,
+    ) -> Result<Vec<BuildCheck>> {
+        if context.host_type == HostType::Cloud {
+            let statuses = self
+                .fetch_commit_statuses(client, context, token, pr_number)
+                .await?;
+            return Ok(statuses.into_iter().map(BuildCheck::from).collect());
+        }
+
+        let url = format!(
+            "https://{}/rest/api/1.0/projects/{}/repos/{}/pull-requests/{}",
+            context.host, context.owner, context.repo_slug, pr_number
+        );
+
+        let response = client
+            .get(&url)
+            .bearer_auth(token)
+            .send_replayable()
+            .await
+            .context("Failed to connect to Bitbucket Server")?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            return Err(format_api_error(status, &text));
+        }
+
+        let pr: server_prs::PullRequest = response.json().await?;
+        let Some(commit) = pr.from_ref.latest_commit.as_deref() else {
+            return Ok(Vec::new());
+        };
+
+        let url = format!(
+            "https://{}/rest/build-status/1.0/commits/{}?limit=100",
+            context.host, commit
+        );
+
+        let response = client
+            .get(&url)
+            .bearer_auth(token)
+            .send_replayable()
+            .await
+            .context("Failed to connect to Bitbucket Server")?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            return Err(format_api_error(status, &text));
+        }
+
+        let statuses: ServerPaginatedResponse<server_builds::BuildStatus> = response.json().await?;
+
+        let required: BTreeSet<String> = if capabilities::detect(&context.host, token)
+            .await?
+            .supports(Capability::RequiredBuilds)
+        {
+            let conditions = self.fetch_required_builds(client, context, token).await?;
+            let model = if conditions
+                .iter()
+                .any(server_builds::RequiredBuildCondition::uses_branch_model)
+            {
+                self.fetch_branch_model(client, context, token).await?
+            } else {
+                server_builds::BranchModel::default()
+            };
+            conditions
+                .iter()
+                .filter(|c| c.applies_to(&pr.from_ref.display_id, &pr.to_ref.display_id, &model))
+                .flat_map(|c| c.build_parent_keys.iter().cloned())
+                .collect()
+        } else {
+            BTreeSet::new()
+        };
+
+        let mut checks: Vec<BuildCheck> = statuses
+            .values
+            .into_iter()
+            .map(|status| BuildCheck {
+                required: required.contains(&status.key),
+                name: status.name.unwrap_or_else(|| status.key.clone()),
+                state: status.state,
+                key: status.key,
+                url: status.url,
+                description: status.description,
+                duration: status.duration.map(|ms| ms / 1000),
+                progress: None,
+            })
+            .collect();
+
+        // A required build that has not reported yet still blocks the merge
+        for key in required {
+            if !checks.iter().any(|check| check.key == key) {
+                checks.push(BuildCheck {
+                    state: "PENDING".to_string(),
+                    name: key.clone(),
+                    key,
+                    url: None,
+                    description: Some("Required build has not reported a status".to_string()),
+                    duration: None,
+                    required: true,
+                    progress: None,
+                });
+            }
+        }
+
+        Ok(checks)
+    }
+
+    /// Fetches a Server repository's required-builds merge checks
+    ///
+    /// Returns none, with a warning when access is denied, if the
+    /// required-builds API cannot be read.
+    async fn fetch_required_builds(
+        &self,
+        client: &Client,
+        context: &RepoContext,
+        token: &str,
+    ) -> Result<Vec<server_builds::RequiredBuildCondition>> {
+        let url = format!(
+            "https://{}/rest/required-builds/latest/projects/{}/repos/{}/conditions?limit=100",
+            context.host, context.owner, context.repo_slug
+        );
+
+        let response = client
+            .get(&url)
+            .bearer_auth(token)
+            .send_replayable()
+            .await
+            .context("Failed to connect to Bitbucket Server")?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(Vec::new());
+        }
+        if response.status() == reqwest::StatusCode::FORBIDDEN {
+            eprintln!(
+                "{} Cannot read the repository's required builds (permission denied); no build is marked as required",
+                theme().warning_mark()
+            );
+            return Ok(Vec::new());
+        }
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            return Err(format_api_error(status, &text));
+        }
+
+        let conditions: ServerPaginatedResponse<server_builds::RequiredBuildCondition> =
+            response.json().await?;
+        Ok(conditions.values)
+    }
+
+    /// Fetches a Server repository's branching model, which required-builds
+    /// conditions may select branches by
+    ///
+    /// Returns an empty model, with a warning, if it cannot be read; the
+    /// conditions that need it then match no branch.
+    async fn fetch_branch_model(
+        &self,
+        client: &Client,
+        context: &RepoContext,
+        token: &str,
+    ) -> Result<server_builds::BranchModel> {
+        let url = format!(
+            "https://{}/rest/branch-utils/1.0/projects/{}/repos/{}/branchmodel",
+            context.host, context.owner, context.repo_slug
+        );
+
+        let response = client
+            .get(&url)
+            .bearer_auth(token)
+            .send_replayable()
+            .await
+            .context("Failed to connect to Bitbucket Server")?;
+
+        if matches!(
+            response.status(),
+            reqwest::StatusCode::NOT_FOUND | reqwest::StatusCode::FORBIDDEN
+        ) {
+            eprintln!(
+                "{} Cannot read the repository's branching model; builds required by branch type are not marked as required",
+                theme().warning_mark()
+            );
+            return Ok(server_builds::BranchModel::default());
+        }
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            return Err(format_api_error(status, &text))
+                .context("Failed to read the branching model the required builds refer to");
+        }
+
+        Ok(response.json().await?)
+    }
+
+    // Helper methods
+
+    /// Get current branch name
+    fn get_current_branch(&self) -> Result<String> {
+        let output = Command::new("git")
+            .args(["rev-parse", "--abbrev-ref", "HEAD"])
+            .output()
+            .context("Failed to get current branch")?;
+
+        if !output.status.success() {
+            anyhow::bail!("Not in a git repository");
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+
+    /// Get first commit message for auto-fill
+    fn get_first_commit_message(&self, source: &str, dest: &str) -> Result<String> {
+        let output = Command::new("git")
+            .args(["log", "--format=%s", "-1", &format!("{}..{}", dest, source)])
+            .output()?;
+
+        if output.status.success() && !output.stdout.is_empty() {
+            Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+        } else {
+            // Fallback to current branch name
+            Ok(source.to_string())
+        }
+    }
+
+    /// Get commit descriptions for auto-fill
+    fn get_commit_descriptions(&self, source: &str, dest: &str) -> Result<String> {
+        let output = Command::new("git")
+            .args(["log", "--format=%B", &format!("{}..{}", dest, source)])
+            .output()?;
+
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+
+    /// Find PR for current branch
+    async fn find_pr_for_current_branch(
+        &self,
+        context: &RepoContext,
+        keyring: &KeyringStore,
+    ) -> Result<u32> {
         let current_branch = self.get_current_branch()?;
 
         let token = keyring.get(&context.host)?.ok_or_else(|| {
@@ -2148,7 +4139,11 @@ impl PrCommand {
                 context.owner, context.repo_slug, current_branch
             );
 
-            let response = client.get(&url).bearer_auth(&token).send().await?;
+            let response = client
+                .get(&url)
+                .bearer_auth(&token)
+                .send_replayable()
+                .await?;
 
             if response.status().is_success() {
                 let prs: PaginatedResponse<cloud_prs::PullRequest> = response.json().await?;
@@ -2162,7 +4157,11 @@ impl PrCommand {
                 context.host, context.owner, context.repo_slug
             );
 
-            let response = client.get(&url).bearer_auth(&token).send().await?;
+            let response = client
+                .get(&url)
+                .bearer_auth(&token)
+                .send_replayable()
+                .await?;
 
             if response.status().is_success() {
                 let prs: ServerPaginatedResponse<server_prs::PullRequest> = response.json().await?;
@@ -2202,18 +4201,29 @@ impl PrCommand {
         );
     }
 
-    /// Add a comment to a PR
+    /// Add a comment to a PR and return its ID
+    ///
+    /// With an anchor the comment is attached to a line of the diff, through
+    /// the `inline` object on Cloud and the `anchor` object on Server. With a
+    /// parent it is posted as a reply to that comment.
     async fn add_comment(
         &self,
         context: &RepoContext,
         token: &str,
         pr_number: u32,
         body: &str,
-    ) -> Result<()> {
+        inline: Option<&CheckedAnchor>,
+        parent: Option<u64>,
+    ) -> Result<u64> {
         let client = Client::builder()
             .user_agent(format!("bb/{}", crate::VERSION))
             .build()?;
 
+        #[derive(Deserialize)]
+        struct Created {
+            id: u64,
+        }
+
         if context.host_type == HostType::Cloud {
             let url = format!(
                 "https://api.bitbucket.org/2.0/repositories/{}/{}/pullrequests/{}/comments",
@@ -2223,6 +4233,15 @@ impl PrCommand {
             #[derive(Serialize)]
             struct CommentRequest {
                 content: ContentBody,
+                #[serde(skip_serializing_if = "Option::is_none")]
+                inline: Option<InlineBody>,
+                #[serde(skip_serializing_if = "Option::is_none")]
+                parent: Option<ParentRef>,
+            }
+
+            #[derive(Serialize)]
+            struct ParentRef {
+                id: u64,
             }
 
             #[derive(Serialize)]
@@ -2230,17 +4249,51 @@ impl PrCommand {
                 raw: String,
             }
 
+            #[derive(Serialize)]
+            struct InlineBody {
+                path: String,
+                #[serde(skip_serializing_if = "Option::is_none")]
+                from: Option<u32>,
+                #[serde(skip_serializing_if = "Option::is_none")]
+                to: Option<u32>,
+                #[serde(skip_serializing_if = "Option::is_none")]
+                start_from: Option<u32>,
+                #[serde(skip_serializing_if = "Option::is_none")]
+                start_to: Option<u32>,
+            }
+
             let request_body = CommentRequest {
                 content: ContentBody {
                     raw: body.to_string(),
                 },
+                inline: inline.map(|checked| {
+                    let anchor = &checked.anchor;
+                    let (line, start) = (anchor.line, anchor.start_line);
+                    match anchor.side {
+                        Side::Old => InlineBody {
+                            path: anchor.path.clone(),
+                            from: line,
+                            to: None,
+                            start_from: start,
+                            start_to: None,
+                        },
+                        Side::New => InlineBody {
+                            path: anchor.path.clone(),
+                            from: None,
+                            to: line,
+                            start_from: None,
+                            start_to: start,
+                        },
+                    }
+                }),
+                parent: parent.map(|id| ParentRef { id }),
             };
 
             let response = client
                 .post(&url)
                 .bearer_auth(token)
                 .json(&request_body)
-                .send()
+                .send_replayable()
                 .await?;
 
             if !response.status().is_success() {
@@ -2248,6 +4301,9 @@ impl PrCommand {
                 let text = response.text().await.unwrap_or_default();
                 anyhow::bail!("Failed to add comment ({}): {}", status, text);
             }
+
+            let created: Created = response.json().await?;
+            Ok(created.id)
         } else {
             let url = format!(
                 "https://{}/rest/api/1.0/projects/{}/repos/{}/pull-requests/{}/comments",
@@ -2257,17 +4313,65 @@ impl PrCommand {
             #[derive(Serialize)]
             struct CommentRequest {
                 text: String,
+                #[serde(skip_serializing_if = "Option::is_none")]
+                anchor: Option<AnchorBody>,
+                #[serde(skip_serializing_if = "Option::is_none")]
+                parent: Option<ParentRef>,
+            }
+
+            #[derive(Serialize)]
+            struct ParentRef {
+                id: u64,
+            }
+
+            #[derive(Serialize)]
+            #[serde(rename_all = "camelCase")]
+            struct AnchorBody {
+                path: String,
+                #[serde(skip_serializing_if = "Option::is_none")]
+                src_path: Option<String>,
+                line: Option<u32>,
+                line_type: &'static str,
+                file_type: &'static str,
+                diff_type: &'static str,
+                #[serde(skip_serializing_if = "Option::is_none")]
+                multiline_marker: Option<MultilineMarker>,
+            }
+
+            #[derive(Serialize)]
+            #[serde(rename_all = "camelCase")]
+            struct MultilineMarker {
+                start_line: u32,
+                start_line_type: &'static str,
             }
 
             let request_body = CommentRequest {
                 text: body.to_string(),
+                anchor: inline.map(|checked| AnchorBody {
+                    path: checked.anchor.path.clone(),
+                    src_path: checked.src_path.clone(),
+                    line: checked.anchor.line,
+                    line_type: server_line_type(checked.line_kind),
+                    file_type: match checked.anchor.side {
+                        Side::Old => "FROM",
+                        Side::New => "TO",
+                    },
+                    diff_type: "EFFECTIVE",
+                    multiline_marker: checked.anchor.start_line.zip(checked.start_kind).map(
+                        |(start_line, kind)| MultilineMarker {
+                            start_line,
+                            start_line_type: server_line_type(kind),
+                        },
+                    ),
+                }),
+                parent: parent.map(|id| ParentRef { id }),
             };
 
             let response = client
                 .post(&url)
                 .bearer_auth(token)
                 .json(&request_body)
-                .send()
+                .send_replayable()
                 .await?;
 
             if !response.status().is_success() {
@@ -2275,49 +4379,40 @@ impl PrCommand {
                 let text = response.text().await.unwrap_or_default();
                 anyhow::bail!("Failed to add comment ({}): {}", status, text);
             }
-        }
 
-        Ok(())
+            let created: Created = response.json().await?;
+            Ok(created.id)
+        }
     }
 
-    /// Fetch and display comments
-    async fn fetch_and_display_comments(
+    /// Fetch a single comment's text and, on Server, its version
+    async fn fetch_comment(
         &self,
         context: &RepoContext,
         token: &str,
         pr_number: u32,
-        global: &GlobalOptions,
-    ) -> Result<()> {
+        id: u64,
+    ) -> Result<CommentRevision> {
         let client = Client::builder()
             .user_agent(format!("bb/{}", crate::VERSION))
             .build()?;
 
-        let output = OutputWriter::new(if global.json {
-            OutputFormat::Json
-        } else {
-            OutputFormat::Table
-        });
-
-        if context.host_type == HostType::Cloud {
-            let url = format!(
-                "https://api.bitbucket.org/2.0/repositories/{}/{}/pullrequests/{}/comments",
-                context.owner, context.repo_slug, pr_number
-            );
-
-            let response = client.get(&url).bearer_auth(token).send().await?;
+        let response = client
+            .get(comment_url(context, pr_number, id))
+            .bearer_auth(token)
+            .send_replayable()
+            .await?;
 
-            if !response.status().is_success() {
-                let status = response.status();
-                let text = response.text().await.unwrap_or_default();
-                return Err(format_api_error(status, &text));
-            }
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            return Err(format_api_error(status, &text));
+        }
 
+        if context.host_type == HostType::Cloud {
             #[derive(Deserialize)]
             struct CloudComment {
-                id: u64,
-                user: crate::api::common::UserRef,
                 content: ContentBody,
-                created_on: String,
             }
 
             #[derive(Deserialize)]
@@ -2325,142 +4420,1933 @@ impl PrCommand {
                 raw: String,
             }
 
+            let comment: CloudComment = response.json().await?;
+            Ok(CommentRevision {
+                text: comment.content.raw,
+                version: None,
+            })
+        } else {
             #[derive(Deserialize)]
-            struct CommentResponse {
-                values: Vec<CloudComment>,
+            struct ServerComment {
+                text: String,
+                version: u32,
             }
 
-            let comments: CommentResponse = response.json().await?;
+            let comment: ServerComment = response.json().await?;
+            Ok(CommentRevision {
+                text: comment.text,
+                version: Some(comment.version),
+            })
+        }
+    }
 
-            let items: Vec<PrComment> = comments
-                .values
-                .into_iter()
-                .map(|c| PrComment {
-                    id: c.id,
-                    author: c.user.username.unwrap_or(c.user.name),
-                    content: c.content.raw,
-                    created_on: c.created_on,
-                })
-                .collect();
+    /// Replace the text of a comment
+    ///
+    /// On Server the update carries the version the text was read at, so an
+    /// edit made in the meantime is not silently overwritten.
+    async fn update_comment(
+        &self,
+        context: &RepoContext,
+        token: &str,
+        pr_number: u32,
+        id: u64,
+        body: &str,
+        version: Option<u32>,
+    ) -> Result<()> {
+        let client = Client::builder()
+            .user_agent(format!("bb/{}", crate::VERSION))
+            .build()?;
 
-            if items.is_empty() {
-                println!("No comments on PR #{}", pr_number);
-            } else {
-                output.write_list(&items)?;
-            }
+        let request_body = if context.host_type == HostType::Cloud {
+            serde_json::json!({ "content": { "raw": body } })
         } else {
-            let url = format!(
-                "https://{}/rest/api/1.0/projects/{}/repos/{}/pull-requests/{}/activities",
-                context.host, context.owner, context.repo_slug, pr_number
-            );
-
-            let response = client.get(&url).bearer_auth(token).send().await?;
+            serde_json::json!({ "text": body, "version": version.unwrap_or_default() })
+        };
 
-            if !response.status().is_success() {
-                let status = response.status();
-                let text = response.text().await.unwrap_or_default();
-                return Err(format_api_error(status, &text));
-            }
+        let response = client
+            .put(comment_url(context, pr_number, id))
+            .bearer_auth(token)
+            .json(&request_body)
+            .send_replayable()
+            .await?;
 
-            #[derive(Deserialize)]
-            struct Activity {
-                id: u64,
-                user: server_prs::User,
-                action: String,
-                #[serde(default)]
-                comment: Option<ServerComment>,
-                #[serde(rename = "createdDate")]
-                created_date: u64,
-            }
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            return Err(comment_write_error(status, &text, id));
+        }
 
-            #[derive(Deserialize)]
-            struct ServerComment {
-                text: String,
-            }
+        Ok(())
+    }
 
-            #[derive(Deserialize)]
-            struct ActivityResponse {
-                values: Vec<Activity>,
-            }
+    /// Delete a comment
+    async fn delete_comment(
+        &self,
+        context: &RepoContext,
+        token: &str,
+        pr_number: u32,
+        id: u64,
+    ) -> Result<()> {
+        let client = Client::builder()
+            .user_agent(format!("bb/{}", crate::VERSION))
+            .build()?;
 
-            let activities: ActivityResponse = response.json().await?;
+        let mut url = comment_url(context, pr_number, id);
+        if context.host_type == HostType::Server {
+            let existing = self.fetch_comment(context, token, pr_number, id).await?;
+            url.push_str(&format!(
+                "?version={}",
+                existing.version.unwrap_or_default()
+            ));
+        }
 
-            let items: Vec<PrComment> = activities
-                .values
-                .into_iter()
-                .filter(|a| a.action == "COMMENTED" && a.comment.is_some())
-                .map(|a| PrComment {
-                    id: a.id,
-                    author: a.user.display_name,
-                    content: a.comment.map(|c| c.text).unwrap_or_default(),
-                    created_on: format_server_timestamp(a.created_date),
-                })
-                .collect();
+        let response = client
+            .delete(&url)
+            .bearer_auth(token)
+            .send_replayable()
+            .await?;
 
-            if items.is_empty() {
-                println!("No comments on PR #{}", pr_number);
-            } else {
-                output.write_list(&items)?;
-            }
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            return Err(comment_write_error(status, &text, id));
         }
 
         Ok(())
     }
 
-    /// Add a reviewer to a PR
-    async fn add_reviewer(
+    /// Resolve or reopen the thread started by a comment
+    ///
+    /// Cloud resolves a comment through its `/resolve` resource; Server sets
+    /// `threadResolved` on the thread's root comment.
+    async fn set_thread_resolved(
         &self,
         context: &RepoContext,
         token: &str,
         pr_number: u32,
-        reviewer: &str,
+        id: u64,
+        resolved: bool,
     ) -> Result<()> {
         let client = Client::builder()
             .user_agent(format!("bb/{}", crate::VERSION))
             .build()?;
 
-        if context.host_type == HostType::Cloud {
-            println!("Note: Adding reviewers via CLI is limited on Cloud. Use web interface for full control.");
-        } else {
-            let url = format!(
-                "https://{}/rest/api/1.0/projects/{}/repos/{}/pull-requests/{}/participants",
-                context.host, context.owner, context.repo_slug, pr_number
-            );
-
-            #[derive(Serialize)]
-            struct AddReviewer {
-                user: UserRef,
-                role: String,
+        let url = comment_url(context, pr_number, id);
+        let request = if context.host_type == HostType::Cloud {
+            let url = format!("{}/resolve", url);
+            if resolved {
+                client.post(&url)
+            } else {
+                client.delete(&url)
             }
+        } else {
+            let existing = self.fetch_comment(context, token, pr_number, id).await?;
+            client.put(&url).json(&serde_json::json!({
+                "version": existing.version.unwrap_or_default(),
+                "threadResolved": resolved,
+            }))
+        };
 
-            #[derive(Serialize)]
-            struct UserRef {
-                name: String,
-            }
+        let response = request.bearer_auth(token).send_replayable().await?;
 
-            let body = AddReviewer {
-                user: UserRef {
-                    name: reviewer.to_string(),
-                },
-                role: "REVIEWER".to_string(),
-            };
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            return Err(comment_write_error(status, &text, id));
+        }
+
+        Ok(())
+    }
+
+    /// Count the open tasks on a PR, treating a server that predates
+    /// blocker comments as having none
+    async fn fetch_open_task_count(
+        &self,
+        context: &RepoContext,
+        token: &str,
+        pr_number: u32,
+    ) -> Result<usize> {
+        if context.host_type == HostType::Server
+            && !capabilities::detect(&context.host, token)
+                .await?
+                .supports(Capability::BlockerComments)
+        {
+            return Ok(0);
+        }
+
+        Ok(self
+            .fetch_tasks(context, token, pr_number)
+            .await?
+            .iter()
+            .filter(|t| t.is_open())
+            .count())
+    }
+
+    /// Fetch the tasks on a PR
+    ///
+    /// Cloud has a dedicated tasks resource; Server represents tasks as
+    /// blocker comments.
+    async fn fetch_tasks(
+        &self,
+        context: &RepoContext,
+        token: &str,
+        pr_number: u32,
+    ) -> Result<Vec<PrTask>> {
+        let client = Client::builder()
+            .user_agent(format!("bb/{}", crate::VERSION))
+            .build()?;
+
+        let url = if context.host_type == HostType::Cloud {
+            format!(
+                "https://api.bitbucket.org/2.0/repositories/{}/{}/pullrequests/{}/tasks?pagelen=100",
+                context.owner, context.repo_slug, pr_number
+            )
+        } else {
+            format!(
+                "https://{}/rest/api/1.0/projects/{}/repos/{}/pull-requests/{}/blocker-comments?limit=100",
+                context.host, context.owner, context.repo_slug, pr_number
+            )
+        };
+
+        let response = client
+            .get(&url)
+            .bearer_auth(token)
+            .send_replayable()
+            .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            return Err(format_api_error(status, &text));
+        }
+
+        if context.host_type == HostType::Cloud {
+            #[derive(Deserialize)]
+            struct CloudTask {
+                id: u64,
+                state: String,
+                content: ContentBody,
+                creator: crate::api::common::UserRef,
+                created_on: String,
+                #[serde(default)]
+                comment: Option<CloudCommentRef>,
+            }
+
+            #[derive(Deserialize)]
+            struct ContentBody {
+                raw: String,
+            }
+
+            #[derive(Deserialize)]
+            struct CloudCommentRef {
+                id: u64,
+            }
+
+            let tasks: PaginatedResponse<CloudTask> = response.json().await?;
+            Ok(tasks
+                .values
+                .into_iter()
+                .map(|t| PrTask {
+                    id: t.id,
+                    state: if t.state == "RESOLVED" {
+                        "RESOLVED".to_string()
+                    } else {
+                        "OPEN".to_string()
+                    },
+                    content: t.content.raw,
+                    author: t.creator.username.unwrap_or(t.creator.name),
+                    created_on: t.created_on,
+                    comment: t.comment.map(|c| c.id),
+                })
+                .collect())
+        } else {
+            #[derive(Deserialize)]
+            #[serde(rename_all = "camelCase")]
+            struct BlockerComment {
+                id: u64,
+                text: String,
+                author: server_prs::User,
+                created_date: u64,
+                #[serde(default)]
+                state: String,
+            }
+
+            let tasks: ServerPaginatedResponse<BlockerComment> = response.json().await?;
+            Ok(tasks
+                .values
+                .into_iter()
+                .map(|t| PrTask {
+                    id: t.id,
+                    state: if t.state == "RESOLVED" {
+                        "RESOLVED".to_string()
+                    } else {
+                        "OPEN".to_string()
+                    },
+                    content: t.text,
+                    author: t.author.display_name,
+                    created_on: format_server_timestamp(t.created_date),
+                    comment: None,
+                })
+                .collect())
+        }
+    }
+
+    /// Create a task, optionally attached to a comment, and return its ID
+    async fn create_task(
+        &self,
+        context: &RepoContext,
+        token: &str,
+        pr_number: u32,
+        body: &str,
+        comment: Option<u64>,
+    ) -> Result<u64> {
+        let client = Client::builder()
+            .user_agent(format!("bb/{}", crate::VERSION))
+            .build()?;
+
+        let (url, mut request_body) = if context.host_type == HostType::Cloud {
+            (
+                format!(
+                    "https://api.bitbucket.org/2.0/repositories/{}/{}/pullrequests/{}/tasks",
+                    context.owner, context.repo_slug, pr_number
+                ),
+                serde_json::json!({ "content": { "raw": body } }),
+            )
+        } else {
+            (
+                format!(
+                    "https://{}/rest/api/1.0/projects/{}/repos/{}/pull-requests/{}/comments",
+                    context.host, context.owner, context.repo_slug, pr_number
+                ),
+                serde_json::json!({ "text": body, "severity": "BLOCKER" }),
+            )
+        };
+        if let Some(id) = comment {
+            let key = if context.host_type == HostType::Cloud {
+                "comment"
+            } else {
+                "parent"
+            };
+            request_body[key] = serde_json::json!({ "id": id });
+        }
+
+        let response = client
+            .post(&url)
+            .bearer_auth(token)
+            .json(&request_body)
+            .send_replayable()
+            .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            return Err(format_api_error(status, &text));
+        }
+
+        #[derive(Deserialize)]
+        struct Created {
+            id: u64,
+        }
+
+        let created: Created = response.json().await?;
+        Ok(created.id)
+    }
+
+    /// Resolve or reopen a task
+    ///
+    /// On Server the blocker comment's `state` is updated, which needs its
+    /// current version.
+    async fn set_task_resolved(
+        &self,
+        context: &RepoContext,
+        token: &str,
+        pr_number: u32,
+        id: u64,
+        resolved: bool,
+    ) -> Result<()> {
+        let client = Client::builder()
+            .user_agent(format!("bb/{}", crate::VERSION))
+            .build()?;
+
+        let (url, request_body) = if context.host_type == HostType::Cloud {
+            (
+                task_url(context, pr_number, id),
+                serde_json::json!({
+                    "state": if resolved { "RESOLVED" } else { "UNRESOLVED" },
+                }),
+            )
+        } else {
+            let existing = self.fetch_comment(context, token, pr_number, id).await?;
+            (
+                comment_url(context, pr_number, id),
+                serde_json::json!({
+                    "version": existing.version.unwrap_or_default(),
+                    "state": if resolved { "RESOLVED" } else { "OPEN" },
+                }),
+            )
+        };
+
+        let response = client
+            .put(&url)
+            .bearer_auth(token)
+            .json(&request_body)
+            .send_replayable()
+            .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            return Err(format_api_error(status, &text));
+        }
+
+        Ok(())
+    }
+
+    /// Delete a task
+    async fn delete_task(
+        &self,
+        context: &RepoContext,
+        token: &str,
+        pr_number: u32,
+        id: u64,
+    ) -> Result<()> {
+        if context.host_type == HostType::Server {
+            return self.delete_comment(context, token, pr_number, id).await;
+        }
+
+        let client = Client::builder()
+            .user_agent(format!("bb/{}", crate::VERSION))
+            .build()?;
+
+        let response = client
+            .delete(task_url(context, pr_number, id))
+            .bearer_auth(token)
+            .send_replayable()
+            .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            return Err(format_api_error(status, &text));
+        }
+
+        Ok(())
+    }
+
+    /// Find the ID of the authenticated user's most recent comment on a PR
+    async fn last_own_comment(
+        &self,
+        context: &RepoContext,
+        token: &str,
+        pr_number: u32,
+    ) -> Result<u64> {
+        let client = Client::builder()
+            .user_agent(format!("bb/{}", crate::VERSION))
+            .build()?;
+
+        // Cloud comments are matched on the user's UUID, Server ones on the
+        // username that `whoami` reports
+        let me = if context.host_type == HostType::Cloud {
+            #[derive(Deserialize)]
+            struct CloudUser {
+                uuid: String,
+            }
 
             let response = client
-                .post(&url)
+                .get("https://api.bitbucket.org/2.0/user")
                 .bearer_auth(token)
-                .json(&body)
-                .send()
+                .send_replayable()
                 .await?;
 
-            if !response.status().is_success() {
-                let status = response.status();
-                let text = response.text().await.unwrap_or_default();
-                anyhow::bail!("Failed to add reviewer ({}): {}", status, text);
+            if !response.status().is_success() {
+                let status = response.status();
+                let text = response.text().await.unwrap_or_default();
+                return Err(format_api_error(status, &text));
+            }
+
+            response.json::<CloudUser>().await?.uuid
+        } else {
+            let response = client
+                .get(format!(
+                    "https://{}/plugins/servlet/applinks/whoami",
+                    context.host
+                ))
+                .bearer_auth(token)
+                .send_replayable()
+                .await?;
+
+            if !response.status().is_success() {
+                let status = response.status();
+                let text = response.text().await.unwrap_or_default();
+                return Err(format_api_error(status, &text));
+            }
+
+            response.text().await?.trim().to_string()
+        };
+
+        self.fetch_comments(context, token, pr_number)
+            .await?
+            .iter()
+            .filter(|c| c.author_key == me)
+            .map(|c| c.id)
+            .max()
+            .ok_or_else(|| anyhow::anyhow!("You have not commented on PR #{}", pr_number))
+    }
+
+    /// Fetch the unified diff of a PR
+    async fn fetch_diff(
+        &self,
+        context: &RepoContext,
+        token: &str,
+        pr_number: u32,
+    ) -> Result<String> {
+        let client = Client::builder()
+            .user_agent(format!("bb/{}", crate::VERSION))
+            .build()?;
+
+        // Server's `/diff` resource is JSON; the `.diff` suffix gives a patch
+        let url = if context.host_type == HostType::Cloud {
+            format!(
+                "https://api.bitbucket.org/2.0/repositories/{}/{}/pullrequests/{}/diff",
+                context.owner, context.repo_slug, pr_number
+            )
+        } else {
+            format!(
+                "https://{}/rest/api/1.0/projects/{}/repos/{}/pull-requests/{}.diff",
+                context.host, context.owner, context.repo_slug, pr_number
+            )
+        };
+
+        let response = client
+            .get(&url)
+            .bearer_auth(token)
+            .send_replayable()
+            .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            return Err(format_api_error(status, &text));
+        }
+
+        Ok(response.text().await?)
+    }
+
+    /// Fetch and display comments
+    async fn fetch_and_display_comments(
+        &self,
+        context: &RepoContext,
+        token: &str,
+        pr_number: u32,
+        global: &GlobalOptions,
+    ) -> Result<()> {
+        let output = OutputWriter::new(if global.json {
+            OutputFormat::Json
+        } else {
+            OutputFormat::Table
+        });
+
+        let items = self.fetch_comments(context, token, pr_number).await?;
+
+        if items.is_empty() {
+            println!("No comments on PR #{}", pr_number);
+        } else if global.json || template().is_some() {
+            output.write_list(&items)?;
+        } else {
+            print!(
+                "{}",
+                self.render_comment_list(context, token, pr_number, &items)
+                    .await?
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Render comments for the terminal, fetching the diff to show the code
+    /// that inline comments refer to
+    async fn render_comment_list(
+        &self,
+        context: &RepoContext,
+        token: &str,
+        pr_number: u32,
+        comments: &[PrComment],
+    ) -> Result<String> {
+        let files = if comments.iter().any(|c| c.inline.is_some()) {
+            diff::parse(&self.fetch_diff(context, token, pr_number).await?)
+        } else {
+            Vec::new()
+        };
+
+        Ok(render_comments(comments, &files, theme()))
+    }
+
+    /// Fetch the comments on a PR, including inline comments and replies
+    async fn fetch_comments(
+        &self,
+        context: &RepoContext,
+        token: &str,
+        pr_number: u32,
+    ) -> Result<Vec<PrComment>> {
+        let client = Client::builder()
+            .user_agent(format!("bb/{}", crate::VERSION))
+            .build()?;
+
+        if context.host_type == HostType::Cloud {
+            #[derive(Deserialize)]
+            struct CloudComment {
+                id: u64,
+                user: crate::api::common::UserRef,
+                content: ContentBody,
+                created_on: String,
+                #[serde(default)]
+                inline: Option<CloudInline>,
+                #[serde(default)]
+                parent: Option<CloudParent>,
+                #[serde(default)]
+                deleted: bool,
+                #[serde(default)]
+                resolution: Option<serde_json::Value>,
+            }
+
+            #[derive(Deserialize)]
+            struct CloudParent {
+                id: u64,
+            }
+
+            #[derive(Deserialize)]
+            struct CloudInline {
+                path: String,
+                #[serde(default)]
+                from: Option<u32>,
+                #[serde(default)]
+                to: Option<u32>,
+                #[serde(default)]
+                start_from: Option<u32>,
+                #[serde(default)]
+                start_to: Option<u32>,
+            }
+
+            #[derive(Deserialize)]
+            struct ContentBody {
+                raw: String,
+            }
+
+            // Busy PRs span several pages; `--edit-last` needs all of them
+            let mut values: Vec<CloudComment> = Vec::new();
+            let mut next = Some(format!(
+                "https://api.bitbucket.org/2.0/repositories/{}/{}/pullrequests/{}/comments?pagelen=100",
+                context.owner, context.repo_slug, pr_number
+            ));
+            while let Some(url) = next {
+                let response = client
+                    .get(&url)
+                    .bearer_auth(token)
+                    .send_replayable()
+                    .await?;
+
+                if !response.status().is_success() {
+                    let status = response.status();
+                    let text = response.text().await.unwrap_or_default();
+                    return Err(format_api_error(status, &text));
+                }
+
+                let page: PaginatedResponse<CloudComment> = response.json().await?;
+                next = page.next;
+                values.extend(page.values);
+            }
+
+            // Deleted comments are only kept to hold their replies together
+            let parents: HashSet<u64> = values
+                .iter()
+                .filter_map(|c| c.parent.as_ref().map(|p| p.id))
+                .collect();
+
+            let items = values
+                .into_iter()
+                .filter(|c| !c.deleted || parents.contains(&c.id))
+                .map(|c| PrComment {
+                    id: c.id,
+                    author_key: c.user.uuid.clone().unwrap_or_default(),
+                    author: c.user.username.unwrap_or(c.user.name),
+                    content: if c.deleted {
+                        "(deleted)".to_string()
+                    } else {
+                        c.content.raw
+                    },
+                    created_on: c.created_on,
+                    parent: c.parent.map(|p| p.id),
+                    resolved: c.resolution.is_some_and(|r| !r.is_null()),
+                    // `to` is set for lines that exist in the new version
+                    inline: c.inline.map(|i| match i.to {
+                        Some(to) => InlineAnchor {
+                            path: i.path,
+                            line: Some(to),
+                            side: Side::New,
+                            start_line: i.start_to,
+                        },
+                        None => InlineAnchor {
+                            path: i.path,
+                            line: i.from,
+                            side: if i.from.is_some() {
+                                Side::Old
+                            } else {
+                                Side::New
+                            },
+                            start_line: i.start_from,
+                        },
+                    }),
+                })
+                .collect();
+
+            Ok(items)
+        } else {
+            #[derive(Deserialize)]
+            #[serde(rename_all = "camelCase")]
+            struct Activity {
+                action: String,
+                #[serde(default)]
+                comment: Option<ServerComment>,
+                #[serde(default)]
+                comment_anchor: Option<ServerAnchor>,
+            }
+
+            #[derive(Deserialize)]
+            #[serde(rename_all = "camelCase")]
+            struct ServerComment {
+                id: u64,
+                text: String,
+                author: server_prs::User,
+                created_date: u64,
+                #[serde(default)]
+                comments: Vec<ServerComment>,
+                #[serde(default)]
+                thread_resolved: bool,
+            }
+
+            #[derive(Deserialize)]
+            #[serde(rename_all = "camelCase")]
+            struct ServerAnchor {
+                path: String,
+                #[serde(default)]
+                line: Option<u32>,
+                #[serde(default)]
+                file_type: Option<String>,
+                #[serde(default)]
+                multiline_marker: Option<ServerMultilineMarker>,
+            }
+
+            #[derive(Deserialize)]
+            #[serde(rename_all = "camelCase")]
+            struct ServerMultilineMarker {
+                start_line: u32,
+            }
+
+            /// Collects the IDs of every reply below `comment`
+            fn reply_ids(comment: &ServerComment, ids: &mut HashSet<u64>) {
+                for reply in &comment.comments {
+                    ids.insert(reply.id);
+                    reply_ids(reply, ids);
+                }
+            }
+
+            /// Adds a comment and then its replies
+            fn flatten(
+                comment: ServerComment,
+                parent: Option<u64>,
+                anchor: &Option<InlineAnchor>,
+                seen: &mut HashSet<u64>,
+                items: &mut Vec<PrComment>,
+            ) {
+                if !seen.insert(comment.id) {
+                    return;
+                }
+                items.push(PrComment {
+                    id: comment.id,
+                    author: comment.author.display_name,
+                    author_key: comment.author.name,
+                    content: comment.text,
+                    created_on: format_server_timestamp(comment.created_date),
+                    inline: anchor.clone(),
+                    parent,
+                    resolved: comment.thread_resolved,
+                });
+                for reply in comment.comments {
+                    flatten(reply, Some(comment.id), anchor, seen, items);
+                }
+            }
+
+            let mut activities: Vec<Activity> = Vec::new();
+            let mut start = 0;
+            loop {
+                let url = format!(
+                    "https://{}/rest/api/1.0/projects/{}/repos/{}/pull-requests/{}/activities?limit=100&start={}",
+                    context.host, context.owner, context.repo_slug, pr_number, start
+                );
+
+                let response = client
+                    .get(&url)
+                    .bearer_auth(token)
+                    .send_replayable()
+                    .await?;
+
+                if !response.status().is_success() {
+                    let status = response.status();
+                    let text = response.text().await.unwrap_or_default();
+                    return Err(format_api_error(status, &text));
+                }
+
+                let page: ServerPaginatedResponse<Activity> = response.json().await?;
+                activities.extend(page.values);
+                match page.next_page_start {
+                    Some(next) if !page.is_last_page => start = next,
+                    _ => break,
+                }
+            }
+
+            // Each comment appears once per activity that touched it (added,
+            // edited, replied to); threads are rebuilt from the root comments
+            let mut replies = HashSet::new();
+            for activity in &activities {
+                if let Some(comment) = &activity.comment {
+                    reply_ids(comment, &mut replies);
+                }
+            }
+
+            let mut seen = HashSet::new();
+            let mut items = Vec::new();
+            for activity in activities {
+                if activity.action != "COMMENTED" {
+                    continue;
+                }
+                let Some(comment) = activity.comment else {
+                    continue;
+                };
+                if replies.contains(&comment.id) {
+                    continue;
+                }
+                let anchor = activity.comment_anchor.map(|anchor| InlineAnchor {
+                    path: anchor.path,
+                    line: anchor.line,
+                    side: match anchor.file_type.as_deref() {
+                        Some("FROM") => Side::Old,
+                        _ => Side::New,
+                    },
+                    start_line: anchor.multiline_marker.map(|m| m.start_line),
+                });
+                flatten(comment, None, &anchor, &mut seen, &mut items);
+            }
+
+            Ok(items)
+        }
+    }
+
+    /// Add a reviewer to a Server/DC PR
+    async fn add_reviewer(
+        &self,
+        context: &RepoContext,
+        token: &str,
+        pr_number: u32,
+        reviewer: &ResolvedUser,
+    ) -> Result<()> {
+        let client = Client::builder()
+            .user_agent(format!("bb/{}", crate::VERSION))
+            .build()?;
+
+        let url = format!(
+            "https://{}/rest/api/1.0/projects/{}/repos/{}/pull-requests/{}/participants",
+            context.host, context.owner, context.repo_slug, pr_number
+        );
+
+        #[derive(Serialize)]
+        struct AddReviewer {
+            user: UserRef,
+            role: String,
+        }
+
+        #[derive(Serialize)]
+        struct UserRef {
+            name: String,
+        }
+
+        let body = AddReviewer {
+            user: UserRef {
+                name: reviewer.name().to_string(),
+            },
+            role: "REVIEWER".to_string(),
+        };
+
+        let response = client
+            .post(&url)
+            .bearer_auth(token)
+            .json(&body)
+            .send_replayable()
+            .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            anyhow::bail!("Failed to add reviewer ({}): {}", status, text);
+        }
+
+        Ok(())
+    }
+
+    /// Remove a reviewer from a Server/DC PR
+    async fn remove_reviewer(
+        &self,
+        context: &RepoContext,
+        token: &str,
+        pr_number: u32,
+        reviewer: &ResolvedUser,
+    ) -> Result<()> {
+        let client = Client::builder()
+            .user_agent(format!("bb/{}", crate::VERSION))
+            .build()?;
+
+        let url = format!(
+            "https://{}/rest/api/1.0/projects/{}/repos/{}/pull-requests/{}/participants/{}",
+            context.host, context.owner, context.repo_slug, pr_number, reviewer.id
+        );
+
+        let response = client
+            .delete(&url)
+            .bearer_auth(token)
+            .send_replayable()
+            .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            anyhow::bail!("Failed to remove reviewer ({}): {}", status, text);
+        }
+
+        Ok(())
+    }
+}
+
+/// How long `pr merge --auto` waits on Cloud when no --timeout is given
+const AUTO_MERGE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(60 * 60);
+
+/// How often `pr merge --auto` polls a Cloud pull request
+const AUTO_MERGE_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// A build status reported on a Cloud pull request's source commit
+#[derive(Debug, Clone, Default, Deserialize)]
+struct CommitStatus {
+    /// SUCCESSFUL, FAILED, INPROGRESS or STOPPED
+    state: String,
+    name: String,
+    #[serde(default)]
+    key: String,
+    #[serde(default)]
+    url: Option<String>,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    created_on: Option<String>,
+    #[serde(default)]
+    updated_on: Option<String>,
+}
+
+/// A build reported on a pull request, as listed by `pr checks`
+#[derive(Debug, Clone, Serialize)]
+struct BuildCheck {
+    state: String,
+    key: String,
+    name: String,
+    url: Option<String>,
+    description: Option<String>,
+    /// Seconds the build ran for, once it has finished
+    duration: Option<u64>,
+    /// Whether a merge check requires this build to pass
+    required: bool,
+    /// Pipelines step progress, with `--steps`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    progress: Option<String>,
+}
+
+impl BuildCheck {
+    /// Whether the build has finished, successfully or not
+    fn is_finished(&self) -> bool {
+        !matches!(self.state.as_str(), "INPROGRESS" | "PENDING" | "UNKNOWN")
+    }
+
+    /// Whether the build failed or was stopped
+    fn is_failed(&self) -> bool {
+        matches!(self.state.as_str(), "FAILED" | "STOPPED" | "CANCELLED")
+    }
+}
+
+/// Extracts the build number from the URL of a Pipelines result page, e.g.
+/// `https://bitbucket.org/acme/widgets/pipelines/results/12`
+fn pipeline_build_number(url: &str) -> Option<u64> {
+    let (_, rest) = url.split_once("/pipelines/results/")?;
+    let digits: String = rest.chars().take_while(|c| c.is_ascii_digit()).collect();
+    digits.parse().ok()
+}
+
+/// Summarises a running pipeline's steps, e.g. `1/3 steps, running Test`
+fn step_progress(steps: &[PipelineStep]) -> Option<String> {
+    if steps.is_empty() {
+        return None;
+    }
+    let completed = steps.iter().filter(|s| s.state.name == "COMPLETED").count();
+    let mut progress = format!("{}/{} steps", completed, steps.len());
+    if let Some(running) = steps.iter().find(|s| s.state.name == "IN_PROGRESS") {
+        progress.push_str(&format!(", running {}", running.name));
+    }
+    Some(progress)
+}
+
+impl From<CommitStatus> for BuildCheck {
+    fn from(status: CommitStatus) -> Self {
+        let duration = match (&status.created_on, &status.updated_on) {
+            (Some(created), Some(updated)) if status.state != "INPROGRESS" => {
+                let created = chrono::DateTime::parse_from_rfc3339(created).ok();
+                let updated = chrono::DateTime::parse_from_rfc3339(updated).ok();
+                created
+                    .zip(updated)
+                    .and_then(|(c, u)| u.signed_duration_since(c).to_std().ok())
+                    .map(|d| d.as_secs())
+            }
+            _ => None,
+        };
+
+        Self {
+            state: status.state,
+            key: status.key,
+            name: status.name,
+            url: status.url,
+            description: status.description,
+            duration,
+            required: false,
+            progress: None,
+        }
+    }
+}
+
+impl TableRow for BuildCheck {
+    const COLUMNS: &'static [&'static str] = &[
+        "state",
+        "name",
+        "key",
+        "required",
+        "duration",
+        "url",
+        "description",
+    ];
+    const DEFAULT_COLUMNS: &'static [&'static str] =
+        &["state", "name", "required", "duration", "url"];
+
+    fn cell(&self, column: &str) -> String {
+        match column {
+            "state" => self.state.clone(),
+            "name" => match &self.progress {
+                Some(progress) => format!("{} ({})", self.name, progress),
+                None => self.name.clone(),
+            },
+            "key" => self.key.clone(),
+            "required" => if self.required { "required" } else { "" }.to_string(),
+            "duration" => self
+                .duration
+                .map(|secs| format_duration(std::time::Duration::from_secs(secs)))
+                .unwrap_or_default(),
+            "url" => self.url.clone().unwrap_or_default(),
+            "description" => self.description.clone().unwrap_or_default(),
+            _ => String::new(),
+        }
+    }
+
+    fn cell_color(&self, column: &str) -> Option<Color> {
+        match column {
+            "state" => theme().state_color(&self.state),
+            _ => None,
+        }
+    }
+}
+
+impl TableOutput for BuildCheck {
+    fn print_table(&self, _color: bool) {
+        print_row(self);
+    }
+
+    fn print_markdown(&self) {
+        let required = if self.required { " (required)" } else { "" };
+        match &self.url {
+            Some(url) => println!("- **{}** [{}]({}){}", self.state, self.name, url, required),
+            None => println!("- **{}** {}{}", self.state, self.name, required),
+        }
+    }
+}
+
+/// Whether a pull request can be merged, as a checklist
+#[derive(Debug, Clone, Serialize)]
+struct Mergeability {
+    id: u64,
+    can_merge: bool,
+    checks: Vec<MergeCheck>,
+    /// Caveats about how complete the checklist is
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    notes: Vec<String>,
+}
+
+/// One requirement for merging a pull request
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct MergeCheck {
+    name: String,
+    state: CheckState,
+    detail: String,
+}
+
+/// Outcome of a [`MergeCheck`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum CheckState {
+    Passed,
+    /// Not met yet, but may be met without anyone acting (running builds)
+    Pending,
+    Failed,
+    /// Reported for information only, such as a build no merge check requires
+    Info,
+}
+
+impl CheckState {
+    /// Whether the check stops the pull request from being merged
+    fn blocks(self) -> bool {
+        matches!(self, CheckState::Pending | CheckState::Failed)
+    }
+}
+
+impl MergeCheck {
+    fn new(name: &str, state: CheckState, detail: impl Into<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            state,
+            detail: detail.into(),
+        }
+    }
+}
+
+impl Mergeability {
+    fn new(id: u64, checks: Vec<MergeCheck>, notes: Vec<String>) -> Self {
+        Self {
+            id,
+            can_merge: !checks.iter().any(|c| c.state.blocks()),
+            checks,
+            notes,
+        }
+    }
+
+    /// Details of the checks that are not met, e.g. `["0/1 approvals"]`
+    fn blockers(&self) -> Vec<String> {
+        self.checks
+            .iter()
+            .filter(|c| c.state.blocks())
+            .map(|c| c.detail.clone())
+            .collect()
+    }
+
+    /// One-line summary for `pr view`
+    fn summary(&self) -> String {
+        if self.can_merge {
+            "Yes".to_string()
+        } else {
+            format!("No ({})", self.blockers().join(", "))
+        }
+    }
+
+    /// Renders the checklist, one check per line
+    fn render(&self, color: bool) -> String {
+        let theme = theme();
+        let mut out = if self.can_merge {
+            format!("PR #{} can be merged\n\n", self.id)
+        } else {
+            format!("PR #{} cannot be merged\n\n", self.id)
+        };
+        for check in &self.checks {
+            let mark = match (color, check.state) {
+                (true, CheckState::Passed) => theme.success_mark(),
+                (true, CheckState::Pending) => theme.pending_mark(),
+                (true, CheckState::Failed) => theme.failure_mark(),
+                (true, CheckState::Info) => theme.muted("-"),
+                (false, CheckState::Passed) => "[x]".to_string(),
+                (false, CheckState::Pending) => "[~]".to_string(),
+                (false, CheckState::Failed) => "[ ]".to_string(),
+                (false, CheckState::Info) => "[-]".to_string(),
+            };
+            out.push_str(&format!("{} {}: {}\n", mark, check.name, check.detail));
+        }
+        for note in &self.notes {
+            out.push_str(&format!("\nNote: {}\n", note));
+        }
+        out
+    }
+}
+
+/// Summarises mergeability for `pr view`, or `Unknown` with a warning when it
+/// could not be checked, so that e.g. a missing permission on the merge
+/// checks does not hide the pull request itself
+fn mergeability_summary(mergeability: Result<Mergeability>) -> String {
+    match mergeability {
+        Ok(mergeability) => mergeability.summary(),
+        Err(e) => {
+            eprintln!(
+                "{} Could not check whether the PR can be merged: {:#}",
+                theme().warning_mark(),
+                e
+            );
+            "Unknown".to_string()
+        }
+    }
+}
+
+impl TableOutput for Mergeability {
+    fn print_table(&self, color: bool) {
+        print!("{}", self.render(color));
+    }
+
+    fn print_markdown(&self) {
+        println!(
+            "## PR #{} {}",
+            self.id,
+            if self.can_merge {
+                "can be merged"
+            } else {
+                "cannot be merged"
+            }
+        );
+        println!();
+        for check in &self.checks {
+            let mark = if check.state.blocks() { " " } else { "x" };
+            println!("- [{}] **{}**: {}", mark, check.name, check.detail);
+        }
+        for note in &self.notes {
+            println!();
+            println!("> {}", note);
+        }
+    }
+}
+
+/// A Cloud repository's merge checks, with the branching model that the
+/// checks selecting branches by type are matched against
+#[derive(Debug, Default)]
+struct MergeRules {
+    restrictions: Vec<BranchRestriction>,
+    model: BranchingModel,
+}
+
+/// Note added when a Cloud repository's merge checks cannot be read
+const MERGE_CHECKS_UNREADABLE: &str =
+    "Cannot read the repository's merge checks (needs admin access); only builds and tasks were checked";
+
+/// Evaluates a Cloud pull request against its merge requirements
+///
+/// The repository's merge checks (`rules`) that apply to the destination
+/// branch add required approvals, passing builds, resolved tasks and no
+/// requested changes; `None` means they could not be read. Failed, stopped
+/// or running builds only block when a merge check requires passing builds
+/// or the merge checks are unknown. Open tasks block unless `force` is set.
+fn cloud_merge_checks(
+    pr: &cloud_prs::PullRequest,
+    statuses: &[CommitStatus],
+    rules: Option<&MergeRules>,
+    force: bool,
+) -> Vec<MergeCheck> {
+    use CheckState::{Failed, Info, Passed, Pending};
+
+    let branch = &pr.destination.branch.name;
+    let required = |kind: &str| {
+        rules
+            .into_iter()
+            .flat_map(|rules| {
+                rules
+                    .restrictions
+                    .iter()
+                    .filter(move |r| r.kind == kind && r.applies_to(branch, &rules.model))
+            })
+            .map(|r| r.value.unwrap_or(1))
+            .max()
+    };
+    let plural = |n: usize, what: &str| format!("{} {}{}", n, what, if n == 1 { "" } else { "s" });
+
+    let mut checks = Vec::new();
+
+    if pr.state != "OPEN" {
+        checks.push(MergeCheck::new(
+            "State",
+            Failed,
+            format!("pull request is {}", pr.state.to_lowercase()),
+        ));
+    }
+    if pr.draft {
+        checks.push(MergeCheck::new("Draft", Failed, "pull request is a draft"));
+    }
+
+    let approvals = pr.participants.iter().filter(|p| p.approved).count();
+    checks.push(match required("require_approvals_to_merge") {
+        Some(needed) => MergeCheck::new(
+            "Approvals",
+            if approvals >= needed as usize {
+                Passed
+            } else {
+                Failed
+            },
+            format!("{}/{} approvals", approvals, needed),
+        ),
+        None => MergeCheck::new("Approvals", Passed, plural(approvals, "approval")),
+    });
+    if let Some(needed) = required("require_default_reviewer_approvals_to_merge") {
+        let reviewer_approvals = pr
+            .participants
+            .iter()
+            .filter(|p| p.approved && p.role == "REVIEWER")
+            .count();
+        checks.push(MergeCheck::new(
+            "Reviewer approvals",
+            if reviewer_approvals >= needed as usize {
+                Passed
+            } else {
+                Failed
+            },
+            format!("{}/{} reviewer approvals", reviewer_approvals, needed),
+        ));
+    }
+
+    if required("require_no_changes_requested").is_some() {
+        let requested = pr
+            .participants
+            .iter()
+            .filter(|p| p.state.as_deref() == Some("changes_requested"))
+            .count();
+        checks.push(if requested > 0 {
+            MergeCheck::new(
+                "Changes requested",
+                Failed,
+                plural(requested, "change request"),
+            )
+        } else {
+            MergeCheck::new("Changes requested", Passed, "none")
+        });
+    }
+
+    let failed: Vec<&str> = statuses
+        .iter()
+        .filter(|s| s.state == "FAILED" || s.state == "STOPPED")
+        .map(|s| s.name.as_str())
+        .collect();
+    let running = statuses.iter().filter(|s| s.state == "INPROGRESS").count();
+    let passed = statuses.iter().filter(|s| s.state == "SUCCESSFUL").count();
+    let needed = required("require_passing_builds_to_merge");
+    let short = needed.filter(|&n| passed < n as usize);
+    let (state, detail) = if !failed.is_empty() {
+        (Failed, format!("failed: {}", failed.join(", ")))
+    } else if running > 0 || short.is_some() {
+        let mut parts = Vec::new();
+        if running > 0 {
+            parts.push(plural(running, "build") + " running");
+        }
+        if let Some(n) = short {
+            parts.push(format!("{}/{} passing builds", passed, n));
+        }
+        (if running > 0 { Pending } else { Failed }, parts.join(", "))
+    } else if statuses.is_empty() {
+        (Passed, "none reported".to_string())
+    } else {
+        (Passed, format!("{} passed", passed))
+    };
+    // Bitbucket ignores builds unless a merge check requires them; when the
+    // merge checks cannot be read they are assumed to be required
+    checks.push(if state == Passed || needed.is_some() || rules.is_none() {
+        MergeCheck::new("Builds", state, detail)
+    } else {
+        MergeCheck::new("Builds", Info, detail + " (not required)")
+    });
+
+    let open = pr.task_count as usize;
+    checks.push(if open == 0 {
+        MergeCheck::new("Tasks", Passed, "none open")
+    } else if !force || required("require_tasks_to_be_completed").is_some() {
+        MergeCheck::new("Tasks", Failed, plural(open, "open task"))
+    } else {
+        MergeCheck::new(
+            "Tasks",
+            Passed,
+            plural(open, "open task") + ", merging anyway",
+        )
+    });
+
+    checks
+}
+
+/// Turns Server's merge status into a checklist
+fn server_merge_checks(status: &server_prs::MergeStatus) -> Vec<MergeCheck> {
+    use CheckState::{Failed, Passed, Pending};
+
+    let mut checks = vec![match status.outcome.as_deref() {
+        _ if status.conflicted => MergeCheck::new("Conflicts", Failed, "merge has conflicts"),
+        Some("UNKNOWN") => MergeCheck::new("Conflicts", Pending, "not computed yet"),
+        _ => MergeCheck::new("Conflicts", Passed, "none"),
+    }];
+
+    checks.extend(status.vetoes.iter().map(|veto| {
+        MergeCheck::new(
+            &veto.summary_message,
+            Failed,
+            veto.detailed_message
+                .clone()
+                .unwrap_or_else(|| veto.summary_message.clone()),
+        )
+    }));
+
+    if status.vetoes.is_empty() && !status.conflicted {
+        checks.push(MergeCheck::new(
+            "Merge checks",
+            if status.can_merge { Passed } else { Failed },
+            if status.can_merge {
+                "all passed"
+            } else {
+                "merge not allowed"
+            },
+        ));
+    }
+
+    checks
+}
+
+/// The largest page Cloud returns for pull request lists
+const CLOUD_PR_PAGELEN: u32 = 50;
+
+/// How many pages Server `pr list` reads looking for PRs that match the
+/// filters it applies itself
+const MAX_SERVER_PR_PAGES: usize = 10;
+
+/// Builds the Cloud `pr list` query: `state`, a BBQL `q` and `sort`.
+fn cloud_list_query(args: &ListArgs, sort: Option<&str>, reverse: bool) -> Result<QueryParams> {
+    reject_unsupported_list_filters(args)?;
+
+    let mut q = Bbql::new();
+    if let Some(author) = &args.author {
+        q.equals(&cloud_user_field("author", author), author);
+    }
+    if let Some(reviewer) = &args.reviewer {
+        q.equals(&cloud_user_field("reviewers", reviewer), reviewer);
+    }
+    if let Some(base) = &args.base {
+        q.equals("destination.branch.name", base);
+    }
+    if let Some(head) = &args.head {
+        q.equals("source.branch.name", head);
+    }
+    if let Some(search) = &args.search {
+        q.contains_any(&["title", "description"], search);
+    }
+    if let Some(date) = &args.created_after {
+        q.on_or_after("created_on", &parse_date(date)?);
+    }
+    if let Some(date) = &args.updated_after {
+        q.on_or_after("updated_on", &parse_date(date)?);
+    }
+    if let Some(draft) = draft_filter(args) {
+        q.is("draft", draft);
+    }
+
+    // Columns the API can sort by; anything else is only sorted locally
+    let sort = sort.and_then(|column| match column {
+        "id" | "title" | "state" | "created_on" | "updated_on" => Some(column),
+        _ => None,
+    });
+
+    let mut query = QueryParams::new();
+    query
+        .push("pagelen", args.limit.min(CLOUD_PR_PAGELEN))
+        .push(
+            "state",
+            args.state.as_deref().unwrap_or("open").to_uppercase(),
+        )
+        .push_opt("q", q.build())
+        .push_opt(
+            "sort",
+            sort.map(|field| format!("{}{}", if reverse { "-" } else { "" }, field)),
+        );
+    Ok(query)
+}
+
+/// Builds the Server `pr list` query: `state`, `role.N`/`username.N`
+/// participant filters, `at` and `order`.
+///
+/// Server can filter by one branch only. With both `--base` and `--head`,
+/// `at` holds the base and the head is checked after the response arrives,
+/// as are the dates; `pr list` pages on until enough PRs match, the pages
+/// are older than the dates, or [`MAX_SERVER_PR_PAGES`] have been read.
+fn server_list_query(args: &ListArgs, sort: Option<&str>, reverse: bool) -> Result<QueryParams> {
+    reject_unsupported_list_filters(args)?;
+    for date in [&args.created_after, &args.updated_after]
+        .into_iter()
+        .flatten()
+    {
+        parse_date(date)?;
+    }
+
+    let state = match args.state.as_deref() {
+        Some("open") | None => "OPEN",
+        Some("merged") => "MERGED",
+        Some("declined") => "DECLINED",
+        Some("superseded") => {
+            anyhow::bail!("--state superseded is only available on Bitbucket Cloud")
+        }
+        Some(other) => anyhow::bail!("Invalid state '{}'", other),
+    };
+
+    let mut query = QueryParams::new();
+    query.push("limit", args.limit).push("state", state);
+
+    let participants = [("AUTHOR", &args.author), ("REVIEWER", &args.reviewer)];
+    for (n, (role, user)) in participants
+        .into_iter()
+        .filter_map(|(role, user)| user.as_ref().map(|user| (role, user)))
+        .enumerate()
+    {
+        query
+            .push(format!("role.{}", n + 1), role)
+            .push(format!("username.{}", n + 1), user);
+    }
+
+    match (&args.base, &args.head) {
+        (Some(base), _) => {
+            query.push("at", format!("refs/heads/{}", base));
+        }
+        (None, Some(head)) => {
+            query
+                .push("at", format!("refs/heads/{}", head))
+                .push("direction", "OUTGOING");
+        }
+        (None, None) => {}
+    }
+
+    query
+        .push_opt("filterText", args.search.as_deref())
+        .push_opt("draft", draft_filter(args));
+
+    if sort == Some("id") {
+        query.push("order", if reverse { "NEWEST" } else { "OLDEST" });
+    }
+
+    Ok(query)
+}
+
+/// The `--draft`/`--no-draft` filter: `Some(true)` for drafts only,
+/// `Some(false)` for PRs ready for review, `None` for both.
+fn draft_filter(args: &ListArgs) -> Option<bool> {
+    if args.draft {
+        Some(true)
+    } else if args.no_draft {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+/// The state shown for a PR: `DRAFT` for open drafts, otherwise the API state
+fn display_state(state: String, draft: bool) -> String {
+    if draft && state == "OPEN" {
+        "DRAFT".to_string()
+    } else {
+        state
+    }
+}
+
+/// Fails for `pr list` filters that Bitbucket pull requests have no data for.
+fn reject_unsupported_list_filters(args: &ListArgs) -> Result<()> {
+    if args.assignee.is_some() {
+        anyhow::bail!("Bitbucket pull requests have no assignee; use --reviewer instead");
+    }
+    if args.label.is_some() {
+        anyhow::bail!("Bitbucket pull requests have no labels; use --search instead");
+    }
+    Ok(())
+}
+
+/// Picks the BBQL field for a Cloud user given as a UUID (`{...}`), an
+/// Atlassian account ID (`557058:...`) or a nickname.
+fn cloud_user_field(prefix: &str, user: &str) -> String {
+    let field = if user.starts_with('{') {
+        "uuid"
+    } else if user.contains(':') {
+        "account_id"
+    } else {
+        "nickname"
+    };
+    format!("{}.{}", prefix, field)
+}
+
+/// The reviewers of a PR after `pr edit`: the current ones without the
+/// removed users, followed by the added users that were not reviewers yet
+fn updated_reviewers(
+    current: Vec<String>,
+    added: &[ResolvedUser],
+    removed: &[ResolvedUser],
+) -> Vec<String> {
+    let mut reviewers: Vec<String> = current
+        .into_iter()
+        .filter(|id| !removed.iter().any(|r| &r.id == id))
+        .collect();
+    for user in added {
+        if !reviewers.contains(&user.id) {
+            reviewers.push(user.id.clone());
+        }
+    }
+    reviewers
+}
+
+/// The body given with `--body` or `--body-file`, if any
+fn comment_body(args: &CommentArgs) -> Result<Option<String>> {
+    if let Some(body) = &args.body {
+        Ok(Some(body.clone()))
+    } else if let Some(file) = &args.body_file {
+        Ok(Some(std::fs::read_to_string(file)?))
+    } else {
+        Ok(None)
+    }
+}
+
+/// The API URL of a single PR comment
+fn comment_url(context: &RepoContext, pr_number: u32, id: u64) -> String {
+    if context.host_type == HostType::Cloud {
+        format!(
+            "https://api.bitbucket.org/2.0/repositories/{}/{}/pullrequests/{}/comments/{}",
+            context.owner, context.repo_slug, pr_number, id
+        )
+    } else {
+        format!(
+            "https://{}/rest/api/1.0/projects/{}/repos/{}/pull-requests/{}/comments/{}",
+            context.host, context.owner, context.repo_slug, pr_number, id
+        )
+    }
+}
+
+/// The Cloud API URL of a single PR task
+fn task_url(context: &RepoContext, pr_number: u32, id: u64) -> String {
+    format!(
+        "https://api.bitbucket.org/2.0/repositories/{}/{}/pullrequests/{}/tasks/{}",
+        context.owner, context.repo_slug, pr_number, id
+    )
+}
+
+/// Starts the body of a Server pull request update from the PR as read
+///
+/// Server replaces the title, description and reviewers with what the
+/// update carries, so they are sent unchanged unless the caller overrides
+/// them, along with the version the PR was read at.
+fn server_pr_update(pr: &server_prs::PullRequest) -> serde_json::Value {
+    let reviewers: Vec<_> = pr
+        .reviewers
+        .iter()
+        .map(|r| serde_json::json!({ "user": { "name": r.user.name } }))
+        .collect();
+    serde_json::json!({
+        "version": pr.version,
+        "title": pr.title,
+        "description": pr.description,
+        "reviewers": reviewers,
+    })
+}
+
+/// Error for a failed comment update, explaining Server version conflicts
+fn comment_write_error(status: reqwest::StatusCode, text: &str, id: u64) -> anyhow::Error {
+    if status == reqwest::StatusCode::CONFLICT {
+        anyhow::anyhow!(
+            "Comment #{} was changed by someone else while you were editing. Run the command again to work on the latest version.",
+            id
+        )
+    } else {
+        format_api_error(status, text)
+    }
+}
+
+/// Builds the inline anchor requested by `--file`, `--line`, `--side` and
+/// `--range`, or `None` for a general comment
+fn inline_anchor(args: &CommentArgs) -> Result<Option<InlineAnchor>> {
+    let Some(path) = &args.file else {
+        return Ok(None);
+    };
+
+    let side = match args.side.as_deref() {
+        Some("old") => Side::Old,
+        _ => Side::New,
+    };
+
+    let (start_line, line) = match (&args.range, args.line) {
+        (Some(range), line) => {
+            let (start, end) = parse_line_range(range)?;
+            if let Some(line) = line.filter(|&line| line != end) {
+                anyhow::bail!("--line {} must be the last line of --range {}", line, range);
             }
+            ((start < end).then_some(start), end)
         }
+        (None, Some(line)) => (None, line),
+        (None, None) => anyhow::bail!("--file needs --line or --range"),
+    };
+
+    Ok(Some(InlineAnchor {
+        path: path.trim_start_matches("./").to_string(),
+        line: Some(line),
+        side,
+        start_line,
+    }))
+}
 
-        Ok(())
+/// Parses a `START-END` line range
+fn parse_line_range(range: &str) -> Result<(u32, u32)> {
+    let parsed = range.split_once('-').and_then(|(start, end)| {
+        Some((
+            start.trim().parse::<u32>().ok()?,
+            end.trim().parse::<u32>().ok()?,
+        ))
+    });
+
+    match parsed {
+        Some((start, end)) if start >= 1 && start <= end => Ok((start, end)),
+        _ => anyhow::bail!(
+            "Invalid line range '{}'. Use START-END, e.g. 110-120",
+            range
+        ),
+    }
+}
+
+/// Checks that an inline anchor points at lines in the PR diff
+///
+/// The anchor's path may be either the old or new name of a renamed file;
+/// the returned anchor always uses the new name.
+fn check_anchor(files: &[DiffFile], anchor: &InlineAnchor) -> Result<CheckedAnchor> {
+    let file = diff::find_file(files, &anchor.path)
+        .ok_or_else(|| anyhow::anyhow!("'{}' is not changed in this pull request", anchor.path))?;
+    let side = anchor.side;
+
+    let not_in_diff = |line: u32| {
+        let ranges: Vec<String> = file
+            .ranges(side)
+            .iter()
+            .map(|(start, end)| {
+                if start == end {
+                    start.to_string()
+                } else {
+                    format!("{}-{}", start, end)
+                }
+            })
+            .collect();
+        if ranges.is_empty() {
+            anyhow::anyhow!(
+                "{} has no lines on the {} side of the diff to comment on",
+                file.path(),
+                side
+            )
+        } else {
+            anyhow::anyhow!(
+                "Line {} of {} is not in the diff ({} side). Lines you can comment on: {}",
+                line,
+                file.path(),
+                side,
+                ranges.join(", ")
+            )
+        }
+    };
+
+    let line = anchor
+        .line
+        .ok_or_else(|| anyhow::anyhow!("--file needs --line or --range"))?;
+    let line_kind = file.line(side, line).ok_or_else(|| not_in_diff(line))?.kind;
+
+    let start_kind = match anchor.start_line {
+        Some(start) => {
+            let kind = file
+                .line(side, start)
+                .ok_or_else(|| not_in_diff(start))?
+                .kind;
+            if file.hunk(side, start) != file.hunk(side, line) {
+                anyhow::bail!(
+                    "Lines {}-{} of {} span more than one hunk of the diff",
+                    start,
+                    line,
+                    file.path()
+                );
+            }
+            Some(kind)
+        }
+        None => None,
+    };
+
+    let src_path = match (&file.old_path, &file.new_path) {
+        (Some(old), Some(new)) if old != new => Some(old.clone()),
+        _ => None,
+    };
+
+    Ok(CheckedAnchor {
+        anchor: InlineAnchor {
+            path: file.path().to_string(),
+            ..anchor.clone()
+        },
+        src_path,
+        line_kind,
+        start_kind,
+    })
+}
+
+/// The Server `lineType` for a diff line
+fn server_line_type(kind: LineKind) -> &'static str {
+    match kind {
+        LineKind::Added => "ADDED",
+        LineKind::Removed => "REMOVED",
+        LineKind::Context => "CONTEXT",
+    }
+}
+
+/// Number of diff lines shown above an inline comment's position
+const EXCERPT_CONTEXT: usize = 3;
+
+/// Renders comments for the terminal
+///
+/// Comments are shown as threads, with replies indented below the comment
+/// they answer. General threads come first. Inline threads follow, grouped
+/// by file and ordered by line, each group of threads on the same position
+/// shown under the lines of the diff leading up to it.
+fn render_comments(comments: &[PrComment], files: &[DiffFile], theme: &Theme) -> String {
+    let color = theme.color_enabled();
+    let ids: HashSet<u64> = comments.iter().map(|c| c.id).collect();
+
+    // A reply whose parent is missing (e.g. on another page) starts a thread
+    let mut roots: Vec<&PrComment> = Vec::new();
+    let mut replies: HashMap<u64, Vec<&PrComment>> = HashMap::new();
+    for comment in comments {
+        match comment
+            .parent
+            .filter(|parent| *parent != comment.id && ids.contains(parent))
+        {
+            Some(parent) => replies.entry(parent).or_default().push(comment),
+            None => roots.push(comment),
+        }
+    }
+    roots.sort_by_key(|c| c.id);
+    for thread in replies.values_mut() {
+        thread.sort_by_key(|c| c.id);
+    }
+
+    let mut content = String::new();
+    for root in roots.iter().filter(|c| c.inline.is_none()) {
+        render_thread(&mut content, root, &replies, color, 0);
+    }
+
+    let mut by_file: BTreeMap<&str, Vec<(&InlineAnchor, &PrComment)>> = BTreeMap::new();
+    for root in &roots {
+        if let Some(anchor) = &root.inline {
+            by_file
+                .entry(anchor.path.as_str())
+                .or_default()
+                .push((anchor, root));
+        }
+    }
+
+    for (path, mut entries) in by_file {
+        entries.sort_by_key(|(anchor, comment)| (anchor.line, comment.id));
+        content.push_str(&format!("{}\n", theme.heading(path)));
+
+        let mut previous: Option<&InlineAnchor> = None;
+        for (anchor, comment) in entries {
+            if previous != Some(anchor) {
+                match diff_excerpt(files, anchor) {
+                    Some(excerpt) => {
+                        content.push_str(&format!("{}\n", colorize_diff(&excerpt, theme)))
+                    }
+                    None if anchor.line.is_some() => content
+                        .push_str(&format!("{} (no longer in the diff)\n", anchor.location())),
+                    None => {}
+                }
+                previous = Some(anchor);
+            }
+            render_thread(&mut content, comment, &replies, color, 0);
+        }
+    }
+
+    content
+}
+
+/// Appends a comment and, indented below it, its replies
+fn render_thread(
+    content: &mut String,
+    comment: &PrComment,
+    replies: &HashMap<u64, Vec<&PrComment>>,
+    color: bool,
+    depth: usize,
+) {
+    content.push_str(&comment.render(color, depth));
+    for reply in replies.get(&comment.id).into_iter().flatten() {
+        render_thread(content, reply, replies, color, depth + 1);
+    }
+}
+
+/// The hunk header and diff lines leading up to an inline anchor
+fn diff_excerpt(files: &[DiffFile], anchor: &InlineAnchor) -> Option<String> {
+    let line = anchor.line?;
+    let file = diff::find_file(files, &anchor.path)?;
+    let hunk = file.hunk(anchor.side, line)?;
+    let end = hunk.position(anchor.side, line)?;
+    let start = anchor
+        .start_line
+        .and_then(|start| hunk.position(anchor.side, start))
+        .unwrap_or(end)
+        .min(end);
+
+    let mut lines = vec![hunk.header.clone()];
+    lines.extend(
+        hunk.lines[start.saturating_sub(EXCERPT_CONTEXT)..=end]
+            .iter()
+            .map(|line| line.to_string()),
+    );
+    Some(lines.join("\n"))
+}
+
+/// Renders a hunk for the interactive review, with old and new line
+/// numbers and a marker on lines that have pending comments
+fn render_hunk(hunk: &diff::Hunk, pending: &[PendingComment], path: &str, theme: &Theme) -> String {
+    let number = |n: Option<u32>| n.map(|n| n.to_string()).unwrap_or_default();
+
+    let mut lines = vec![theme.paint(&hunk.header, Color::DarkCyan)];
+    for line in &hunk.lines {
+        let marked = pending
+            .iter()
+            .any(|c| c.path == path && line.number(c.side) == Some(c.line));
+        let text = format!(
+            "{:>5} {:>5} {} {}",
+            number(line.old),
+            number(line.new),
+            if marked { "*" } else { " " },
+            line
+        );
+        lines.push(match line.kind {
+            LineKind::Added => theme.paint(&text, Color::DarkGreen),
+            LineKind::Removed => theme.paint(&text, Color::DarkRed),
+            LineKind::Context => text,
+        });
     }
+    lines.join("\n")
+}
+
+/// The lines of a hunk that can be commented on, as `(side, line, label)`
+///
+/// Removed lines are addressed on the old side and all others on the new
+/// side, as in the pull request's diff view.
+fn hunk_line_choices(hunk: &diff::Hunk) -> Vec<(Side, u32, String)> {
+    hunk.lines
+        .iter()
+        .filter_map(|line| {
+            let side = match line.kind {
+                LineKind::Removed => Side::Old,
+                LineKind::Added | LineKind::Context => Side::New,
+            };
+            let number = line.number(side)?;
+            Some((side, number, format!("{:>5} {}", number, line)))
+        })
+        .collect()
 }
 
 /// Format Unix timestamp (milliseconds) to readable string
@@ -2475,3 +6361,607 @@ fn format_server_timestamp(ms: u64) -> String {
         None => ms.to_string(),
     }
 }
+
+/// Colorize a unified diff for terminal display
+///
+/// Added lines are green, removed lines red, hunk headers cyan and file
+/// headers bold. When the theme has color turned off the diff is returned
+/// unchanged, so piped output stays a plain patch.
+fn colorize_diff(diff: &str, theme: &Theme) -> String {
+    if !theme.color_enabled() {
+        return diff.to_string();
+    }
+
+    diff.lines()
+        .map(|line| {
+            if line.starts_with("diff --git")
+                || line.starts_with("+++")
+                || line.starts_with("---")
+                || line.starts_with("index ")
+            {
+                theme.heading(line)
+            } else if line.starts_with("@@") {
+                theme.paint(line, Color::DarkCyan)
+            } else if line.starts_with('+') {
+                theme.paint(line, Color::DarkGreen)
+            } else if line.starts_with('-') {
+                theme.paint(line, Color::DarkRed)
+            } else {
+                line.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn list_args() -> ListArgs {
+        ListArgs {
+            state: None,
+            author: None,
+            reviewer: None,
+            assignee: None,
+            base: None,
+            head: None,
+            label: None,
+            limit: 30,
+            search: None,
+            created_after: None,
+            updated_after: None,
+            draft: false,
+            no_draft: false,
+        }
+    }
+
+    #[test]
+    fn test_cloud_list_query() {
+        assert_eq!(
+            cloud_list_query(&list_args(), None, false)
+                .unwrap()
+                .to_string(),
+            "pagelen=30&state=OPEN"
+        );
+
+        let args = ListArgs {
+            state: Some("merged".to_string()),
+            author: Some("{1234}".to_string()),
+            reviewer: Some("557058:abcd".to_string()),
+            head: Some("feature/x".to_string()),
+            created_after: Some("2026-01-31".to_string()),
+            ..list_args()
+        };
+        let query = cloud_list_query(&args, Some("updated_on"), true).unwrap();
+        assert_eq!(query.get("state"), Some("MERGED"));
+        assert_eq!(
+            query.get("q"),
+            Some(
+                "author.uuid = \"{1234}\" AND reviewers.account_id = \"557058:abcd\" AND \
+                 source.branch.name = \"feature/x\" AND created_on >= 2026-01-31T00:00:00+00:00"
+            )
+        );
+        assert_eq!(query.get("sort"), Some("-updated_on"));
+
+        // Columns the API cannot sort by stay client-side
+        let query = cloud_list_query(&list_args(), Some("branches"), false).unwrap();
+        assert_eq!(query.get("sort"), None);
+    }
+
+    #[test]
+    fn test_server_list_query() {
+        let args = ListArgs {
+            author: Some("jdoe".to_string()),
+            reviewer: Some("asmith".to_string()),
+            base: Some("release/1.0".to_string()),
+            search: Some("fix cache".to_string()),
+            ..list_args()
+        };
+        assert_eq!(
+            server_list_query(&args, Some("id"), true)
+                .unwrap()
+                .to_string(),
+            "limit=30&state=OPEN&role.1=AUTHOR&username.1=jdoe&role.2=REVIEWER&username.2=asmith\
+             &at=refs%2Fheads%2Frelease%2F1.0&filterText=fix%20cache&order=NEWEST"
+        );
+
+        let args = ListArgs {
+            reviewer: Some("asmith".to_string()),
+            head: Some("feature/x".to_string()),
+            ..list_args()
+        };
+        let query = server_list_query(&args, None, false).unwrap();
+        assert_eq!(query.get("role.1"), Some("REVIEWER"));
+        assert_eq!(query.get("at"), Some("refs/heads/feature/x"));
+        assert_eq!(query.get("direction"), Some("OUTGOING"));
+    }
+
+    #[test]
+    fn test_list_query_rejects_bad_input() {
+        let args = ListArgs {
+            assignee: Some("jdoe".to_string()),
+            ..list_args()
+        };
+        assert!(cloud_list_query(&args, None, false).is_err());
+        assert!(server_list_query(&args, None, false).is_err());
+
+        let args = ListArgs {
+            updated_after: Some("yesterday".to_string()),
+            ..list_args()
+        };
+        assert!(cloud_list_query(&args, None, false).is_err());
+        assert!(server_list_query(&args, None, false).is_err());
+
+        let args = ListArgs {
+            state: Some("superseded".to_string()),
+            ..list_args()
+        };
+        assert!(cloud_list_query(&args, None, false).is_ok());
+        let err = server_list_query(&args, None, false).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("only available on Bitbucket Cloud"));
+    }
+
+    #[test]
+    fn test_list_queries_filter_drafts() {
+        let drafts = ListArgs {
+            draft: true,
+            ..list_args()
+        };
+        assert_eq!(
+            cloud_list_query(&drafts, None, false).unwrap().get("q"),
+            Some("draft = true")
+        );
+        assert_eq!(
+            server_list_query(&drafts, None, false)
+                .unwrap()
+                .get("draft"),
+            Some("true")
+        );
+
+        let ready = ListArgs {
+            no_draft: true,
+            ..list_args()
+        };
+        assert_eq!(
+            cloud_list_query(&ready, None, false).unwrap().get("q"),
+            Some("draft = false")
+        );
+        assert_eq!(
+            server_list_query(&list_args(), None, false)
+                .unwrap()
+                .get("draft"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_display_state() {
+        assert_eq!(display_state("OPEN".to_string(), true), "DRAFT");
+        assert_eq!(display_state("OPEN".to_string(), false), "OPEN");
+        assert_eq!(display_state("MERGED".to_string(), true), "MERGED");
+    }
+
+    const DIFF: &str = "diff --git a/src/lib.rs b/src/lib.rs\n\
+                        --- a/src/lib.rs\n\
+                        +++ b/src/lib.rs\n\
+                        @@ -1,2 +1,2 @@\n \
+                        fn main() {\n\
+                        -    old();\n\
+                        +    new();";
+
+    #[test]
+    fn test_colorize_diff_without_color_is_unchanged() {
+        assert_eq!(colorize_diff(DIFF, &Theme::new(false, false)), DIFF);
+    }
+
+    #[test]
+    fn test_colorize_diff_styles_changed_lines() {
+        let colored = colorize_diff(DIFF, &Theme::new(true, false));
+        let lines: Vec<&str> = colored.lines().collect();
+
+        assert_eq!(lines.len(), DIFF.lines().count());
+        assert_eq!(lines[3], "\u{1b}[36m@@ -1,2 +1,2 @@\u{1b}[0m");
+        assert_eq!(lines[4], " fn main() {");
+        assert_eq!(lines[5], "\u{1b}[31m-    old();\u{1b}[0m");
+        assert_eq!(lines[6], "\u{1b}[32m+    new();\u{1b}[0m");
+    }
+
+    fn comment_args() -> CommentArgs {
+        CommentArgs {
+            command: None,
+            number: Some(42),
+            body: None,
+            body_file: None,
+            edit_last: false,
+            edit: None,
+            reply_to: None,
+            file: Some("./src/lib.rs".to_string()),
+            line: None,
+            side: None,
+            range: None,
+        }
+    }
+
+    fn comment(id: u64, inline: Option<InlineAnchor>) -> PrComment {
+        PrComment {
+            id,
+            author: "jdoe".to_string(),
+            content: format!("comment {}", id),
+            created_on: "2026-01-12".to_string(),
+            inline,
+            parent: None,
+            resolved: false,
+            author_key: String::new(),
+        }
+    }
+
+    fn anchor(line: u32, side: Side) -> InlineAnchor {
+        InlineAnchor {
+            path: "src/lib.rs".to_string(),
+            line: Some(line),
+            side,
+            start_line: None,
+        }
+    }
+
+    #[test]
+    fn test_inline_anchor_from_args() {
+        let general = CommentArgs {
+            file: None,
+            ..comment_args()
+        };
+        assert_eq!(inline_anchor(&general).unwrap(), None);
+
+        let args = CommentArgs {
+            line: Some(2),
+            ..comment_args()
+        };
+        assert_eq!(inline_anchor(&args).unwrap(), Some(anchor(2, Side::New)));
+
+        let args = CommentArgs {
+            range: Some("1-2".to_string()),
+            side: Some("old".to_string()),
+            ..comment_args()
+        };
+        let range = inline_anchor(&args).unwrap().unwrap();
+        assert_eq!(range.start_line, Some(1));
+        assert_eq!(range.location(), "src/lib.rs:1-2 (old)");
+
+        assert!(inline_anchor(&comment_args()).is_err());
+        for bad in ["2-1", "0-3", "x-2", "12"] {
+            let args = CommentArgs {
+                range: Some(bad.to_string()),
+                ..comment_args()
+            };
+            assert!(inline_anchor(&args).is_err(), "{}", bad);
+        }
+        let args = CommentArgs {
+            line: Some(5),
+            range: Some("1-2".to_string()),
+            ..comment_args()
+        };
+        assert!(inline_anchor(&args).is_err());
+    }
+
+    #[test]
+    fn test_check_anchor_against_diff() {
+        let files = diff::parse(DIFF);
+
+        let checked = check_anchor(&files, &anchor(2, Side::New)).unwrap();
+        assert_eq!(checked.line_kind, LineKind::Added);
+        assert_eq!(server_line_type(checked.line_kind), "ADDED");
+
+        let checked = check_anchor(&files, &anchor(2, Side::Old)).unwrap();
+        assert_eq!(checked.line_kind, LineKind::Removed);
+
+        let err = check_anchor(&files, &anchor(9, Side::New)).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Line 9 of src/lib.rs is not in the diff (new side). Lines you can comment on: 1-2"
+        );
+
+        let other = InlineAnchor {
+            path: "README.md".to_string(),
+            ..anchor(1, Side::New)
+        };
+        assert!(check_anchor(&files, &other)
+            .unwrap_err()
+            .to_string()
+            .contains("'README.md' is not changed"));
+    }
+
+    #[test]
+    fn test_render_comments_groups_inline_by_file() {
+        let files = diff::parse(DIFF);
+        let comments = vec![
+            comment(3, Some(anchor(2, Side::New))),
+            comment(1, None),
+            comment(2, Some(anchor(2, Side::New))),
+            comment(4, Some(anchor(40, Side::New))),
+        ];
+
+        assert_eq!(
+            render_comments(&comments, &files, &Theme::new(false, false)),
+            "#1 @jdoe (2026-01-12):\n  comment 1\n\n\
+             src/lib.rs\n\
+             @@ -1,2 +1,2 @@\n fn main() {\n-    old();\n+    new();\n\
+             #2 @jdoe (2026-01-12):\n  comment 2\n\n\
+             #3 @jdoe (2026-01-12):\n  comment 3\n\n\
+             src/lib.rs:40 (no longer in the diff)\n\
+             #4 @jdoe (2026-01-12):\n  comment 4\n\n"
+        );
+    }
+
+    #[test]
+    fn test_render_comments_nests_replies() {
+        let reply = |id: u64, parent: u64| PrComment {
+            parent: Some(parent),
+            ..comment(id, None)
+        };
+        let comments = vec![
+            reply(5, 2),
+            PrComment {
+                resolved: true,
+                content: "two\nlines".to_string(),
+                ..comment(2, Some(anchor(2, Side::New)))
+            },
+            reply(3, 1),
+            comment(1, None),
+            reply(4, 3),
+            // The parent is not in the list, so this starts its own thread
+            reply(6, 99),
+        ];
+
+        assert_eq!(
+            render_comments(&comments, &[], &Theme::new(false, false)),
+            "#1 @jdoe (2026-01-12):\n  comment 1\n\n\
+             \x20   #3 @jdoe (2026-01-12):\n      comment 3\n\n\
+             \x20       #4 @jdoe (2026-01-12):\n          comment 4\n\n\
+             #6 @jdoe (2026-01-12):\n  comment 6\n\n\
+             src/lib.rs\n\
+             src/lib.rs:2 (no longer in the diff)\n\
+             #2 @jdoe (2026-01-12) [resolved]:\n  two\n  lines\n\n\
+             \x20   #5 @jdoe (2026-01-12):\n      comment 5\n\n"
+        );
+    }
+
+    #[test]
+    fn test_render_hunk_marks_pending_comments() {
+        let files = diff::parse(DIFF);
+        let hunk = &files[0].hunks[0];
+        let pending = vec![PendingComment {
+            path: "src/lib.rs".to_string(),
+            line: 2,
+            side: Side::New,
+            body: "Why?".to_string(),
+            task: false,
+            posted_id: None,
+        }];
+
+        assert_eq!(
+            render_hunk(hunk, &pending, "src/lib.rs", &Theme::new(false, false)),
+            "@@ -1,2 +1,2 @@\n\
+             \x20   1     1    fn main() {\n\
+             \x20   2         -    old();\n\
+             \x20         2 * +    new();"
+        );
+        assert!(!render_hunk(hunk, &pending, "README.md", &Theme::new(false, false)).contains('*'));
+    }
+
+    #[test]
+    fn test_hunk_line_choices_use_old_side_for_removed_lines() {
+        let files = diff::parse(DIFF);
+        let choices: Vec<(Side, u32)> = hunk_line_choices(&files[0].hunks[0])
+            .into_iter()
+            .map(|(side, line, _)| (side, line))
+            .collect();
+
+        assert_eq!(
+            choices,
+            vec![(Side::New, 1), (Side::Old, 2), (Side::New, 2)]
+        );
+    }
+
+    fn cloud_pr(participants: serde_json::Value, task_count: u32) -> cloud_prs::PullRequest {
+        let repo =
+            serde_json::json!({"uuid": "{1}", "name": "widgets", "full_name": "acme/widgets"});
+        serde_json::from_value(serde_json::json!({
+            "id": 7,
+            "title": "Add cache",
+            "state": "OPEN",
+            "author": {"display_name": "Jane"},
+            "source": {"branch": {"name": "feature/cache"}, "repository": repo},
+            "destination": {"branch": {"name": "main"}, "repository": repo},
+            "participants": participants,
+            "created_on": "2026-01-01T00:00:00Z",
+            "updated_on": "2026-01-01T00:00:00Z",
+            "task_count": task_count,
+        }))
+        .unwrap()
+    }
+
+    fn restriction(kind: &str, value: Option<u32>, pattern: &str) -> BranchRestriction {
+        BranchRestriction {
+            kind: kind.to_string(),
+            value,
+            branch_match_kind: "glob".to_string(),
+            pattern: pattern.to_string(),
+            branch_type: None,
+        }
+    }
+
+    fn rules(restrictions: Vec<BranchRestriction>) -> MergeRules {
+        MergeRules {
+            restrictions,
+            model: BranchingModel::default(),
+        }
+    }
+
+    fn status(state: &str) -> CommitStatus {
+        CommitStatus {
+            state: state.to_string(),
+            name: "build".to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn blockers(checks: Vec<MergeCheck>) -> Vec<String> {
+        Mergeability::new(7, checks, Vec::new()).blockers()
+    }
+
+    #[test]
+    fn test_cloud_merge_checks_follow_branch_restrictions() {
+        let approver = serde_json::json!([
+            {"user": {"display_name": "Sam"}, "role": "REVIEWER", "approved": true},
+            {"user": {"display_name": "Ana"}, "role": "REVIEWER", "approved": false,
+             "state": "changes_requested"},
+        ]);
+        let pr = cloud_pr(approver, 1);
+        let restrictions = rules(vec![
+            restriction("require_approvals_to_merge", Some(2), "main"),
+            restriction("require_no_changes_requested", None, "*"),
+            restriction("require_passing_builds_to_merge", Some(1), "release/*"),
+        ]);
+
+        assert_eq!(
+            blockers(cloud_merge_checks(
+                &pr,
+                &[status("INPROGRESS")],
+                Some(&restrictions),
+                false
+            )),
+            vec!["1/2 approvals", "1 change request", "1 open task"]
+        );
+        assert_eq!(
+            blockers(cloud_merge_checks(&pr, &[status("SUCCESSFUL")], None, true)),
+            Vec::<String>::new()
+        );
+    }
+
+    #[test]
+    fn test_cloud_merge_checks_keep_required_tasks_with_force() {
+        let pr = cloud_pr(serde_json::json!([]), 2);
+        let restrictions = rules(vec![
+            restriction("require_tasks_to_be_completed", None, "ma*"),
+            restriction("require_passing_builds_to_merge", Some(1), "main"),
+        ]);
+
+        let checks = cloud_merge_checks(&pr, &[status("FAILED")], Some(&restrictions), true);
+        assert_eq!(blockers(checks), vec!["failed: build", "2 open tasks"]);
+    }
+
+    #[test]
+    fn test_cloud_merge_checks_only_block_on_required_builds() {
+        let pr = cloud_pr(serde_json::json!([]), 0);
+
+        let checks = cloud_merge_checks(&pr, &[status("FAILED")], Some(&rules(vec![])), false);
+        let builds = checks.iter().find(|c| c.name == "Builds").unwrap();
+        assert_eq!(builds.state, CheckState::Info);
+        assert_eq!(builds.detail, "failed: build (not required)");
+        assert!(blockers(checks).is_empty());
+
+        // Unknown merge checks may require builds
+        let checks = cloud_merge_checks(&pr, &[status("INPROGRESS")], None, false);
+        assert_eq!(blockers(checks), vec!["1 build running"]);
+    }
+
+    #[test]
+    fn test_cloud_merge_checks_match_branching_model() {
+        let pr = cloud_pr(serde_json::json!([]), 0);
+        let mut restriction = restriction("require_approvals_to_merge", Some(1), "");
+        restriction.branch_match_kind = "branching_model".to_string();
+        restriction.branch_type = Some("development".to_string());
+        let mut restrictions = rules(vec![restriction]);
+
+        let checks = cloud_merge_checks(&pr, &[], Some(&restrictions), false);
+        assert!(blockers(checks).is_empty());
+
+        restrictions.model = serde_json::from_value(
+            serde_json::json!({"development": {"branch": {"name": "main"}}}),
+        )
+        .unwrap();
+        let checks = cloud_merge_checks(&pr, &[], Some(&restrictions), false);
+        assert_eq!(blockers(checks), vec!["0/1 approvals"]);
+    }
+
+    #[test]
+    fn test_updated_reviewers_keeps_order_without_duplicates() {
+        let user = |id: &str| ResolvedUser {
+            id: id.to_string(),
+            username: None,
+            display_name: id.to_string(),
+            email: None,
+            account_id: None,
+        };
+        let current = vec!["{a}".to_string(), "{b}".to_string()];
+
+        assert_eq!(
+            updated_reviewers(current, &[user("{c}"), user("{a}")], &[user("{b}")]),
+            ["{a}", "{c}"]
+        );
+    }
+
+    #[test]
+    fn test_pipeline_build_number_from_result_url() {
+        assert_eq!(
+            pipeline_build_number("https://bitbucket.org/acme/widgets/pipelines/results/12"),
+            Some(12)
+        );
+        assert_eq!(
+            pipeline_build_number(
+                "https://bitbucket.org/acme/widgets/pipelines/results/7/steps/%7Babc%7D"
+            ),
+            Some(7)
+        );
+        assert_eq!(
+            pipeline_build_number("https://ci.example.com/build/12"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_step_progress_counts_completed_steps() {
+        let steps: Vec<PipelineStep> = serde_json::from_value(serde_json::json!([
+            {"uuid": "{1}", "name": "Build", "state": {"name": "COMPLETED", "type": "completed"}},
+            {"uuid": "{2}", "name": "Test", "state": {"name": "IN_PROGRESS", "type": "in_progress"}},
+            {"uuid": "{3}", "name": "Deploy", "state": {"name": "PENDING", "type": "pending"}}
+        ]))
+        .unwrap();
+
+        assert_eq!(
+            step_progress(&steps).as_deref(),
+            Some("1/3 steps, running Test")
+        );
+        assert_eq!(step_progress(&steps[..1]).as_deref(), Some("1/1 steps"));
+        assert_eq!(step_progress(&[]), None);
+    }
+
+    #[test]
+    fn test_server_merge_checks_list_vetoes() {
+        let status: server_prs::MergeStatus = serde_json::from_value(serde_json::json!({
+            "canMerge": false,
+            "conflicted": false,
+            "outcome": "CLEAN",
+            "vetoes": [{
+                "summaryMessage": "Requires approvals",
+                "detailedMessage": "You need 1 more approval before this pull request can be merged."
+            }]
+        }))
+        .unwrap();
+
+        let mergeability = Mergeability::new(42, server_merge_checks(&status), Vec::new());
+        assert_eq!(
+            mergeability.render(false),
+            "PR #42 cannot be merged\n\n\
+             [x] Conflicts: none\n\
+             [ ] Requires approvals: You need 1 more approval before this pull request can be merged.\n"
+        );
+        assert_eq!(
+            mergeability.summary(),
+            "No (You need 1 more approval before this pull request can be merged.)"
+        );
+    }
+}
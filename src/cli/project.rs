@@ -15,7 +15,6 @@
 
 use anyhow::{bail, Result};
 use clap::{Args, Subcommand};
-use console::style;
 use serde::{Deserialize, Serialize};
 
 use crate::api::client::BitbucketClient;
@@ -27,7 +26,10 @@ use crate::auth::{AuthCredential, KeyringStore};
 use crate::config::Config;
 use crate::context::{ContextResolver, HostType, RepoContext};
 use crate::interactive::prompt_confirm_with_default;
-use crate::output::{OutputFormat, OutputWriter, TableOutput};
+use crate::output::{
+    print_row, stdout_is_tty, theme, write_json, Color, OutputFormat, OutputWriter, TableOutput,
+    TableRow,
+};
 use crate::util::open_browser;
 
 use super::GlobalOptions;
@@ -288,36 +290,32 @@ struct ProjectListItem {
     project_type: String, // "cloud" or "server"
 }
 
-impl TableOutput for ProjectListItem {
-    fn print_table(&self, color: bool) {
-        let key_display = if color {
-            style(&self.key).cyan().bold().to_string()
-        } else {
-            self.key.clone()
-        };
-
-        let privacy = if self.is_private { "private" } else { "public" };
-        let privacy_display = if color {
-            if self.is_private {
-                style(privacy).yellow().to_string()
-            } else {
-                style(privacy).green().to_string()
-            }
-        } else {
-            privacy.to_string()
-        };
+impl TableRow for ProjectListItem {
+    const COLUMNS: &'static [&'static str] = &["key", "name", "visibility", "description"];
+    const DEFAULT_COLUMNS: &'static [&'static str] = Self::COLUMNS;
+
+    fn cell(&self, column: &str) -> String {
+        match column {
+            "key" => self.key.clone(),
+            "name" => self.name.clone(),
+            "visibility" => visibility(self.is_private).to_string(),
+            "description" => self.description.clone().unwrap_or_else(|| "-".to_string()),
+            _ => String::new(),
+        }
+    }
 
-        let description = self.description.as_deref().unwrap_or("-");
-        let desc_truncated = if description.len() > 35 {
-            format!("{}...", &description[..32])
-        } else {
-            description.to_string()
-        };
+    fn cell_color(&self, column: &str) -> Option<Color> {
+        match column {
+            "key" => Some(Color::Cyan),
+            "visibility" => Some(visibility_color(self.is_private)),
+            _ => None,
+        }
+    }
+}
 
-        println!(
-            "{:<12} {:<25} {:<10} {}",
-            key_display, self.name, privacy_display, desc_truncated
-        );
+impl TableOutput for ProjectListItem {
+    fn print_table(&self, _color: bool) {
+        print_row(self);
     }
 
     fn print_markdown(&self) {
@@ -344,7 +342,7 @@ struct ProjectDetail {
 impl TableOutput for ProjectDetail {
     fn print_table(&self, color: bool) {
         let title = if color {
-            style(&self.name).bold().to_string()
+            theme().heading(&self.name)
         } else {
             self.name.clone()
         };
@@ -352,9 +350,9 @@ impl TableOutput for ProjectDetail {
         let privacy = if self.is_private { "Private" } else { "Public" };
         let privacy_display = if color {
             if self.is_private {
-                style(privacy).yellow().to_string()
+                theme().paint(privacy, Color::DarkYellow)
             } else {
-                style(privacy).green().to_string()
+                theme().paint(privacy, Color::DarkGreen)
             }
         } else {
             privacy.to_string()
@@ -406,36 +404,31 @@ struct RepoListItem {
     is_private: bool,
 }
 
-impl TableOutput for RepoListItem {
-    fn print_table(&self, color: bool) {
-        let name_display = if color {
-            style(&self.name).bold().to_string()
-        } else {
-            self.name.clone()
-        };
-
-        let privacy = if self.is_private { "private" } else { "public" };
-        let privacy_display = if color {
-            if self.is_private {
-                style(privacy).yellow().to_string()
-            } else {
-                style(privacy).green().to_string()
-            }
-        } else {
-            privacy.to_string()
-        };
+impl TableRow for RepoListItem {
+    const COLUMNS: &'static [&'static str] = &["slug", "name", "visibility", "description"];
+    const DEFAULT_COLUMNS: &'static [&'static str] = Self::COLUMNS;
+
+    fn cell(&self, column: &str) -> String {
+        match column {
+            "slug" => self.slug.clone(),
+            "name" => self.name.clone(),
+            "visibility" => visibility(self.is_private).to_string(),
+            "description" => self.description.clone().unwrap_or_else(|| "-".to_string()),
+            _ => String::new(),
+        }
+    }
 
-        let description = self.description.as_deref().unwrap_or("-");
-        let desc_truncated = if description.len() > 40 {
-            format!("{}...", &description[..37])
-        } else {
-            description.to_string()
-        };
+    fn cell_color(&self, column: &str) -> Option<Color> {
+        match column {
+            "visibility" => Some(visibility_color(self.is_private)),
+            _ => None,
+        }
+    }
+}
 
-        println!(
-            "{:<25} {:<30} {:<10} {}",
-            self.slug, name_display, privacy_display, desc_truncated
-        );
+impl TableOutput for RepoListItem {
+    fn print_table(&self, _color: bool) {
+        print_row(self);
     }
 
     fn print_markdown(&self) {
@@ -455,28 +448,47 @@ struct MemberItem {
     permission: String,
 }
 
-impl TableOutput for MemberItem {
-    fn print_table(&self, color: bool) {
-        let name_display = if color {
-            style(&self.display_name).bold().to_string()
-        } else {
-            self.display_name.clone()
-        };
+impl TableRow for MemberItem {
+    const COLUMNS: &'static [&'static str] = &["username", "display_name", "permission"];
+    const DEFAULT_COLUMNS: &'static [&'static str] = Self::COLUMNS;
 
-        let permission_display = if color {
-            match self.permission.to_lowercase().as_str() {
-                "admin" | "project_admin" => style(&self.permission).magenta().to_string(),
-                "write" | "project_write" => style(&self.permission).cyan().to_string(),
-                _ => self.permission.clone(),
-            }
-        } else {
-            self.permission.clone()
-        };
+    fn cell(&self, column: &str) -> String {
+        match column {
+            "username" => self.username.clone(),
+            "display_name" => self.display_name.clone(),
+            "permission" => self.permission.clone(),
+            _ => String::new(),
+        }
+    }
 
-        println!(
-            "{:<25} {:<30} {}",
-            self.username, name_display, permission_display
-        );
+    fn cell_color(&self, column: &str) -> Option<Color> {
+        match (column, self.permission.to_lowercase().as_str()) {
+            ("permission", "admin" | "project_admin") => Some(Color::Magenta),
+            ("permission", "write" | "project_write") => Some(Color::Cyan),
+            _ => None,
+        }
+    }
+}
+
+fn visibility(is_private: bool) -> &'static str {
+    if is_private {
+        "private"
+    } else {
+        "public"
+    }
+}
+
+fn visibility_color(is_private: bool) -> Color {
+    if is_private {
+        Color::Yellow
+    } else {
+        Color::Green
+    }
+}
+
+impl TableOutput for MemberItem {
+    fn print_table(&self, _color: bool) {
+        print_row(self);
     }
 
     fn print_markdown(&self) {
@@ -658,7 +670,9 @@ impl ProjectCommand {
 
         let writer = OutputWriter::new(self.get_format(global));
 
-        if !global.json {
+        let decorate = !global.json && stdout_is_tty();
+
+        if decorate {
             println!();
             if let Some(ws) = workspace {
                 println!("Projects in workspace '{}':", ws);
@@ -666,19 +680,11 @@ impl ProjectCommand {
                 println!("Projects:");
             }
             println!();
-            println!(
-                "{} {} {} {}",
-                style(format!("{:<12}", "KEY")).bold(),
-                style(format!("{:<25}", "NAME")).bold(),
-                style(format!("{:<10}", "VISIBILITY")).bold(),
-                style("DESCRIPTION").bold()
-            );
-            println!("{}", "-".repeat(80));
         }
 
-        writer.write_list(items)?;
+        writer.write_table(items)?;
 
-        if !global.json {
+        if decorate {
             println!();
             println!("Showing {} project(s)", items.len());
         }
@@ -861,13 +867,13 @@ impl ProjectCommand {
                 "name": project.name,
                 "url": format!("https://{}/projects/{}", context.host, project.key),
             });
-            println!("{}", serde_json::to_string_pretty(&result)?);
+            write_json(&result)?;
         } else {
             println!();
             println!(
                 "{} Created project {}",
-                style("✓").green(),
-                style(&project.key).cyan().bold()
+                theme().success_mark(),
+                theme().emphasis(&project.key)
             );
             println!();
             println!("  Name: {}", project.name);
@@ -963,13 +969,13 @@ impl ProjectCommand {
                 "success": true,
                 "deleted": args.project,
             });
-            println!("{}", serde_json::to_string_pretty(&result)?);
+            write_json(&result)?;
         } else {
             println!();
             println!(
                 "{} Deleted project {}",
-                style("✓").green(),
-                style(&args.project).red()
+                theme().success_mark(),
+                theme().paint(&args.project, Color::DarkRed)
             );
         }
 
@@ -1103,23 +1109,17 @@ impl ProjectCommand {
 
         let writer = OutputWriter::new(self.get_format(global));
 
-        if !global.json {
+        let decorate = !global.json && stdout_is_tty();
+
+        if decorate {
             println!();
             println!("Repositories in project '{}':", project_key);
             println!();
-            println!(
-                "{} {} {} {}",
-                style(format!("{:<25}", "SLUG")).bold(),
-                style(format!("{:<30}", "NAME")).bold(),
-                style(format!("{:<10}", "VISIBILITY")).bold(),
-                style("DESCRIPTION").bold()
-            );
-            println!("{}", "-".repeat(90));
         }
 
-        writer.write_list(items)?;
+        writer.write_table(items)?;
 
-        if !global.json {
+        if decorate {
             println!();
             println!("Showing {} repository(ies)", items.len());
         }
@@ -1166,22 +1166,17 @@ impl ProjectCommand {
 
         let writer = OutputWriter::new(self.get_format(global));
 
-        if !global.json {
+        let decorate = !global.json && stdout_is_tty();
+
+        if decorate {
             println!();
             println!("Members of project '{}':", args.project);
             println!();
-            println!(
-                "{} {} {}",
-                style(format!("{:<25}", "USERNAME")).bold(),
-                style(format!("{:<30}", "NAME")).bold(),
-                style("PERMISSION").bold()
-            );
-            println!("{}", "-".repeat(70));
         }
 
-        writer.write_list(&items)?;
+        writer.write_table(&items)?;
 
-        if !global.json {
+        if decorate {
             println!();
             println!("Showing {} member(s)", items.len());
         }
@@ -1228,15 +1223,15 @@ impl ProjectCommand {
                 "user": args.user,
                 "permission": args.permission,
             });
-            println!("{}", serde_json::to_string_pretty(&result)?);
+            write_json(&result)?;
         } else {
             println!();
             println!(
                 "{} Added {} to project {} with {} permission",
-                style("✓").green(),
-                style(&args.user).cyan(),
-                style(&args.project).cyan().bold(),
-                style(&args.permission).yellow()
+                theme().success_mark(),
+                theme().emphasis(&args.user),
+                theme().emphasis(&args.project),
+                theme().paint(&args.permission, Color::DarkYellow)
             );
         }
 
@@ -1272,14 +1267,14 @@ impl ProjectCommand {
                 "user": args.user,
                 "removed": true,
             });
-            println!("{}", serde_json::to_string_pretty(&result)?);
+            write_json(&result)?;
         } else {
             println!();
             println!(
                 "{} Removed {} from project {}",
-                style("✓").green(),
-                style(&args.user).red(),
-                style(&args.project).cyan().bold()
+                theme().success_mark(),
+                theme().paint(&args.user, Color::DarkRed),
+                theme().emphasis(&args.project)
             );
         }
 
@@ -1323,7 +1318,7 @@ impl ProjectCommand {
                     })
                 }).collect::<Vec<_>>(),
             });
-            println!("{}", serde_json::to_string_pretty(&result)?);
+            write_json(&result)?;
         } else {
             println!();
             println!("Permissions for project '{}':", project_key);
@@ -1336,8 +1331,8 @@ impl ProjectCommand {
                 println!("  {}", "-".repeat(60));
                 for perm in &users.values {
                     let permission_display = match perm.permission.as_str() {
-                        "PROJECT_ADMIN" => style(&perm.permission).magenta().to_string(),
-                        "PROJECT_WRITE" => style(&perm.permission).cyan().to_string(),
+                        "PROJECT_ADMIN" => theme().paint(&perm.permission, Color::DarkMagenta),
+                        "PROJECT_WRITE" => theme().paint(&perm.permission, Color::DarkCyan),
                         _ => perm.permission.clone(),
                     };
                     println!(
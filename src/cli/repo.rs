@@ -20,7 +20,6 @@
 
 use anyhow::{Context, Result};
 use clap::{Args, Subcommand};
-use console::style;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use std::process::Command;
@@ -29,11 +28,17 @@ use super::GlobalOptions;
 use crate::api::cloud::repositories as cloud_repos;
 use crate::api::common::{PaginatedResponse, ServerPaginatedResponse};
 use crate::api::format_api_error;
+use crate::api::replay::ReplayExt;
+use crate::api::server::capabilities::{self, Capability};
 use crate::api::server::repositories as server_repos;
 use crate::auth::KeyringStore;
 use crate::config::{is_cloud_host, Config};
 use crate::context::{ContextResolver, HostType, RepoContext};
-use crate::output::{print_field, print_header, OutputFormat, OutputWriter, TableOutput};
+use crate::hooks::Hooks;
+use crate::output::{
+    print_field, print_header, print_row, require_json_fields, stdout_is_tty, write_json, Color,
+    JsonFields, OutputFormat, OutputWriter, TableOutput, TableRow,
+};
 
 /// Manage repositories
 #[derive(Args, Debug)]
@@ -255,31 +260,40 @@ struct RepoListItem {
     updated_on: String,
 }
 
-impl TableOutput for RepoListItem {
-    fn print_table(&self, color: bool) {
-        let visibility = if self.is_private { "private" } else { "public" };
-        let visibility_styled = if color {
-            if self.is_private {
-                style(visibility).yellow().to_string()
-            } else {
-                style(visibility).green().to_string()
-            }
-        } else {
-            visibility.to_string()
-        };
+impl TableRow for RepoListItem {
+    const COLUMNS: &'static [&'static str] = &[
+        "name",
+        "visibility",
+        "language",
+        "description",
+        "updated_on",
+    ];
+    const DEFAULT_COLUMNS: &'static [&'static str] =
+        &["name", "visibility", "language", "description"];
+
+    fn cell(&self, column: &str) -> String {
+        match column {
+            "name" => self.full_name.clone(),
+            "visibility" => if self.is_private { "private" } else { "public" }.to_string(),
+            "language" => self.language.clone().unwrap_or_else(|| "-".to_string()),
+            "description" => self.description.clone().unwrap_or_default(),
+            "updated_on" => self.updated_on.clone(),
+            _ => String::new(),
+        }
+    }
 
-        let lang = self.language.as_deref().unwrap_or("-");
-        let desc = self.description.as_deref().unwrap_or("");
-        let desc_truncated = if desc.len() > 50 {
-            format!("{}...", &desc[..47])
-        } else {
-            desc.to_string()
-        };
+    fn cell_color(&self, column: &str) -> Option<Color> {
+        match column {
+            "visibility" if self.is_private => Some(Color::Yellow),
+            "visibility" => Some(Color::Green),
+            _ => None,
+        }
+    }
+}
 
-        println!(
-            "{:<40} {:<10} {:<12} {}",
-            self.full_name, visibility_styled, lang, desc_truncated
-        );
+impl TableOutput for RepoListItem {
+    fn print_table(&self, _color: bool) {
+        print_row(self);
     }
 
     fn print_markdown(&self) {
@@ -421,6 +435,12 @@ impl RepoCommand {
 
         let is_cloud = is_cloud_host(&host);
 
+        if is_cloud {
+            require_json_fields(cloud_repos::Repository::json_fields())?;
+        } else {
+            require_json_fields(server_repos::Repository::json_fields())?;
+        }
+
         // Get authentication token
         let token = keyring.get(&host)?.ok_or_else(|| {
             anyhow::anyhow!("Not authenticated for {}. Run 'bb auth login' first.", host)
@@ -474,7 +494,7 @@ impl RepoCommand {
             let response = client
                 .get(&url)
                 .bearer_auth(&token)
-                .send()
+                .send_replayable()
                 .await
                 .context("Failed to connect to Bitbucket Cloud")?;
 
@@ -486,6 +506,10 @@ impl RepoCommand {
 
             let repos: PaginatedResponse<cloud_repos::Repository> = response.json().await?;
 
+            if global.json {
+                return write_json(&repos.values);
+            }
+
             // Convert to list items
             let items: Vec<RepoListItem> = repos
                 .values
@@ -502,15 +526,10 @@ impl RepoCommand {
             if items.is_empty() {
                 println!("No repositories found in workspace '{}'", workspace);
             } else {
-                if !global.json {
+                if stdout_is_tty() {
                     println!("Repositories in '{}':\n", workspace);
-                    println!(
-                        "{:<40} {:<10} {:<12} DESCRIPTION",
-                        "NAME", "VISIBILITY", "LANGUAGE"
-                    );
-                    println!("{}", "-".repeat(90));
                 }
-                output.write_list(&items)?;
+                output.write_table(&items)?;
             }
         } else {
             // Bitbucket Server/DC
@@ -527,7 +546,7 @@ impl RepoCommand {
             let response = client
                 .get(&url)
                 .bearer_auth(&token)
-                .send()
+                .send_replayable()
                 .await
                 .context("Failed to connect to Bitbucket Server")?;
 
@@ -539,6 +558,10 @@ impl RepoCommand {
 
             let repos: ServerPaginatedResponse<server_repos::Repository> = response.json().await?;
 
+            if global.json {
+                return write_json(&repos.values);
+            }
+
             // Convert to list items
             let items: Vec<RepoListItem> = repos
                 .values
@@ -555,15 +578,10 @@ impl RepoCommand {
             if items.is_empty() {
                 println!("No repositories found in project '{}'", project);
             } else {
-                if !global.json {
+                if stdout_is_tty() {
                     println!("Repositories in '{}':\n", project);
-                    println!(
-                        "{:<40} {:<10} {:<12} DESCRIPTION",
-                        "NAME", "VISIBILITY", "LANGUAGE"
-                    );
-                    println!("{}", "-".repeat(90));
                 }
-                output.write_list(&items)?;
+                output.write_table(&items)?;
             }
         }
 
@@ -582,6 +600,12 @@ impl RepoCommand {
             resolver.resolve(global)?
         };
 
+        if context.host_type == HostType::Cloud {
+            require_json_fields(cloud_repos::Repository::json_fields())?;
+        } else {
+            require_json_fields(server_repos::Repository::json_fields())?;
+        }
+
         // If web flag, open in browser
         if args.web {
             let url = context.web_url();
@@ -618,7 +642,7 @@ impl RepoCommand {
             let response = client
                 .get(&url)
                 .bearer_auth(&token)
-                .send()
+                .send_replayable()
                 .await
                 .context("Failed to connect to Bitbucket Cloud")?;
 
@@ -630,6 +654,10 @@ impl RepoCommand {
 
             let repo: cloud_repos::Repository = response.json().await?;
 
+            if global.json {
+                return write_json(&repo);
+            }
+
             RepoDetail {
                 full_name: repo.full_name.clone(),
                 description: repo.description,
@@ -652,7 +680,7 @@ impl RepoCommand {
             let response = client
                 .get(&url)
                 .bearer_auth(&token)
-                .send()
+                .send_replayable()
                 .await
                 .context("Failed to connect to Bitbucket Server")?;
 
@@ -664,6 +692,10 @@ impl RepoCommand {
 
             let repo: server_repos::Repository = response.json().await?;
 
+            if global.json {
+                return write_json(&repo);
+            }
+
             // Extract clone URLs
             let ssh_url = repo
                 .links
@@ -778,7 +810,7 @@ impl RepoCommand {
                 .post(&url)
                 .bearer_auth(&token)
                 .json(&body)
-                .send()
+                .send_replayable()
                 .await
                 .context("Failed to connect to Bitbucket Cloud")?;
 
@@ -818,7 +850,7 @@ impl RepoCommand {
                 .post(&url)
                 .bearer_auth(&token)
                 .json(&body)
-                .send()
+                .send_replayable()
                 .await
                 .context("Failed to connect to Bitbucket Server")?;
 
@@ -895,7 +927,11 @@ impl RepoCommand {
                     context.host, context.owner, context.repo_slug
                 );
 
-                let response = client.get(&url).bearer_auth(&token).send().await?;
+                let response = client
+                    .get(&url)
+                    .bearer_auth(&token)
+                    .send_replayable()
+                    .await?;
 
                 if !response.status().is_success() {
                     anyhow::bail!(
@@ -1014,7 +1050,7 @@ impl RepoCommand {
                 .post(&url)
                 .bearer_auth(&token)
                 .json(&body)
-                .send()
+                .send_replayable()
                 .await
                 .context("Failed to connect to Bitbucket Cloud")?;
 
@@ -1068,7 +1104,7 @@ impl RepoCommand {
                 .post(&url)
                 .bearer_auth(&token)
                 .json(&body)
-                .send()
+                .send_replayable()
                 .await
                 .context("Failed to connect to Bitbucket Server")?;
 
@@ -1123,6 +1159,10 @@ impl RepoCommand {
             }
         }
 
+        let hooks = Hooks::load("repo delete", &context, global.json).await?;
+        let operation = serde_json::json!({ "repository": context.full_name() });
+        hooks.run_pre(&operation)?;
+
         let token = keyring.get(&context.host)?.ok_or_else(|| {
             anyhow::anyhow!(
                 "Not authenticated for {}. Run 'bb auth login' first.",
@@ -1149,7 +1189,7 @@ impl RepoCommand {
             let response = client
                 .delete(&url)
                 .bearer_auth(&token)
-                .send()
+                .send_replayable()
                 .await
                 .context("Failed to connect to Bitbucket Cloud")?;
 
@@ -1167,7 +1207,7 @@ impl RepoCommand {
             let response = client
                 .delete(&url)
                 .bearer_auth(&token)
-                .send()
+                .send_replayable()
                 .await
                 .context("Failed to connect to Bitbucket Server")?;
 
@@ -1180,46 +1220,88 @@ impl RepoCommand {
 
         output.write_success(&format!("Deleted repository {}", context.full_name()));
 
+        hooks.run_post(&operation, &serde_json::json!({ "deleted": true }));
+
         Ok(())
     }
 
     /// Archive a repository
     async fn archive(&self, global: &GlobalOptions) -> Result<()> {
-        // This is Cloud-only feature currently
+        self.set_archived(true, global).await
+    }
+
+    /// Unarchive a repository
+    async fn unarchive(&self, global: &GlobalOptions) -> Result<()> {
+        self.set_archived(false, global).await
+    }
+
+    /// Archive or unarchive a repository
+    async fn set_archived(&self, archived: bool, global: &GlobalOptions) -> Result<()> {
         let config = Config::load().unwrap_or_default();
         let resolver = ContextResolver::new(config.clone());
         let keyring = KeyringStore::new();
 
         let context = resolver.resolve(global)?;
+        let verb = if archived { "archive" } else { "unarchive" };
 
-        if context.host_type != HostType::Cloud {
-            anyhow::bail!("Archive is only available for Bitbucket Cloud repositories");
+        if context.host_type == HostType::Cloud {
+            // Bitbucket Cloud doesn't have a direct archive API
+            println!("Repository {}ing is not yet supported via API.", verb);
+            println!(
+                "Please {} the repository via the Bitbucket web interface:",
+                verb
+            );
+            println!("  {}/admin", context.web_url());
+            return Ok(());
         }
 
-        let _token = keyring
+        let token = keyring
             .get(&context.host)?
             .ok_or_else(|| anyhow::anyhow!("Not authenticated. Run 'bb auth login' first."))?;
 
-        // Archive is done via a PUT to update the repo with archived=true
-        // However, Bitbucket Cloud doesn't have a direct archive API
-        // We'd need to use the web UI or specific endpoints
+        capabilities::detect(&context.host, &token)
+            .await?
+            .require_verified(Capability::ArchivedRepositories)?;
 
-        println!("Repository archiving is not yet supported via API.");
-        println!("Please archive the repository via the Bitbucket web interface:");
-        println!("  {}/admin", context.web_url());
+        let client = Client::builder()
+            .user_agent(format!("bb/{}", crate::VERSION))
+            .build()?;
 
-        Ok(())
-    }
+        let output = OutputWriter::new(if global.json {
+            OutputFormat::Json
+        } else {
+            OutputFormat::Table
+        });
 
-    /// Unarchive a repository
-    async fn unarchive(&self, global: &GlobalOptions) -> Result<()> {
-        let config = Config::load().unwrap_or_default();
-        let resolver = ContextResolver::new(config.clone());
-        let context = resolver.resolve(global)?;
+        let url = format!(
+            "https://{}/rest/api/1.0/projects/{}/repos/{}",
+            context.host, context.owner, context.repo_slug
+        );
+
+        #[derive(Serialize)]
+        struct ArchiveRequest {
+            archived: bool,
+        }
+
+        let response = client
+            .put(&url)
+            .bearer_auth(&token)
+            .json(&ArchiveRequest { archived })
+            .send_replayable()
+            .await
+            .context("Failed to connect to Bitbucket Server")?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            return Err(format_api_error(status, &text));
+        }
 
-        println!("Repository unarchiving is not yet supported via API.");
-        println!("Please unarchive the repository via the Bitbucket web interface:");
-        println!("  {}/admin", context.web_url());
+        output.write_success(&format!(
+            "{}d repository {}",
+            if archived { "Archive" } else { "Unarchive" },
+            context.full_name()
+        ));
 
         Ok(())
     }
@@ -1265,7 +1347,7 @@ impl RepoCommand {
                 .put(&url)
                 .bearer_auth(&token)
                 .json(&body)
-                .send()
+                .send_replayable()
                 .await?;
 
             if !response.status().is_success() {
@@ -1298,7 +1380,7 @@ impl RepoCommand {
                 .put(&url)
                 .bearer_auth(&token)
                 .json(&body)
-                .send()
+                .send_replayable()
                 .await?;
 
             if !response.status().is_success() {
@@ -1419,7 +1501,7 @@ impl RepoCommand {
                 .put(&url)
                 .bearer_auth(&token)
                 .json(&body)
-                .send()
+                .send_replayable()
                 .await?;
 
             if !response.status().is_success() {
@@ -1457,7 +1539,7 @@ impl RepoCommand {
                 .put(&url)
                 .bearer_auth(&token)
                 .json(&body)
-                .send()
+                .send_replayable()
                 .await?;
 
             if !response.status().is_success() {
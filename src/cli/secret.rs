@@ -38,7 +38,6 @@ use std::io::{self, BufRead};
 
 use anyhow::{bail, Result};
 use clap::{Args, Subcommand};
-use console::style;
 use serde::{Deserialize, Serialize};
 
 use crate::api::common::PaginatedResponse;
@@ -46,7 +45,7 @@ use crate::api::BitbucketClient;
 use crate::auth::{AuthCredential, KeyringStore};
 use crate::config::Config;
 use crate::context::{ContextResolver, HostType, RepoContext};
-use crate::output::OutputWriter;
+use crate::output::{print_row, stdout_is_tty, theme, write_json, Color, OutputWriter, TableRow};
 
 use super::GlobalOptions;
 
@@ -172,19 +171,33 @@ struct VariableListItem {
     secured: bool,
 }
 
+impl TableRow for VariableListItem {
+    const COLUMNS: &'static [&'static str] = &["uuid", "key", "secured", "value"];
+    const DEFAULT_COLUMNS: &'static [&'static str] = &["key", "secured", "value"];
+
+    fn cell(&self, column: &str) -> String {
+        match column {
+            "uuid" => self.uuid.clone(),
+            "key" => self.key.clone(),
+            "secured" => if self.secured { "yes" } else { "no" }.to_string(),
+            "value" if self.secured => "********".to_string(),
+            "value" => self.value.clone().unwrap_or_else(|| "-".to_string()),
+            _ => String::new(),
+        }
+    }
+
+    fn cell_color(&self, column: &str) -> Option<Color> {
+        match column {
+            "secured" if self.secured => Some(Color::Yellow),
+            "secured" => Some(Color::DarkGrey),
+            _ => None,
+        }
+    }
+}
+
 impl crate::output::TableOutput for VariableListItem {
     fn print_table(&self, _color: bool) {
-        let secured_str = if self.secured {
-            style("yes").yellow().to_string()
-        } else {
-            style("no").dim().to_string()
-        };
-        let value_str = if self.secured {
-            "********".to_string()
-        } else {
-            self.value.clone().unwrap_or_else(|| "-".to_string())
-        };
-        println!("{:<30} {:<10} {}", &self.key, secured_str, value_str);
+        print_row(self);
     }
 
     fn print_markdown(&self) {
@@ -284,7 +297,9 @@ impl SecretCommand {
 
         let writer = OutputWriter::new(self.get_format(global));
 
-        if !global.json {
+        let decorate = !global.json && stdout_is_tty();
+
+        if decorate {
             let scope = if args.workspace.is_some() {
                 "Workspace"
             } else if args.environment.is_some() {
@@ -294,36 +309,15 @@ impl SecretCommand {
             };
 
             println!();
-            println!("{}", style(format!("{} Variables", scope)).bold());
-            println!("{}", "-".repeat(60));
-            println!(
-                "{} {} {}",
-                style(format!("{:<30}", "KEY")).bold(),
-                style(format!("{:<10}", "SECURED")).bold(),
-                style("VALUE").bold()
-            );
-            println!("{}", "-".repeat(60));
-
-            for item in &items {
-                let secured_str = if item.secured {
-                    style("yes").yellow().to_string()
-                } else {
-                    style("no").dim().to_string()
-                };
-
-                let value_str = if item.secured {
-                    "********".to_string()
-                } else {
-                    item.value.clone().unwrap_or_else(|| "-".to_string())
-                };
-
-                println!("{:<30} {:<10} {}", item.key, secured_str, value_str);
-            }
+            println!("{}", theme().heading(format!("{} Variables", scope)));
+            println!();
+        }
 
+        writer.write_table(&items)?;
+
+        if decorate {
             println!();
             println!("Showing {} variable(s)", items.len());
-        } else {
-            writer.write_list(&items)?;
         }
 
         Ok(())
@@ -386,12 +380,12 @@ impl SecretCommand {
                     "key": args.name,
                     "secured": args.secured,
                 });
-                println!("{}", serde_json::to_string_pretty(&result)?);
+                write_json(&result)?;
             } else {
                 println!(
                     "{} Updated variable {}",
-                    style("✓").green(),
-                    style(&args.name).cyan()
+                    theme().success_mark(),
+                    theme().emphasis(&args.name)
                 );
             }
         } else {
@@ -405,12 +399,12 @@ impl SecretCommand {
                     "key": args.name,
                     "secured": args.secured,
                 });
-                println!("{}", serde_json::to_string_pretty(&result)?);
+                write_json(&result)?;
             } else {
                 println!(
                     "{} Created variable {}",
-                    style("✓").green(),
-                    style(&args.name).cyan()
+                    theme().success_mark(),
+                    theme().emphasis(&args.name)
                 );
             }
         }
@@ -460,7 +454,7 @@ impl SecretCommand {
                 .interact()?;
 
             if !confirmed {
-                println!("{} Cancelled.", style("!").yellow());
+                println!("{} Cancelled.", theme().warning_mark());
                 return Ok(());
             }
         }
@@ -473,12 +467,12 @@ impl SecretCommand {
                 "success": true,
                 "key": args.name,
             });
-            println!("{}", serde_json::to_string_pretty(&result)?);
+            write_json(&result)?;
         } else {
             println!(
                 "{} Deleted variable {}",
-                style("✓").green(),
-                style(&args.name).cyan()
+                theme().success_mark(),
+                theme().emphasis(&args.name)
             );
         }
 
@@ -535,7 +529,7 @@ impl SecretCommand {
                 .interact()?;
 
             if !confirmed {
-                println!("{} Cancelled.", style("!").yellow());
+                println!("{} Cancelled.", theme().warning_mark());
                 return Ok(());
             }
         }
@@ -564,12 +558,12 @@ impl SecretCommand {
                 let update_url = format!("{}/{}", base_url, existing_var.uuid);
                 let _: PipelineVariable = client.put(&update_url, &body).await?;
                 updated += 1;
-                println!("  {} Updated {}", style("↻").yellow(), key);
+                println!("  {} Updated {}", theme().info_mark(), key);
             } else {
                 // Create
                 let _: PipelineVariable = client.post(&base_url, &body).await?;
                 created += 1;
-                println!("  {} Created {}", style("+").green(), key);
+                println!("  {} Created {}", theme().success_mark(), key);
             }
         }
 
@@ -579,12 +573,12 @@ impl SecretCommand {
                 "created": created,
                 "updated": updated,
             });
-            println!("{}", serde_json::to_string_pretty(&result)?);
+            write_json(&result)?;
         } else {
             println!();
             println!(
                 "{} Sync complete: {} created, {} updated",
-                style("✓").green(),
+                theme().success_mark(),
                 created,
                 updated
             );
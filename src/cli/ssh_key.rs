@@ -32,7 +32,6 @@ use std::process::Command;
 
 use anyhow::{bail, Result};
 use clap::{Args, Subcommand};
-use console::style;
 use serde::{Deserialize, Serialize};
 
 use crate::api::common::PaginatedResponse;
@@ -40,7 +39,7 @@ use crate::api::BitbucketClient;
 use crate::auth::{AuthCredential, KeyringStore};
 use crate::config::Config;
 use crate::context::{ContextResolver, HostType};
-use crate::output::OutputWriter;
+use crate::output::{print_row, stdout_is_tty, theme, write_json, OutputWriter, TableRow};
 
 use super::GlobalOptions;
 
@@ -126,14 +125,26 @@ struct SshKeyDetail {
     last_used: Option<String>,
 }
 
+impl TableRow for SshKeyListItem {
+    const COLUMNS: &'static [&'static str] = &["uuid", "label", "key", "created_on", "last_used"];
+    const DEFAULT_COLUMNS: &'static [&'static str] = &["uuid", "label", "key"];
+
+    fn cell(&self, column: &str) -> String {
+        let or_dash = |value: &Option<String>| value.clone().unwrap_or_else(|| "-".to_string());
+        match column {
+            "uuid" => self.uuid.clone(),
+            "label" => self.label.clone(),
+            "key" => self.key_preview.clone(),
+            "created_on" => or_dash(&self.created_on),
+            "last_used" => or_dash(&self.last_used),
+            _ => String::new(),
+        }
+    }
+}
+
 impl crate::output::TableOutput for SshKeyListItem {
     fn print_table(&self, _color: bool) {
-        println!(
-            "{:<38} {:<20} {}",
-            &self.uuid,
-            truncate(&self.label, 18),
-            truncate(&self.key_preview, 30)
-        );
+        print_row(self);
     }
 
     fn print_markdown(&self) {
@@ -243,31 +254,19 @@ impl SshKeyCommand {
 
         let writer = OutputWriter::new(self.get_format(global));
 
-        if !global.json {
+        let decorate = !global.json && stdout_is_tty();
+
+        if decorate {
             println!();
-            println!("{}", style("SSH Keys").bold());
-            println!("{}", "-".repeat(80));
-            println!(
-                "{} {} {}",
-                style(format!("{:<38}", "UUID")).bold(),
-                style(format!("{:<20}", "LABEL")).bold(),
-                style("KEY").bold()
-            );
-            println!("{}", "-".repeat(80));
-
-            for item in &items {
-                println!(
-                    "{:<38} {:<20} {}",
-                    &item.uuid,
-                    truncate(&item.label, 18),
-                    truncate(&item.key_preview, 30)
-                );
-            }
+            println!("{}", theme().heading("SSH Keys"));
+            println!();
+        }
+
+        writer.write_table(&items)?;
 
+        if decorate {
             println!();
             println!("Showing {} SSH key(s)", items.len());
-        } else {
-            writer.write_list(&items)?;
         }
 
         Ok(())
@@ -310,12 +309,12 @@ impl SshKeyCommand {
                 "uuid": key.uuid,
                 "label": key.label,
             });
-            println!("{}", serde_json::to_string_pretty(&result)?);
+            write_json(&result)?;
         } else {
             println!(
                 "{} Added SSH key {}",
-                style("✓").green(),
-                style(&args.title).cyan()
+                theme().success_mark(),
+                theme().emphasis(&args.title)
             );
             println!("  UUID: {}", key.uuid);
         }
@@ -337,7 +336,7 @@ impl SshKeyCommand {
                 .interact()?;
 
             if !confirmed {
-                println!("{} Cancelled.", style("!").yellow());
+                println!("{} Cancelled.", theme().warning_mark());
                 return Ok(());
             }
         }
@@ -354,12 +353,12 @@ impl SshKeyCommand {
                 "success": true,
                 "uuid": args.id,
             });
-            println!("{}", serde_json::to_string_pretty(&result)?);
+            write_json(&result)?;
         } else {
             println!(
                 "{} Deleted SSH key {}",
-                style("✓").green(),
-                style(&args.id).cyan()
+                theme().success_mark(),
+                theme().emphasis(&args.id)
             );
         }
 
@@ -377,7 +376,7 @@ impl SshKeyCommand {
 
         println!(
             "{} Testing SSH connection to {}...",
-            style("→").cyan(),
+            theme().info_mark(),
             ssh_host
         );
         println!();
@@ -405,9 +404,9 @@ impl SshKeyCommand {
                 "host": ssh_host,
                 "output": combined_output.trim(),
             });
-            println!("{}", serde_json::to_string_pretty(&result)?);
+            write_json(&result)?;
         } else if combined_output.contains("logged in as") {
-            println!("{} SSH connection successful!", style("✓").green());
+            println!("{} SSH connection successful!", theme().success_mark());
             // Extract username if present
             if let Some(start) = combined_output.find("logged in as") {
                 let user_part = &combined_output[start..];
@@ -418,7 +417,7 @@ impl SshKeyCommand {
         } else if combined_output.contains("Permission denied") {
             println!(
                 "{} SSH connection failed: Permission denied",
-                style("✗").red()
+                theme().failure_mark()
             );
             println!();
             println!("Make sure you have:");
@@ -428,7 +427,7 @@ impl SshKeyCommand {
             println!("Add a key with:");
             println!("  bb ssh-key add --title \"My Key\" --key-file ~/.ssh/id_ed25519.pub");
         } else {
-            println!("{} SSH connection status unknown", style("?").yellow());
+            println!("{} SSH connection status unknown", theme().unknown_mark());
             if !combined_output.is_empty() {
                 println!();
                 println!("Output: {}", combined_output.trim());
@@ -438,12 +437,3 @@ impl SshKeyCommand {
         Ok(())
     }
 }
-
-/// Truncate string to max length with ellipsis
-fn truncate(s: &str, max_len: usize) -> String {
-    if s.len() > max_len {
-        format!("{}...", &s[..max_len - 3])
-    } else {
-        s.to_string()
-    }
-}
@@ -29,7 +29,6 @@
 
 use anyhow::{bail, Result};
 use clap::{Args, Subcommand};
-use console::style;
 use serde::{Deserialize, Serialize};
 
 use crate::api::common::PaginatedResponse;
@@ -37,7 +36,9 @@ use crate::api::BitbucketClient;
 use crate::auth::{AuthCredential, KeyringStore};
 use crate::config::Config;
 use crate::context::{ContextResolver, HostType, RepoContext};
-use crate::output::{OutputWriter, TableOutput};
+use crate::output::{
+    print_row, stdout_is_tty, theme, write_json, Color, OutputWriter, TableOutput, TableRow,
+};
 
 use super::GlobalOptions;
 
@@ -216,19 +217,38 @@ struct DeliveryListItem {
     success: bool,
 }
 
+impl TableRow for WebhookListItem {
+    const COLUMNS: &'static [&'static str] = &["uuid", "active", "url", "events", "description"];
+    const DEFAULT_COLUMNS: &'static [&'static str] = &["uuid", "active", "url", "events"];
+
+    fn cell(&self, column: &str) -> String {
+        match column {
+            "uuid" => self.uuid.clone(),
+            "active" => if self.active { "active" } else { "inactive" }.to_string(),
+            "url" => self.url.clone(),
+            "events" if self.events.len() > 2 => format!(
+                "{}, +{}",
+                self.events[..2].join(", "),
+                self.events.len() - 2
+            ),
+            "events" => self.events.join(", "),
+            "description" => self.description.clone().unwrap_or_else(|| "-".to_string()),
+            _ => String::new(),
+        }
+    }
+
+    fn cell_color(&self, column: &str) -> Option<Color> {
+        match column {
+            "active" if self.active => Some(Color::Green),
+            "active" => Some(Color::DarkGrey),
+            _ => None,
+        }
+    }
+}
+
 impl TableOutput for WebhookListItem {
     fn print_table(&self, _color: bool) {
-        let status = if self.active {
-            style("active").green().to_string()
-        } else {
-            style("inactive").red().to_string()
-        };
-        println!(
-            "{:<38} {:<10} {}",
-            &self.uuid,
-            status,
-            truncate(&self.url, 40)
-        );
+        print_row(self);
     }
 
     fn print_markdown(&self) {
@@ -236,21 +256,37 @@ impl TableOutput for WebhookListItem {
     }
 }
 
+impl TableRow for DeliveryListItem {
+    const COLUMNS: &'static [&'static str] =
+        &["uuid", "event", "triggered_at", "status_code", "success"];
+    const DEFAULT_COLUMNS: &'static [&'static str] = Self::COLUMNS;
+
+    fn cell(&self, column: &str) -> String {
+        match column {
+            "uuid" => self.uuid.clone(),
+            "event" => self.event.clone(),
+            "triggered_at" => self.triggered_at.chars().take(19).collect(),
+            "status_code" => self
+                .status_code
+                .map(|c| c.to_string())
+                .unwrap_or_else(|| "-".to_string()),
+            "success" => if self.success { "yes" } else { "no" }.to_string(),
+            _ => String::new(),
+        }
+    }
+
+    fn cell_color(&self, column: &str) -> Option<Color> {
+        match column {
+            "success" if self.success => Some(Color::Green),
+            "success" => Some(Color::Red),
+            _ => None,
+        }
+    }
+}
+
 impl TableOutput for DeliveryListItem {
     fn print_table(&self, _color: bool) {
-        let status = if self.success {
-            style("success").green().to_string()
-        } else {
-            style("failed").red().to_string()
-        };
-        let code = self
-            .status_code
-            .map(|c| c.to_string())
-            .unwrap_or_else(|| "-".to_string());
-        println!(
-            "{:<38} {:<25} {:<10} {}",
-            &self.uuid, &self.event, status, code
-        );
+        print_row(self);
     }
 
     fn print_markdown(&self) {
@@ -340,25 +376,17 @@ impl WebhookCommand {
 
         let writer = OutputWriter::new(self.get_format(global));
 
-        if !global.json {
+        let decorate = !global.json && stdout_is_tty();
+
+        if decorate {
             println!();
-            println!(
-                "{} {} {} {}",
-                style(format!("{:<38}", "UUID")).bold(),
-                style(format!("{:<8}", "ACTIVE")).bold(),
-                style(format!("{:<30}", "URL")).bold(),
-                style("EVENTS").bold()
-            );
-            println!("{}", "-".repeat(100));
+        }
 
-            for item in &items {
-                item.print_table();
-            }
+        writer.write_table(&items)?;
 
+        if decorate {
             println!();
             println!("Showing {} webhook(s)", items.len());
-        } else {
-            writer.write_list(&items)?;
         }
 
         Ok(())
@@ -429,12 +457,12 @@ impl WebhookCommand {
                 "uuid": hook.uuid,
                 "url": hook.url,
             });
-            println!("{}", serde_json::to_string_pretty(&result)?);
+            write_json(&result)?;
         } else {
             println!(
                 "{} Created webhook {}",
-                style("✓").green(),
-                style(&hook.uuid).cyan()
+                theme().success_mark(),
+                theme().emphasis(&hook.uuid)
             );
             println!("  URL: {}", hook.url);
             println!("  Events: {}", args.events.join(", "));
@@ -490,12 +518,12 @@ impl WebhookCommand {
                 "uuid": hook.uuid,
                 "url": hook.url,
             });
-            println!("{}", serde_json::to_string_pretty(&result)?);
+            write_json(&result)?;
         } else {
             println!(
                 "{} Updated webhook {}",
-                style("✓").green(),
-                style(&hook.uuid).cyan()
+                theme().success_mark(),
+                theme().emphasis(&hook.uuid)
             );
         }
 
@@ -516,7 +544,7 @@ impl WebhookCommand {
                 .interact()?;
 
             if !confirmed {
-                println!("{} Cancelled.", style("!").yellow());
+                println!("{} Cancelled.", theme().warning_mark());
                 return Ok(());
             }
         }
@@ -539,12 +567,12 @@ impl WebhookCommand {
                 "success": true,
                 "uuid": args.uuid,
             });
-            println!("{}", serde_json::to_string_pretty(&result)?);
+            write_json(&result)?;
         } else {
             println!(
                 "{} Deleted webhook {}",
-                style("✓").green(),
-                style(&args.uuid).cyan()
+                theme().success_mark(),
+                theme().emphasis(&args.uuid)
             );
         }
 
@@ -587,26 +615,17 @@ impl WebhookCommand {
 
         let writer = OutputWriter::new(self.get_format(global));
 
-        if !global.json {
+        let decorate = !global.json && stdout_is_tty();
+
+        if decorate {
             println!();
-            println!(
-                "{} {} {} {} {}",
-                style(format!("{:<38}", "UUID")).bold(),
-                style(format!("{:<25}", "EVENT")).bold(),
-                style(format!("{:<20}", "TRIGGERED")).bold(),
-                style(format!("{:<6}", "STATUS")).bold(),
-                style("SUCCESS").bold()
-            );
-            println!("{}", "-".repeat(100));
+        }
 
-            for item in &items {
-                item.print_table();
-            }
+        writer.write_table(&items)?;
 
+        if decorate {
             println!();
             println!("Showing {} delivery/deliveries", items.len());
-        } else {
-            writer.write_list(&items)?;
         }
 
         Ok(())
@@ -636,12 +655,12 @@ impl WebhookCommand {
                 "uuid": args.uuid,
                 "message": "Test payload sent",
             });
-            println!("{}", serde_json::to_string_pretty(&result)?);
+            write_json(&result)?;
         } else {
             println!(
                 "{} Test payload sent to webhook {}",
-                style("✓").green(),
-                style(&args.uuid).cyan()
+                theme().success_mark(),
+                theme().emphasis(&args.uuid)
             );
         }
 
@@ -649,70 +668,14 @@ impl WebhookCommand {
     }
 }
 
-// Implement Display for list items
-impl WebhookListItem {
-    pub fn print_table(&self) {
-        let active_str = if self.active {
-            style("active").green().to_string()
-        } else {
-            style("inactive").dim().to_string()
-        };
-
-        let url_truncated = if self.url.len() > 28 {
-            format!("{}...", &self.url[..25])
-        } else {
-            self.url.clone()
-        };
-
-        let events_str = if self.events.len() > 2 {
-            format!(
-                "{}, +{}",
-                self.events[..2].join(", "),
-                self.events.len() - 2
-            )
-        } else {
-            self.events.join(", ")
-        };
-
-        println!(
-            "{:<38} {:<8} {:<30} {}",
-            self.uuid, active_str, url_truncated, events_str
-        );
-    }
-}
-
-impl DeliveryListItem {
-    pub fn print_table(&self) {
-        let success_str = if self.success {
-            style("yes").green().to_string()
-        } else {
-            style("no").red().to_string()
-        };
-
-        let status_str = self
-            .status_code
-            .map(|s| s.to_string())
-            .unwrap_or_else(|| "-".to_string());
-
-        println!(
-            "{:<38} {:<25} {:<20} {:<6} {}",
-            self.uuid,
-            self.event,
-            &self.triggered_at[..std::cmp::min(19, self.triggered_at.len())],
-            status_str,
-            success_str
-        );
-    }
-}
-
 // TableOutput implementations
 
 impl TableOutput for WebhookDetail {
     fn print_table(&self, _color: bool) {
         println!();
-        println!("{}", style("Webhook Details").bold());
+        println!("{}", theme().heading("Webhook Details"));
         println!("{}", "-".repeat(60));
-        println!("UUID:                 {}", style(&self.uuid).cyan());
+        println!("UUID:                 {}", theme().emphasis(&self.uuid));
         println!("URL:                  {}", self.url);
         println!(
             "Description:          {}",
@@ -721,9 +684,9 @@ impl TableOutput for WebhookDetail {
         println!(
             "Active:               {}",
             if self.active {
-                style("yes").green()
+                theme().paint("yes", Color::DarkGreen)
             } else {
-                style("no").dim()
+                theme().muted("no")
             }
         );
         println!("Events:               {}", self.events.join(", "));
@@ -743,12 +706,3 @@ impl TableOutput for WebhookDetail {
         println!("- **Events**: {}", self.events.join(", "));
     }
 }
-
-/// Truncate string to max length with ellipsis
-fn truncate(s: &str, max_len: usize) -> String {
-    if s.len() > max_len {
-        format!("{}...", &s[..max_len - 3])
-    } else {
-        s.to_string()
-    }
-}
@@ -14,7 +14,6 @@
 
 use anyhow::Result;
 use clap::{Args, Subcommand};
-use console::style;
 use serde::{Deserialize, Serialize};
 
 use crate::api::client::BitbucketClient;
@@ -22,7 +21,10 @@ use crate::api::cloud::workspaces::{Workspace, WorkspaceMember};
 use crate::api::common::PaginatedResponse;
 use crate::auth::{AuthCredential, KeyringStore};
 use crate::config::Config;
-use crate::output::{OutputFormat, OutputWriter, TableOutput};
+use crate::output::{
+    print_row, stdout_is_tty, theme, write_json, Color, OutputFormat, OutputWriter, TableOutput,
+    TableRow,
+};
 use crate::util::open_browser;
 
 use super::GlobalOptions;
@@ -133,33 +135,36 @@ struct WorkspaceListItem {
     is_private: bool,
 }
 
-impl TableOutput for WorkspaceListItem {
-    fn print_table(&self, color: bool) {
-        let privacy = if self.is_private { "private" } else { "public" };
-        let privacy_display = if color {
-            if self.is_private {
-                style(privacy).yellow().to_string()
-            } else {
-                style(privacy).green().to_string()
-            }
-        } else {
-            privacy.to_string()
-        };
+impl TableRow for WorkspaceListItem {
+    const COLUMNS: &'static [&'static str] = &["slug", "name", "permission", "visibility"];
+    const DEFAULT_COLUMNS: &'static [&'static str] = Self::COLUMNS;
+
+    fn cell(&self, column: &str) -> String {
+        match column {
+            "slug" => self.slug.clone(),
+            "name" => self.name.clone(),
+            "permission" => self.permission.clone(),
+            "visibility" => visibility(self.is_private).to_string(),
+            _ => String::new(),
+        }
+    }
 
-        let permission_display = if color {
-            match self.permission.to_lowercase().as_str() {
-                "owner" => style(&self.permission).cyan().bold().to_string(),
-                "admin" => style(&self.permission).magenta().to_string(),
-                _ => self.permission.clone(),
-            }
-        } else {
-            self.permission.clone()
-        };
+    fn cell_color(&self, column: &str) -> Option<Color> {
+        match column {
+            "permission" => match self.permission.to_lowercase().as_str() {
+                "owner" => Some(Color::Cyan),
+                "admin" => Some(Color::Magenta),
+                _ => None,
+            },
+            "visibility" => Some(visibility_color(self.is_private)),
+            _ => None,
+        }
+    }
+}
 
-        println!(
-            "{:<25} {:<30} {:<12} {}",
-            self.slug, self.name, permission_display, privacy_display
-        );
+impl TableOutput for WorkspaceListItem {
+    fn print_table(&self, _color: bool) {
+        print_row(self);
     }
 
     fn print_markdown(&self) {
@@ -183,7 +188,7 @@ struct WorkspaceDetail {
 impl TableOutput for WorkspaceDetail {
     fn print_table(&self, color: bool) {
         let title = if color {
-            style(&self.name).bold().to_string()
+            theme().heading(&self.name)
         } else {
             self.name.clone()
         };
@@ -191,9 +196,9 @@ impl TableOutput for WorkspaceDetail {
         let privacy = if self.is_private { "Private" } else { "Public" };
         let privacy_display = if color {
             if self.is_private {
-                style(privacy).yellow().to_string()
+                theme().paint(privacy, Color::DarkYellow)
             } else {
-                style(privacy).green().to_string()
+                theme().paint(privacy, Color::DarkGreen)
             }
         } else {
             privacy.to_string()
@@ -230,17 +235,24 @@ struct MemberListItem {
     uuid: String,
 }
 
-impl TableOutput for MemberListItem {
-    fn print_table(&self, color: bool) {
-        let name = if color {
-            style(&self.display_name).bold().to_string()
-        } else {
-            self.display_name.clone()
-        };
-
-        let nickname = self.nickname.as_deref().unwrap_or("-");
+impl TableRow for MemberListItem {
+    const COLUMNS: &'static [&'static str] = &["name", "nickname", "account_id", "uuid"];
+    const DEFAULT_COLUMNS: &'static [&'static str] = &["name", "nickname", "uuid"];
+
+    fn cell(&self, column: &str) -> String {
+        match column {
+            "name" => self.display_name.clone(),
+            "nickname" => self.nickname.clone().unwrap_or_else(|| "-".to_string()),
+            "account_id" => self.account_id.clone().unwrap_or_else(|| "-".to_string()),
+            "uuid" => self.uuid.clone(),
+            _ => String::new(),
+        }
+    }
+}
 
-        println!("{:<30} {:<20} {}", name, nickname, self.uuid);
+impl TableOutput for MemberListItem {
+    fn print_table(&self, _color: bool) {
+        print_row(self);
     }
 
     fn print_markdown(&self) {
@@ -257,36 +269,48 @@ struct ProjectListItem {
     is_private: bool,
 }
 
-impl TableOutput for ProjectListItem {
-    fn print_table(&self, color: bool) {
-        let key_display = if color {
-            style(&self.key).cyan().bold().to_string()
-        } else {
-            self.key.clone()
-        };
+impl TableRow for ProjectListItem {
+    const COLUMNS: &'static [&'static str] = &["key", "name", "visibility", "description"];
+    const DEFAULT_COLUMNS: &'static [&'static str] = Self::COLUMNS;
+
+    fn cell(&self, column: &str) -> String {
+        match column {
+            "key" => self.key.clone(),
+            "name" => self.name.clone(),
+            "visibility" => visibility(self.is_private).to_string(),
+            "description" => self.description.clone().unwrap_or_else(|| "-".to_string()),
+            _ => String::new(),
+        }
+    }
 
-        let privacy = if self.is_private { "private" } else { "public" };
-        let privacy_display = if color {
-            if self.is_private {
-                style(privacy).yellow().to_string()
-            } else {
-                style(privacy).green().to_string()
-            }
-        } else {
-            privacy.to_string()
-        };
+    fn cell_color(&self, column: &str) -> Option<Color> {
+        match column {
+            "key" => Some(Color::Cyan),
+            "visibility" => Some(visibility_color(self.is_private)),
+            _ => None,
+        }
+    }
+}
 
-        let description = self.description.as_deref().unwrap_or("-");
-        let desc_truncated = if description.len() > 40 {
-            format!("{}...", &description[..37])
-        } else {
-            description.to_string()
-        };
+fn visibility(is_private: bool) -> &'static str {
+    if is_private {
+        "private"
+    } else {
+        "public"
+    }
+}
 
-        println!(
-            "{:<10} {:<25} {:<10} {}",
-            key_display, self.name, privacy_display, desc_truncated
-        );
+fn visibility_color(is_private: bool) -> Color {
+    if is_private {
+        Color::Yellow
+    } else {
+        Color::Green
+    }
+}
+
+impl TableOutput for ProjectListItem {
+    fn print_table(&self, _color: bool) {
+        print_row(self);
     }
 
     fn print_markdown(&self) {
@@ -362,21 +386,15 @@ impl WorkspaceCommand {
 
         let writer = OutputWriter::new(self.get_format(global));
 
-        if !global.json {
+        let decorate = !global.json && stdout_is_tty();
+
+        if decorate {
             println!();
-            println!(
-                "{} {} {} {}",
-                style(format!("{:<25}", "SLUG")).bold(),
-                style(format!("{:<30}", "NAME")).bold(),
-                style(format!("{:<12}", "PERMISSION")).bold(),
-                style("VISIBILITY").bold()
-            );
-            println!("{}", "-".repeat(80));
         }
 
-        writer.write_list(&items)?;
+        writer.write_table(&items)?;
 
-        if !global.json {
+        if decorate {
             println!();
             println!("Showing {} workspace(s)", items.len());
         }
@@ -466,22 +484,17 @@ impl WorkspaceCommand {
 
         let writer = OutputWriter::new(self.get_format(global));
 
-        if !global.json {
+        let decorate = !global.json && stdout_is_tty();
+
+        if decorate {
             println!();
             println!("Members of workspace '{}':", workspace_slug);
             println!();
-            println!(
-                "{} {} {}",
-                style(format!("{:<30}", "NAME")).bold(),
-                style(format!("{:<20}", "NICKNAME")).bold(),
-                style("UUID").bold()
-            );
-            println!("{}", "-".repeat(70));
         }
 
-        writer.write_list(&items)?;
+        writer.write_table(&items)?;
 
-        if !global.json {
+        if decorate {
             println!();
             println!("Showing {} member(s)", items.len());
         }
@@ -528,23 +541,17 @@ impl WorkspaceCommand {
 
         let writer = OutputWriter::new(self.get_format(global));
 
-        if !global.json {
+        let decorate = !global.json && stdout_is_tty();
+
+        if decorate {
             println!();
             println!("Projects in workspace '{}':", workspace_slug);
             println!();
-            println!(
-                "{} {} {} {}",
-                style(format!("{:<10}", "KEY")).bold(),
-                style(format!("{:<25}", "NAME")).bold(),
-                style(format!("{:<10}", "VISIBILITY")).bold(),
-                style("DESCRIPTION").bold()
-            );
-            println!("{}", "-".repeat(80));
         }
 
-        writer.write_list(&items)?;
+        writer.write_table(&items)?;
 
-        if !global.json {
+        if decorate {
             println!();
             println!("Showing {} project(s)", items.len());
         }
@@ -581,10 +588,10 @@ impl WorkspaceCommand {
                 "workspace": workspace.slug,
                 "name": workspace.name,
             });
-            println!("{}", serde_json::to_string_pretty(&result)?);
+            write_json(&result)?;
         } else {
             println!();
-            println!("{} Switched default workspace", style("✓").green());
+            println!("{} Switched default workspace", theme().success_mark());
             println!();
             println!("  Workspace: {} ({})", workspace.name, workspace.slug);
             println!();
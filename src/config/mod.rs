@@ -19,6 +19,7 @@
 //! - **Core Configuration**: General CLI settings like editor, pager, browser preferences
 //! - **Host Configuration**: Per-host settings for different Bitbucket instances
 //! - **Aliases**: Custom command shortcuts defined by the user
+//! - **Hooks**: Commands run before and after selected `bb` commands
 //!
 //! ## Configuration File Location
 //!
@@ -47,6 +48,9 @@
 //! [aliases]
 //! co = "pr checkout"
 //! pv = "pr view"
+//!
+//! [hooks."pr merge"]
+//! post = ["/usr/local/bin/announce-merge"]
 //! ```
 //!
 //! ## Usage
@@ -72,12 +76,15 @@
 //!
 //! - [`file`]: Low-level configuration file I/O operations
 //! - [`hosts`]: Host-specific configuration and utilities
+//! - [`repo`]: Per-repository settings from `.bb.toml`
 
 mod file;
 mod hosts;
+mod repo;
 
 pub use file::*;
 pub use hosts::*;
+pub use repo::*;
 
 use std::collections::HashMap;
 use std::path::PathBuf;
@@ -97,6 +104,8 @@ use serde::{Deserialize, Serialize};
 /// * `core` - Core CLI configuration options (editor, pager, git protocol, etc.)
 /// * `hosts` - Map of hostname to host-specific configuration
 /// * `aliases` - Map of alias name to command expansion
+/// * `colors` - Map of state name to display colour
+/// * `hooks` - Map of command name to the hooks run around it
 ///
 /// # Examples
 ///
@@ -145,6 +154,21 @@ pub struct Config {
     /// For example: `{"co": "pr checkout"}` allows `bb co` to expand to `bb pr checkout`.
     #[serde(default)]
     pub aliases: HashMap<String, String>,
+
+    /// State colour overrides.
+    ///
+    /// Keys are state names such as `MERGED` or `FAILED`, values are colour
+    /// names such as `"blue"` or `"bright_red"`, or ANSI numbers `0`-`255`.
+    /// States not listed keep their default colours.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub colors: HashMap<String, String>,
+
+    /// Command hooks.
+    ///
+    /// Keys are command names such as `"pr create"`, values the
+    /// [`CommandHooks`] run before and after that command.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub hooks: HashMap<String, CommandHooks>,
 }
 
 /// Core configuration options for the Bitbucket CLI.
@@ -160,6 +184,11 @@ pub struct Config {
 /// * `browser` - Web browser for opening URLs
 /// * `git_protocol` - Git protocol preference ("https" or "ssh")
 /// * `prompt` - Interactive prompt behavior ("enabled" or "disabled")
+/// * `color` - When to colour output ("auto", "always" or "never")
+/// * `theme` - Output theme ("default" or "plain")
+/// * `repo_hooks` - Whether hooks from `.bb.toml` run ("enabled" or "disabled")
+/// * `extension_token` - Whether extensions and hooks get a long-lived
+///   `BB_TOKEN` ("enabled" or "disabled")
 ///
 /// # Default Values
 ///
@@ -170,6 +199,10 @@ pub struct Config {
 /// | `browser` | `None` (uses system default) |
 /// | `git_protocol` | `"https"` |
 /// | `prompt` | `"enabled"` |
+/// | `color` | `None` (same as `"auto"`) |
+/// | `theme` | `None` (same as `"default"`) |
+/// | `repo_hooks` | `None` (same as `"disabled"`) |
+/// | `extension_token` | `None` (same as `"disabled"`) |
 ///
 /// # Examples
 ///
@@ -251,6 +284,66 @@ pub struct CoreConfig {
     /// Setting to `"disabled"` is useful for scripting and CI/CD environments.
     #[serde(default = "default_prompt")]
     pub prompt: String,
+
+    /// When to colour output.
+    ///
+    /// # Valid Values
+    ///
+    /// - `"auto"` - Colour when stdout is a terminal, honouring `NO_COLOR`
+    ///   and `CLICOLOR_FORCE` (default)
+    /// - `"always"` - Always colour output
+    /// - `"never"` - Never colour output
+    ///
+    /// # Notes
+    ///
+    /// The global `--color` flag takes precedence over this setting.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+
+    /// Output theme.
+    ///
+    /// # Valid Values
+    ///
+    /// - `"default"` - Coloured output with status glyphs such as `✓`
+    /// - `"plain"` - Words instead of glyphs and ASCII table borders, with
+    ///   colour off unless `--color=always` is given; suited to screen
+    ///   readers and log files
+    ///
+    /// # Notes
+    ///
+    /// The `BB_THEME` environment variable takes precedence over this setting.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub theme: Option<String>,
+
+    /// Whether hooks defined in a repository's `.bb.toml` are run.
+    ///
+    /// # Valid Values
+    ///
+    /// - `"enabled"` - Run repository hooks after the hooks from this file
+    /// - `"disabled"` - Ignore repository hooks (default)
+    ///
+    /// # Notes
+    ///
+    /// Repository hooks arrive with `git clone`, so they are off until the
+    /// user decides to trust them.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repo_hooks: Option<String>,
+
+    /// Whether extensions and hooks get `BB_TOKEN` when the stored
+    /// credential is long-lived.
+    ///
+    /// # Valid Values
+    ///
+    /// - `"enabled"` - Pass the stored credential as `BB_TOKEN`
+    /// - `"disabled"` - Only pass short-lived OAuth access tokens (default)
+    ///
+    /// # Notes
+    ///
+    /// Personal access tokens, API tokens and app passwords do not expire
+    /// on their own, so a hook or extension that leaks one leaks access
+    /// until the user revokes it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extension_token: Option<String>,
 }
 
 /// Returns the default git protocol value.
@@ -292,6 +385,10 @@ impl Default for CoreConfig {
     /// - `browser`: `None`
     /// - `git_protocol`: `"https"`
     /// - `prompt`: `"enabled"`
+    /// - `color`: `None`
+    /// - `theme`: `None`
+    /// - `repo_hooks`: `None`
+    /// - `extension_token`: `None`
     ///
     /// # Examples
     ///
@@ -308,6 +405,10 @@ impl Default for CoreConfig {
             browser: None,
             git_protocol: default_git_protocol(),
             prompt: default_prompt(),
+            color: None,
+            theme: None,
+            repo_hooks: None,
+            extension_token: None,
         }
     }
 }
@@ -636,6 +737,10 @@ impl Config {
     /// | `"browser"` | `core.browser` | Optional |
     /// | `"git_protocol"` | `core.git_protocol` | Required |
     /// | `"prompt"` | `core.prompt` | Required |
+    /// | `"color"` | `core.color` | Optional |
+    /// | `"theme"` | `core.theme` | Optional |
+    /// | `"repo_hooks"` | `core.repo_hooks` | Optional |
+    /// | `"extension_token"` | `core.extension_token` | Optional |
     ///
     /// # Returns
     ///
@@ -665,6 +770,10 @@ impl Config {
             "browser" => self.core.browser.clone(),
             "git_protocol" => Some(self.core.git_protocol.clone()),
             "prompt" => Some(self.core.prompt.clone()),
+            "color" => self.core.color.clone(),
+            "theme" => self.core.theme.clone(),
+            "repo_hooks" => self.core.repo_hooks.clone(),
+            "extension_token" => self.core.extension_token.clone(),
             _ => None,
         }
     }
@@ -689,6 +798,10 @@ impl Config {
     /// | `"browser"` | `core.browser` | Sets to `Some(value)` |
     /// | `"git_protocol"` | `core.git_protocol` | Should be "https" or "ssh" |
     /// | `"prompt"` | `core.prompt` | Should be "enabled" or "disabled" |
+    /// | `"color"` | `core.color` | Should be "auto", "always" or "never" |
+    /// | `"theme"` | `core.theme` | Should be "default" or "plain" |
+    /// | `"repo_hooks"` | `core.repo_hooks` | Should be "enabled" or "disabled" |
+    /// | `"extension_token"` | `core.extension_token` | Should be "enabled" or "disabled" |
     ///
     /// # Returns
     ///
@@ -737,6 +850,22 @@ impl Config {
                 self.core.prompt = value;
                 true
             }
+            "color" => {
+                self.core.color = Some(value);
+                true
+            }
+            "theme" => {
+                self.core.theme = Some(value);
+                true
+            }
+            "repo_hooks" => {
+                self.core.repo_hooks = Some(value);
+                true
+            }
+            "extension_token" => {
+                self.core.extension_token = Some(value);
+                true
+            }
             _ => false,
         }
     }
@@ -0,0 +1,189 @@
+//
+//  bitbucket-cli
+//  config/repo.rs
+//
+//  Created by Ngonidzashe Mangudya on 2026/01/12.
+//  Copyright (c) 2025 IAMNGONI. All rights reserved.
+//
+
+//! # Repository Configuration Module
+//!
+//! A repository can carry shared `bb` settings in a `.bb.toml` file at the
+//! root of its working tree. Today the file holds command hooks:
+//!
+//! ```toml
+//! [hooks."pr create"]
+//! pre = ["scripts/check-jira-key.sh"]
+//!
+//! [hooks."pr merge"]
+//! post = ["bb notify merged"]
+//! ```
+//!
+//! Because the file arrives with `git clone`, its hooks only run once the
+//! user opts in with `bb config set repo_hooks enabled`, and only for the
+//! repository the file was checked out from.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::{read_config_file, Config};
+use crate::context::{ContextResolver, GitContext, RepoContext};
+
+/// File name of the repository configuration file.
+pub const REPO_CONFIG_FILE: &str = ".bb.toml";
+
+/// The hooks configured for one command.
+///
+/// Each entry is a command line, split like a shell would split it.
+///
+/// # Fields
+///
+/// * `pre` - Commands run before the operation; a failure aborts it
+/// * `post` - Commands run after the operation succeeded
+///
+/// # Examples
+///
+/// ```rust
+/// use bitbucket_cli::config::CommandHooks;
+///
+/// let hooks: CommandHooks = toml::from_str(r#"pre = ["./check.sh"]"#).unwrap();
+/// assert_eq!(hooks.pre, vec!["./check.sh"]);
+/// assert!(hooks.post.is_empty());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CommandHooks {
+    /// Commands run before the operation.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pre: Vec<String>,
+
+    /// Commands run after the operation.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub post: Vec<String>,
+}
+
+/// Settings loaded from a repository's `.bb.toml`.
+///
+/// # Fields
+///
+/// * `hooks` - Command hooks, keyed by command name such as `"pr create"`
+/// * `path` - Where the file was loaded from
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RepoConfig {
+    /// Command hooks, keyed by command name.
+    #[serde(default)]
+    pub hooks: HashMap<String, CommandHooks>,
+
+    /// The file this configuration was read from.
+    #[serde(skip)]
+    pub path: PathBuf,
+}
+
+impl RepoConfig {
+    /// Loads `.bb.toml` from the root of the current git working tree.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(config))` - If the current directory is inside a working
+    ///   tree with a `.bb.toml`
+    /// * `Ok(None)` - If it is not in a repository or the file does not exist
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file exists but cannot be read or parsed.
+    pub fn load() -> Result<Option<Self>> {
+        let Ok(git) = GitContext::open() else {
+            return Ok(None);
+        };
+        match git.root_dir() {
+            Some(root) => Self::load_from(root),
+            None => Ok(None),
+        }
+    }
+
+    /// Loads `.bb.toml` from the current git working tree if it is a
+    /// checkout of `repo`.
+    ///
+    /// A command run with `--repo` against another repository must not pick
+    /// up the settings of whichever checkout it happens to be run from, so
+    /// the working tree's `origin` remote has to name `repo`.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(config))` - If the working tree is a checkout of `repo`
+    ///   with a `.bb.toml`
+    /// * `Ok(None)` - Otherwise
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file exists but cannot be read or parsed.
+    pub fn load_for(repo: &RepoContext) -> Result<Option<Self>> {
+        let Ok(git) = GitContext::open() else {
+            return Ok(None);
+        };
+        let checkout = git.origin_url()?.and_then(|url| {
+            ContextResolver::new(Config::default())
+                .parse_remote_url(&url)
+                .ok()
+        });
+        let same_repo = checkout.is_some_and(|c| {
+            c.host.eq_ignore_ascii_case(&repo.host)
+                && c.owner.eq_ignore_ascii_case(&repo.owner)
+                && c.repo_slug.eq_ignore_ascii_case(&repo.repo_slug)
+        });
+        match git.root_dir() {
+            Some(root) if same_repo => Self::load_from(root),
+            _ => Ok(None),
+        }
+    }
+
+    /// Loads `.bb.toml` from a directory.
+    ///
+    /// # Parameters
+    ///
+    /// * `dir` - The directory expected to hold `.bb.toml`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file exists but cannot be read or parsed.
+    pub fn load_from(dir: &Path) -> Result<Option<Self>> {
+        let path = dir.join(REPO_CONFIG_FILE);
+        if !path.is_file() {
+            return Ok(None);
+        }
+
+        let content = read_config_file(&path)?;
+        let mut config: Self =
+            toml::from_str(&content).with_context(|| format!("Invalid {}", path.display()))?;
+        config.path = path;
+        Ok(Some(config))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_repo_config() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(RepoConfig::load_from(dir.path()).unwrap(), None);
+
+        std::fs::write(
+            dir.path().join(REPO_CONFIG_FILE),
+            "[hooks.\"pr create\"]\npre = [\"./check.sh --strict\"]\n",
+        )
+        .unwrap();
+        let config = RepoConfig::load_from(dir.path()).unwrap().unwrap();
+        assert_eq!(
+            config.hooks["pr create"].pre,
+            vec!["./check.sh --strict".to_string()]
+        );
+        assert_eq!(config.path, dir.path().join(REPO_CONFIG_FILE));
+
+        std::fs::write(dir.path().join(REPO_CONFIG_FILE), "hooks = 1\n").unwrap();
+        assert!(RepoConfig::load_from(dir.path()).is_err());
+    }
+}
@@ -0,0 +1,612 @@
+//
+//  bitbucket-cli
+//  extension/install.rs
+//
+//  Created by Ngonidzashe Mangudya on 2026/01/12.
+//  Copyright (c) 2025 IAMNGONI. All rights reserved.
+//
+
+//! Fetching and building extensions
+//!
+//! An extension repository is installed in one of two ways:
+//!
+//! - **Downloads**: when the repository is on Bitbucket Cloud and its
+//!   Downloads contain a `bb-<name>` binary for the current platform, that
+//!   binary is installed directly.
+//! - **Source**: otherwise the repository is cloned with `git`. Scripts are
+//!   run from the clone; Cargo and Go projects (as generated by
+//!   `bb extension create --precompiled`) are built in place.
+
+use std::path::Path;
+use std::process::Command;
+
+use anyhow::{bail, Context, Result};
+use reqwest::Client;
+use serde::Deserialize;
+
+use super::ExtensionKind;
+use crate::api::format_api_error;
+use crate::api::replay::ReplayExt;
+use crate::auth::KeyringStore;
+use crate::config::BITBUCKET_CLOUD;
+
+/// Where an extension is installed from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExtensionSource {
+    /// The URL passed to `git clone`.
+    pub url: String,
+
+    /// The extension name without the `bb-` prefix.
+    pub name: String,
+
+    /// The Bitbucket Cloud `(workspace, repo)`, when the source is on Cloud.
+    ///
+    /// Only Cloud repositories have Downloads to look for binaries in.
+    pub cloud_repo: Option<(String, String)>,
+}
+
+impl ExtensionSource {
+    /// Parses an extension repository reference.
+    ///
+    /// # Parameters
+    ///
+    /// * `spec` - `owner/repo`, a git URL (`https://`, `ssh://`, `file://`,
+    ///   `git@host:path`) or a local path
+    /// * `use_ssh` - Whether `owner/repo` should be cloned over SSH
+    ///
+    /// # Returns
+    ///
+    /// The parsed source. `owner/repo` refers to Bitbucket Cloud.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `spec` starts with `-` or no repository name can
+    /// be found in it.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let source = ExtensionSource::parse("myteam/bb-lint", false)?;
+    /// assert_eq!(source.url, "https://bitbucket.org/myteam/bb-lint.git");
+    /// assert_eq!(source.name, "lint");
+    /// ```
+    pub fn parse(spec: &str, use_ssh: bool) -> Result<Self> {
+        let spec = spec.trim().trim_end_matches('/');
+
+        // git would read it as an option
+        if spec.starts_with('-') {
+            bail!("Invalid extension repository '{}'", spec);
+        }
+
+        let is_url = spec.contains("://")
+            || spec.starts_with("git@")
+            || spec.starts_with('/')
+            || spec.starts_with('.');
+
+        if !is_url {
+            let parts: Vec<&str> = spec.split('/').collect();
+            if parts.len() != 2 || parts.iter().any(|p| p.is_empty()) {
+                bail!(
+                    "Invalid extension repository '{}'. Use OWNER/REPO or a git URL",
+                    spec
+                );
+            }
+            let (owner, repo) = (parts[0], parts[1].trim_end_matches(".git"));
+            let url = if use_ssh {
+                format!("git@{}:{}/{}.git", BITBUCKET_CLOUD, owner, repo)
+            } else {
+                format!("https://{}/{}/{}.git", BITBUCKET_CLOUD, owner, repo)
+            };
+            return Ok(Self {
+                url,
+                name: extension_name(repo),
+                cloud_repo: Some((owner.to_string(), repo.to_string())),
+            });
+        }
+
+        let repo = spec
+            .rsplit(['/', ':'])
+            .next()
+            .map(|r| r.trim_end_matches(".git"))
+            .filter(|r| !r.is_empty())
+            .ok_or_else(|| anyhow::anyhow!("Could not parse repository URL: {}", spec))?;
+
+        Ok(Self {
+            url: spec.to_string(),
+            name: extension_name(repo),
+            cloud_repo: parse_cloud_repo(spec),
+        })
+    }
+}
+
+/// Strips the `bb-` prefix from a repository name.
+fn extension_name(repo: &str) -> String {
+    repo.strip_prefix("bb-").unwrap_or(repo).to_string()
+}
+
+/// Extracts `(workspace, repo)` from a Bitbucket Cloud HTTPS or SSH URL.
+fn parse_cloud_repo(url: &str) -> Option<(String, String)> {
+    let rest = url
+        .strip_prefix("https://")
+        .map(|r| r.split_once('@').map_or(r, |(_, host)| host))
+        .and_then(|r| r.strip_prefix(&format!("{}/", BITBUCKET_CLOUD)))
+        .or_else(|| url.strip_prefix(&format!("git@{}:", BITBUCKET_CLOUD)))
+        .or_else(|| url.strip_prefix(&format!("ssh://git@{}/", BITBUCKET_CLOUD)))?;
+
+    let mut parts = rest.trim_end_matches(".git").split('/');
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(owner), Some(repo), None) if !owner.is_empty() && !repo.is_empty() => {
+            Some((owner.to_string(), repo.to_string()))
+        }
+        _ => None,
+    }
+}
+
+/// Runs `git` and returns its trimmed standard output.
+///
+/// # Errors
+///
+/// Returns an error containing git's standard error if it exits non-zero,
+/// or if git is not installed.
+pub fn git(dir: Option<&Path>, args: &[&str]) -> Result<String> {
+    let mut command = Command::new("git");
+    if let Some(dir) = dir {
+        command.arg("-C").arg(dir);
+    }
+
+    let output = command
+        .args(args)
+        .output()
+        .context("Failed to run git. Is it installed and on PATH?")?;
+
+    if !output.status.success() {
+        bail!(
+            "git {} failed: {}",
+            args.first().unwrap_or(&""),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Works out how a cloned extension repository should be installed.
+///
+/// A `Cargo.toml` means a Rust project and a `go.mod` a Go module; anything
+/// else must contain a `bb-<name>` script at its root.
+///
+/// # Errors
+///
+/// Returns an error if the repository is neither a project nor contains the
+/// `bb-<name>` script.
+pub fn detect_kind(dir: &Path, name: &str) -> Result<ExtensionKind> {
+    if dir.join("Cargo.toml").exists() {
+        return Ok(ExtensionKind::Rust);
+    }
+    if dir.join("go.mod").exists() {
+        return Ok(ExtensionKind::Go);
+    }
+    if dir.join(format!("bb-{}", name)).is_file() {
+        return Ok(ExtensionKind::Script);
+    }
+
+    bail!(
+        "Repository is not a bb extension: expected a bb-{} script, a Cargo.toml or a go.mod at its root",
+        name
+    )
+}
+
+/// Produces the `bb-<name>` executable at the root of a cloned repository.
+///
+/// Rust projects are built with `cargo build --release` and the binary is
+/// copied up from `target/release`; Go modules are built with
+/// `go build -o bb-<name>`; scripts are made executable.
+///
+/// # Errors
+///
+/// Returns an error if the toolchain is missing or the build fails.
+pub fn build(dir: &Path, name: &str, kind: ExtensionKind) -> Result<()> {
+    let executable = format!("bb-{}{}", name, std::env::consts::EXE_SUFFIX);
+
+    match kind {
+        ExtensionKind::Rust => {
+            run_build(dir, "cargo", &["build", "--release", "--quiet"])?;
+            let built = dir.join("target").join("release").join(&executable);
+            if !built.exists() {
+                bail!(
+                    "cargo build did not produce {}; the binary must be named bb-{}",
+                    executable,
+                    name
+                );
+            }
+            std::fs::copy(&built, dir.join(&executable))?;
+        }
+        ExtensionKind::Go => {
+            run_build(dir, "go", &["build", "-o", &executable])?;
+        }
+        ExtensionKind::Script | ExtensionKind::Binary => {}
+    }
+
+    make_executable(&dir.join(&executable))
+}
+
+/// Runs a build tool in `dir`, with a helpful error when it is missing.
+fn run_build(dir: &Path, tool: &str, args: &[&str]) -> Result<()> {
+    let status = Command::new(tool)
+        .args(args)
+        .current_dir(dir)
+        .status()
+        .with_context(|| format!("Building this extension requires {} on PATH", tool))?;
+
+    if !status.success() {
+        bail!("{} {} failed", tool, args.join(" "));
+    }
+
+    Ok(())
+}
+
+/// Sets the executable bits on Unix; a no-op elsewhere.
+pub fn make_executable(path: &Path) -> Result<()> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mut perms = std::fs::metadata(path)?.permissions();
+        perms.set_mode(perms.mode() | 0o755);
+        std::fs::set_permissions(path, perms)?;
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+
+    Ok(())
+}
+
+/// A file in a repository's Downloads.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Download {
+    /// The file name.
+    pub name: String,
+
+    /// The file size in bytes.
+    #[serde(default)]
+    pub size: Option<u64>,
+
+    /// When the file was uploaded.
+    #[serde(default)]
+    pub created_on: Option<String>,
+
+    links: DownloadLinks,
+}
+
+impl Download {
+    /// Identifies this upload of the file, for `extensions.lock`.
+    ///
+    /// Release tooling often reuses a fixed file name such as
+    /// `bb-lint-linux-amd64`, so the name alone cannot tell a new upload
+    /// from the installed one. The upload date and size can.
+    pub fn revision(&self) -> Option<String> {
+        match (&self.created_on, self.size) {
+            (None, None) => None,
+            (created_on, size) => Some(format!(
+                "{}/{}",
+                created_on.as_deref().unwrap_or("-"),
+                size.map_or_else(|| "-".to_string(), |s| s.to_string())
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct DownloadLinks {
+    #[serde(rename = "self")]
+    self_link: DownloadLink,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct DownloadLink {
+    href: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct DownloadsPage {
+    values: Vec<Download>,
+}
+
+/// Looks for a binary for this platform in a Cloud repository's Downloads.
+///
+/// # Parameters
+///
+/// * `owner` / `repo` - The Bitbucket Cloud repository
+/// * `name` - The extension name without the `bb-` prefix
+/// * `pin` - When set, only files whose name contains this version match
+///   (see [`asset_has_version`])
+///
+/// # Returns
+///
+/// The matching download, or `None` if the repository has no Downloads, is
+/// not readable, or has no file for this platform.
+///
+/// # Errors
+///
+/// Returns an error for unexpected API failures.
+pub async fn find_download(
+    owner: &str,
+    repo: &str,
+    name: &str,
+    pin: Option<&str>,
+) -> Result<Option<Download>> {
+    let url = format!(
+        "https://api.bitbucket.org/2.0/repositories/{}/{}/downloads?pagelen=100",
+        owner, repo
+    );
+
+    let response = authorized(client()?.get(&url)).send_replayable().await?;
+    let status = response.status();
+    if status.as_u16() == 403 || status.as_u16() == 404 {
+        return Ok(None);
+    }
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        return Err(format_api_error(status, &body));
+    }
+
+    let page: DownloadsPage = response.json().await?;
+    Ok(page.values.into_iter().find(|download| {
+        asset_matches_platform(
+            &download.name,
+            name,
+            std::env::consts::OS,
+            std::env::consts::ARCH,
+        ) && pin.is_none_or(|pin| asset_has_version(&download.name, pin))
+    }))
+}
+
+/// Returns whether an asset name contains a version as a whole token.
+///
+/// A leading `v` on either side is ignored, but the version may not be part
+/// of a longer one, so `1.2` matches `bb-lint-v1.2-linux-amd64` but not
+/// `bb-lint-1.20-linux-amd64`, `bb-lint-11.2-linux-amd64` or
+/// `bb-lint-1.2.1-linux-amd64`.
+fn asset_has_version(asset: &str, version: &str) -> bool {
+    let version = version.trim_start_matches('v');
+    if version.is_empty() {
+        return false;
+    }
+
+    asset.match_indices(version).any(|(start, _)| {
+        let before = asset[..start].chars().next_back();
+        let mut after = asset[start + version.len()..].chars();
+        let starts_token = !before.is_some_and(|c| c.is_ascii_digit() || c == '.');
+        let ends_token = match after.next() {
+            None => true,
+            Some('.') => !after.next().is_some_and(|c| c.is_ascii_digit()),
+            Some(c) => !c.is_ascii_digit(),
+        };
+        starts_token && ends_token
+    })
+}
+
+/// Downloads a file from a repository's Downloads to `dest`.
+///
+/// The file is written to a temporary file next to `dest`, made executable
+/// and then renamed over `dest`, so an existing binary is replaced in one
+/// step and left alone if anything fails.
+///
+/// # Errors
+///
+/// Returns an error if the request fails or the file cannot be written.
+pub async fn fetch_download(download: &Download, dest: &Path) -> Result<()> {
+    let response = authorized(client()?.get(&download.links.self_link.href))
+        .send_replayable()
+        .await?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        return Err(format_api_error(status, &body));
+    }
+
+    let bytes = response.bytes().await?;
+    let file_name = dest
+        .file_name()
+        .map(|name| name.to_string_lossy())
+        .unwrap_or_default();
+    let staging = dest.with_file_name(format!(".{}.tmp", file_name));
+
+    let written = std::fs::write(&staging, &bytes)
+        .with_context(|| format!("Failed to write {}", staging.display()))
+        .and_then(|()| make_executable(&staging))
+        .and_then(|()| {
+            std::fs::rename(&staging, dest)
+                .with_context(|| format!("Failed to replace {}", dest.display()))
+        });
+    if written.is_err() {
+        let _ = std::fs::remove_file(&staging);
+    }
+    written
+}
+
+fn client() -> Result<Client> {
+    Ok(Client::builder()
+        .user_agent(format!("bb/{}", crate::VERSION))
+        .build()?)
+}
+
+/// Adds the Bitbucket Cloud credential, if there is one; public
+/// repositories can be read without it.
+fn authorized(request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+    match KeyringStore::new().get(BITBUCKET_CLOUD).ok().flatten() {
+        Some(token) => request.bearer_auth(token),
+        None => request,
+    }
+}
+
+/// Whether a Downloads file is a `bb-<name>` binary for a platform.
+///
+/// The file name must start with `bb-<name>` and mention both the operating
+/// system and the architecture, using the spellings release tooling commonly
+/// produces (`linux`, `darwin`/`macos`, `windows`; `x86_64`/`amd64`,
+/// `aarch64`/`arm64`). Archives and checksum files are not binaries.
+///
+/// # Parameters
+///
+/// * `asset` - The file name, e.g. `bb-lint-v1.2.0-linux-amd64`
+/// * `name` - The extension name without the `bb-` prefix
+/// * `os` / `arch` - Values of `std::env::consts::OS` and `ARCH`
+///
+/// # Example
+///
+/// ```rust,ignore
+/// assert!(asset_matches_platform("bb-lint_darwin_arm64", "lint", "macos", "aarch64"));
+/// assert!(!asset_matches_platform("bb-lint-linux-amd64.tar.gz", "lint", "linux", "x86_64"));
+/// ```
+pub fn asset_matches_platform(asset: &str, name: &str, os: &str, arch: &str) -> bool {
+    const NOT_BINARIES: &[&str] = &[
+        ".tar.gz", ".tgz", ".zip", ".sha256", ".sig", ".asc", ".txt", ".md", ".deb", ".rpm",
+    ];
+
+    let asset = asset.to_lowercase();
+    let Some(rest) = asset.strip_prefix(&format!("bb-{}", name.to_lowercase())) else {
+        return false;
+    };
+    if rest.starts_with(|c: char| c.is_ascii_alphanumeric())
+        || NOT_BINARIES.iter().any(|ext| asset.ends_with(ext))
+    {
+        return false;
+    }
+
+    let normalized = asset.replace("x86_64", "amd64").replace("x86-64", "amd64");
+    let tokens: Vec<&str> = normalized
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .collect();
+
+    let os_names: &[&str] = match os {
+        "linux" => &["linux"],
+        "macos" => &["darwin", "macos", "osx", "apple"],
+        "windows" => &["windows", "win64", "win"],
+        other => return tokens.contains(&other),
+    };
+    let arch_names: &[&str] = match arch {
+        "x86_64" => &["amd64", "x64"],
+        "aarch64" => &["arm64", "aarch64"],
+        other => return tokens.contains(&other),
+    };
+
+    os_names.iter().any(|os| tokens.contains(os))
+        && arch_names.iter().any(|arch| tokens.contains(arch))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_owner_repo() {
+        let source = ExtensionSource::parse("myteam/bb-lint", false).unwrap();
+        assert_eq!(source.url, "https://bitbucket.org/myteam/bb-lint.git");
+        assert_eq!(source.name, "lint");
+        assert_eq!(
+            source.cloud_repo,
+            Some(("myteam".to_string(), "bb-lint".to_string()))
+        );
+
+        let source = ExtensionSource::parse("myteam/bb-lint", true).unwrap();
+        assert_eq!(source.url, "git@bitbucket.org:myteam/bb-lint.git");
+    }
+
+    #[test]
+    fn test_parse_git_urls() {
+        let source = ExtensionSource::parse("git@bitbucket.org:myteam/bb-lint.git", false).unwrap();
+        assert_eq!(source.name, "lint");
+        assert_eq!(
+            source.cloud_repo,
+            Some(("myteam".to_string(), "bb-lint".to_string()))
+        );
+
+        let source =
+            ExtensionSource::parse("https://git.example.com/scm/tools/bb-deploy.git", false)
+                .unwrap();
+        assert_eq!(
+            source.url,
+            "https://git.example.com/scm/tools/bb-deploy.git"
+        );
+        assert_eq!(source.name, "deploy");
+        assert_eq!(source.cloud_repo, None);
+
+        let source = ExtensionSource::parse("/tmp/work/bb-hello/", false).unwrap();
+        assert_eq!(source.url, "/tmp/work/bb-hello");
+        assert_eq!(source.name, "hello");
+    }
+
+    #[test]
+    fn test_parse_rejects_bad_shorthand() {
+        assert!(ExtensionSource::parse("bb-lint", false).is_err());
+        assert!(ExtensionSource::parse("a/b/c", false).is_err());
+        assert!(ExtensionSource::parse("--upload-pack=touch /tmp/x", false).is_err());
+    }
+
+    #[test]
+    fn test_asset_matches_platform() {
+        assert!(asset_matches_platform(
+            "bb-lint-linux-amd64",
+            "lint",
+            "linux",
+            "x86_64"
+        ));
+        assert!(asset_matches_platform(
+            "bb-lint_v1.2.0_Linux_x86_64",
+            "lint",
+            "linux",
+            "x86_64"
+        ));
+        assert!(asset_matches_platform(
+            "bb-lint-darwin-arm64",
+            "lint",
+            "macos",
+            "aarch64"
+        ));
+        assert!(asset_matches_platform(
+            "bb-lint-windows-amd64.exe",
+            "lint",
+            "windows",
+            "x86_64"
+        ));
+
+        // "darwin" must not be read as "win"
+        assert!(!asset_matches_platform(
+            "bb-lint-darwin-amd64",
+            "lint",
+            "windows",
+            "x86_64"
+        ));
+        assert!(!asset_matches_platform(
+            "bb-lint-linux-arm64",
+            "lint",
+            "linux",
+            "x86_64"
+        ));
+        assert!(!asset_matches_platform(
+            "bb-lint-linux-amd64.tar.gz",
+            "lint",
+            "linux",
+            "x86_64"
+        ));
+        assert!(!asset_matches_platform(
+            "bb-linter-linux-amd64",
+            "lint",
+            "linux",
+            "x86_64"
+        ));
+    }
+
+    #[test]
+    fn test_asset_has_version() {
+        assert!(asset_has_version("bb-lint-v1.2-linux-amd64", "1.2"));
+        assert!(asset_has_version("bb-lint_1.2_linux_amd64", "v1.2"));
+        assert!(asset_has_version("bb-lint-1.2.exe", "1.2"));
+
+        assert!(!asset_has_version("bb-lint-v1.20-linux-amd64", "1.2"));
+        assert!(!asset_has_version("bb-lint-v11.2-linux-amd64", "1.2"));
+        assert!(!asset_has_version("bb-lint-v1.2.1-linux-amd64", "1.2"));
+        assert!(!asset_has_version("bb-lint-linux-amd64", "v"));
+    }
+}
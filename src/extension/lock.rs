@@ -0,0 +1,215 @@
+//
+//  bitbucket-cli
+//  extension/lock.rs
+//
+//  Created by Ngonidzashe Mangudya on 2026/01/12.
+//  Copyright (c) 2025 IAMNGONI. All rights reserved.
+//
+
+//! The `extensions.lock` manifest
+//!
+//! Every extension installed by `bb extension install` gets an entry in
+//! `extensions.lock`, stored next to the extensions themselves. The entry
+//! records where the extension came from and exactly what was installed, so
+//! that `bb extension upgrade` knows what to fetch and pinned extensions stay
+//! where they were put.
+//!
+//! ```toml
+//! [extensions.lint]
+//! source = "https://bitbucket.org/myteam/bb-lint.git"
+//! kind = "script"
+//! ref = "main"
+//! commit = "4f6c2a1e9b7d0c3f5a8e2d1b6c9f0a3e7d4b2c1a"
+//! pinned = false
+//! ```
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::config::{read_config_file, write_config_file};
+
+/// File name of the lock manifest inside the extension directory.
+pub const LOCK_FILE: &str = "extensions.lock";
+
+/// How an installed extension was produced.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExtensionKind {
+    /// A script cloned from the repository and run as-is
+    Script,
+    /// A Cargo project cloned and built with `cargo build --release`
+    Rust,
+    /// A Go module cloned and built with `go build`
+    Go,
+    /// A precompiled binary taken from the repository's Downloads
+    Binary,
+}
+
+impl ExtensionKind {
+    /// Whether the installed executable is a compiled binary.
+    pub fn is_precompiled(&self) -> bool {
+        !matches!(self, ExtensionKind::Script)
+    }
+}
+
+impl std::fmt::Display for ExtensionKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            ExtensionKind::Script => "script",
+            ExtensionKind::Rust => "rust",
+            ExtensionKind::Go => "go",
+            ExtensionKind::Binary => "binary",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// A single extension recorded in `extensions.lock`.
+///
+/// # Fields
+///
+/// * `source` - The git URL the extension was installed from
+/// * `kind` - How the executable was produced
+/// * `git_ref` - The branch, tag or commit that was checked out
+/// * `commit` - The full commit hash that was installed (git installs only)
+/// * `asset` - The Downloads file that was installed (binary installs only)
+/// * `asset_revision` - The upload date and size of that file
+/// * `pinned` - Whether `git_ref` was given with `--pin`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LockedExtension {
+    /// The git URL the extension was installed from.
+    pub source: String,
+
+    /// How the executable was produced.
+    pub kind: ExtensionKind,
+
+    /// The branch, tag or commit that was checked out.
+    #[serde(rename = "ref", default, skip_serializing_if = "Option::is_none")]
+    pub git_ref: Option<String>,
+
+    /// The full commit hash that was installed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+
+    /// The name of the downloaded binary, for binary installs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub asset: Option<String>,
+
+    /// The upload date and size of the downloaded binary, so that a new
+    /// upload under the same name is recognised as an upgrade.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub asset_revision: Option<String>,
+
+    /// Whether the extension is pinned to `git_ref`.
+    ///
+    /// Pinned extensions are left alone by `bb extension upgrade`.
+    #[serde(default)]
+    pub pinned: bool,
+}
+
+impl LockedExtension {
+    /// A short description of the installed version, for display.
+    ///
+    /// Returns the abbreviated commit for git installs and the asset name
+    /// for binary installs.
+    pub fn version(&self) -> Option<String> {
+        match (&self.commit, &self.asset) {
+            (Some(commit), _) => Some(commit.chars().take(7).collect()),
+            (None, Some(asset)) => Some(asset.clone()),
+            (None, None) => self.git_ref.clone(),
+        }
+    }
+}
+
+/// The contents of `extensions.lock`, keyed by extension name.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ExtensionLock {
+    /// Installed extensions, keyed by name without the `bb-` prefix.
+    #[serde(default)]
+    pub extensions: BTreeMap<String, LockedExtension>,
+}
+
+impl ExtensionLock {
+    /// Loads the lock manifest from an extension directory.
+    ///
+    /// # Parameters
+    ///
+    /// * `dir` - The extension directory
+    ///
+    /// # Returns
+    ///
+    /// The parsed manifest, or an empty one if the file does not exist.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file exists but cannot be read or parsed.
+    pub fn load(dir: &Path) -> Result<Self> {
+        let path = dir.join(LOCK_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = read_config_file(&path)?;
+        toml::from_str(&content).with_context(|| format!("Failed to parse {}", path.display()))
+    }
+
+    /// Writes the lock manifest to an extension directory.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the manifest cannot be serialized or written.
+    pub fn save(&self, dir: &Path) -> Result<()> {
+        let content = toml::to_string_pretty(self)?;
+        write_config_file(&dir.join(LOCK_FILE), &content)
+    }
+
+    /// Returns the entry for an extension, if it was installed by `bb`.
+    pub fn get(&self, name: &str) -> Option<&LockedExtension> {
+        self.extensions.get(name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lock_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let mut lock = ExtensionLock::default();
+        lock.extensions.insert(
+            "lint".to_string(),
+            LockedExtension {
+                source: "https://bitbucket.org/myteam/bb-lint.git".to_string(),
+                kind: ExtensionKind::Script,
+                git_ref: Some("v1.2.0".to_string()),
+                commit: Some("4f6c2a1e9b7d0c3f5a8e2d1b6c9f0a3e7d4b2c1a".to_string()),
+                asset: None,
+                asset_revision: None,
+                pinned: true,
+            },
+        );
+        lock.save(dir.path()).unwrap();
+
+        let content = std::fs::read_to_string(dir.path().join(LOCK_FILE)).unwrap();
+        assert!(content.contains("[extensions.lint]"));
+        assert!(content.contains("ref = \"v1.2.0\""));
+
+        let loaded = ExtensionLock::load(dir.path()).unwrap();
+        assert_eq!(loaded, lock);
+        assert_eq!(loaded.get("lint").unwrap().version().unwrap(), "4f6c2a1");
+    }
+
+    #[test]
+    fn test_missing_lock_is_empty() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(ExtensionLock::load(dir.path())
+            .unwrap()
+            .extensions
+            .is_empty());
+    }
+}
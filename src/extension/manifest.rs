@@ -0,0 +1,232 @@
+//
+//  bitbucket-cli
+//  extension/manifest.rs
+//
+//  Created by Ngonidzashe Mangudya on 2026/01/12.
+//  Copyright (c) 2025 IAMNGONI. All rights reserved.
+//
+
+//! The optional `bb-extension.toml` manifest
+//!
+//! An extension can describe itself with a `bb-extension.toml` next to its
+//! executable, at the root of its repository:
+//!
+//! ```toml
+//! name = "lint"
+//! description = "Lint pull request descriptions"
+//! version = "1.2.0"
+//! min_bb_version = "0.2.0"
+//! scopes = ["pullrequest", "repository"]
+//!
+//! [commands]
+//! check = "Check the pull request for the current branch"
+//! fix = "Rewrite the description to match the template"
+//! ```
+//!
+//! Every field is optional. Extensions without a manifest still work; they
+//! are identified by their `bb-<name>` file name alone.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::config::read_config_file;
+use crate::util::compare_versions;
+
+/// File name of the extension manifest.
+pub const MANIFEST_FILE: &str = "bb-extension.toml";
+
+/// The contents of a `bb-extension.toml` manifest.
+///
+/// # Fields
+///
+/// * `name` - The extension's display name
+/// * `description` - A one-line summary, shown by `bb extension list`
+/// * `version` - The extension's own version
+/// * `min_bb_version` - The oldest `bb` the extension works with
+/// * `scopes` - API scopes the extension's requests need
+/// * `commands` - Help text for the extension's subcommands, keyed by name
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ExtensionManifest {
+    /// The extension's display name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    /// A one-line summary of what the extension does.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// The extension's own version.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+
+    /// The oldest `bb` version the extension supports.
+    ///
+    /// `bb` refuses to run the extension if it is older than this.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_bb_version: Option<String>,
+
+    /// API scopes the extension needs from `BB_TOKEN`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub scopes: Vec<String>,
+
+    /// Help text for the extension's subcommands, keyed by subcommand name.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub commands: BTreeMap<String, String>,
+}
+
+impl ExtensionManifest {
+    /// Parses a manifest from TOML.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the TOML is malformed or a field has the wrong type.
+    pub fn parse(content: &str) -> Result<Self> {
+        Ok(toml::from_str(content)?)
+    }
+
+    /// Loads the manifest that belongs to an extension executable.
+    ///
+    /// The manifest is read from the directory holding the executable, but
+    /// only when that directory is the extension's own (`bb-<name>`), so
+    /// that a manifest is never picked up from a shared directory on `PATH`.
+    ///
+    /// # Parameters
+    ///
+    /// * `executable` - Path to the `bb-<name>` executable
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(manifest))` - If the extension has a manifest
+    /// * `Ok(None)` - If it does not
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the manifest exists but cannot be read or parsed.
+    pub fn for_executable(executable: &Path) -> Result<Option<Self>> {
+        let (Some(dir), Some(file_name)) = (executable.parent(), executable.file_stem()) else {
+            return Ok(None);
+        };
+        if dir.file_name() != Some(file_name) {
+            return Ok(None);
+        }
+
+        let path = dir.join(MANIFEST_FILE);
+        if !path.is_file() {
+            return Ok(None);
+        }
+
+        let content = read_config_file(&path)?;
+        Self::parse(&content)
+            .with_context(|| format!("Invalid extension manifest {}", path.display()))
+            .map(Some)
+    }
+
+    /// Checks that this `bb` is new enough for the extension.
+    ///
+    /// # Parameters
+    ///
+    /// * `extension` - The extension name, for the error message
+    /// * `bb_version` - The running `bb` version, normally `crate::VERSION`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `min_bb_version` is newer than `bb_version`, or if
+    /// it is not a version number.
+    pub fn check_compatible(&self, extension: &str, bb_version: &str) -> Result<()> {
+        let Some(required) = &self.min_bb_version else {
+            return Ok(());
+        };
+
+        // Only a version number compares with itself
+        let minimum = required.trim_start_matches('v');
+        if compare_versions(minimum, minimum).is_none() {
+            bail!(
+                "Extension '{}' has an invalid min_bb_version '{}' in {}",
+                extension,
+                required,
+                MANIFEST_FILE
+            );
+        }
+
+        match compare_versions(bb_version, minimum) {
+            Some(std::cmp::Ordering::Less) => bail!(
+                "Extension '{}' requires bb {} or newer, but this is bb {}. Upgrade bb to use it.",
+                extension,
+                minimum,
+                bb_version
+            ),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MANIFEST: &str = r#"
+name = "lint"
+description = "Lint pull request descriptions"
+version = "1.2.0"
+min_bb_version = "0.2.0"
+scopes = ["pullrequest"]
+
+[commands]
+check = "Check the current pull request"
+"#;
+
+    #[test]
+    fn test_parse_manifest() {
+        let manifest = ExtensionManifest::parse(MANIFEST).unwrap();
+        assert_eq!(manifest.name.as_deref(), Some("lint"));
+        assert_eq!(manifest.version.as_deref(), Some("1.2.0"));
+        assert_eq!(manifest.scopes, vec!["pullrequest"]);
+        assert_eq!(
+            manifest.commands.get("check").map(String::as_str),
+            Some("Check the current pull request")
+        );
+
+        assert_eq!(
+            ExtensionManifest::parse("").unwrap(),
+            ExtensionManifest::default()
+        );
+        assert!(ExtensionManifest::parse("scopes = \"repo\"").is_err());
+    }
+
+    #[test]
+    fn test_check_compatible() {
+        let manifest = ExtensionManifest::parse(MANIFEST).unwrap();
+        assert!(manifest.check_compatible("lint", "0.2.0").is_ok());
+        assert!(manifest.check_compatible("lint", "0.10.1").is_ok());
+
+        let err = manifest.check_compatible("lint", "0.1.9").unwrap_err();
+        assert!(err.to_string().contains("requires bb 0.2.0 or newer"));
+        assert!(manifest.check_compatible("lint", "0.2.0-rc1").is_err());
+        assert!(manifest.check_compatible("lint", "0.2.1-rc1").is_ok());
+
+        assert!(ExtensionManifest::default()
+            .check_compatible("lint", "0.1.0")
+            .is_ok());
+    }
+
+    #[test]
+    fn test_manifest_only_read_from_extension_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join(MANIFEST_FILE), MANIFEST).unwrap();
+        assert_eq!(
+            ExtensionManifest::for_executable(&dir.path().join("bb-lint")).unwrap(),
+            None
+        );
+
+        let ext_dir = dir.path().join("bb-lint");
+        std::fs::create_dir(&ext_dir).unwrap();
+        std::fs::write(ext_dir.join(MANIFEST_FILE), MANIFEST).unwrap();
+        let manifest = ExtensionManifest::for_executable(&ext_dir.join("bb-lint"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(manifest.min_bb_version.as_deref(), Some("0.2.0"));
+    }
+}
@@ -30,10 +30,38 @@
 //! - **Go**: Compiled Go binaries for cross-platform support
 //! - **Rust**: Compiled Rust binaries for performance-critical extensions
 //!
+//! ## Installation
+//!
+//! [`ExtensionManager::install`] installs from Bitbucket Cloud Downloads when
+//! a binary for this platform exists, and otherwise clones the repository and
+//! builds it if it is a Rust or Go project. Installed extensions live in a
+//! `bb-<name>` directory in the extension directory and are recorded in
+//! `extensions.lock` (see [`ExtensionLock`]).
+//!
+//! ## Extension Environment
+//!
+//! Extensions run with the CLI's environment plus a few variables describing
+//! the context `bb` resolved for the invocation (see [`ExtensionEnv`]):
+//!
+//! | Variable       | Value                                                  |
+//! |----------------|--------------------------------------------------------|
+//! | `BB_HOST`      | The Bitbucket host, e.g. `bitbucket.org`               |
+//! | `BB_REPO`      | The repository as `owner/slug`, when one was resolved  |
+//! | `BB_HOST_TYPE` | `cloud` or `server`                                    |
+//! | `BB_JSON`      | `1` when `--json` was given before the extension name  |
+//! | `BB_TOKEN`     | The access token for `BB_HOST`, see below              |
+//!
+//! `BB_TOKEN` is only set by default when the user logged in to Cloud with
+//! OAuth, whose access tokens expire within hours. Personal access tokens,
+//! API tokens and app passwords stay valid until revoked, and `bb` cannot
+//! trade them for a shorter-lived token, so they are only passed after
+//! `bb config set extension_token enabled`. Extensions should use the token
+//! for the duration of the run and never persist it.
+//!
 //! ## Example
 //!
 //! ```no_run
-//! use bitbucket_cli::extension::ExtensionManager;
+//! use bitbucket_cli::extension::{ExtensionEnv, ExtensionManager};
 //!
 //! // Create an extension manager
 //! let manager = ExtensionManager::new().expect("Failed to create manager");
@@ -46,15 +74,30 @@
 //!
 //! // Find and execute a specific extension
 //! if let Some(ext) = manager.find("my-extension").expect("Failed to find") {
-//!     let exit_code = ext.execute(&["arg1".to_string()]).expect("Failed to execute");
+//!     let exit_code = ext
+//!         .execute(&["arg1".to_string()], &ExtensionEnv::default())
+//!         .expect("Failed to execute");
 //!     println!("Extension exited with code: {}", exit_code);
 //! }
 //! ```
 
+mod install;
+mod lock;
+mod manifest;
+
+pub use install::asset_matches_platform;
+pub use lock::*;
+pub use manifest::*;
+
 use std::path::{Path, PathBuf};
 use std::process::Command;
 
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
+
+use crate::auth::{refresh_stored_token, KeyringStore};
+use crate::config::Config;
+use crate::context::{HostType, RepoContext};
+use install::{build, detect_kind, fetch_download, find_download, git, ExtensionSource};
 
 /// Represents metadata and state for a CLI extension.
 ///
@@ -69,12 +112,13 @@ use anyhow::{Context, Result};
 /// * `precompiled` - Whether this is a precompiled binary (vs. interpreted script)
 /// * `source` - The source repository URL if the extension was installed from a repo
 /// * `pinned_version` - Specific version to use if the extension is pinned
+/// * `manifest` - The extension's `bb-extension.toml`, if it has one
 ///
 /// # Example
 ///
 /// ```no_run
 /// use std::path::PathBuf;
-/// use bitbucket_cli::extension::Extension;
+/// use bitbucket_cli::extension::{Extension, ExtensionEnv};
 ///
 /// // Create an extension from a discovered executable
 /// let path = PathBuf::from("/usr/local/bin/bb-lint");
@@ -82,7 +126,7 @@ use anyhow::{Context, Result};
 ///     println!("Found extension: {}", ext.name);
 ///
 ///     // Execute the extension
-///     let exit_code = ext.execute(&["--fix".to_string()]).unwrap();
+///     let exit_code = ext.execute(&["--fix".to_string()], &ExtensionEnv::default()).unwrap();
 /// }
 /// ```
 ///
@@ -121,6 +165,19 @@ pub struct Extension {
     /// When set, upgrade operations will target this specific version
     /// rather than the latest available.
     pub pinned_version: Option<String>,
+
+    /// The extension's `bb-extension.toml` manifest, if it has one.
+    ///
+    /// Provides the description, version and minimum `bb` version shown by
+    /// `bb extension list` and `bb extension info`.
+    pub manifest: Option<ExtensionManifest>,
+
+    /// Why the extension's manifest could not be read, if it is broken.
+    ///
+    /// A broken manifest does not hide the extension from `bb extension
+    /// list`, but [`Extension::execute`] refuses to run it, since its
+    /// `min_bb_version` cannot be checked.
+    pub manifest_error: Option<String>,
 }
 
 impl Extension {
@@ -157,7 +214,7 @@ impl Extension {
     /// # Notes
     ///
     /// - The `precompiled` field is set to `true` by default
-    /// - The `source` and `pinned_version` fields are set to `None`
+    /// - The `source`, `pinned_version` and `manifest` fields are set to `None`
     /// - The path is stored as-is without canonicalization
     pub fn from_path(path: PathBuf) -> Option<Self> {
         let name = path.file_name()?.to_str()?;
@@ -165,7 +222,11 @@ impl Extension {
             return None;
         }
 
-        let name = name.strip_prefix("bb-")?.to_string();
+        let name = name.strip_prefix("bb-")?;
+        let name = name
+            .strip_suffix(std::env::consts::EXE_SUFFIX)
+            .unwrap_or(name)
+            .to_string();
 
         Some(Self {
             name,
@@ -173,9 +234,25 @@ impl Extension {
             precompiled: true,
             source: None,
             pinned_version: None,
+            manifest: None,
+            manifest_error: None,
         })
     }
 
+    /// Reads the extension's manifest into `manifest`, or the reason it
+    /// could not be read into `manifest_error`.
+    fn load_manifest(&mut self) {
+        match ExtensionManifest::for_executable(&self.path) {
+            Ok(manifest) => self.manifest = manifest,
+            Err(e) => self.manifest_error = Some(format!("{:#}", e)),
+        }
+    }
+
+    /// Returns the description from the extension's manifest, if any.
+    pub fn description(&self) -> Option<&str> {
+        self.manifest.as_ref()?.description.as_deref()
+    }
+
     /// Executes the extension with the provided arguments.
     ///
     /// This method spawns a new process for the extension executable,
@@ -184,17 +261,19 @@ impl Extension {
     /// # Parameters
     ///
     /// * `args` - Command-line arguments to pass to the extension
+    /// * `env` - The `BB_*` environment describing the current context
     ///
     /// # Returns
     ///
     /// * `Ok(i32)` - The exit code from the extension process
-    /// * `Err` - If the extension could not be executed
+    /// * `Err` - If the extension could not be executed, or its manifest is
+    ///   invalid or asks for a newer `bb` than this one
     ///
     /// # Example
     ///
     /// ```no_run
     /// use std::path::PathBuf;
-    /// use bitbucket_cli::extension::Extension;
+    /// use bitbucket_cli::extension::{Extension, ExtensionEnv};
     ///
     /// let ext = Extension::from_path(PathBuf::from("/usr/bin/bb-lint")).unwrap();
     ///
@@ -202,7 +281,7 @@ impl Extension {
     /// let exit_code = ext.execute(&[
     ///     "--fix".to_string(),
     ///     "src/".to_string(),
-    /// ]).expect("Failed to execute");
+    /// ], &ExtensionEnv::default()).expect("Failed to execute");
     ///
     /// if exit_code == 0 {
     ///     println!("Extension completed successfully");
@@ -213,12 +292,21 @@ impl Extension {
     ///
     /// # Notes
     ///
-    /// - The extension inherits the current process's environment
+    /// - The extension inherits the current process's environment, plus the
+    ///   variables from `env` (see [`ExtensionEnv`])
     /// - Standard I/O streams are inherited (stdin, stdout, stderr)
     /// - If the process is terminated by a signal, exit code 1 is returned
-    pub fn execute(&self, args: &[String]) -> Result<i32> {
+    pub fn execute(&self, args: &[String], env: &ExtensionEnv) -> Result<i32> {
+        if let Some(error) = &self.manifest_error {
+            bail!("Not running extension '{}': {}", self.name, error);
+        }
+        if let Some(manifest) = &self.manifest {
+            manifest.check_compatible(&self.name, crate::VERSION)?;
+        }
+
         let status = Command::new(&self.path)
             .args(args)
+            .envs(env.vars())
             .status()
             .with_context(|| format!("Failed to execute extension: {}", self.name))?;
 
@@ -226,6 +314,148 @@ impl Extension {
     }
 }
 
+/// The context passed to an extension through `BB_*` environment variables.
+///
+/// `bb` resolves the host, repository and credential once, then hands them to
+/// the extension so that it does not have to repeat the lookup. Because
+/// `BB_HOST` and `BB_REPO` are also read by `bb` itself, an extension that
+/// calls back into `bb` works against the same repository.
+///
+/// # Fields
+///
+/// * `host` - The Bitbucket host (`BB_HOST`)
+/// * `repo` - The repository as `owner/slug` (`BB_REPO`)
+/// * `host_type` - Whether the host is Cloud or Server (`BB_HOST_TYPE`)
+/// * `json` - Whether JSON output was requested (`BB_JSON`)
+/// * `token` - The access token for the host (`BB_TOKEN`)
+///
+/// # Example
+///
+/// ```no_run
+/// use bitbucket_cli::context::HostType;
+/// use bitbucket_cli::extension::ExtensionEnv;
+///
+/// let env = ExtensionEnv {
+///     host: Some("bitbucket.org".to_string()),
+///     repo: Some("myworkspace/my-repo".to_string()),
+///     host_type: Some(HostType::Cloud),
+///     json: false,
+///     token: None,
+/// };
+///
+/// for (key, value) in env.vars() {
+///     println!("{}={}", key, value);
+/// }
+/// ```
+///
+/// # Notes
+///
+/// - Unknown values are left unset rather than passed as empty strings
+/// - The token is never logged or written to disk by `bb`
+#[derive(Debug, Clone, Default)]
+pub struct ExtensionEnv {
+    /// The Bitbucket host, e.g. `bitbucket.org`.
+    pub host: Option<String>,
+
+    /// The repository as `owner/slug`, if one could be resolved.
+    pub repo: Option<String>,
+
+    /// The type of the host.
+    pub host_type: Option<HostType>,
+
+    /// Whether the user asked for JSON output.
+    pub json: bool,
+
+    /// The access token for the host, see [`session_token`].
+    pub token: Option<String>,
+}
+
+impl ExtensionEnv {
+    /// Builds the environment for a resolved repository.
+    ///
+    /// The token comes from [`session_token`], so it is left unset if the
+    /// user is not logged in or has not allowed long-lived tokens.
+    ///
+    /// # Parameters
+    ///
+    /// * `context` - The resolved repository
+    /// * `json` - Whether the user asked for JSON output
+    /// * `config` - The user's configuration
+    pub async fn for_context(context: &RepoContext, json: bool, config: &Config) -> Self {
+        let token = session_token(&context.host, config).await;
+
+        Self {
+            host: Some(context.host.clone()),
+            repo: Some(context.full_name()),
+            host_type: Some(context.host_type.clone()),
+            json,
+            token,
+        }
+    }
+
+    /// Returns the environment variables to set for the extension process.
+    ///
+    /// # Returns
+    ///
+    /// A list of `(name, value)` pairs. `BB_JSON` is always present; the
+    /// other variables are only included when their value is known.
+    pub fn vars(&self) -> Vec<(&'static str, String)> {
+        let mut vars = Vec::new();
+
+        if let Some(host) = &self.host {
+            vars.push(("BB_HOST", host.clone()));
+        }
+        if let Some(repo) = &self.repo {
+            vars.push(("BB_REPO", repo.clone()));
+        }
+        if let Some(host_type) = &self.host_type {
+            let value = match host_type {
+                HostType::Cloud => "cloud",
+                HostType::Server => "server",
+            };
+            vars.push(("BB_HOST_TYPE", value.to_string()));
+        }
+        vars.push(("BB_JSON", if self.json { "1" } else { "0" }.to_string()));
+        if let Some(token) = &self.token {
+            vars.push(("BB_TOKEN", token.clone()));
+        }
+
+        vars
+    }
+}
+
+/// Looks up the token to pass to extensions and hooks as `BB_TOKEN`.
+///
+/// A Cloud OAuth login stores a refresh token next to the access token; the
+/// access token expires within hours, so it is refreshed first and the new
+/// one is passed. Any other credential does not expire on its own and is
+/// only passed once the user has run `bb config set extension_token enabled`.
+///
+/// # Returns
+///
+/// The token, or `None` if the user is not logged in to `host`, the OAuth
+/// token could not be refreshed, or the stored credential is long-lived and
+/// not allowed.
+pub async fn session_token(host: &str, config: &Config) -> Option<String> {
+    let keyring = KeyringStore::new();
+    let token = keyring.get(host).ok().flatten()?;
+
+    match refresh_stored_token(&keyring, host).await {
+        Ok(Some(tokens)) => return Some(tokens.access_token),
+        Ok(None) => {}
+        Err(e) => {
+            tracing::debug!("Could not refresh the OAuth token for {}: {:#}", host, e);
+            return None;
+        }
+    }
+
+    if config.core.extension_token.as_deref() == Some("enabled") {
+        Some(token)
+    } else {
+        None
+    }
+}
+
 /// Manages the lifecycle of CLI extensions.
 ///
 /// The `ExtensionManager` provides a centralized interface for discovering,
@@ -345,16 +575,46 @@ impl ExtensionManager {
     pub fn list(&self) -> Result<Vec<Extension>> {
         let mut extensions = Vec::new();
 
-        if !self.extension_dir.exists() {
-            return Ok(extensions);
-        }
-
-        for entry in std::fs::read_dir(&self.extension_dir)? {
-            let entry = entry?;
-            let path = entry.path();
+        if self.extension_dir.exists() {
+            let lock = ExtensionLock::load(&self.extension_dir)?;
+
+            for entry in std::fs::read_dir(&self.extension_dir)? {
+                let entry = entry?;
+                let mut path = entry.path();
+
+                // Installed extensions are a bb-<name> directory holding the
+                // bb-<name> executable
+                if path.is_dir() {
+                    match path.file_name() {
+                        Some(dir_name) => {
+                            let executable = format!(
+                                "{}{}",
+                                dir_name.to_string_lossy(),
+                                std::env::consts::EXE_SUFFIX
+                            );
+                            path = path.join(executable);
+                        }
+                        None => continue,
+                    }
+                    if !path.is_file() {
+                        continue;
+                    }
+                }
 
-            if let Some(ext) = Extension::from_path(path) {
-                extensions.push(ext);
+                if let Some(mut ext) = Extension::from_path(path) {
+                    if let Some(locked) = lock.get(&ext.name) {
+                        ext.source = Some(locked.source.clone());
+                        ext.precompiled = locked.kind.is_precompiled();
+                        ext.pinned_version = locked
+                            .git_ref
+                            .clone()
+                            .filter(|_| locked.pinned)
+                            .or_else(|| locked.asset.clone().filter(|_| locked.pinned));
+                    }
+                    // A broken manifest should not hide the extension
+                    ext.load_manifest();
+                    extensions.push(ext);
+                }
             }
         }
 
@@ -364,9 +624,15 @@ impl ExtensionManager {
                 if let Ok(entries) = std::fs::read_dir(&dir) {
                     for entry in entries.flatten() {
                         let path = entry.path();
-                        if let Some(ext) = Extension::from_path(path) {
+                        // Skip bb-* files that are not programs, such as a
+                        // bb-extension.toml in a directory on PATH
+                        if !is_executable(&path) {
+                            continue;
+                        }
+                        if let Some(mut ext) = Extension::from_path(path) {
                             // Avoid duplicates
                             if !extensions.iter().any(|e| e.name == ext.name) {
+                                ext.load_manifest();
                                 extensions.push(ext);
                             }
                         }
@@ -396,14 +662,14 @@ impl ExtensionManager {
     /// # Example
     ///
     /// ```no_run
-    /// use bitbucket_cli::extension::ExtensionManager;
+    /// use bitbucket_cli::extension::{ExtensionEnv, ExtensionManager};
     ///
     /// let manager = ExtensionManager::new().unwrap();
     ///
     /// match manager.find("lint").expect("Failed to search") {
     ///     Some(ext) => {
     ///         println!("Found: {}", ext.path.display());
-    ///         ext.execute(&[]).expect("Failed to run");
+    ///         ext.execute(&[], &ExtensionEnv::default()).expect("Failed to run");
     ///     }
     ///     None => println!("Extension 'lint' not found"),
     /// }
@@ -415,73 +681,124 @@ impl ExtensionManager {
 
     /// Installs an extension from a repository.
     ///
-    /// Downloads and installs an extension from a Bitbucket repository.
-    /// The repository can be specified in several formats.
+    /// If the repository is on Bitbucket Cloud and its Downloads contain a
+    /// `bb-<name>` binary for this platform, that binary is installed.
+    /// Otherwise the repository is cloned: scripts are used as they are, and
+    /// Rust or Go projects (as generated by [`ExtensionManager::create`]) are
+    /// built. The result is recorded in `extensions.lock`.
     ///
     /// # Parameters
     ///
     /// * `repo` - Repository URL or owner/repo identifier
-    /// * `_pin` - Optional version to pin the extension to (currently unused)
+    /// * `pin` - Optional branch, tag or commit to install and stay on
     ///
     /// # Returns
     ///
     /// * `Ok(Extension)` - The newly installed extension
-    /// * `Err` - If installation fails or is not yet implemented
+    /// * `Err` - If the extension is already installed, would be shadowed by
+    ///   a built-in command, the pin starts with `-`, or it could not be
+    ///   fetched or built
     ///
     /// # Supported Repository Formats
     ///
-    /// - `owner/repo` - Simple owner/repository format
+    /// - `owner/repo` - A Bitbucket Cloud repository, cloned over HTTPS or
+    ///   SSH according to `git_protocol`
     /// - `https://bitbucket.org/owner/repo` - Full HTTPS URL
-    /// - `git@bitbucket.org:owner/repo.git` - SSH URL (partial support)
+    /// - `git@bitbucket.org:owner/repo.git` - SSH URL
+    /// - Any other git URL or local path, e.g. a Bitbucket Server clone URL
     ///
     /// # Example
     ///
     /// ```no_run
     /// use bitbucket_cli::extension::ExtensionManager;
     ///
-    /// let manager = ExtensionManager::new().unwrap();
+    /// # async fn example() -> anyhow::Result<()> {
+    /// let manager = ExtensionManager::new()?;
     ///
     /// // Install from owner/repo format
-    /// let ext = manager.install("myteam/bb-lint", None)
-    ///     .expect("Failed to install");
+    /// let ext = manager.install("myteam/bb-lint", None).await?;
     ///
     /// // Install with version pinning
-    /// let ext = manager.install("myteam/bb-format", Some("v1.2.0"))
-    ///     .expect("Failed to install");
+    /// let ext = manager.install("myteam/bb-format", Some("v1.2.0")).await?;
+    /// # Ok(())
+    /// # }
     /// ```
     ///
     /// # Notes
     ///
     /// - Creates the extension directory if it does not exist
-    /// - Currently returns an error as installation is not yet implemented
-    /// - Future implementation will support downloading releases and building from source
-    pub fn install(&self, repo: &str, _pin: Option<&str>) -> Result<Extension> {
+    /// - The extension is prepared in a temporary directory and only moved
+    ///   into place once it has been fetched and built
+    /// - A pinned binary must have the pinned version in its file name
+    pub async fn install(&self, repo: &str, pin: Option<&str>) -> Result<Extension> {
+        let use_ssh = Config::load().unwrap_or_default().core.git_protocol != "https";
+        let source = ExtensionSource::parse(repo, use_ssh)?;
+        let name = source.name.clone();
+
+        if let Some(pin) = pin.filter(|p| p.starts_with('-')) {
+            bail!(
+                "Invalid pin '{}': a branch, tag or commit cannot start with '-'",
+                pin
+            );
+        }
+
+        if crate::alias::is_builtin_command(&name) {
+            bail!(
+                "'{}' is a built-in command, so a bb-{} extension could never run",
+                name,
+                name
+            );
+        }
+
+        let target = self.extension_dir.join(format!("bb-{}", name));
+        if target.exists() {
+            bail!(
+                "Extension '{}' is already installed. Use 'bb extension upgrade {}' or remove it first",
+                name,
+                name
+            );
+        }
+
         // Create extension directory if it doesn't exist
         std::fs::create_dir_all(&self.extension_dir)?;
 
-        // Parse repository URL
-        let (_owner, repo_name) = parse_repo_url(repo)?;
-        let name = repo_name.strip_prefix("bb-").unwrap_or(&repo_name);
-
-        // TODO: Actually download and install the extension
-        // This would involve:
-        // 1. Clone the repository or download release binary
-        // 2. Build if necessary
-        // 3. Copy to extension directory
-
-        anyhow::bail!(
-            "Extension installation from {} not yet implemented. \
-            Extension name would be: {}",
-            repo,
-            name
-        )
+        let staging = self.extension_dir.join(format!(".bb-{}.tmp", name));
+        if staging.exists() {
+            std::fs::remove_dir_all(&staging)?;
+        }
+
+        let locked = match fetch_extension(&source, pin, &staging).await {
+            Ok(locked) => locked,
+            Err(e) => {
+                let _ = std::fs::remove_dir_all(&staging);
+                return Err(e);
+            }
+        };
+        std::fs::rename(&staging, &target)?;
+
+        let mut lock = ExtensionLock::load(&self.extension_dir)?;
+        lock.extensions.insert(name.clone(), locked);
+        lock.save(&self.extension_dir)?;
+
+        self.find(&name)?
+            .ok_or_else(|| anyhow::anyhow!("Installed extension '{}' could not be found", name))
+    }
+
+    /// Returns the `extensions.lock` entry for an extension.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(LockedExtension))` - If the extension was installed by `bb`
+    /// * `Ok(None)` - If it is not installed, or was put in place by hand
+    /// * `Err` - If the lock file cannot be read
+    pub fn locked(&self, name: &str) -> Result<Option<LockedExtension>> {
+        Ok(ExtensionLock::load(&self.extension_dir)?.get(name).cloned())
     }
 
     /// Removes an installed extension.
     ///
-    /// Deletes the extension executable from the managed extension directory.
-    /// This only removes extensions installed via the extension manager; it
-    /// does not affect extensions in the system PATH.
+    /// Deletes the extension from the extension directory and from
+    /// `extensions.lock`.
     ///
     /// # Parameters
     ///
@@ -512,18 +829,29 @@ impl ExtensionManager {
     pub fn remove(&self, name: &str) -> Result<()> {
         let ext_path = self.extension_dir.join(format!("bb-{}", name));
 
-        if ext_path.exists() {
+        if ext_path.is_dir() {
+            std::fs::remove_dir_all(&ext_path)?;
+        } else if ext_path.exists() {
             std::fs::remove_file(&ext_path)?;
-            Ok(())
         } else {
-            anyhow::bail!("Extension not found: {}", name)
+            bail!("Extension not found: {}", name)
         }
+
+        let mut lock = ExtensionLock::load(&self.extension_dir)?;
+        if lock.extensions.remove(name).is_some() {
+            lock.save(&self.extension_dir)?;
+        }
+
+        Ok(())
     }
 
-    /// Upgrades an installed extension to the latest version.
+    /// Upgrades an installed extension.
     ///
-    /// Updates an extension by fetching the latest version from its source
-    /// repository. Requires the extension to have source information recorded.
+    /// Git installs are fetched and moved to the latest commit of the branch
+    /// they were installed from, then rebuilt if needed. Binary installs are
+    /// replaced when the repository's Downloads file for this platform has a
+    /// different name, upload date or size than the installed one. Pinned
+    /// extensions are left untouched.
     ///
     /// # Parameters
     ///
@@ -531,39 +859,110 @@ impl ExtensionManager {
     ///
     /// # Returns
     ///
-    /// * `Ok(())` - If the upgrade succeeds
-    /// * `Err` - If the extension is not found, has no source, or upgrade fails
+    /// * `Ok(UpgradeOutcome)` - What the upgrade did
+    /// * `Err` - If the extension is not found, was not installed by `bb`,
+    ///   or the upgrade fails
     ///
     /// # Example
     ///
     /// ```no_run
-    /// use bitbucket_cli::extension::ExtensionManager;
+    /// use bitbucket_cli::extension::{ExtensionManager, UpgradeOutcome};
     ///
-    /// let manager = ExtensionManager::new().unwrap();
+    /// # async fn example() -> anyhow::Result<()> {
+    /// let manager = ExtensionManager::new()?;
     ///
-    /// // Upgrade a specific extension
-    /// manager.upgrade("lint").expect("Failed to upgrade");
+    /// match manager.upgrade("lint").await? {
+    ///     UpgradeOutcome::Upgraded { from, to } => println!("{} -> {}", from, to),
+    ///     UpgradeOutcome::UpToDate(version) => println!("already at {}", version),
+    ///     UpgradeOutcome::Pinned(version) => println!("pinned to {}", version),
+    /// }
+    /// # Ok(())
+    /// # }
     /// ```
     ///
     /// # Notes
     ///
-    /// - Currently returns an error as upgrade is not yet implemented
-    /// - Requires the extension to have been installed with source tracking
-    /// - Pinned versions will be upgraded to the pinned version, not latest
-    pub fn upgrade(&self, name: &str) -> Result<()> {
-        let ext = self
-            .find(name)?
-            .ok_or_else(|| anyhow::anyhow!("Extension not found: {}", name))?;
+    /// - Requires an `extensions.lock` entry, so extensions found on `PATH`
+    ///   cannot be upgraded
+    /// - To move a pinned extension, remove it and install again with `--pin`
+    pub async fn upgrade(&self, name: &str) -> Result<UpgradeOutcome> {
+        let mut lock = ExtensionLock::load(&self.extension_dir)?;
 
-        if ext.source.is_none() {
-            anyhow::bail!(
+        let Some(locked) = lock.get(name).cloned() else {
+            if self.find(name)?.is_none() {
+                bail!("Extension not found: {}", name);
+            }
+            bail!(
                 "Cannot upgrade extension without source information: {}",
                 name
             );
+        };
+
+        let current = locked.version().unwrap_or_default();
+        if locked.pinned {
+            return Ok(UpgradeOutcome::Pinned(
+                locked.git_ref.clone().unwrap_or(current),
+            ));
         }
 
-        // TODO: Implement upgrade logic
-        anyhow::bail!("Extension upgrade not yet implemented")
+        let dir = self.extension_dir.join(format!("bb-{}", name));
+        let updated = match locked.kind {
+            ExtensionKind::Binary => {
+                let source = ExtensionSource::parse(&locked.source, false)?;
+                let (owner, repo) = source.cloud_repo.ok_or_else(|| {
+                    anyhow::anyhow!("{} is not a Bitbucket Cloud repository", locked.source)
+                })?;
+                let download =
+                    find_download(&owner, &repo, name, None)
+                        .await?
+                        .ok_or_else(|| {
+                            anyhow::anyhow!(
+                                "No download for this platform found in {}",
+                                locked.source
+                            )
+                        })?;
+
+                let revision = download.revision();
+                if locked.asset.as_deref() == Some(download.name.as_str())
+                    && locked.asset_revision == revision
+                {
+                    return Ok(UpgradeOutcome::UpToDate(current));
+                }
+
+                let executable = format!("bb-{}{}", name, std::env::consts::EXE_SUFFIX);
+                fetch_download(&download, &dir.join(executable)).await?;
+                LockedExtension {
+                    asset: Some(download.name),
+                    asset_revision: revision,
+                    ..locked
+                }
+            }
+            kind => {
+                let branch = locked.git_ref.clone().unwrap_or_else(|| "HEAD".to_string());
+                git(Some(&dir), &["fetch", "--quiet", "origin"])?;
+                let latest = git(Some(&dir), &["rev-parse", &format!("origin/{}", branch)])?;
+
+                if locked.commit.as_deref() == Some(latest.as_str()) {
+                    return Ok(UpgradeOutcome::UpToDate(current));
+                }
+
+                git(Some(&dir), &["reset", "--hard", "--quiet", &latest])?;
+                build(&dir, name, kind)?;
+                LockedExtension {
+                    commit: Some(latest),
+                    ..locked
+                }
+            }
+        };
+
+        let outcome = UpgradeOutcome::Upgraded {
+            from: current,
+            to: updated.version().unwrap_or_default(),
+        };
+        lock.extensions.insert(name.to_string(), updated);
+        lock.save(&self.extension_dir)?;
+
+        Ok(outcome)
     }
 
     /// Creates a new extension project scaffold.
@@ -612,12 +1011,14 @@ impl ExtensionManager {
     /// **Shell (default):**
     /// ```text
     /// bb-<name>/
+    /// ├── bb-extension.toml
     /// └── bb-<name>  (executable script)
     /// ```
     ///
     /// **Rust:**
     /// ```text
     /// bb-<name>/
+    /// ├── bb-extension.toml
     /// ├── Cargo.toml
     /// └── src/
     ///     └── main.rs
@@ -626,6 +1027,7 @@ impl ExtensionManager {
     /// **Go:**
     /// ```text
     /// bb-<name>/
+    /// ├── bb-extension.toml
     /// ├── go.mod
     /// └── main.go
     /// ```
@@ -652,11 +1054,84 @@ impl ExtensionManager {
             Some(lang) => anyhow::bail!("Unsupported language: {}", lang),
             None => create_shell_extension(&project_dir, name)?,
         }
+        create_manifest(&project_dir, name)?;
 
         Ok(project_dir)
     }
 }
 
+/// The result of [`ExtensionManager::upgrade`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum UpgradeOutcome {
+    /// A newer version was installed
+    Upgraded {
+        /// The version that was installed before
+        from: String,
+        /// The version that is installed now
+        to: String,
+    },
+    /// The installed version is already the latest
+    UpToDate(String),
+    /// The extension is pinned to this version and was not changed
+    Pinned(String),
+}
+
+/// Fetches and builds an extension into `dest`, returning its lock entry.
+///
+/// Platform binaries from a Cloud repository's Downloads are preferred;
+/// otherwise the repository is cloned, checked out at `pin` and built.
+async fn fetch_extension(
+    source: &ExtensionSource,
+    pin: Option<&str>,
+    dest: &Path,
+) -> Result<LockedExtension> {
+    let name = &source.name;
+
+    if let Some((owner, repo)) = &source.cloud_repo {
+        if let Some(download) = find_download(owner, repo, name, pin).await? {
+            std::fs::create_dir_all(dest)?;
+            let executable = format!("bb-{}{}", name, std::env::consts::EXE_SUFFIX);
+            fetch_download(&download, &dest.join(executable)).await?;
+
+            return Ok(LockedExtension {
+                source: source.url.clone(),
+                kind: ExtensionKind::Binary,
+                git_ref: pin.map(String::from),
+                commit: None,
+                asset_revision: download.revision(),
+                asset: Some(download.name),
+                pinned: pin.is_some(),
+            });
+        }
+    }
+
+    let dest_arg = dest.to_string_lossy();
+    git(None, &["clone", "--quiet", "--", &source.url, &dest_arg])?;
+
+    let git_ref = match pin {
+        Some(pin) => {
+            git(Some(dest), &["checkout", "--quiet", pin, "--"])
+                .with_context(|| format!("Could not check out '{}'", pin))?;
+            pin.to_string()
+        }
+        None => git(Some(dest), &["rev-parse", "--abbrev-ref", "HEAD"])?,
+    };
+    let commit = git(Some(dest), &["rev-parse", "HEAD"])?;
+
+    let kind = detect_kind(dest, name)?;
+    build(dest, name, kind)?;
+
+    Ok(LockedExtension {
+        source: source.url.clone(),
+        kind,
+        git_ref: Some(git_ref),
+        commit: Some(commit),
+        asset: None,
+        asset_revision: None,
+        pinned: pin.is_some(),
+    })
+}
+
 impl Default for ExtensionManager {
     /// Creates a default `ExtensionManager` with fallback behavior.
     ///
@@ -718,72 +1193,54 @@ fn get_extension_dir() -> Result<PathBuf> {
     Ok(base.data_dir().join("extensions"))
 }
 
-/// Parses a repository URL or identifier into owner and repository name.
-///
-/// Supports multiple repository reference formats commonly used with
-/// Bitbucket repositories.
-///
-/// # Parameters
-///
-/// * `url` - The repository URL or identifier to parse
+/// Whether a path is a file that can be run.
 ///
-/// # Returns
-///
-/// * `Ok((owner, repo))` - Tuple containing the owner and repository name
-/// * `Err` - If the URL format is not recognized
-///
-/// # Supported Formats
-///
-/// | Format | Example | Owner | Repo |
-/// |--------|---------|-------|------|
-/// | Simple | `myteam/bb-lint` | `myteam` | `bb-lint` |
-/// | HTTPS  | `https://bitbucket.org/myteam/bb-lint` | `myteam` | `bb-lint` |
-/// | HTTPS with .git | `https://bitbucket.org/myteam/bb-lint.git` | `myteam` | `bb-lint` |
-///
-/// # Example
-///
-/// ```ignore
-/// let (owner, repo) = parse_repo_url("myteam/bb-lint")?;
-/// assert_eq!(owner, "myteam");
-/// assert_eq!(repo, "bb-lint");
+/// On Unix this checks the executable bits; elsewhere any file counts.
+fn is_executable(path: &Path) -> bool {
+    let Ok(metadata) = std::fs::metadata(path) else {
+        return false;
+    };
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        metadata.is_file() && metadata.permissions().mode() & 0o111 != 0
+    }
+    #[cfg(not(unix))]
+    {
+        metadata.is_file()
+    }
+}
+
+/// Writes a starter `bb-extension.toml` for a new extension.
 ///
-/// let (owner, repo) = parse_repo_url("https://bitbucket.org/myteam/bb-lint.git")?;
-/// assert_eq!(owner, "myteam");
-/// assert_eq!(repo, "bb-lint");
-/// ```
+/// The minimum `bb` version is set to the running version, since that is the
+/// version the scaffold was generated against.
 ///
-/// # Notes
+/// # Parameters
 ///
-/// - Whitespace is trimmed from the input
-/// - The `.git` suffix is automatically stripped from URLs
-/// - SSH URLs (`git@bitbucket.org:owner/repo.git`) are not fully supported
-fn parse_repo_url(url: &str) -> Result<(String, String)> {
-    // Handle various formats:
-    // - owner/repo
-    // - https://bitbucket.org/owner/repo
-    // - git@bitbucket.org:owner/repo.git
-
-    let url = url.trim();
-
-    if url.contains('/') && !url.contains(':') && !url.contains("://") {
-        // Simple owner/repo format
-        let parts: Vec<&str> = url.split('/').collect();
-        if parts.len() == 2 {
-            return Ok((parts[0].to_string(), parts[1].to_string()));
-        }
-    }
+/// * `dir` - The extension project directory
+/// * `name` - The extension name without the `bb-` prefix
+fn create_manifest(dir: &Path, name: &str) -> Result<()> {
+    let manifest = format!(
+        r#"name = "{name}"
+description = "A bb CLI extension"
+version = "0.1.0"
+min_bb_version = "{bb_version}"
 
-    // Try to extract from URL
-    let url = url.strip_suffix(".git").unwrap_or(url);
+# API scopes the extension needs from BB_TOKEN
+scopes = []
 
-    if let Some(rest) = url.strip_prefix("https://bitbucket.org/") {
-        let parts: Vec<&str> = rest.split('/').collect();
-        if parts.len() >= 2 {
-            return Ok((parts[0].to_string(), parts[1].to_string()));
-        }
-    }
+# Help text for the extension's subcommands
+[commands]
+"#,
+        name = name,
+        bb_version = crate::VERSION
+    );
 
-    anyhow::bail!("Could not parse repository URL: {}", url)
+    std::fs::write(dir.join(MANIFEST_FILE), manifest)?;
+
+    Ok(())
 }
 
 /// Creates a shell script extension scaffold.
@@ -0,0 +1,414 @@
+//
+//  bitbucket-cli
+//  hooks/mod.rs
+//
+//  Created by Ngonidzashe Mangudya on 2026/01/12.
+//  Copyright (c) 2025 IAMNGONI. All rights reserved.
+//
+
+//! Command Hooks
+//!
+//! Hooks let a team enforce its own policy around `bb` without forking it:
+//! require a Jira key in pull request titles, post to chat after a merge,
+//! refuse to delete repositories outside office hours, and so on.
+//!
+//! # Defining Hooks
+//!
+//! Hooks are listed per command, either in the user's `config.toml` or in a
+//! `.bb.toml` at the root of the repository:
+//!
+//! ```toml
+//! [hooks."pr create"]
+//! pre = ["scripts/require-jira-key.sh"]
+//!
+//! [hooks."pr merge"]
+//! post = ["bb chat-notify merged", "/usr/local/bin/audit-log"]
+//! ```
+//!
+//! Each entry is a command line. A leading `bb` runs this `bb` binary, so
+//! extensions can be used as hooks. Relative paths such as `scripts/x.sh`
+//! are resolved against the directory of the file that defined the hook.
+//!
+//! Hooks from `config.toml` run first. Hooks from `.bb.toml` run after them,
+//! and only once the user has run `bb config set repo_hooks enabled`. They
+//! are skipped when `--repo` names a different repository than the checkout
+//! `bb` runs in, and an untrusted `.bb.toml` is never an error.
+//!
+//! # Supported Commands
+//!
+//! See [`HOOK_COMMANDS`]: `pr create`, `pr merge`, `pipeline run`,
+//! `deploy promote` and `repo delete`.
+//!
+//! # What a Hook Receives
+//!
+//! A JSON description of the operation is written to the hook's stdin:
+//!
+//! ```json
+//! {
+//!   "event": "pre",
+//!   "command": "pr create",
+//!   "repository": { "host": "bitbucket.org", "host_type": "cloud", "full_name": "acme/widgets" },
+//!   "operation": { "title": "Add caching", "source_branch": "feature/cache", ... }
+//! }
+//! ```
+//!
+//! Post hooks additionally get a `result` object, such as the new pull
+//! request's id and URL. The environment carries the same `BB_*` variables
+//! as extensions, plus `BB_HOOK` (`pre` or `post`) and `BB_HOOK_COMMAND`.
+//!
+//! # Outcome
+//!
+//! - A pre hook that exits non-zero aborts the command before any change is
+//!   made. Read-only checks, such as looking up the pull request or its
+//!   merge checks, run before the pre hooks so that a command that would be
+//!   refused anyway does not trigger them
+//! - A post hook that fails only produces a warning, since the operation has
+//!   already happened
+//! - Hook stdout is sent to `bb`'s stderr so that `--json` output stays clean
+//! - Commands run from inside a hook do not trigger hooks again
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use anyhow::{bail, Context, Result};
+use serde_json::{json, Value};
+
+use crate::config::{CommandHooks, Config, RepoConfig, REPO_CONFIG_FILE};
+use crate::context::{HostType, RepoContext};
+use crate::extension::ExtensionEnv;
+use crate::output::theme;
+
+/// The commands that hooks can be attached to.
+pub const HOOK_COMMANDS: &[&str] = &[
+    "pr create",
+    "pr merge",
+    "pipeline run",
+    "deploy promote",
+    "repo delete",
+];
+
+/// Environment variable set while a hook runs, holding `pre` or `post`.
+pub const HOOK_ENV: &str = "BB_HOOK";
+
+/// A single hook command line, with the directory relative paths resolve
+/// against.
+#[derive(Debug, Clone, PartialEq)]
+struct Hook {
+    /// The command line as written in the configuration.
+    command: String,
+
+    /// The directory of the file that defined the hook.
+    base_dir: Option<PathBuf>,
+}
+
+impl Hook {
+    /// Runs the hook with `payload` on stdin.
+    ///
+    /// # Returns
+    ///
+    /// The hook's exit code, or `None` if it was killed by a signal.
+    fn run(
+        &self,
+        event: &str,
+        command_name: &str,
+        env: &ExtensionEnv,
+        payload: &Value,
+    ) -> Result<Option<i32>> {
+        let words = shell_words::split(&self.command)
+            .with_context(|| format!("Invalid hook command: {}", self.command))?;
+        let Some((program, args)) = words.split_first() else {
+            bail!("Empty hook command for '{}'", command_name);
+        };
+
+        let program = self.resolve_program(program)?;
+
+        let mut child = Command::new(&program)
+            .args(args)
+            .envs(env.vars())
+            .env(HOOK_ENV, event)
+            .env("BB_HOOK_COMMAND", command_name)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .with_context(|| format!("Failed to run hook: {}", self.command))?;
+
+        if let Some(mut stdin) = child.stdin.take() {
+            // A hook that never reads its input is fine; ignore the broken pipe.
+            if let Err(e) = stdin.write_all(payload.to_string().as_bytes()) {
+                if e.kind() != std::io::ErrorKind::BrokenPipe {
+                    return Err(e).context("Failed to write to hook");
+                }
+            }
+        }
+
+        let output = child
+            .wait_with_output()
+            .with_context(|| format!("Failed to run hook: {}", self.command))?;
+        std::io::stderr().write_all(&output.stdout)?;
+
+        Ok(output.status.code())
+    }
+
+    /// Resolves the program to run.
+    ///
+    /// `bb` maps to the running executable, and relative paths containing a
+    /// separator are taken relative to the defining file.
+    fn resolve_program(&self, program: &str) -> Result<PathBuf> {
+        if program == "bb" {
+            return std::env::current_exe().context("Failed to locate the bb executable");
+        }
+
+        let path = Path::new(program);
+        match &self.base_dir {
+            Some(base) if path.is_relative() && path.components().count() > 1 => {
+                Ok(base.join(path))
+            }
+            _ => Ok(path.to_path_buf()),
+        }
+    }
+}
+
+/// The hooks that apply to one invocation of a command.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let hooks = Hooks::load("pr merge", &context, global.json).await?;
+/// let operation = serde_json::json!({ "id": 42, "strategy": "squash" });
+///
+/// hooks.run_pre(&operation)?;
+/// // ... merge the pull request ...
+/// hooks.run_post(&operation, &serde_json::json!({ "merged": true }));
+/// ```
+#[derive(Debug)]
+pub struct Hooks {
+    command: String,
+    pre: Vec<Hook>,
+    post: Vec<Hook>,
+    repository: Value,
+    env: ExtensionEnv,
+}
+
+impl Hooks {
+    /// Collects the hooks for a command from `config.toml` and `.bb.toml`.
+    ///
+    /// # Parameters
+    ///
+    /// * `command` - The command name, one of [`HOOK_COMMANDS`]
+    /// * `context` - The repository the command operates on
+    /// * `json` - Whether the user asked for JSON output (`BB_JSON`)
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `config.toml`, or a trusted `.bb.toml`, cannot be
+    /// parsed or defines hooks for a command that does not support them.
+    pub async fn load(command: &str, context: &RepoContext, json: bool) -> Result<Self> {
+        let mut hooks = Self {
+            command: command.to_string(),
+            pre: Vec::new(),
+            post: Vec::new(),
+            repository: json!({
+                "host": context.host,
+                "host_type": match context.host_type {
+                    HostType::Cloud => "cloud",
+                    HostType::Server => "server",
+                },
+                "full_name": context.full_name(),
+            }),
+            env: ExtensionEnv::default(),
+        };
+
+        // Commands run by a hook must not run the hooks again.
+        if std::env::var_os(HOOK_ENV).is_some() {
+            return Ok(hooks);
+        }
+
+        let config = Config::load()?;
+        let config_dir = Config::config_path()?.parent().map(Path::to_path_buf);
+        check_commands(config.hooks.keys(), "config.toml")?;
+        hooks.add(config.hooks.get(command), config_dir);
+
+        // An untrusted file is only read to point out the hooks it would add,
+        // so a broken one must not stop the command
+        let trusted = config.core.repo_hooks.as_deref() == Some("enabled");
+        let repo_config = match RepoConfig::load_for(context) {
+            Ok(repo_config) => repo_config,
+            Err(e) if !trusted => {
+                tracing::debug!("Ignoring {}: {:#}", REPO_CONFIG_FILE, e);
+                None
+            }
+            Err(e) => return Err(e),
+        };
+        if let Some(repo_config) = repo_config {
+            if trusted {
+                check_commands(
+                    repo_config.hooks.keys(),
+                    &repo_config.path.display().to_string(),
+                )?;
+                hooks.add(
+                    repo_config.hooks.get(command),
+                    repo_config.path.parent().map(Path::to_path_buf),
+                );
+            } else if repo_config.hooks.contains_key(command) {
+                eprintln!(
+                    "{} Ignoring '{}' hooks in {}; run 'bb config set repo_hooks enabled' to trust them",
+                    theme().warning_mark(),
+                    command,
+                    repo_config.path.display()
+                );
+            }
+        }
+
+        if !hooks.pre.is_empty() || !hooks.post.is_empty() {
+            hooks.env = ExtensionEnv::for_context(context, json, &config).await;
+        }
+
+        Ok(hooks)
+    }
+
+    /// Appends the hooks from one configuration file.
+    fn add(&mut self, hooks: Option<&CommandHooks>, base_dir: Option<PathBuf>) {
+        let Some(hooks) = hooks else {
+            return;
+        };
+        let hook = |command: &String| Hook {
+            command: command.clone(),
+            base_dir: base_dir.clone(),
+        };
+        self.pre.extend(hooks.pre.iter().map(hook));
+        self.post.extend(hooks.post.iter().map(hook));
+    }
+
+    /// Runs the pre hooks in order.
+    ///
+    /// # Parameters
+    ///
+    /// * `operation` - A description of the pending operation
+    ///
+    /// # Errors
+    ///
+    /// Returns an error, which should abort the command, as soon as a hook
+    /// cannot be started or exits non-zero.
+    pub fn run_pre(&self, operation: &Value) -> Result<()> {
+        let payload = self.payload("pre", operation, None);
+
+        for hook in &self.pre {
+            match hook.run("pre", &self.command, &self.env, &payload)? {
+                Some(0) => {}
+                Some(code) => bail!(
+                    "'{}' was stopped by the pre hook '{}' (exit code {})",
+                    self.command,
+                    hook.command,
+                    code
+                ),
+                None => bail!(
+                    "'{}' was stopped by the pre hook '{}' (terminated by signal)",
+                    self.command,
+                    hook.command
+                ),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Runs the post hooks in order.
+    ///
+    /// Failures are reported as warnings, because the operation they follow
+    /// has already completed.
+    ///
+    /// # Parameters
+    ///
+    /// * `operation` - The description that was given to [`Hooks::run_pre`]
+    /// * `result` - What the operation produced, such as a new id or URL
+    pub fn run_post(&self, operation: &Value, result: &Value) {
+        let payload = self.payload("post", operation, Some(result));
+
+        for hook in &self.post {
+            match hook.run("post", &self.command, &self.env, &payload) {
+                Ok(Some(0)) => {}
+                Ok(Some(code)) => eprintln!(
+                    "{} Post hook '{}' exited with code {}",
+                    theme().warning_mark(),
+                    hook.command,
+                    code
+                ),
+                Ok(None) => eprintln!(
+                    "{} Post hook '{}' was terminated by a signal",
+                    theme().warning_mark(),
+                    hook.command
+                ),
+                Err(e) => eprintln!("{} {:#}", theme().warning_mark(), e),
+            }
+        }
+    }
+
+    /// Builds the JSON document written to a hook's stdin.
+    fn payload(&self, event: &str, operation: &Value, result: Option<&Value>) -> Value {
+        let mut payload = json!({
+            "event": event,
+            "command": self.command,
+            "repository": self.repository,
+            "operation": operation,
+        });
+        if let Some(result) = result {
+            payload["result"] = result.clone();
+        }
+        payload
+    }
+}
+
+/// Fails if a configuration file defines hooks for an unsupported command.
+fn check_commands<'a>(commands: impl Iterator<Item = &'a String>, source: &str) -> Result<()> {
+    for command in commands {
+        if !HOOK_COMMANDS.contains(&command.as_str()) {
+            bail!(
+                "Unknown hook command '{}' in {}. Hooks are supported for: {}",
+                command,
+                source,
+                HOOK_COMMANDS.join(", ")
+            );
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_program() {
+        let hook = Hook {
+            command: String::new(),
+            base_dir: Some(PathBuf::from("/repo")),
+        };
+        assert_eq!(
+            hook.resolve_program("scripts/check.sh").unwrap(),
+            PathBuf::from("/repo/scripts/check.sh")
+        );
+        assert_eq!(
+            hook.resolve_program("check.sh").unwrap(),
+            PathBuf::from("check.sh")
+        );
+        assert_eq!(
+            hook.resolve_program("/usr/bin/true").unwrap(),
+            PathBuf::from("/usr/bin/true")
+        );
+        assert_eq!(
+            hook.resolve_program("bb").unwrap(),
+            std::env::current_exe().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_check_commands() {
+        let known = ["pr merge".to_string()];
+        assert!(check_commands(known.iter(), "config.toml").is_ok());
+
+        let unknown = ["pr merged".to_string()];
+        let err = check_commands(unknown.iter(), "config.toml").unwrap_err();
+        assert!(err.to_string().contains("Unknown hook command 'pr merged'"));
+    }
+}
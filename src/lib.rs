@@ -37,6 +37,9 @@
 //! - [`interactive`]: Interactive prompts and selectors
 //! - [`extension`]: CLI extension system
 //! - [`alias`]: Command alias management
+//! - [`hooks`]: Pre and post command hooks
+//! - [`review`]: Pending pull request reviews
+//! - [`users`]: Resolution of user names to API identifiers
 //! - [`util`]: Utility functions
 //!
 //! ## Example Usage
@@ -131,6 +134,25 @@ pub mod extension;
 /// Aliases can expand to full command strings or execute shell commands.
 pub mod alias;
 
+/// Command hooks.
+///
+/// Runs user- or repository-defined commands before and after selected
+/// `bb` commands, passing a JSON description of the operation on stdin.
+/// A failing pre hook aborts the command.
+pub mod hooks;
+
+/// Pending pull request reviews.
+///
+/// Stores the comments written during an interactive review on disk until
+/// they are submitted together.
+pub mod review;
+
+/// User resolution.
+///
+/// Maps `@handle`, email, display name or Server/DC reviewer group inputs to
+/// the account UUIDs and user slugs the APIs expect, caching the results.
+pub mod users;
+
 /// Utility functions and helpers.
 ///
 /// Common utilities used throughout the codebase including:
@@ -297,6 +319,36 @@ pub mod exit_codes {
     /// `16`
     pub const CANCELLED: i32 = 16;
 
+    /// Required checks failed.
+    ///
+    /// A build status reported on the pull request failed or was stopped
+    /// while the command was waiting for it, e.g. `bb pr merge --auto`.
+    ///
+    /// # Value
+    ///
+    /// `17`
+    pub const CHECKS_FAILED: i32 = 17;
+
+    /// Timed out.
+    ///
+    /// The command stopped waiting because its `--timeout` elapsed before
+    /// the condition it was waiting for was met.
+    ///
+    /// # Value
+    ///
+    /// `18`
+    pub const TIMEOUT: i32 = 18;
+
+    /// Pull request changed.
+    ///
+    /// The pull request was updated with new commits, declined or otherwise
+    /// changed while the command was waiting on it, so it did not act.
+    ///
+    /// # Value
+    ///
+    /// `19`
+    pub const PR_CHANGED: i32 = 19;
+
     /// API rate limit exceeded.
     ///
     /// The Bitbucket API rate limit has been exceeded.
@@ -306,4 +358,53 @@ pub mod exit_codes {
     ///
     /// `32`
     pub const RATE_LIMIT: i32 = 32;
+
+    /// An error that exits the CLI with a specific code.
+    ///
+    /// Commands return this, usually wrapped in an [`anyhow::Error`], when the
+    /// failure should be distinguishable by scripts. Any other error exits
+    /// with [`ERROR`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bitbucket_cli::exit_codes::{self, ExitError};
+    ///
+    /// let err: anyhow::Error = ExitError::new(exit_codes::TIMEOUT, "Timed out").into();
+    /// assert_eq!(exit_codes::code_for(&err), exit_codes::TIMEOUT);
+    /// ```
+    #[derive(Debug)]
+    pub struct ExitError {
+        /// Exit code to use
+        pub code: i32,
+        message: String,
+    }
+
+    impl ExitError {
+        /// Creates an error that exits with `code`
+        pub fn new(code: i32, message: impl Into<String>) -> Self {
+            Self {
+                code,
+                message: message.into(),
+            }
+        }
+    }
+
+    impl std::fmt::Display for ExitError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.write_str(&self.message)
+        }
+    }
+
+    impl std::error::Error for ExitError {}
+
+    /// Returns the exit code for an error.
+    ///
+    /// Uses the code of the first [`ExitError`] in the error's chain, or
+    /// [`ERROR`] if there is none.
+    pub fn code_for(err: &anyhow::Error) -> i32 {
+        err.chain()
+            .find_map(|e| e.downcast_ref::<ExitError>())
+            .map_or(ERROR, |e| e.code)
+    }
 }
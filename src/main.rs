@@ -6,20 +6,38 @@
 //  Copyright (c) 2025 IAMNGONI. All rights reserved.
 //
 
+use std::ffi::OsString;
+
 use anyhow::Result;
-use clap::Parser;
+use clap::{CommandFactory, FromArgMatches};
 use tracing_subscriber::{fmt, prelude::*, EnvFilter};
 
-use bitbucket_cli::cli::{Cli, Commands};
+use bitbucket_cli::alias::{expand_args, run_shell_alias, AliasConfig, Expansion};
+use bitbucket_cli::cli::{expand_global_shorts, run_external, with_extension_help, Cli, Commands};
+use bitbucket_cli::config::Config;
 use bitbucket_cli::exit_codes;
+use bitbucket_cli::output;
 
 #[tokio::main]
 async fn main() -> Result<()> {
     // Initialize logging
     init_logging();
 
-    // Parse CLI arguments
-    let cli = Cli::parse();
+    // Expand aliases, then parse CLI arguments
+    let args = match expand_aliases() {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("Error: {e:#}");
+            std::process::exit(exit_codes::ERROR);
+        }
+    };
+    let mut cli = parse_cli(args);
+
+    // Set up output options; --jq, --template and --format work on JSON, so they imply --json
+    if let Err(e) = configure_output(&mut cli) {
+        eprintln!("Error: {e:#}");
+        std::process::exit(exit_codes::ERROR);
+    }
 
     // Execute command
     let result = run(cli).await;
@@ -29,7 +47,7 @@ async fn main() -> Result<()> {
         Ok(()) => std::process::exit(exit_codes::SUCCESS),
         Err(e) => {
             eprintln!("Error: {e:#}");
-            std::process::exit(exit_codes::ERROR);
+            std::process::exit(exit_codes::code_for(&e));
         }
     }
 }
@@ -44,6 +62,179 @@ fn init_logging() {
         .init();
 }
 
+/// Expand a user alias in the first argument, e.g. `bb co 12`
+///
+/// Shell aliases are run here and the process exits with their status.
+fn expand_aliases() -> Result<Vec<OsString>> {
+    let mut args: Vec<OsString> = std::env::args_os().collect();
+
+    // Only a first argument that is valid UTF-8 and not a flag can be an alias
+    let is_candidate = args
+        .get(1)
+        .and_then(|arg| arg.to_str())
+        .is_some_and(|arg| !arg.starts_with('-'));
+    if !is_candidate {
+        return Ok(args);
+    }
+
+    let config = Config::load().unwrap_or_default();
+    if config.aliases.is_empty() {
+        return Ok(args);
+    }
+
+    let aliases = AliasConfig::from_config_aliases(&config.aliases).aliases;
+    let rest: Vec<String> = args[1..]
+        .iter()
+        .map(|arg| arg.to_string_lossy().into_owned())
+        .collect();
+
+    match expand_args(&rest, &aliases)? {
+        Expansion::Args(expanded) if expanded == rest => Ok(args),
+        Expansion::Args(expanded) => {
+            args.truncate(1);
+            args.extend(expanded.into_iter().map(OsString::from));
+            Ok(args)
+        }
+        Expansion::Shell {
+            name,
+            command,
+            args,
+        } => std::process::exit(run_shell_alias(&name, &command, &args)?),
+    }
+}
+
+/// Parse the command line, listing installed extensions in `bb --help`
+fn parse_cli(args: Vec<OsString>) -> Cli {
+    let mut command = Cli::command();
+
+    // Only look for extensions when the top-level help may be shown
+    let wants_help = args.len() == 1
+        || args
+            .iter()
+            .skip(1)
+            .any(|arg| arg == "-h" || arg == "--help" || arg == "help");
+    if wants_help {
+        command = with_extension_help(command);
+    }
+
+    let args = expand_global_shorts(&command, args);
+    let matches = command.get_matches_from(args);
+    Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit())
+}
+
+/// Apply the global output options: `--color`, `--json`, `--jq`, `--template`,
+/// `--format`, `--columns`, `--sort`, `--reverse` and `--no-pager`
+fn configure_output(cli: &mut Cli) -> Result<()> {
+    configure_theme(cli.global.color.as_deref())?;
+
+    if let Some(ref fields) = cli.global.json_fields {
+        // A bare --json next to --jq or --template asks for the whole model
+        let filtered = cli.global.jq.is_some() || cli.global.template.is_some();
+        if !(fields.is_empty() && filtered) {
+            output::set_json_fields(fields)?;
+        }
+        cli.global.json = true;
+    }
+
+    if let Some(ref expression) = cli.global.jq {
+        output::set_jq_filter(expression)?;
+        cli.global.json = true;
+    }
+
+    if let Some(ref template) = cli.global.template {
+        output::set_template(template)?;
+        cli.global.json = true;
+    }
+
+    if let Some(ref format) = cli.global.format {
+        output::set_output_format(format.parse()?)?;
+        cli.global.json = true;
+    }
+
+    output::set_table_options(output::TableOptions::new(
+        cli.global.columns.as_deref(),
+        cli.global.sort.as_deref(),
+        cli.global.reverse,
+    ))?;
+
+    if cli.global.no_pager {
+        bitbucket_cli::util::disable_pager();
+    }
+
+    Ok(())
+}
+
+/// Set up the output theme from `--color`, the `[colors]` config section
+/// and the `core.color`/`core.theme` settings
+///
+/// A bad setting in the configuration file only warns and falls back to the
+/// default, so that it cannot lock the user out of every command,
+/// `bb config set` included.
+fn configure_theme(color_flag: Option<&str>) -> Result<()> {
+    let config = Config::load().unwrap_or_default();
+    let mut warnings = Vec::new();
+
+    let choice: output::ColorChoice = match color_flag {
+        Some(flag) => flag.parse()?,
+        None => config
+            .core
+            .color
+            .as_deref()
+            .unwrap_or("auto")
+            .parse()
+            .unwrap_or_else(|e| {
+                warnings.push(format!("{:#}; using auto", e));
+                output::ColorChoice::Auto
+            }),
+    };
+
+    let theme_name = std::env::var("BB_THEME")
+        .ok()
+        .or(config.core.theme.clone())
+        .unwrap_or_else(|| "default".to_string());
+    let plain = match theme_name.as_str() {
+        "default" => false,
+        "plain" => true,
+        other => {
+            warnings.push(format!(
+                "Invalid theme '{}'. Valid themes: default, plain; using default",
+                other
+            ));
+            false
+        }
+    };
+
+    // The plain theme is for screen readers and logs, so only colour it on request
+    let color = if plain && choice == output::ColorChoice::Auto {
+        false
+    } else {
+        output::color_enabled_for(
+            choice,
+            |key| std::env::var(key).ok(),
+            || console::Term::stdout().is_term(),
+        )
+    };
+
+    // Prompts and progress bars style through `console` directly
+    console::set_colors_enabled(color);
+    console::set_colors_enabled_stderr(color);
+
+    let theme = output::Theme::new(color, plain);
+    let theme = match theme.clone().with_state_colors(&config.colors) {
+        Ok(themed) => themed,
+        Err(e) => {
+            warnings.push(format!("{:#}; using the default colors", e));
+            theme
+        }
+    };
+    output::set_theme(theme)?;
+
+    for warning in warnings {
+        eprintln!("{} {}", output::theme().warning_mark(), warning);
+    }
+    Ok(())
+}
+
 /// Main command dispatcher
 async fn run(cli: Cli) -> Result<()> {
     match cli.command {
@@ -69,5 +260,10 @@ async fn run(cli: Cli) -> Result<()> {
             println!("bb version {}", bitbucket_cli::VERSION);
             Ok(())
         }
+        Commands::External(args) => {
+            // Forward the extension's exit code as our own
+            let code = run_external(&args, &cli.global).await?;
+            std::process::exit(code);
+        }
     }
 }
@@ -0,0 +1,332 @@
+//
+//  bitbucket-cli
+//  output/export.rs
+//
+//  Created by Ngonidzashe Mangudya on 2026/01/12.
+//  Copyright (c) 2025 IAMNGONI. All rights reserved.
+//
+
+//! # Export Formats
+//!
+//! This module implements `--format csv|tsv|yaml|ndjson`, for loading
+//! command output into spreadsheets, configuration files and log pipelines.
+//!
+//! ## Formats
+//!
+//! | Format | Description |
+//! |--------|-------------|
+//! | `csv` | Comma-separated values with a header row |
+//! | `tsv` | Tab-separated values with a header row |
+//! | `yaml` | YAML document |
+//! | `ndjson` | One compact JSON value per line |
+//!
+//! ## Flattening
+//!
+//! CSV and TSV rows are flat, so nested values are flattened into
+//! dot-path columns:
+//!
+//! | JSON | Columns |
+//! |------|---------|
+//! | `{"author": {"name": "ana"}}` | `author.name` |
+//! | `{"labels": ["bug", "ui"]}` | `labels` = `bug;ui` |
+//! | `{"reviewers": [{"name": "ben"}]}` | `reviewers.0.name` |
+//!
+//! Columns are the union of the fields of all rows, in the order they
+//! are first seen. Objects keep their field order, which for API models
+//! is the order of the struct's fields.
+
+use std::io::{self, Write};
+
+use anyhow::Result;
+use once_cell::sync::OnceCell;
+use serde::Serialize;
+use serde_json::Value;
+
+use super::fields::{json_fields, select_fields};
+use super::{write_json, OutputFormat};
+
+/// The output format selected with `--format`.
+static OUTPUT_FORMAT: OnceCell<OutputFormat> = OnceCell::new();
+
+/// Sets the output format for the rest of the process.
+///
+/// This is called once at startup with the value of the global `--format`
+/// flag.
+///
+/// # Errors
+///
+/// Returns an error if the format has already been set.
+pub fn set_output_format(format: OutputFormat) -> Result<()> {
+    OUTPUT_FORMAT
+        .set(format)
+        .map_err(|_| anyhow::anyhow!("output format has already been set"))
+}
+
+/// Returns the output format selected with `--format`, if any.
+pub fn output_format() -> Option<OutputFormat> {
+    OUTPUT_FORMAT.get().copied()
+}
+
+/// Writes a value to stdout in one of the export formats.
+///
+/// The `--json` field list is applied first, so `--json id,title --format csv`
+/// produces a two-column CSV.
+///
+/// # Parameters
+///
+/// * `value` - The value to write; a list becomes one row or line per element
+/// * `format` - [`OutputFormat::Csv`], [`OutputFormat::Tsv`],
+///   [`OutputFormat::Yaml`] or [`OutputFormat::Ndjson`]. Other formats are
+///   written as pretty-printed JSON.
+///
+/// # Errors
+///
+/// Returns an error if serialization or writing to stdout fails.
+pub fn write_formatted<T: Serialize + ?Sized>(value: &T, format: OutputFormat) -> Result<()> {
+    let mut value = serde_json::to_value(value)?;
+    if let Some(fields) = json_fields() {
+        value = select_fields(value, fields);
+    }
+
+    let stdout = io::stdout();
+    let mut handle = stdout.lock();
+
+    match format {
+        OutputFormat::Csv => write_delimited(&mut handle, &value, b',')?,
+        OutputFormat::Tsv => write_delimited(&mut handle, &value, b'\t')?,
+        OutputFormat::Yaml => write!(handle, "{}", serde_yaml::to_string(&value)?)?,
+        OutputFormat::Ndjson => write_ndjson(&mut handle, &value)?,
+        _ => writeln!(handle, "{}", serde_json::to_string_pretty(&value)?)?,
+    }
+
+    handle.flush()?;
+    Ok(())
+}
+
+/// The items of a list command, fetched page by page.
+///
+/// With `--format ndjson` each page is written as soon as it is added, so
+/// long listings start before the last page is fetched. Any other output
+/// needs the whole list: the items are kept for [`finish`](Self::finish)
+/// or [`into_items`](Self::into_items).
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use bitbucket_cli::output::ListPages;
+///
+/// # fn example(pages: Vec<Vec<u32>>) -> anyhow::Result<()> {
+/// let mut list = ListPages::new(true);
+/// for page in pages {
+///     list.push(page)?;
+/// }
+/// list.finish()?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct ListPages<T> {
+    streaming: bool,
+    items: Vec<T>,
+    count: usize,
+}
+
+impl<T: Serialize> ListPages<T> {
+    /// Starts a list; `json` is whether the command writes JSON output
+    /// rather than a table.
+    pub fn new(json: bool) -> Self {
+        Self {
+            streaming: json && output_format() == Some(OutputFormat::Ndjson),
+            items: Vec::new(),
+            count: 0,
+        }
+    }
+
+    /// Adds a page of items, writing it straight away when streaming.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing to stdout fails.
+    pub fn push(&mut self, page: Vec<T>) -> Result<()> {
+        self.count += page.len();
+        if self.streaming {
+            write_formatted(&page, OutputFormat::Ndjson)
+        } else {
+            self.items.extend(page);
+            Ok(())
+        }
+    }
+
+    /// The number of items added so far.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Writes the list as JSON, unless it was already streamed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if serialization or writing to stdout fails.
+    pub fn finish(self) -> Result<()> {
+        if self.streaming {
+            return Ok(());
+        }
+        write_json(&self.items)
+    }
+
+    /// The items kept for table output; empty when streaming.
+    pub fn into_items(self) -> Vec<T> {
+        self.items
+    }
+}
+
+/// Writes each element of a list as a line of compact JSON.
+///
+/// A value that is not a list is written as a single line.
+fn write_ndjson<W: Write>(writer: &mut W, value: &Value) -> Result<()> {
+    match value {
+        Value::Array(items) => {
+            for item in items {
+                writeln!(writer, "{}", item)?;
+            }
+        }
+        other => writeln!(writer, "{}", other)?,
+    }
+    Ok(())
+}
+
+/// Writes a value as CSV or TSV with a header row.
+fn write_delimited<W: Write>(writer: &mut W, value: &Value, delimiter: u8) -> Result<()> {
+    let (headers, rows) = flatten_rows(value);
+
+    let mut csv = csv::WriterBuilder::new()
+        .delimiter(delimiter)
+        .from_writer(writer);
+
+    csv.write_record(&headers)?;
+    for row in rows {
+        let record: Vec<&str> = headers
+            .iter()
+            .map(|h| {
+                row.iter()
+                    .find(|(k, _)| k == h)
+                    .map(|(_, v)| v.as_str())
+                    .unwrap_or("")
+            })
+            .collect();
+        csv.write_record(&record)?;
+    }
+    csv.flush()?;
+    Ok(())
+}
+
+/// Flattens a value into a header and dot-path rows.
+///
+/// A list becomes one row per element; anything else becomes a single row.
+fn flatten_rows(value: &Value) -> (Vec<String>, Vec<Vec<(String, String)>>) {
+    let items: Vec<&Value> = match value {
+        Value::Array(items) => items.iter().collect(),
+        other => vec![other],
+    };
+
+    let mut headers: Vec<String> = Vec::new();
+    let mut rows = Vec::with_capacity(items.len());
+
+    for item in items {
+        let mut row = Vec::new();
+        match item {
+            Value::Object(_) => flatten_into(item, "", &mut row),
+            scalar => row.push(("value".to_string(), cell(scalar))),
+        }
+        for (key, _) in &row {
+            if !headers.contains(key) {
+                headers.push(key.clone());
+            }
+        }
+        rows.push(row);
+    }
+
+    (headers, rows)
+}
+
+fn flatten_into(value: &Value, prefix: &str, row: &mut Vec<(String, String)>) {
+    let path = |key: &str| {
+        if prefix.is_empty() {
+            key.to_string()
+        } else {
+            format!("{}.{}", prefix, key)
+        }
+    };
+
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map {
+                flatten_into(child, &path(key), row);
+            }
+        }
+        // Lists of scalars read better as a single cell
+        Value::Array(items) if items.iter().all(|i| !i.is_object() && !i.is_array()) => {
+            let joined = items.iter().map(cell).collect::<Vec<_>>().join(";");
+            row.push((prefix.to_string(), joined));
+        }
+        Value::Array(items) => {
+            for (i, child) in items.iter().enumerate() {
+                flatten_into(child, &path(&i.to_string()), row);
+            }
+        }
+        scalar => row.push((prefix.to_string(), cell(scalar))),
+    }
+}
+
+fn cell(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn delimited(value: Value, delimiter: u8) -> String {
+        let mut buf = Vec::new();
+        write_delimited(&mut buf, &value, delimiter).unwrap();
+        String::from_utf8(buf).unwrap()
+    }
+
+    #[test]
+    fn test_csv_flattens_nested_values() {
+        let value = json!([
+            {"id": 1, "title": "Fix, again", "author": {"name": "ana"}, "labels": ["bug", "ui"]},
+            {"id": 2, "title": "Add", "author": {"name": "ben"}, "labels": []}
+        ]);
+        assert_eq!(
+            delimited(value, b','),
+            "id,title,author.name,labels\n1,\"Fix, again\",ana,bug;ui\n2,Add,ben,\n"
+        );
+    }
+
+    #[test]
+    fn test_tsv_indexes_lists_of_objects() {
+        let value = json!({"id": 5, "reviewers": [{"name": "ana"}, {"name": "ben"}]});
+        assert_eq!(
+            delimited(value, b'\t'),
+            "id\treviewers.0.name\treviewers.1.name\n5\tana\tben\n"
+        );
+    }
+
+    #[test]
+    fn test_ragged_rows_share_columns() {
+        let value = json!([{"a": 1}, {"b": 2}]);
+        assert_eq!(delimited(value, b','), "a,b\n1,\n,2\n");
+    }
+
+    #[test]
+    fn test_ndjson_writes_one_line_per_item() {
+        let mut buf = Vec::new();
+        write_ndjson(&mut buf, &json!([{"id": 1}, {"id": 2}])).unwrap();
+        assert_eq!(String::from_utf8(buf).unwrap(), "{\"id\":1}\n{\"id\":2}\n");
+    }
+}
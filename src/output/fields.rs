@@ -0,0 +1,385 @@
+//
+//  bitbucket-cli
+//  output/fields.rs
+//
+//  Created by Ngonidzashe Mangudya on 2026/01/12.
+//  Copyright (c) 2025 IAMNGONI. All rights reserved.
+//
+
+//! # JSON Field Selection
+//!
+//! This module implements `--json FIELDS`, which limits JSON output to a
+//! comma-separated list of fields, in the style of `gh`.
+//!
+//! ## Field Paths
+//!
+//! | Field | Selects |
+//! |-------|---------|
+//! | `title` | The `title` field |
+//! | `merge_commit.hash` | Only `hash` inside `merge_commit` |
+//! | `participants.approved` | `approved` on every element of `participants` |
+//!
+//! ## Discoverability
+//!
+//! Commands that output a full API model implement [`JsonFields`] for it and
+//! call [`require_json_fields`] before making any requests. Running such a
+//! command with `--json` and no field list prints the available fields
+//! instead of output, unless `--jq` or `--template` is given, in which case
+//! they receive the whole model.
+//!
+//! ## Example
+//!
+//! ```rust,ignore
+//! use bitbucket_cli::output::select_fields;
+//! use serde_json::json;
+//!
+//! let pr = json!({"id": 1, "title": "Fix", "merge_commit": {"hash": "abc", "type": "commit"}});
+//! let fields = vec!["id".to_string(), "merge_commit.hash".to_string()];
+//!
+//! assert_eq!(
+//!     select_fields(pr, &fields),
+//!     json!({"id": 1, "merge_commit": {"hash": "abc"}})
+//! );
+//! ```
+
+use once_cell::sync::OnceCell;
+use serde::de::{DeserializeOwned, Deserializer, Visitor};
+use serde_json::{Map, Value};
+
+/// The fields requested with `--json`. Empty when `--json` has no value.
+static JSON_FIELDS: OnceCell<Vec<String>> = OnceCell::new();
+
+/// Types whose JSON representation can be filtered with `--json FIELDS`.
+///
+/// The field names come from the model's `Deserialize` implementation, so
+/// they follow the struct as fields are added or renamed. Models must use
+/// the same names for serializing and deserializing, which is the case
+/// unless a field is renamed or skipped in one direction only.
+pub trait JsonFields: DeserializeOwned {
+    /// Top-level field names as they appear in the serialized output, in
+    /// declaration order.
+    fn json_fields() -> &'static [&'static str] {
+        let mut fields: &'static [&'static str] = &[];
+        // Stops at `deserialize_struct`, which hands over the field names
+        let _ = Self::deserialize(FieldNames(&mut fields));
+        fields
+    }
+}
+
+/// A deserializer that records the field names of the struct asked of it.
+struct FieldNames<'a>(&'a mut &'static [&'static str]);
+
+impl<'de> Deserializer<'de> for FieldNames<'_> {
+    type Error = serde::de::value::Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
+        Err(serde::de::Error::custom("not a struct"))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        _visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        *self.0 = fields;
+        Err(serde::de::Error::custom("field names recorded"))
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map enum identifier ignored_any
+    }
+}
+
+/// Sets the fields requested with `--json` for the rest of the process.
+///
+/// An empty `spec` records that `--json` was given without a field list.
+///
+/// # Errors
+///
+/// Returns an error if a field path is malformed, or if the fields have
+/// already been set.
+pub fn set_json_fields(spec: &str) -> anyhow::Result<()> {
+    let fields = parse_fields(spec)?;
+    JSON_FIELDS
+        .set(fields)
+        .map_err(|_| anyhow::anyhow!("JSON fields have already been set"))
+}
+
+/// Returns the fields requested with `--json`, if a field list was given.
+pub fn json_fields() -> Option<&'static [String]> {
+    JSON_FIELDS
+        .get()
+        .filter(|fields| !fields.is_empty())
+        .map(Vec::as_slice)
+}
+
+/// Checks the requested `--json` fields against the fields a command offers.
+///
+/// Commands that output a full API model call this before doing any work,
+/// so that `--json` without a value lists the fields and an unknown field
+/// is reported before any requests are made.
+///
+/// # Errors
+///
+/// Returns an error listing the available fields if `--json` was given
+/// without a field list, or if a requested field is not available.
+pub fn require_json_fields(available: &[&str]) -> anyhow::Result<()> {
+    let Some(requested) = JSON_FIELDS.get() else {
+        return Ok(());
+    };
+
+    if requested.is_empty() {
+        anyhow::bail!(
+            "Specify one or more comma-separated fields for `--json`:\n{}",
+            format_field_list(available)
+        );
+    }
+
+    for field in requested {
+        let top = field.split('.').next().unwrap_or_default();
+        if !available.contains(&top) {
+            anyhow::bail!(
+                "Unknown JSON field: \"{}\"\nAvailable fields:\n{}",
+                field,
+                format_field_list(available)
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Limits a JSON value to the given field paths.
+///
+/// Arrays are filtered element by element, both at the top level and
+/// inside a path, so `participants.approved` keeps `approved` on every
+/// participant. Fields missing from the value are omitted.
+///
+/// # Parameters
+///
+/// * `value` - The value to filter
+/// * `fields` - Dot-separated field paths
+///
+/// # Returns
+///
+/// The filtered value.
+pub fn select_fields(value: Value, fields: &[String]) -> Value {
+    match value {
+        Value::Array(items) => Value::Array(
+            items
+                .into_iter()
+                .map(|item| select_fields(item, fields))
+                .collect(),
+        ),
+        value => {
+            let mut selected = Value::Object(Map::new());
+            for field in fields {
+                let path: Vec<&str> = field.split('.').collect();
+                if let Some(picked) = select_path(&value, &path) {
+                    merge(&mut selected, picked);
+                }
+            }
+            selected
+        }
+    }
+}
+
+/// Picks a single path out of a value, keeping the surrounding structure.
+fn select_path(value: &Value, path: &[&str]) -> Option<Value> {
+    let Some((key, rest)) = path.split_first() else {
+        return Some(value.clone());
+    };
+
+    match value {
+        Value::Object(map) => {
+            let child = map.get(*key)?;
+            let picked = select_path(child, rest)?;
+            let mut object = Map::new();
+            object.insert((*key).to_string(), picked);
+            Some(Value::Object(object))
+        }
+        Value::Array(items) => Some(Value::Array(
+            items
+                .iter()
+                .map(|item| select_path(item, path).unwrap_or(Value::Null))
+                .collect(),
+        )),
+        // Keep nulls so that `merge_commit.hash` on an open PR is still present
+        Value::Null => Some(Value::Null),
+        _ => None,
+    }
+}
+
+/// Deep-merges `other` into `target`.
+fn merge(target: &mut Value, other: Value) {
+    match (target, other) {
+        (Value::Object(target), Value::Object(other)) => {
+            for (key, value) in other {
+                match target.get_mut(&key) {
+                    Some(existing) => merge(existing, value),
+                    None => {
+                        target.insert(key, value);
+                    }
+                }
+            }
+        }
+        (Value::Array(target), Value::Array(other)) => {
+            for (existing, value) in target.iter_mut().zip(other) {
+                merge(existing, value);
+            }
+        }
+        (target, other) => *target = other,
+    }
+}
+
+/// Splits a `--json` value into field paths.
+///
+/// Field names are identifiers, so a value such as `42` in
+/// `bb pr view --json 42` is rejected: it is an argument that `--json`
+/// took as its field list.
+fn parse_fields(spec: &str) -> anyhow::Result<Vec<String>> {
+    let mut fields = Vec::new();
+    for field in spec.split(',').map(str::trim).filter(|f| !f.is_empty()) {
+        if !field.split('.').all(is_field_name) {
+            anyhow::bail!(
+                "Invalid JSON field: \"{}\"\n\
+                 Put arguments before --json (bb pr view 42 --json title) \
+                 or pass fields as --json=FIELDS",
+                field
+            );
+        }
+        if !fields.iter().any(|f| f == field) {
+            fields.push(field.to_string());
+        }
+    }
+    Ok(fields)
+}
+
+/// Whether `name` can be a JSON field name: letters, digits and
+/// underscores, not starting with a digit
+fn is_field_name(name: &str) -> bool {
+    name.chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn format_field_list(available: &[&str]) -> String {
+    available
+        .iter()
+        .map(|field| format!("  {}", field))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn fields(names: &[&str]) -> Vec<String> {
+        names.iter().map(|n| n.to_string()).collect()
+    }
+
+    #[test]
+    fn test_json_fields_follow_the_serialized_model() {
+        #[derive(serde::Serialize, serde::Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Model {
+            id: u64,
+            #[serde(default)]
+            created_date: Option<String>,
+            #[serde(rename = "self")]
+            self_link: String,
+        }
+        impl JsonFields for Model {}
+
+        let model = Model {
+            id: 1,
+            created_date: None,
+            self_link: String::new(),
+        };
+        let serialized = serde_json::to_value(&model).unwrap();
+        let keys: Vec<&str> = serialized
+            .as_object()
+            .unwrap()
+            .keys()
+            .map(String::as_str)
+            .collect();
+        assert_eq!(Model::json_fields(), keys);
+        assert_eq!(Model::json_fields(), ["id", "createdDate", "self"]);
+    }
+
+    #[test]
+    fn test_select_top_level_fields() {
+        let value = json!({"id": 1, "title": "Fix", "state": "OPEN"});
+        assert_eq!(
+            select_fields(value, &fields(&["id", "title"])),
+            json!({"id": 1, "title": "Fix"})
+        );
+    }
+
+    #[test]
+    fn test_select_nested_paths() {
+        let value = json!({
+            "id": 7,
+            "merge_commit": {"hash": "abc123", "type": "commit"},
+            "participants": [
+                {"user": {"nickname": "ana"}, "approved": true, "role": "REVIEWER"},
+                {"user": {"nickname": "ben"}, "approved": false, "role": "PARTICIPANT"}
+            ]
+        });
+
+        assert_eq!(
+            select_fields(
+                value,
+                &fields(&[
+                    "merge_commit.hash",
+                    "participants.approved",
+                    "participants.user.nickname"
+                ])
+            ),
+            json!({
+                "merge_commit": {"hash": "abc123"},
+                "participants": [
+                    {"approved": true, "user": {"nickname": "ana"}},
+                    {"approved": false, "user": {"nickname": "ben"}}
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn test_select_fields_on_list() {
+        let value = json!([{"id": 1, "title": "A"}, {"id": 2, "title": "B"}]);
+        assert_eq!(
+            select_fields(value, &fields(&["id"])),
+            json!([{"id": 1}, {"id": 2}])
+        );
+    }
+
+    #[test]
+    fn test_null_parent_is_kept() {
+        let value = json!({"id": 1, "merge_commit": null});
+        assert_eq!(
+            select_fields(value, &fields(&["merge_commit.hash"])),
+            json!({"merge_commit": null})
+        );
+    }
+
+    #[test]
+    fn test_parse_fields() {
+        assert_eq!(
+            parse_fields("id, title,id,author.name").unwrap(),
+            fields(&["id", "title", "author.name"])
+        );
+        assert!(parse_fields("").unwrap().is_empty());
+        assert!(parse_fields("author.").is_err());
+        assert!(parse_fields("42").is_err());
+        assert!(parse_fields("title,feature/cache").is_err());
+        assert!(parse_fields("fromRef.displayId,task_count").is_ok());
+    }
+}
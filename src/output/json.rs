@@ -17,7 +17,7 @@
 //! - Pretty-printed JSON output for readability
 //! - Compact JSON output for minimal size
 //! - JSON Lines (NDJSON) format for streaming
-//! - Basic jq-style field extraction
+//! - Embedded jq filtering for `--jq`
 //! - Writer-based output for flexible destinations
 //!
 //! ## Output Formats
@@ -58,14 +58,31 @@
 //! - Parsing in shell scripts with tools like `jq` or `python -m json.tool`
 //! - Integration with other CLI tools and automation pipelines
 
+use once_cell::sync::OnceCell;
 use serde::Serialize;
+
+use super::export::{output_format, write_formatted};
+use super::fields::{json_fields, select_fields};
+use super::template::template;
+use super::OutputFormat;
 use std::io::{self, Write};
 
+/// The `--jq` filter applied to JSON written by [`write_json`].
+static JQ_FILTER: OnceCell<JqFilter> = OnceCell::new();
+
 /// Writes a value as pretty-printed JSON to stdout.
 ///
 /// The output is formatted with indentation and newlines for human
 /// readability. Each top-level element starts on its own line.
 ///
+/// If `--json` was given a field list (see [`set_json_fields`]), only those
+/// fields are written. If a `--jq` filter is active (see [`set_jq_filter`]),
+/// it is applied next and each result is printed on its own line. String
+/// results are printed without quotes, like `jq -r`. If a `--template` is
+/// active (see [`set_template`]), the value is rendered with it instead.
+/// If `--format` selected an export format such as CSV (see
+/// [`set_output_format`]), the value is written in that format.
+///
 /// # Parameters
 ///
 /// * `value` - Any type implementing [`Serialize`]
@@ -105,11 +122,73 @@ use std::io::{self, Write};
 /// - The value cannot be serialized to JSON
 /// - stdout is not writable
 pub fn write_json<T: Serialize>(value: &T) -> anyhow::Result<()> {
+    if let Some(format) = output_format().filter(|f| *f != OutputFormat::Json) {
+        return write_formatted(value, format);
+    }
+
+    if let Some(fields) = json_fields() {
+        let selected = select_fields(serde_json::to_value(value)?, fields);
+        if let Some(template) = template() {
+            return template.write(&selected);
+        }
+        return match JQ_FILTER.get() {
+            Some(filter) => write_jq_results(filter, &selected, true),
+            None => {
+                println!("{}", serde_json::to_string_pretty(&selected)?);
+                Ok(())
+            }
+        };
+    }
+
+    if let Some(template) = template() {
+        return template.write(value);
+    }
+
+    if let Some(filter) = JQ_FILTER.get() {
+        return write_jq_results(filter, value, true);
+    }
+
     let json = serde_json::to_string_pretty(value)?;
     println!("{}", json);
     Ok(())
 }
 
+/// Runs the `--jq` filter on a value and prints each result on its own line.
+fn write_jq_results<T: Serialize>(
+    filter: &JqFilter,
+    value: &T,
+    pretty: bool,
+) -> anyhow::Result<()> {
+    for result in filter.run(serde_json::to_value(value)?)? {
+        match result {
+            serde_json::Value::String(s) => println!("{}", s),
+            other if pretty => println!("{}", serde_json::to_string_pretty(&other)?),
+            other => println!("{}", other),
+        }
+    }
+    Ok(())
+}
+
+/// Sets the jq filter applied by [`write_json`] for the rest of the process.
+///
+/// This is called once at startup with the value of the global `--jq` flag.
+///
+/// # Errors
+///
+/// Returns an error if the expression is not a valid jq program, or if a
+/// filter has already been set.
+pub fn set_jq_filter(expression: &str) -> anyhow::Result<()> {
+    let filter = JqFilter::new(expression)?;
+    JQ_FILTER
+        .set(filter)
+        .map_err(|_| anyhow::anyhow!("jq filter has already been set"))
+}
+
+/// Returns the active `--jq` filter expression, if any.
+pub fn jq_filter() -> Option<&'static str> {
+    JQ_FILTER.get().map(JqFilter::expression)
+}
+
 /// Writes a value as pretty-printed JSON to a custom writer.
 ///
 /// This function allows writing JSON to any destination implementing
@@ -180,10 +259,27 @@ pub fn write_json_to<W: Write, T: Serialize>(writer: &mut W, value: &T) -> anyho
 /// - Minimizing data transfer size is important
 /// - Pretty-printing is not needed
 ///
+/// The `--json` field list and `--jq` filter are applied, as with [`write_json`].
+///
 /// # Errors
 ///
 /// Returns an error if the value cannot be serialized to JSON.
 pub fn write_json_compact<T: Serialize>(value: &T) -> anyhow::Result<()> {
+    if let Some(fields) = json_fields() {
+        let selected = select_fields(serde_json::to_value(value)?, fields);
+        return match JQ_FILTER.get() {
+            Some(filter) => write_jq_results(filter, &selected, false),
+            None => {
+                println!("{}", selected);
+                Ok(())
+            }
+        };
+    }
+
+    if let Some(filter) = JQ_FILTER.get() {
+        return write_jq_results(filter, value, false);
+    }
+
     let json = serde_json::to_string(value)?;
     println!("{}", json);
     Ok(())
@@ -245,73 +341,196 @@ pub fn write_json_lines<T: Serialize>(values: &[T]) -> anyhow::Result<()> {
     Ok(())
 }
 
-/// Applies a basic jq-style filter to a JSON string.
+/// Applies a jq filter to a JSON string.
 ///
-/// This is a simplified implementation supporting only basic operations:
-/// - `.` - Identity filter (returns input unchanged)
-/// - `.field` - Simple field extraction
+/// Filters are evaluated by an embedded jq engine, so no external `jq`
+/// binary is needed. Pipes, `map`, `select`, array slicing, string
+/// interpolation and the rest of the jq standard library are supported.
 ///
 /// # Parameters
 ///
 /// * `json` - A valid JSON string to filter
-/// * `filter` - A jq-style filter expression
+/// * `filter` - A jq filter expression
 ///
 /// # Returns
 ///
-/// Returns the filtered JSON as a pretty-printed string.
+/// Returns each result of the filter as pretty-printed JSON, one per line.
+/// The identity filter `.` returns the input unchanged.
 ///
 /// # Example
 ///
 /// ```rust,ignore
 /// use bitbucket_cli::output::json::apply_jq_filter;
 ///
-/// let json = r#"{"name": "my-repo", "owner": {"username": "admin"}}"#;
+/// let json = r#"[{"id": 1, "state": "OPEN"}, {"id": 2, "state": "MERGED"}]"#;
 ///
-/// // Identity filter
-/// let result = apply_jq_filter(json, ".")?;
-/// assert!(result.contains("my-repo"));
+/// let ids = apply_jq_filter(json, r#".[] | select(.state == "OPEN") | .id"#)?;
+/// assert_eq!(ids, "1");
 ///
-/// // Field extraction
-/// let name = apply_jq_filter(json, ".name")?;
-/// assert_eq!(name, "\"my-repo\"");
+/// let titles = apply_jq_filter(json, r##"map("#\(.id)") | join(", ")"##)?;
+/// assert_eq!(titles, "\"#1, #2\"");
 /// ```
 ///
-/// # Supported Filters
-///
-/// | Filter | Description | Example |
-/// |--------|-------------|---------|
-/// | `.` | Identity | Returns input unchanged |
-/// | `.field` | Field access | `.name` extracts `"name"` field |
-///
 /// # Errors
 ///
 /// Returns an error if:
 /// - The input is not valid JSON
-/// - The filter is not supported
-/// - The requested field does not exist
-///
-/// # Notes
-///
-/// For complex filtering, consider piping output to the actual `jq` command.
-/// This implementation is intentionally limited to avoid introducing a
-/// full jq parser dependency.
+/// - The filter cannot be parsed
+/// - Evaluating the filter fails (for example, indexing a string)
 pub fn apply_jq_filter(json: &str, filter: &str) -> anyhow::Result<String> {
-    // This is a placeholder - in a full implementation, you'd integrate
-    // with a jq library or subprocess
     if filter == "." {
         return Ok(json.to_string());
     }
 
-    // Basic field extraction for simple cases like ".field"
-    if filter.starts_with('.') && !filter.contains(' ') {
-        let field = &filter[1..];
-        let value: serde_json::Value = serde_json::from_str(json)?;
-        if let Some(result) = value.get(field) {
-            return Ok(serde_json::to_string_pretty(result)?);
-        }
+    let value: serde_json::Value = serde_json::from_str(json)?;
+    let results = JqFilter::new(filter)?.run(value)?;
+
+    let lines = results
+        .iter()
+        .map(serde_json::to_string_pretty)
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(lines.join("\n"))
+}
+
+/// A parsed jq filter expression.
+///
+/// The expression is checked for syntax errors and unknown functions when
+/// the filter is created, so a bad `--jq` argument is reported before any
+/// API requests are made.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use bitbucket_cli::output::JqFilter;
+///
+/// let filter = JqFilter::new(".[0:2] | map(.name)")?;
+/// let names = filter.run(serde_json::json!([{"name": "a"}, {"name": "b"}, {"name": "c"}]))?;
+/// assert_eq!(names, vec![serde_json::json!(["a", "b"])]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct JqFilter {
+    expression: String,
+}
+
+impl JqFilter {
+    /// Parses a jq filter expression.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the expression is not a valid jq program.
+    pub fn new(expression: &str) -> anyhow::Result<Self> {
+        let filter = Self {
+            expression: expression.to_string(),
+        };
+        filter.compile()?;
+        Ok(filter)
+    }
+
+    /// Returns the filter expression.
+    pub fn expression(&self) -> &str {
+        &self.expression
     }
 
-    anyhow::bail!("Complex jq filters not yet implemented. Filter: {}", filter)
+    /// Runs the filter against a value and collects its results.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if evaluating the filter fails.
+    pub fn run(&self, input: serde_json::Value) -> anyhow::Result<Vec<serde_json::Value>> {
+        use jaq_core::{Ctx, RcIter};
+        use jaq_json::Val;
+
+        let filter = self.compile()?;
+        let inputs = RcIter::new(core::iter::empty());
+
+        filter
+            .run((Ctx::new([], &inputs), Val::from(input)))
+            .map(|result| {
+                result
+                    .map(serde_json::Value::from)
+                    .map_err(|e| anyhow::anyhow!("jq error: {}", e))
+            })
+            .collect()
+    }
+
+    fn compile(&self) -> anyhow::Result<jaq_core::Filter<jaq_core::Native<jaq_json::Val>>> {
+        use jaq_core::load::{Arena, File, Loader};
+        use jaq_core::Compiler;
+
+        let arena = Arena::default();
+        let loader = Loader::new(jaq_std::defs().chain(jaq_json::defs()));
+        let program = File {
+            code: self.expression.as_str(),
+            path: (),
+        };
+
+        let modules = loader.load(&arena, program).map_err(|errors| {
+            let reason = errors
+                .into_iter()
+                .map(|(_, error)| describe_load_error(error))
+                .collect::<Vec<_>>()
+                .join("; ");
+            anyhow::anyhow!("Invalid jq filter '{}': {}", self.expression, reason)
+        })?;
+
+        Compiler::default()
+            .with_funs(jaq_std::funs().chain(jaq_json::funs()))
+            .compile(modules)
+            .map_err(|errors| {
+                let reason = errors
+                    .into_iter()
+                    .flat_map(|(_, undefined)| undefined)
+                    .map(|(name, kind)| describe_undefined(name, kind))
+                    .collect::<Vec<_>>()
+                    .join("; ");
+                anyhow::anyhow!("Invalid jq filter '{}': {}", self.expression, reason)
+            })
+    }
+}
+
+/// Describes an undefined symbol in a jq filter.
+fn describe_undefined(name: &str, kind: jaq_core::compile::Undefined) -> String {
+    use jaq_core::compile::Undefined;
+
+    match kind {
+        Undefined::Filter(arity) => format!("unknown function '{}/{}'", name, arity),
+        Undefined::Var => format!("undefined variable '{}'", name),
+        Undefined::Mod => format!("unknown module '{}'", name),
+        Undefined::Label => format!("undefined label '{}'", name),
+        _ => format!("undefined symbol '{}'", name),
+    }
+}
+
+/// Describes a jq parse error in a single line.
+fn describe_load_error(error: jaq_core::load::Error<&str>) -> String {
+    use jaq_core::load::Error;
+
+    let describe = |expected: &str, found: &str| {
+        if found.is_empty() {
+            format!("expected {} at end of input", expected)
+        } else {
+            let found: String = found.chars().take(20).collect();
+            format!("expected {} near '{}'", expected, found)
+        }
+    };
+
+    match error {
+        Error::Io(errors) => errors
+            .into_iter()
+            .map(|(path, e)| format!("{}: {}", path, e))
+            .collect::<Vec<_>>()
+            .join("; "),
+        Error::Lex(errors) => errors
+            .into_iter()
+            .map(|(expected, found)| describe(expected.as_str(), found))
+            .collect::<Vec<_>>()
+            .join("; "),
+        Error::Parse(errors) => errors
+            .into_iter()
+            .map(|(expected, found)| describe(expected.as_str(), found))
+            .collect::<Vec<_>>()
+            .join("; "),
+    }
 }
 
 #[cfg(test)]
@@ -331,4 +550,41 @@ mod tests {
         let result = apply_jq_filter(json, ".name").unwrap();
         assert_eq!(result, "\"test\"");
     }
+
+    #[test]
+    fn test_apply_jq_pipe_and_select() {
+        let json = r#"[{"id": 1, "state": "OPEN"}, {"id": 2, "state": "MERGED"}, {"id": 3, "state": "OPEN"}]"#;
+        let result = apply_jq_filter(json, r#".[] | select(.state=="OPEN") | .id"#).unwrap();
+        assert_eq!(result, "1\n3");
+    }
+
+    #[test]
+    fn test_jq_map_slice_and_interpolation() {
+        let input = serde_json::json!([
+            {"id": 1, "title": "First"},
+            {"id": 2, "title": "Second"},
+            {"id": 3, "title": "Third"}
+        ]);
+
+        let filter = JqFilter::new(r##".[1:] | map("#\(.id) \(.title)")"##).unwrap();
+        assert_eq!(
+            filter.run(input).unwrap(),
+            vec![serde_json::json!(["#2 Second", "#3 Third"])]
+        );
+    }
+
+    #[test]
+    fn test_jq_invalid_filter() {
+        let err = JqFilter::new(".[] | select(").unwrap_err();
+        assert!(err.to_string().contains("Invalid jq filter"));
+
+        let err = JqFilter::new("no_such_function").unwrap_err();
+        assert!(err.to_string().contains("no_such_function"));
+    }
+
+    #[test]
+    fn test_jq_runtime_error() {
+        let filter = JqFilter::new(".name").unwrap();
+        assert!(filter.run(serde_json::json!("just a string")).is_err());
+    }
 }
@@ -14,19 +14,25 @@
 //! - **Table format**: Human-readable tabular output for interactive terminal use
 //! - **JSON format**: Machine-readable JSON output for scripting and automation
 //! - **Markdown format**: Formatted markdown output for documentation and reports
+//! - **CSV, TSV, YAML and NDJSON**: Export formats for spreadsheets and pipelines
 //!
 //! ## Architecture
 //!
-//! The module is organized into three submodules:
+//! The module is organized into seven submodules:
 //! - [`table`]: Table formatting utilities using `comfy_table`
 //! - [`json`]: JSON serialization utilities using `serde_json`
+//! - [`export`]: CSV, TSV, YAML and NDJSON output for `--format`
+//! - [`fields`]: `--json FIELDS` field selection
+//! - [`template`]: `--template` Go-style output templates
 //! - [`markdown`]: Markdown formatting and rendering using `termimad`
+//! - [`theme`]: Colours, glyphs and `--color` handling shared by all output
 //!
 //! ## Core Components
 //!
 //! - [`OutputFormat`]: Enum representing the available output formats
 //! - [`OutputWriter`]: Main entry point for writing formatted output
 //! - [`TableOutput`]: Trait for types that can be rendered as tables or markdown
+//! - [`TableRow`]: Trait for list items rendered as rows of a table
 //!
 //! ## Example
 //!
@@ -44,13 +50,21 @@
 //! writer.write_error("Something went wrong");
 //! ```
 
+mod export;
+mod fields;
 mod json;
 mod markdown;
 mod table;
+mod template;
+mod theme;
 
+pub use export::*;
+pub use fields::*;
 pub use json::*;
 pub use markdown::*;
 pub use table::*;
+pub use template::*;
+pub use theme::*;
 
 use serde::Serialize;
 
@@ -99,6 +113,32 @@ pub enum OutputFormat {
     ///
     /// Rendered using `termimad` for terminal-friendly display.
     Markdown,
+    /// Comma-separated values, with nested fields flattened to dot-paths.
+    Csv,
+    /// Tab-separated values, with nested fields flattened to dot-paths.
+    Tsv,
+    /// YAML document.
+    Yaml,
+    /// Newline-delimited JSON, one list element per line.
+    Ndjson,
+}
+
+impl std::str::FromStr for OutputFormat {
+    type Err = anyhow::Error;
+
+    /// Parses a `--format` value.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "table" => Ok(Self::Table),
+            "json" => Ok(Self::Json),
+            "markdown" | "md" => Ok(Self::Markdown),
+            "csv" => Ok(Self::Csv),
+            "tsv" => Ok(Self::Tsv),
+            "yaml" | "yml" => Ok(Self::Yaml),
+            "ndjson" | "jsonl" => Ok(Self::Ndjson),
+            _ => anyhow::bail!("Unknown output format: {}", s),
+        }
+    }
 }
 
 impl Default for OutputFormat {
@@ -164,8 +204,8 @@ pub struct OutputWriter {
 impl OutputWriter {
     /// Creates a new output writer with the specified format.
     ///
-    /// The writer automatically detects whether color output is supported
-    /// based on terminal capabilities.
+    /// Whether color is used comes from the active [`Theme`], which honours
+    /// the global `--color` flag.
     ///
     /// # Parameters
     ///
@@ -186,7 +226,7 @@ impl OutputWriter {
     pub fn new(format: OutputFormat) -> Self {
         Self {
             format,
-            color: console::colors_enabled(),
+            color: theme().color_enabled(),
         }
     }
 
@@ -234,8 +274,8 @@ impl OutputWriter {
 
     /// Checks if color output is enabled.
     ///
-    /// Color support is automatically detected during construction based on
-    /// terminal capabilities and environment variables.
+    /// This is the setting of the active [`Theme`] when the writer was
+    /// constructed.
     ///
     /// # Returns
     ///
@@ -255,9 +295,9 @@ impl OutputWriter {
     /// # Notes
     ///
     /// Colors are typically disabled when:
-    /// - Output is piped to another program
+    /// - `--color=never` is given
+    /// - Output is piped to another program and `--color=always` is not given
     /// - The `NO_COLOR` environment variable is set
-    /// - The terminal does not support ANSI colors
     pub fn color_enabled(&self) -> bool {
         self.color
     }
@@ -283,7 +323,8 @@ impl OutputWriter {
     /// Writes a value to stdout using the configured output format.
     ///
     /// The value must implement both [`Serialize`] (for JSON output) and
-    /// [`TableOutput`] (for table and markdown output).
+    /// [`TableOutput`] (for table and markdown output). If a `--template`
+    /// is active, the serialized value is rendered with it instead.
     ///
     /// # Parameters
     ///
@@ -322,10 +363,13 @@ impl OutputWriter {
     ///
     /// Returns an error if JSON serialization fails (only applicable for JSON format).
     pub fn write<T: Serialize + TableOutput>(&self, value: &T) -> anyhow::Result<()> {
+        if let Some(template) = template() {
+            return template.write(value);
+        }
+
         match self.format {
             OutputFormat::Json => {
-                let json = serde_json::to_string_pretty(value)?;
-                println!("{}", json);
+                write_json(value)?;
             }
             OutputFormat::Table => {
                 value.print_table(self.color);
@@ -333,6 +377,7 @@ impl OutputWriter {
             OutputFormat::Markdown => {
                 value.print_markdown();
             }
+            format => write_formatted(value, format)?,
         }
         Ok(())
     }
@@ -341,6 +386,7 @@ impl OutputWriter {
     ///
     /// For JSON format, the entire list is serialized as a JSON array.
     /// For table and markdown formats, each value is rendered individually.
+    /// If a `--template` is active, it is rendered with the whole list as `.`.
     ///
     /// # Parameters
     ///
@@ -364,10 +410,13 @@ impl OutputWriter {
     ///
     /// Returns an error if JSON serialization fails (only applicable for JSON format).
     pub fn write_list<T: Serialize + TableOutput>(&self, values: &[T]) -> anyhow::Result<()> {
+        if let Some(template) = template() {
+            return template.write(values);
+        }
+
         match self.format {
             OutputFormat::Json => {
-                let json = serde_json::to_string_pretty(values)?;
-                println!("{}", json);
+                write_json(&values)?;
             }
             OutputFormat::Table => {
                 for value in values {
@@ -379,10 +428,49 @@ impl OutputWriter {
                     value.print_markdown();
                 }
             }
+            format => write_formatted(values, format)?,
         }
         Ok(())
     }
 
+    /// Writes a list of rows to stdout using the configured output format.
+    ///
+    /// This is the entry point for list commands. Table output goes through
+    /// [`print_rows`], so it honours `--columns`, `--sort` and `--reverse`
+    /// and falls back to tab-separated values when stdout is not a terminal.
+    /// Other formats behave as in [`write_list`](Self::write_list).
+    ///
+    /// # Parameters
+    ///
+    /// * `rows` - A slice of rows to write
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(())` on success, or an error if serialization fails.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use bitbucket_cli::output::OutputWriter;
+    ///
+    /// let writer = OutputWriter::table();
+    /// writer.write_table(&pull_requests)?;
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if serialization fails, or if `--columns` or `--sort`
+    /// names a column the rows do not have.
+    pub fn write_table<T: Serialize + TableOutput + TableRow>(
+        &self,
+        rows: &[T],
+    ) -> anyhow::Result<()> {
+        if self.format == OutputFormat::Table && template().is_none() {
+            return print_rows(rows, self.color);
+        }
+        self.write_list(rows)
+    }
+
     /// Writes an error message to stderr.
     ///
     /// The message is prefixed with "error:" and styled in red when
@@ -406,12 +494,7 @@ impl OutputWriter {
     ///
     /// Error messages are always written to stderr, regardless of output format.
     pub fn write_error(&self, msg: &str) {
-        use console::style;
-        if self.color {
-            eprintln!("{} {}", style("error:").red().bold(), msg);
-        } else {
-            eprintln!("error: {}", msg);
-        }
+        eprintln!("{} {}", theme().paint("error:", Color::DarkRed), msg);
     }
 
     /// Writes a warning message to stderr.
@@ -437,12 +520,7 @@ impl OutputWriter {
     ///
     /// Warning messages are written to stderr to separate them from normal output.
     pub fn write_warning(&self, msg: &str) {
-        use console::style;
-        if self.color {
-            eprintln!("{} {}", style("warning:").yellow().bold(), msg);
-        } else {
-            eprintln!("warning: {}", msg);
-        }
+        eprintln!("{} {}", theme().paint("warning:", Color::DarkYellow), msg);
     }
 
     /// Writes an informational message to stdout.
@@ -484,12 +562,7 @@ impl OutputWriter {
     /// // Output: ✓ Pull request created successfully
     /// ```
     pub fn write_success(&self, msg: &str) {
-        use console::style;
-        if self.color {
-            println!("{} {}", style("✓").green().bold(), msg);
-        } else {
-            println!("✓ {}", msg);
-        }
+        println!("{} {}", theme().success_mark(), msg);
     }
 }
 
@@ -583,9 +656,24 @@ pub trait TableOutput {
 ///
 /// The underline uses ASCII dashes for maximum terminal compatibility.
 pub fn print_header(text: &str) {
-    use console::style;
-    println!("{}", style(text).bold());
-    println!("{}", "-".repeat(text.len()));
+    println!("{}", format_header(text));
+}
+
+/// Formats a styled header with an underline.
+///
+/// This is the string form of [`print_header`], for output that is built
+/// up before being printed, e.g. to send it through a pager.
+///
+/// # Parameters
+///
+/// * `text` - The header text
+///
+/// # Returns
+///
+/// The bold header and its dashed underline, separated by a newline and
+/// without a trailing newline.
+pub fn format_header(text: &str) -> String {
+    format!("{}\n{}", theme().heading(text), "-".repeat(text.len()))
 }
 
 /// Prints a key-value pair with optional styling.
@@ -618,10 +706,26 @@ pub fn print_header(text: &str) {
 /// This function is commonly used in [`TableOutput::print_table`]
 /// implementations for rendering object fields.
 pub fn print_field(key: &str, value: &str, color: bool) {
-    use console::style;
+    println!("{}", format_field(key, value, color));
+}
+
+/// Formats a key-value pair with optional styling.
+///
+/// This is the string form of [`print_field`].
+///
+/// # Parameters
+///
+/// * `key` - The field name/label
+/// * `value` - The field value
+/// * `color` - Whether to apply color styling
+///
+/// # Returns
+///
+/// The `key: value` line without a trailing newline.
+pub fn format_field(key: &str, value: &str, color: bool) -> String {
     if color {
-        println!("{}: {}", style(key).dim(), value);
+        format!("{}: {}", theme().muted(key), value)
     } else {
-        println!("{}: {}", key, value);
+        format!("{}: {}", key, value)
     }
 }
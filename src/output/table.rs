@@ -32,17 +32,323 @@
 //!     .print();
 //! ```
 //!
+//! ## List Output
+//!
+//! List commands render their rows through [`TableRow`] and [`print_rows`],
+//! which apply the global `--columns`, `--sort` and `--reverse` options:
+//!
+//! ```text
+//! bb pr list --columns id,title,author --sort updated_on --reverse
+//! ```
+//!
+//! When stdout is not a terminal, rows are written as plain tab-separated
+//! values without a header, so they can be piped to `cut`, `awk` or `sort`.
+//!
 //! ## Notes
 //!
 //! Tables are rendered using UTF-8 box-drawing characters for a clean,
 //! modern appearance. Content is dynamically arranged to fit the terminal width.
 
-use comfy_table::{presets::UTF8_FULL, Cell, Color, ContentArrangement, Table};
+use std::cmp::Ordering;
+
+use comfy_table::{
+    presets::{ASCII_FULL, NOTHING, UTF8_FULL},
+    Cell, ContentArrangement, Row, Table,
+};
+use console::Term;
+use once_cell::sync::OnceCell;
+use serde::Serialize;
+use serde_json::Value;
+
+use super::theme;
+
+/// Cell colors for [`TableRow::cell_color`].
+pub use comfy_table::Color;
+
+/// The `--columns`, `--sort` and `--reverse` options for list output.
+static TABLE_OPTIONS: OnceCell<TableOptions> = OnceCell::new();
+
+/// Column selection and ordering for list output.
+///
+/// These come from the global `--columns`, `--sort` and `--reverse` flags
+/// and apply to every command that renders rows with [`print_rows`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TableOptions {
+    /// Columns to show, in order. Empty means the row type's defaults.
+    pub columns: Vec<String>,
+    /// Column to sort rows by.
+    pub sort: Option<String>,
+    /// Whether to reverse the row order, after sorting.
+    pub reverse: bool,
+}
+
+impl TableOptions {
+    /// Creates table options from the raw flag values.
+    ///
+    /// # Parameters
+    ///
+    /// * `columns` - Comma-separated column names from `--columns`
+    /// * `sort` - Column name from `--sort`
+    /// * `reverse` - Whether `--reverse` was given
+    pub fn new(columns: Option<&str>, sort: Option<&str>, reverse: bool) -> Self {
+        let columns = columns
+            .map(|spec| {
+                spec.split(',')
+                    .map(|c| c.trim().to_lowercase())
+                    .filter(|c| !c.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Self {
+            columns,
+            sort: sort.map(|s| s.trim().to_lowercase()),
+            reverse,
+        }
+    }
+}
+
+/// Sets the list output options for the rest of the process.
+///
+/// This is called once at startup with the values of the global
+/// `--columns`, `--sort` and `--reverse` flags.
+///
+/// # Errors
+///
+/// Returns an error if the options have already been set.
+pub fn set_table_options(options: TableOptions) -> anyhow::Result<()> {
+    TABLE_OPTIONS
+        .set(options)
+        .map_err(|_| anyhow::anyhow!("table options have already been set"))
+}
+
+/// Returns the list output options, or the defaults if none were set.
+pub fn table_options() -> TableOptions {
+    TABLE_OPTIONS.get().cloned().unwrap_or_default()
+}
+
+/// Types that are rendered as one row of a list table.
+///
+/// Column names are lowercase and double as the values accepted by
+/// `--columns` and `--sort`. Headers are derived from them, so
+/// `updated_on` is shown as `UPDATED ON`.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use bitbucket_cli::output::TableRow;
+///
+/// impl TableRow for RepoListItem {
+///     const COLUMNS: &'static [&'static str] = &["name", "private", "updated_on"];
+///     const DEFAULT_COLUMNS: &'static [&'static str] = &["name", "private"];
+///
+///     fn cell(&self, column: &str) -> String {
+///         match column {
+///             "name" => self.name.clone(),
+///             "private" => format_bool(self.private, false),
+///             "updated_on" => self.updated_on.clone(),
+///             _ => String::new(),
+///         }
+///     }
+/// }
+/// ```
+pub trait TableRow {
+    /// Every column this row type can show.
+    const COLUMNS: &'static [&'static str];
+
+    /// Columns shown when `--columns` is not given.
+    const DEFAULT_COLUMNS: &'static [&'static str];
+
+    /// Returns the plain text of a cell.
+    fn cell(&self, column: &str) -> String;
+
+    /// Returns the color of a cell, if it has one.
+    ///
+    /// Colors are applied by the table rather than embedded in the text,
+    /// so that column widths stay correct.
+    fn cell_color(&self, _column: &str) -> Option<Color> {
+        None
+    }
+}
+
+/// Prints a list of rows using the global table options.
+///
+/// On a terminal the rows are rendered as a borderless table whose columns
+/// are sized to the terminal width; long cells are truncated with `…`.
+/// Otherwise each row is written as a line of tab-separated values.
+///
+/// Rows are sorted by the serialized value of the `--sort` column when the
+/// row has a field of that name, so timestamps and numbers sort naturally,
+/// and by the cell text otherwise. Missing values sort last.
+///
+/// # Parameters
+///
+/// * `rows` - The rows to print
+/// * `color` - Whether to apply cell colors
+///
+/// # Errors
+///
+/// Returns an error if `--columns` or `--sort` names an unknown column.
+pub fn print_rows<T: TableRow + Serialize>(rows: &[T], color: bool) -> anyhow::Result<()> {
+    print!("{}", render_rows(rows, color)?);
+    Ok(())
+}
+
+/// Renders a list of rows as [`print_rows`] prints them.
+///
+/// Commands that redraw a list in place use this to know how many lines
+/// they printed. Every line, including the last, ends with a newline.
+///
+/// # Errors
+///
+/// Returns an error if `--columns` or `--sort` names an unknown column.
+pub fn render_rows<T: TableRow + Serialize>(rows: &[T], color: bool) -> anyhow::Result<String> {
+    let options = table_options();
+    let columns = resolve_columns::<T>(&options)?;
+    let rows = sort_rows(rows, &options)?;
+
+    let mut out = String::new();
+    if stdout_is_tty() {
+        out.push_str(&build_row_table(&rows, &columns, color).to_string());
+        out.push('\n');
+    } else {
+        for row in rows {
+            let line: Vec<String> = columns
+                .iter()
+                .map(|c| row.cell(c).replace(['\t', '\n'], " "))
+                .collect();
+            out.push_str(&line.join("\t"));
+            out.push('\n');
+        }
+    }
+
+    Ok(out)
+}
+
+/// Prints a single row's default columns on one line.
+///
+/// This backs [`TableOutput::print_table`](super::TableOutput::print_table)
+/// for row types; lists should use [`print_rows`] so that columns line up.
+pub fn print_row<T: TableRow>(row: &T) {
+    let cells: Vec<String> = T::DEFAULT_COLUMNS.iter().map(|c| row.cell(c)).collect();
+    println!("{}", cells.join("  "));
+}
+
+/// Returns whether stdout is a terminal.
+///
+/// List commands use this to skip titles and footers when their rows are
+/// written as tab-separated values for another program.
+pub fn stdout_is_tty() -> bool {
+    Term::stdout().is_term()
+}
+
+/// Returns the columns to show, validating `--columns` against `T::COLUMNS`.
+fn resolve_columns<T: TableRow>(options: &TableOptions) -> anyhow::Result<Vec<&'static str>> {
+    if options.columns.is_empty() {
+        return Ok(T::DEFAULT_COLUMNS.to_vec());
+    }
+
+    options
+        .columns
+        .iter()
+        .map(|name| find_column::<T>(name))
+        .collect()
+}
+
+fn find_column<T: TableRow>(name: &str) -> anyhow::Result<&'static str> {
+    T::COLUMNS
+        .iter()
+        .copied()
+        .find(|c| *c == name)
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "Unknown column: \"{}\"\nAvailable columns: {}",
+                name,
+                T::COLUMNS.join(", ")
+            )
+        })
+}
+
+/// Orders rows by `--sort` and `--reverse`.
+fn sort_rows<'a, T: TableRow + Serialize>(
+    rows: &'a [T],
+    options: &TableOptions,
+) -> anyhow::Result<Vec<&'a T>> {
+    let mut sorted: Vec<&T> = rows.iter().collect();
+
+    if let Some(ref sort) = options.sort {
+        let column = find_column::<T>(sort)?;
+        let mut keyed: Vec<(Value, &T)> = sorted
+            .into_iter()
+            .map(|row| (sort_key(row, column), row))
+            .collect();
+        keyed.sort_by(|(a, _), (b, _)| compare_values(a, b));
+        sorted = keyed.into_iter().map(|(_, row)| row).collect();
+    }
+
+    if options.reverse {
+        sorted.reverse();
+    }
+
+    Ok(sorted)
+}
+
+/// Returns the value a row is sorted by for a column.
+///
+/// This is the row's serialized field of the same name if there is one,
+/// otherwise the cell text, read as a number when it looks like one (`#42`).
+fn sort_key<T: TableRow + Serialize>(row: &T, column: &str) -> Value {
+    if let Some(value) = serde_json::to_value(row)
+        .ok()
+        .and_then(|value| value.get(column).cloned())
+    {
+        return value;
+    }
+
+    let text = row.cell(column);
+    text.trim_start_matches('#')
+        .parse::<f64>()
+        .ok()
+        .and_then(serde_json::Number::from_f64)
+        .map(Value::Number)
+        .unwrap_or(Value::String(text))
+}
+
+fn compare_values(a: &Value, b: &Value) -> Ordering {
+    match (a, b) {
+        (Value::Number(a), Value::Number(b)) => a
+            .as_f64()
+            .partial_cmp(&b.as_f64())
+            .unwrap_or(Ordering::Equal),
+        (Value::String(a), Value::String(b)) => a.to_lowercase().cmp(&b.to_lowercase()),
+        (Value::Bool(a), Value::Bool(b)) => a.cmp(b),
+        // Missing values sort last
+        (Value::Null, Value::Null) => Ordering::Equal,
+        (Value::Null, _) => Ordering::Greater,
+        (_, Value::Null) => Ordering::Less,
+        (a, b) => a.to_string().cmp(&b.to_string()),
+    }
+}
+
+/// Builds the terminal table for [`print_rows`].
+fn build_row_table<T: TableRow>(rows: &[&T], columns: &[&str], color: bool) -> Table {
+    let mut builder = TableBuilder::new()
+        .color(color)
+        .borderless()
+        .headers(columns.iter().map(|c| c.replace('_', " ").to_uppercase()));
+
+    for row in rows {
+        builder = builder.styled_row(columns.iter().map(|c| (row.cell(c), row.cell_color(c))));
+    }
+
+    builder.build()
+}
 
 /// Creates a new styled table with default settings.
 ///
 /// The table is configured with:
-/// - UTF-8 full border preset for clean visual appearance
+/// - UTF-8 full border preset for clean visual appearance, or ASCII
+///   borders with the plain theme
 /// - Dynamic content arrangement to fit terminal width
 ///
 /// # Returns
@@ -67,7 +373,11 @@ use comfy_table::{presets::UTF8_FULL, Cell, Color, ContentArrangement, Table};
 pub fn create_table() -> Table {
     let mut table = Table::new();
     table
-        .load_preset(UTF8_FULL)
+        .load_preset(if theme().is_plain() {
+            ASCII_FULL
+        } else {
+            UTF8_FULL
+        })
         .set_content_arrangement(ContentArrangement::Dynamic);
     table
 }
@@ -115,6 +425,7 @@ pub struct TableBuilder {
     table: Table,
     headers: Vec<String>,
     color: bool,
+    borderless: bool,
 }
 
 impl TableBuilder {
@@ -140,14 +451,15 @@ impl TableBuilder {
         Self {
             table: create_table(),
             headers: Vec::new(),
-            color: console::colors_enabled(),
+            color: theme().color_enabled(),
+            borderless: false,
         }
     }
 
     /// Sets whether color output is enabled.
     ///
-    /// By default, color support is auto-detected based on terminal
-    /// capabilities. Use this method to override the detection.
+    /// By default, the setting of the active theme is used. Use this
+    /// method to override it.
     ///
     /// # Parameters
     ///
@@ -173,6 +485,35 @@ impl TableBuilder {
         self
     }
 
+    /// Switches to a borderless layout for list output.
+    ///
+    /// Columns are separated by two spaces, and each row is kept to a
+    /// single line: cells that do not fit the terminal are truncated with
+    /// `…` (`...` with the plain theme) rather than wrapped.
+    ///
+    /// # Returns
+    ///
+    /// The builder instance for method chaining.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use bitbucket_cli::output::table::TableBuilder;
+    ///
+    /// TableBuilder::new()
+    ///     .borderless()
+    ///     .headers(["ID", "TITLE"])
+    ///     .row(["#1", "Fix the build"])
+    ///     .print();
+    /// ```
+    pub fn borderless(mut self) -> Self {
+        self.borderless = true;
+        self.table
+            .load_preset(NOTHING)
+            .set_truncation_indicator(theme().ellipsis());
+        self
+    }
+
     /// Sets the table headers.
     ///
     /// Headers are displayed in cyan when color is enabled.
@@ -262,6 +603,45 @@ impl TableBuilder {
         self
     }
 
+    /// Adds a single row whose cells may each have a color.
+    ///
+    /// Colors are only applied when color output is enabled. Cell text
+    /// must be plain; embedded ANSI codes would throw off column widths.
+    ///
+    /// # Parameters
+    ///
+    /// * `cells` - An iterator of `(text, color)` pairs
+    ///
+    /// # Returns
+    ///
+    /// The builder instance for method chaining.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use bitbucket_cli::output::table::{Color, TableBuilder};
+    ///
+    /// TableBuilder::new()
+    ///     .headers(["ID", "STATE"])
+    ///     .styled_row([("#1".to_string(), None), ("OPEN".to_string(), Some(Color::Green))])
+    ///     .print();
+    /// ```
+    pub fn styled_row<I>(mut self, cells: I) -> Self
+    where
+        I: IntoIterator<Item = (String, Option<Color>)>,
+    {
+        let color = self.color;
+        let mut row = Row::from(cells.into_iter().map(|(text, fg)| match fg {
+            Some(fg) if color => Cell::new(text).fg(fg),
+            _ => Cell::new(text),
+        }));
+        if self.borderless {
+            row.max_height(1);
+        }
+        self.table.add_row(row);
+        self
+    }
+
     /// Adds multiple rows to the table at once.
     ///
     /// This is a convenience method for adding several rows in one call.
@@ -318,7 +698,7 @@ impl TableBuilder {
     ///     .print();
     /// ```
     pub fn print(self) {
-        println!("{}", self.table);
+        println!("{}", self.build());
     }
 
     /// Builds and returns the underlying table.
@@ -343,7 +723,14 @@ impl TableBuilder {
     /// // Custom handling of the table
     /// let output = format!("{}", table);
     /// ```
-    pub fn build(self) -> Table {
+    pub fn build(mut self) -> Table {
+        if self.borderless {
+            // Without borders, a two-space gap between columns is all the spacing needed
+            let last = self.table.column_count().saturating_sub(1);
+            for (i, column) in self.table.column_iter_mut().enumerate() {
+                column.set_padding((0, if i == last { 0 } else { 2 }));
+            }
+        }
         self.table
     }
 }
@@ -363,11 +750,9 @@ impl Default for TableBuilder {
 
 /// Formats a status string with semantic colors.
 ///
-/// Different status values are colored based on their semantic meaning:
-/// - **Green**: open, active, running, in_progress
-/// - **Blue**: merged, completed, passed, successful
-/// - **Red**: declined, closed, failed, error
-/// - **Yellow**: draft, pending, waiting
+/// Status values are colored with the state colors of the active
+/// [`Theme`](super::Theme), which users can override in the `[colors]`
+/// section of the configuration file.
 ///
 /// # Parameters
 ///
@@ -401,14 +786,7 @@ pub fn format_status(status: &str, color: bool) -> String {
         return status.to_string();
     }
 
-    use console::style;
-    match status.to_lowercase().as_str() {
-        "open" | "active" | "running" | "in_progress" => style(status).green().to_string(),
-        "merged" | "completed" | "passed" | "successful" => style(status).blue().to_string(),
-        "declined" | "closed" | "failed" | "error" => style(status).red().to_string(),
-        "draft" | "pending" | "waiting" => style(status).yellow().to_string(),
-        _ => status.to_string(),
-    }
+    theme().state(status)
 }
 
 /// Formats a boolean value as a human-readable Yes/No string.
@@ -443,11 +821,10 @@ pub fn format_status(status: &str, color: bool) -> String {
 /// compared to "true"/"false".
 pub fn format_bool(value: bool, color: bool) -> String {
     if color {
-        use console::style;
         if value {
-            style("Yes").green().to_string()
+            theme().paint("Yes", Color::DarkGreen)
         } else {
-            style("No").dim().to_string()
+            theme().muted("No")
         }
     } else if value {
         "Yes".to_string()
@@ -496,3 +873,100 @@ pub fn truncate(s: &str, max_len: usize) -> String {
         s[..max_len].to_string()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Serialize)]
+    struct Item {
+        id: u64,
+        name: String,
+        updated_on: Option<String>,
+    }
+
+    impl TableRow for Item {
+        const COLUMNS: &'static [&'static str] = &["id", "name", "label", "updated_on"];
+        const DEFAULT_COLUMNS: &'static [&'static str] = &["id", "name"];
+
+        fn cell(&self, column: &str) -> String {
+            match column {
+                "id" => format!("#{}", self.id),
+                "name" => self.name.clone(),
+                "label" => format!("#{}", self.id * 10),
+                "updated_on" => self.updated_on.clone().unwrap_or_default(),
+                _ => String::new(),
+            }
+        }
+    }
+
+    fn items() -> Vec<Item> {
+        vec![
+            Item {
+                id: 10,
+                name: "beta".into(),
+                updated_on: Some("2026-01-02".into()),
+            },
+            Item {
+                id: 9,
+                name: "Alpha".into(),
+                updated_on: None,
+            },
+            Item {
+                id: 100,
+                name: "gamma".into(),
+                updated_on: Some("2026-01-01".into()),
+            },
+        ]
+    }
+
+    fn ids(rows: &[&Item]) -> Vec<u64> {
+        rows.iter().map(|r| r.id).collect()
+    }
+
+    #[test]
+    fn test_resolve_columns() {
+        let defaults = TableOptions::default();
+        assert_eq!(resolve_columns::<Item>(&defaults).unwrap(), ["id", "name"]);
+
+        let options = TableOptions::new(Some("Name, id"), None, false);
+        assert_eq!(resolve_columns::<Item>(&options).unwrap(), ["name", "id"]);
+
+        let unknown = TableOptions::new(Some("id,title"), None, false);
+        let err = resolve_columns::<Item>(&unknown).unwrap_err().to_string();
+        assert!(err.contains("Unknown column: \"title\""));
+        assert!(err.contains("id, name, label, updated_on"));
+    }
+
+    #[test]
+    fn test_sort_by_field_value() {
+        let items = items();
+
+        let by_id = TableOptions::new(None, Some("id"), false);
+        assert_eq!(ids(&sort_rows(&items, &by_id).unwrap()), [9, 10, 100]);
+
+        let by_name = TableOptions::new(None, Some("name"), true);
+        assert_eq!(ids(&sort_rows(&items, &by_name).unwrap()), [100, 10, 9]);
+    }
+
+    #[test]
+    fn test_sort_puts_missing_values_last() {
+        let items = items();
+        let options = TableOptions::new(None, Some("updated_on"), false);
+        assert_eq!(ids(&sort_rows(&items, &options).unwrap()), [100, 10, 9]);
+    }
+
+    #[test]
+    fn test_sort_by_numeric_cell_text() {
+        let items = items();
+        let options = TableOptions::new(None, Some("label"), false);
+        assert_eq!(ids(&sort_rows(&items, &options).unwrap()), [9, 10, 100]);
+    }
+
+    #[test]
+    fn test_reverse_without_sort() {
+        let items = items();
+        let options = TableOptions::new(None, None, true);
+        assert_eq!(ids(&sort_rows(&items, &options).unwrap()), [100, 9, 10]);
+    }
+}
@@ -0,0 +1,1258 @@
+//
+//  bitbucket-cli
+//  output/template.rs
+//
+//  Created by Ngonidzashe Mangudya on 2026/01/12.
+//  Copyright (c) 2025 IAMNGONI. All rights reserved.
+//
+
+//! # Template Output
+//!
+//! This module implements `--template`, which formats JSON output with a
+//! Go `text/template`-style template, in the style of `gh`.
+//!
+//! ## Syntax
+//!
+//! | Action | Description |
+//! |--------|-------------|
+//! | `{{.field.sub}}` | Field of the current value |
+//! | `{{$.field}}` | Field of the root value |
+//! | `{{range .}}...{{else}}...{{end}}` | Loop over an array (`$i, $v :=` optional) |
+//! | `{{if cond}}...{{else if cond}}...{{else}}...{{end}}` | Conditional |
+//! | `{{with .field}}...{{end}}` | Run with `.` set to a value, if it is non-empty |
+//! | `{{$x := pipeline}}` | Variable assignment |
+//! | `{{fn arg1 arg2 \| fn2}}` | Function call and pipes |
+//! | `{{fn (fn2 arg)}}` | Parenthesized call as an argument |
+//! | `{{- ` / ` -}}` | Trim surrounding whitespace |
+//! | `{{/* comment */}}` | Comment |
+//!
+//! ## Functions
+//!
+//! | Function | Description |
+//! |----------|-------------|
+//! | `color <style> <text>` | Colour text, e.g. `color "green+b" .state` |
+//! | `timeago <time>` | Relative time, e.g. "3 days ago" |
+//! | `truncate <length> <text>` | Shorten text, adding "..." |
+//! | `join <sep> <list>` | Join a list into a string |
+//! | `pluck <field> <list>` | Collect a field from each element of a list |
+//! | `tablerow <fields>...` | Add an aligned table row |
+//! | `tablerender` | Print the rows added so far |
+//! | `printf`, `len`, `index`, `eq`, `ne`, `lt`, `le`, `gt`, `ge`, `and`, `or`, `not` | As in Go |
+//!
+//! Only this subset is supported: literals are double-quoted strings,
+//! integers, `true`, `false` and `nil`, and `range` loops over arrays. A Go
+//! template crate such as `gtmpl` was not used because its functions cannot
+//! reach render state, which `tablerow` and `tablerender` need, and its
+//! values would have to be converted from and to `serde_json` on every call.
+//!
+//! ## Example
+//!
+//! ```rust,ignore
+//! use bitbucket_cli::output::Template;
+//! use serde_json::json;
+//!
+//! let template = Template::parse(r#"{{range .}}{{.id}}: {{.title}}{{"\n"}}{{end}}"#)?;
+//! let output = template.render(&json!([{"id": 1, "title": "Fix"}]), false)?;
+//! assert_eq!(output, "1: Fix\n");
+//! ```
+
+use anyhow::{bail, Result};
+use once_cell::sync::OnceCell;
+use serde::Serialize;
+use serde_json::Value;
+
+use super::theme;
+use crate::util::format_relative_time;
+
+/// The `--template` used to format JSON output.
+static TEMPLATE: OnceCell<Template> = OnceCell::new();
+
+/// Sets the template used to format output for the rest of the process.
+///
+/// This is called once at startup with the value of the global
+/// `--template` flag.
+///
+/// # Errors
+///
+/// Returns an error if the template cannot be parsed, or if a template
+/// has already been set.
+pub fn set_template(source: &str) -> Result<()> {
+    let template = Template::parse(source)?;
+    TEMPLATE
+        .set(template)
+        .map_err(|_| anyhow::anyhow!("template has already been set"))
+}
+
+/// Returns the active `--template`, if any.
+pub fn template() -> Option<&'static Template> {
+    TEMPLATE.get()
+}
+
+/// A parsed output template.
+#[derive(Debug, Clone)]
+pub struct Template {
+    nodes: Vec<Node>,
+}
+
+impl Template {
+    /// Parses a template.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the template has a syntax error, such as an
+    /// unclosed action or a `range` without `end`.
+    pub fn parse(source: &str) -> Result<Self> {
+        let items = lex(source)?;
+        let mut parser = Parser { items, pos: 0 };
+        let (nodes, end) = parser.parse_list()?;
+        match end {
+            Terminator::Eof => Ok(Self { nodes }),
+            Terminator::End => bail!("template: unexpected {{{{end}}}}"),
+            Terminator::Else(_) => bail!("template: unexpected {{{{else}}}}"),
+        }
+    }
+
+    /// Renders the template against a value.
+    ///
+    /// # Parameters
+    ///
+    /// * `data` - The value bound to `.` and `$`
+    /// * `color` - Whether the `color` function emits ANSI styles
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a function is called with bad arguments.
+    pub fn render(&self, data: &Value, color: bool) -> Result<String> {
+        let mut renderer = Renderer {
+            color,
+            out: String::new(),
+            rows: Vec::new(),
+            vars: vec![("$".to_string(), data.clone())],
+        };
+        renderer.exec_list(&self.nodes, data)?;
+        renderer.flush_table();
+        Ok(renderer.out)
+    }
+
+    /// Serializes a value and prints the rendered template to stdout.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if serialization or rendering fails.
+    pub fn write<T: Serialize + ?Sized>(&self, value: &T) -> Result<()> {
+        let data = serde_json::to_value(value)?;
+        print!("{}", self.render(&data, theme().color_enabled())?);
+        Ok(())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Lexing
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone)]
+enum Item {
+    Text(String),
+    Action(Vec<Token>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Dot,
+    Field(Vec<String>),
+    Var(String, Vec<String>),
+    Ident(String),
+    Str(String),
+    Number(Value),
+    LParen,
+    RParen,
+    Pipe,
+    Comma,
+    Declare,
+}
+
+/// Splits a template into text and action items.
+fn lex(source: &str) -> Result<Vec<Item>> {
+    let mut items = Vec::new();
+    let mut rest = source;
+    let mut trim_next = false;
+
+    while let Some(start) = rest.find("{{") {
+        let mut text = &rest[..start];
+        if trim_next {
+            text = text.trim_start();
+        }
+
+        let mut inner = &rest[start + 2..];
+        if inner.starts_with("- ") || inner.starts_with("-\t") || inner.starts_with("-\n") {
+            text = text.trim_end();
+            inner = &inner[1..];
+        }
+        if !text.is_empty() {
+            items.push(Item::Text(text.to_string()));
+        }
+
+        let end = find_action_end(inner)?;
+        let mut body = &inner[..end];
+        rest = &inner[end + 2..];
+
+        trim_next = body.ends_with(" -") || body.ends_with("\t-") || body.ends_with("\n-");
+        if trim_next {
+            body = &body[..body.len() - 1];
+        }
+
+        let body = body.trim();
+        if body.starts_with("/*") && body.ends_with("*/") {
+            continue;
+        }
+        items.push(Item::Action(tokenize(body)?));
+    }
+
+    let text = if trim_next { rest.trim_start() } else { rest };
+    if !text.is_empty() {
+        items.push(Item::Text(text.to_string()));
+    }
+
+    Ok(items)
+}
+
+/// Finds the closing `}}` of an action, skipping over string literals.
+fn find_action_end(s: &str) -> Result<usize> {
+    let bytes = s.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'"' => {
+                i += 1;
+                while i < bytes.len() && bytes[i] != b'"' {
+                    if bytes[i] == b'\\' {
+                        i += 1;
+                    }
+                    i += 1;
+                }
+            }
+            b'}' if bytes.get(i + 1) == Some(&b'}') => return Ok(i),
+            _ => {}
+        }
+        i += 1;
+    }
+    bail!("template: unclosed action")
+}
+
+fn is_ident_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Reads a `.a.b.c` field chain starting at a `.`.
+fn read_fields(chars: &[char], pos: &mut usize) -> Vec<String> {
+    let mut fields = Vec::new();
+    while *pos < chars.len() && chars[*pos] == '.' {
+        let start = *pos + 1;
+        let mut end = start;
+        while end < chars.len() && is_ident_char(chars[end]) {
+            end += 1;
+        }
+        if end == start {
+            break;
+        }
+        fields.push(chars[start..end].iter().collect());
+        *pos = end;
+    }
+    fields
+}
+
+/// Splits the inside of an action into tokens.
+fn tokenize(body: &str) -> Result<Vec<Token>> {
+    let chars: Vec<char> = body.chars().collect();
+    let mut tokens = Vec::new();
+    let mut pos = 0;
+
+    while pos < chars.len() {
+        let c = chars[pos];
+        match c {
+            c if c.is_whitespace() => pos += 1,
+            '(' => {
+                tokens.push(Token::LParen);
+                pos += 1;
+            }
+            ')' => {
+                tokens.push(Token::RParen);
+                pos += 1;
+            }
+            '|' => {
+                tokens.push(Token::Pipe);
+                pos += 1;
+            }
+            ',' => {
+                tokens.push(Token::Comma);
+                pos += 1;
+            }
+            ':' if chars.get(pos + 1) == Some(&'=') => {
+                tokens.push(Token::Declare);
+                pos += 2;
+            }
+            '"' => {
+                let mut s = String::new();
+                pos += 1;
+                loop {
+                    match chars.get(pos) {
+                        None => bail!("template: unterminated string"),
+                        Some('"') => break,
+                        Some('\\') => {
+                            pos += 1;
+                            match chars.get(pos) {
+                                Some('n') => s.push('\n'),
+                                Some('t') => s.push('\t'),
+                                Some('r') => s.push('\r'),
+                                Some(&other) => s.push(other),
+                                None => bail!("template: unterminated string"),
+                            }
+                        }
+                        Some(&other) => s.push(other),
+                    }
+                    pos += 1;
+                }
+                pos += 1;
+                tokens.push(Token::Str(s));
+            }
+            '.' => {
+                let fields = read_fields(&chars, &mut pos);
+                if fields.is_empty() {
+                    pos += 1;
+                    tokens.push(Token::Dot);
+                } else {
+                    tokens.push(Token::Field(fields));
+                }
+            }
+            '$' => {
+                let start = pos;
+                pos += 1;
+                while pos < chars.len() && is_ident_char(chars[pos]) {
+                    pos += 1;
+                }
+                let name: String = chars[start..pos].iter().collect();
+                let fields = read_fields(&chars, &mut pos);
+                tokens.push(Token::Var(name, fields));
+            }
+            c if c.is_ascii_digit()
+                || (c == '-' && chars.get(pos + 1).is_some_and(|d| d.is_ascii_digit())) =>
+            {
+                let start = pos;
+                pos += 1;
+                while pos < chars.len() && chars[pos].is_ascii_digit() {
+                    pos += 1;
+                }
+                let literal: String = chars[start..pos].iter().collect();
+                let Ok(number) = literal.parse::<i64>() else {
+                    bail!("template: bad number '{}'", literal);
+                };
+                tokens.push(Token::Number(Value::from(number)));
+            }
+            c if is_ident_char(c) => {
+                let start = pos;
+                while pos < chars.len() && is_ident_char(chars[pos]) {
+                    pos += 1;
+                }
+                tokens.push(Token::Ident(chars[start..pos].iter().collect()));
+            }
+            other => bail!("template: unexpected '{}' in action", other),
+        }
+    }
+
+    Ok(tokens)
+}
+
+// ---------------------------------------------------------------------------
+// Parsing
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone)]
+enum Node {
+    Text(String),
+    Action(Pipeline),
+    If {
+        cond: Pipeline,
+        then: Vec<Node>,
+        otherwise: Vec<Node>,
+    },
+    Range {
+        pipe: Pipeline,
+        body: Vec<Node>,
+        otherwise: Vec<Node>,
+    },
+    With {
+        pipe: Pipeline,
+        body: Vec<Node>,
+        otherwise: Vec<Node>,
+    },
+}
+
+#[derive(Debug, Clone)]
+struct Pipeline {
+    decl: Vec<String>,
+    commands: Vec<Vec<Arg>>,
+}
+
+#[derive(Debug, Clone)]
+enum Arg {
+    Dot,
+    Field(Vec<String>),
+    Var(String, Vec<String>),
+    Func(String),
+    Literal(Value),
+    Sub(Pipeline),
+}
+
+enum Terminator {
+    Eof,
+    End,
+    /// `{{else}}`, or `{{else if ...}}` with the rest of the action
+    Else(Vec<Token>),
+}
+
+struct Parser {
+    items: Vec<Item>,
+    pos: usize,
+}
+
+impl Parser {
+    /// Parses nodes until `{{end}}`, `{{else}}` or the end of the template.
+    fn parse_list(&mut self) -> Result<(Vec<Node>, Terminator)> {
+        let mut nodes = Vec::new();
+
+        while self.pos < self.items.len() {
+            let item = self.items[self.pos].clone();
+            self.pos += 1;
+
+            match item {
+                Item::Text(text) => nodes.push(Node::Text(text)),
+                Item::Action(tokens) => match tokens.first() {
+                    Some(Token::Ident(kw)) if kw == "end" => return Ok((nodes, Terminator::End)),
+                    Some(Token::Ident(kw)) if kw == "else" => {
+                        return Ok((nodes, Terminator::Else(tokens[1..].to_vec())))
+                    }
+                    Some(Token::Ident(kw)) if kw == "if" || kw == "range" || kw == "with" => {
+                        let kw = kw.clone();
+                        nodes.push(self.parse_block(&kw, &tokens[1..])?);
+                    }
+                    None => bail!("template: empty action"),
+                    _ => nodes.push(Node::Action(parse_pipeline(&tokens)?)),
+                },
+            }
+        }
+
+        Ok((nodes, Terminator::Eof))
+    }
+
+    /// Parses the body of an `if`, `range` or `with` block.
+    fn parse_block(&mut self, keyword: &str, tokens: &[Token]) -> Result<Node> {
+        let pipe = parse_pipeline(tokens)?;
+        let (body, end) = self.parse_list()?;
+
+        let otherwise = match end {
+            Terminator::End => Vec::new(),
+            Terminator::Eof => bail!("template: missing {{{{end}}}} for {{{{{}}}}}", keyword),
+            Terminator::Else(rest) => match rest.first() {
+                // `else if` chains share the outer `end`
+                Some(Token::Ident(kw)) if kw == "if" => vec![self.parse_block("if", &rest[1..])?],
+                None => {
+                    let (otherwise, end) = self.parse_list()?;
+                    if !matches!(end, Terminator::End) {
+                        bail!("template: missing {{{{end}}}} for {{{{{}}}}}", keyword);
+                    }
+                    otherwise
+                }
+                Some(_) => bail!("template: unexpected tokens after {{{{else}}}}"),
+            },
+        };
+
+        Ok(match keyword {
+            "if" => Node::If {
+                cond: pipe,
+                then: body,
+                otherwise,
+            },
+            "range" => Node::Range {
+                pipe,
+                body,
+                otherwise,
+            },
+            _ => Node::With {
+                pipe,
+                body,
+                otherwise,
+            },
+        })
+    }
+}
+
+/// Parses a pipeline, including an optional `$x :=` declaration.
+fn parse_pipeline(tokens: &[Token]) -> Result<Pipeline> {
+    let mut decl = Vec::new();
+    let mut rest = tokens;
+
+    if let Some(idx) = tokens.iter().position(|t| *t == Token::Declare) {
+        for token in &tokens[..idx] {
+            match token {
+                Token::Var(name, fields) if fields.is_empty() => decl.push(name.clone()),
+                Token::Comma => {}
+                _ => bail!("template: bad variable declaration"),
+            }
+        }
+        rest = &tokens[idx + 1..];
+    }
+
+    let mut pos = 0;
+    let pipeline = parse_commands(rest, &mut pos)?;
+    if pos != rest.len() {
+        bail!("template: unexpected ')'");
+    }
+
+    Ok(Pipeline {
+        decl,
+        commands: pipeline,
+    })
+}
+
+/// Parses `|`-separated commands until the end or an unmatched `)`.
+fn parse_commands(tokens: &[Token], pos: &mut usize) -> Result<Vec<Vec<Arg>>> {
+    let mut commands = Vec::new();
+    let mut current = Vec::new();
+
+    while *pos < tokens.len() {
+        let token = &tokens[*pos];
+        *pos += 1;
+        match token {
+            Token::Pipe => {
+                if current.is_empty() {
+                    bail!("template: missing command before '|'");
+                }
+                commands.push(std::mem::take(&mut current));
+            }
+            Token::RParen => {
+                *pos -= 1;
+                break;
+            }
+            Token::LParen => {
+                let sub = parse_commands(tokens, pos)?;
+                if tokens.get(*pos) != Some(&Token::RParen) {
+                    bail!("template: unclosed '('");
+                }
+                *pos += 1;
+                current.push(Arg::Sub(Pipeline {
+                    decl: Vec::new(),
+                    commands: sub,
+                }));
+            }
+            Token::Dot => current.push(Arg::Dot),
+            Token::Field(fields) => current.push(Arg::Field(fields.clone())),
+            Token::Var(name, fields) => current.push(Arg::Var(name.clone(), fields.clone())),
+            Token::Str(s) => current.push(Arg::Literal(Value::String(s.clone()))),
+            Token::Number(n) => current.push(Arg::Literal(n.clone())),
+            Token::Ident(name) => current.push(match name.as_str() {
+                "true" => Arg::Literal(Value::Bool(true)),
+                "false" => Arg::Literal(Value::Bool(false)),
+                "nil" => Arg::Literal(Value::Null),
+                _ => Arg::Func(name.clone()),
+            }),
+            Token::Comma | Token::Declare => bail!("template: unexpected token"),
+        }
+    }
+
+    if current.is_empty() {
+        bail!("template: missing value");
+    }
+    commands.push(current);
+    Ok(commands)
+}
+
+// ---------------------------------------------------------------------------
+// Execution
+// ---------------------------------------------------------------------------
+
+struct Renderer {
+    color: bool,
+    out: String,
+    rows: Vec<Vec<String>>,
+    vars: Vec<(String, Value)>,
+}
+
+impl Renderer {
+    fn exec_list(&mut self, nodes: &[Node], dot: &Value) -> Result<()> {
+        let scope = self.vars.len();
+        for node in nodes {
+            self.exec(node, dot)?;
+        }
+        self.vars.truncate(scope);
+        Ok(())
+    }
+
+    fn exec(&mut self, node: &Node, dot: &Value) -> Result<()> {
+        match node {
+            Node::Text(text) => self.out.push_str(text),
+            Node::Action(pipe) => {
+                let value = self.eval_pipeline(pipe, dot)?;
+                if pipe.decl.is_empty() {
+                    self.out.push_str(&to_text(&value));
+                } else {
+                    self.vars.push((pipe.decl[0].clone(), value));
+                }
+            }
+            Node::If {
+                cond,
+                then,
+                otherwise,
+            } => {
+                if is_truthy(&self.eval_pipeline(cond, dot)?) {
+                    self.exec_list(then, dot)?;
+                } else {
+                    self.exec_list(otherwise, dot)?;
+                }
+            }
+            Node::With {
+                pipe,
+                body,
+                otherwise,
+            } => {
+                let value = self.eval_pipeline(pipe, dot)?;
+                if is_truthy(&value) {
+                    self.exec_list(body, &value)?;
+                } else {
+                    self.exec_list(otherwise, dot)?;
+                }
+            }
+            Node::Range {
+                pipe,
+                body,
+                otherwise,
+            } => {
+                let value = self.eval_pipeline(pipe, dot)?;
+                let items = match value {
+                    Value::Array(items) => items,
+                    Value::Null => Vec::new(),
+                    other => bail!("template: can't range over {}", to_text(&other)),
+                };
+
+                if items.is_empty() {
+                    return self.exec_list(otherwise, dot);
+                }
+
+                for (i, item) in items.into_iter().enumerate() {
+                    let scope = self.vars.len();
+                    match pipe.decl.as_slice() {
+                        [value_var] => self.vars.push((value_var.clone(), item.clone())),
+                        [index_var, value_var] => {
+                            self.vars.push((index_var.clone(), Value::from(i)));
+                            self.vars.push((value_var.clone(), item.clone()));
+                        }
+                        _ => {}
+                    }
+                    self.exec_list(body, &item)?;
+                    self.vars.truncate(scope);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn eval_pipeline(&mut self, pipe: &Pipeline, dot: &Value) -> Result<Value> {
+        let mut piped: Option<Value> = None;
+        for command in &pipe.commands {
+            piped = Some(self.eval_command(command, dot, piped)?);
+        }
+        Ok(piped.unwrap_or(Value::Null))
+    }
+
+    fn eval_command(
+        &mut self,
+        command: &[Arg],
+        dot: &Value,
+        piped: Option<Value>,
+    ) -> Result<Value> {
+        if let Some(Arg::Func(name)) = command.first() {
+            let mut args = command[1..]
+                .iter()
+                .map(|arg| self.eval_arg(arg, dot))
+                .collect::<Result<Vec<_>>>()?;
+            args.extend(piped);
+            return self.call(name, args);
+        }
+
+        if command.len() > 1 || piped.is_some() {
+            bail!("template: can't give arguments to a non-function");
+        }
+        self.eval_arg(&command[0], dot)
+    }
+
+    fn eval_arg(&mut self, arg: &Arg, dot: &Value) -> Result<Value> {
+        Ok(match arg {
+            Arg::Dot => dot.clone(),
+            Arg::Field(fields) => lookup(dot, fields),
+            Arg::Var(name, fields) => {
+                let Some((_, value)) = self.vars.iter().rev().find(|(n, _)| n == name) else {
+                    bail!("template: undefined variable {}", name);
+                };
+                lookup(value, fields)
+            }
+            Arg::Literal(value) => value.clone(),
+            Arg::Func(name) => self.call(name, Vec::new())?,
+            Arg::Sub(pipe) => self.eval_pipeline(pipe, dot)?,
+        })
+    }
+
+    fn call(&mut self, name: &str, args: Vec<Value>) -> Result<Value> {
+        let arity = |n: usize| -> Result<()> {
+            if args.len() != n {
+                bail!(
+                    "template: {} expects {} argument{}, got {}",
+                    name,
+                    n,
+                    if n == 1 { "" } else { "s" },
+                    args.len()
+                );
+            }
+            Ok(())
+        };
+
+        Ok(match name {
+            "color" => {
+                arity(2)?;
+                Value::String(colorize(
+                    &to_text(&args[0]),
+                    &to_text(&args[1]),
+                    self.color,
+                )?)
+            }
+            "timeago" => {
+                arity(1)?;
+                match timestamp(&args[0]) {
+                    Some(ts) => Value::String(format_relative_time(ts)),
+                    None => Value::String(to_text(&args[0])),
+                }
+            }
+            "truncate" => {
+                arity(2)?;
+                let Some(len) = args[0].as_u64() else {
+                    bail!("template: truncate length must be a number");
+                };
+                Value::String(truncate_chars(&to_text(&args[1]), len as usize))
+            }
+            "join" => {
+                arity(2)?;
+                let sep = to_text(&args[0]);
+                let joined = match &args[1] {
+                    Value::Array(items) => items.iter().map(to_text).collect::<Vec<_>>().join(&sep),
+                    other => to_text(other),
+                };
+                Value::String(joined)
+            }
+            "pluck" => {
+                arity(2)?;
+                let field = to_text(&args[0]);
+                match &args[1] {
+                    Value::Array(items) => Value::Array(
+                        items
+                            .iter()
+                            .map(|item| item.get(&field).cloned().unwrap_or(Value::Null))
+                            .collect(),
+                    ),
+                    _ => Value::Array(Vec::new()),
+                }
+            }
+            "tablerow" => {
+                self.rows.push(args.iter().map(to_text).collect());
+                Value::String(String::new())
+            }
+            "tablerender" => {
+                arity(0)?;
+                self.flush_table();
+                Value::String(String::new())
+            }
+            "len" => {
+                arity(1)?;
+                Value::from(match &args[0] {
+                    Value::Array(items) => items.len(),
+                    Value::Object(map) => map.len(),
+                    Value::String(s) => s.chars().count(),
+                    Value::Null => 0,
+                    other => bail!("template: len of {}", to_text(other)),
+                })
+            }
+            "index" => {
+                if args.is_empty() {
+                    bail!("template: index expects at least 1 argument");
+                }
+                let mut value = args[0].clone();
+                for key in &args[1..] {
+                    value = match key {
+                        Value::Number(n) => n
+                            .as_u64()
+                            .and_then(|i| value.get(i as usize).cloned())
+                            .unwrap_or(Value::Null),
+                        other => value.get(to_text(other)).cloned().unwrap_or(Value::Null),
+                    };
+                }
+                value
+            }
+            "printf" => {
+                if args.is_empty() {
+                    bail!("template: printf expects a format string");
+                }
+                Value::String(sprintf(&to_text(&args[0]), &args[1..]))
+            }
+            "not" => {
+                arity(1)?;
+                Value::Bool(!is_truthy(&args[0]))
+            }
+            "and" => args
+                .iter()
+                .find(|v| !is_truthy(v))
+                .or(args.last())
+                .cloned()
+                .unwrap_or(Value::Null),
+            "or" => args
+                .iter()
+                .find(|v| is_truthy(v))
+                .or(args.last())
+                .cloned()
+                .unwrap_or(Value::Null),
+            "eq" => {
+                if args.len() < 2 {
+                    bail!("template: eq expects at least 2 arguments");
+                }
+                Value::Bool(args[1..].iter().any(|v| values_equal(&args[0], v)))
+            }
+            "ne" => {
+                arity(2)?;
+                Value::Bool(!values_equal(&args[0], &args[1]))
+            }
+            "lt" | "le" | "gt" | "ge" => {
+                arity(2)?;
+                let ordering = compare(&args[0], &args[1])?;
+                Value::Bool(match name {
+                    "lt" => ordering.is_lt(),
+                    "le" => ordering.is_le(),
+                    "gt" => ordering.is_gt(),
+                    _ => ordering.is_ge(),
+                })
+            }
+            _ => bail!("template: function \"{}\" not defined", name),
+        })
+    }
+
+    /// Writes pending `tablerow` rows as aligned columns.
+    fn flush_table(&mut self) {
+        if self.rows.is_empty() {
+            return;
+        }
+
+        let columns = self.rows.iter().map(Vec::len).max().unwrap_or(0);
+        let widths: Vec<usize> = (0..columns)
+            .map(|col| {
+                self.rows
+                    .iter()
+                    .filter_map(|row| row.get(col))
+                    .map(|cell| console::measure_text_width(cell))
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+
+        for row in std::mem::take(&mut self.rows) {
+            let mut line = String::new();
+            for (i, cell) in row.iter().enumerate() {
+                line.push_str(cell);
+                if i + 1 < row.len() {
+                    let pad = widths[i] - console::measure_text_width(cell);
+                    line.push_str(&" ".repeat(pad + 2));
+                }
+            }
+            self.out.push_str(line.trim_end());
+            self.out.push('\n');
+        }
+    }
+}
+
+/// Follows a field chain, yielding `null` for anything missing.
+fn lookup(value: &Value, fields: &[String]) -> Value {
+    let mut current = value;
+    for field in fields {
+        match current.get(field) {
+            Some(next) => current = next,
+            None => return Value::Null,
+        }
+    }
+    current.clone()
+}
+
+/// Renders a value as template output text.
+fn to_text(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Go template truthiness: false, 0, null and empty values are false.
+fn is_truthy(value: &Value) -> bool {
+    match value {
+        Value::Null => false,
+        Value::Bool(b) => *b,
+        Value::Number(n) => n.as_f64().is_some_and(|f| f != 0.0),
+        Value::String(s) => !s.is_empty(),
+        Value::Array(items) => !items.is_empty(),
+        Value::Object(map) => !map.is_empty(),
+    }
+}
+
+fn values_equal(a: &Value, b: &Value) -> bool {
+    match (a.as_f64(), b.as_f64()) {
+        (Some(x), Some(y)) => x == y,
+        _ => a == b,
+    }
+}
+
+fn compare(a: &Value, b: &Value) -> Result<std::cmp::Ordering> {
+    match (a, b) {
+        (Value::Number(x), Value::Number(y)) => {
+            let (x, y) = (x.as_f64().unwrap_or(0.0), y.as_f64().unwrap_or(0.0));
+            Ok(x.partial_cmp(&y).unwrap_or(std::cmp::Ordering::Equal))
+        }
+        (Value::String(x), Value::String(y)) => Ok(x.cmp(y)),
+        _ => bail!("template: can't compare {} and {}", to_text(a), to_text(b)),
+    }
+}
+
+/// Applies a style such as `green`, `red+b` or `yellow+bu` to text.
+///
+/// The colour part takes `console`'s dotted names (`cyan`, `bright.red`,
+/// `on_blue`, `208`) and may be empty, as in `+b`. Unknown names and
+/// modifiers are errors, even when colour is off.
+fn colorize(spec: &str, text: &str, enabled: bool) -> Result<String> {
+    let (color, modifiers) = spec.split_once('+').unwrap_or((spec, ""));
+    let mut style = console::Style::new().force_styling(true);
+
+    if !color.is_empty() {
+        if let Some(part) = color.split('.').find(|part| !is_style_name(part)) {
+            bail!("template: unknown color \"{}\" in \"{}\"", part, spec);
+        }
+        style = console::Style::from_dotted_str(color).force_styling(true);
+    }
+
+    for m in modifiers.chars().filter(|&m| m != '+') {
+        style = match m {
+            'b' => style.bold(),
+            'd' => style.dim(),
+            'i' => style.italic(),
+            'u' => style.underlined(),
+            _ => bail!(
+                "template: unknown style modifier '{}' in \"{}\"; use b, d, i or u",
+                m,
+                spec
+            ),
+        };
+    }
+
+    Ok(if enabled {
+        style.apply_to(text).to_string()
+    } else {
+        text.to_string()
+    })
+}
+
+/// Whether `console::Style::from_dotted_str` knows a dotted part.
+fn is_style_name(part: &str) -> bool {
+    const NAMES: &[&str] = &[
+        "black",
+        "red",
+        "green",
+        "yellow",
+        "blue",
+        "magenta",
+        "cyan",
+        "white",
+        "bright",
+        "on_black",
+        "on_red",
+        "on_green",
+        "on_yellow",
+        "on_blue",
+        "on_magenta",
+        "on_cyan",
+        "on_white",
+        "on_bright",
+        "bold",
+        "dim",
+        "underlined",
+        "blink",
+        "blink_fast",
+        "reverse",
+        "hidden",
+        "strikethrough",
+    ];
+    NAMES.contains(&part)
+        || part.parse::<u8>().is_ok()
+        || part
+            .strip_prefix("on_")
+            .is_some_and(|n| n.parse::<u8>().is_ok())
+}
+
+/// Go's marker for a verb that cannot format its argument, naming the
+/// argument's type as decoded JSON has it in Go.
+fn bad_verb(verb: char, arg: &Value) -> String {
+    let type_name = match arg {
+        Value::Null => return format!("%!{}(<nil>)", verb),
+        Value::Bool(_) => "bool",
+        Value::Number(_) => "float64",
+        Value::String(_) => "string",
+        Value::Array(_) => "[]interface {}",
+        Value::Object(_) => "map[string]interface {}",
+    };
+    format!("%!{}({}={})", verb, type_name, to_text(arg))
+}
+
+/// Converts an ISO 8601 string or a millisecond timestamp to Unix seconds.
+fn timestamp(value: &Value) -> Option<i64> {
+    match value {
+        Value::String(s) => chrono::DateTime::parse_from_rfc3339(s)
+            .ok()
+            .map(|dt| dt.timestamp()),
+        // Bitbucket Server timestamps are in milliseconds
+        Value::Number(n) => n
+            .as_i64()
+            .map(|ts| if ts > 100_000_000_000 { ts / 1000 } else { ts }),
+        _ => None,
+    }
+}
+
+/// Truncates to `max` characters, ending with "..." when shortened.
+fn truncate_chars(s: &str, max: usize) -> String {
+    if s.chars().count() <= max {
+        return s.to_string();
+    }
+    if max <= 3 {
+        return s.chars().take(max).collect();
+    }
+    let mut out: String = s.chars().take(max - 3).collect();
+    out.push_str("...");
+    out
+}
+
+/// A small `printf` supporting `%v`, `%s`, `%d`, `%f`, `%q`, `%t` and `%%`,
+/// with `-` alignment, width and precision.
+///
+/// Mistakes are marked in the output as Go does: `%!d(MISSING)` for a verb
+/// without an argument, `%!x(string=abc)` for an unsupported verb and
+/// `%!(NOVERB)` for a trailing `%`.
+fn sprintf(format: &str, args: &[Value]) -> String {
+    let mut out = String::new();
+    let mut args = args.iter();
+    let mut chars = format.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            out.push(c);
+            continue;
+        }
+        if chars.peek() == Some(&'%') {
+            chars.next();
+            out.push('%');
+            continue;
+        }
+
+        let left = chars.next_if_eq(&'-').is_some();
+        let mut width = String::new();
+        while let Some(d) = chars.next_if(|d| d.is_ascii_digit()) {
+            width.push(d);
+        }
+        let mut precision = None;
+        if chars.next_if_eq(&'.').is_some() {
+            let mut p = String::new();
+            while let Some(d) = chars.next_if(|d| d.is_ascii_digit()) {
+                p.push(d);
+            }
+            precision = p.parse::<usize>().ok();
+        }
+
+        let Some(verb) = chars.next() else {
+            out.push_str("%!(NOVERB)");
+            break;
+        };
+        let Some(arg) = args.next() else {
+            out.push_str(&format!("%!{}(MISSING)", verb));
+            continue;
+        };
+        let text = match verb {
+            'q' => Value::String(to_text(arg)).to_string(),
+            'f' => {
+                let f = arg.as_f64().unwrap_or(0.0);
+                format!("{:.*}", precision.unwrap_or(6), f)
+            }
+            'd' => arg
+                .as_f64()
+                .map(|f| (f as i64).to_string())
+                .unwrap_or_else(|| to_text(arg)),
+            'v' | 's' | 't' => match precision {
+                Some(p) => to_text(arg).chars().take(p).collect(),
+                None => to_text(arg),
+            },
+            _ => {
+                out.push_str(&bad_verb(verb, arg));
+                continue;
+            }
+        };
+
+        let width = width.parse::<usize>().unwrap_or(0);
+        let pad = width.saturating_sub(console::measure_text_width(&text));
+        if left {
+            out.push_str(&text);
+            out.push_str(&" ".repeat(pad));
+        } else {
+            out.push_str(&" ".repeat(pad));
+            out.push_str(&text);
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn render(template: &str, data: Value) -> String {
+        Template::parse(template)
+            .unwrap()
+            .render(&data, false)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_range_and_fields() {
+        let data = json!([
+            {"id": 1, "title": "Fix login", "author": {"display_name": "Ana"}},
+            {"id": 2, "title": "Add cache", "author": {"display_name": "Ben"}}
+        ]);
+        assert_eq!(
+            render(
+                r#"{{range .}}{{.id}}	{{.title}} ({{.author.display_name}}){{"\n"}}{{end}}"#,
+                data
+            ),
+            "1\tFix login (Ana)\n2\tAdd cache (Ben)\n"
+        );
+    }
+
+    #[test]
+    fn test_if_else_and_comparisons() {
+        let template = r#"{{if eq .state "OPEN"}}open{{else if eq .state "MERGED"}}merged{{else}}other{{end}}"#;
+        assert_eq!(render(template, json!({"state": "OPEN"})), "open");
+        assert_eq!(render(template, json!({"state": "MERGED"})), "merged");
+        assert_eq!(render(template, json!({"state": "DECLINED"})), "other");
+        assert_eq!(render("{{if gt .n 2}}big{{end}}", json!({"n": 3})), "big");
+    }
+
+    #[test]
+    fn test_range_else_variables_and_trim() {
+        assert_eq!(render("{{range .}}x{{else}}none{{end}}", json!([])), "none");
+        assert_eq!(
+            render(
+                "{{range $i, $pr := .}}{{$i}}={{$pr.id}} {{end}}",
+                json!([{"id": 7}, {"id": 9}])
+            ),
+            "0=7 1=9 "
+        );
+        assert_eq!(
+            render("{{- range . }}\n  {{ . -}}\n{{ end }}", json!(["a", "b"])),
+            "\n  a\n  b"
+        );
+    }
+
+    #[test]
+    fn test_helpers() {
+        let data = json!({
+            "title": "A rather long pull request title",
+            "labels": ["bug", "ui"],
+            "reviewers": [{"name": "ana"}, {"name": "ben"}]
+        });
+        assert_eq!(render("{{truncate 10 .title}}", data.clone()), "A rathe...");
+        assert_eq!(render(r#"{{join ", " .labels}}"#, data.clone()), "bug, ui");
+        assert_eq!(
+            render(r#"{{pluck "name" .reviewers | join ","}}"#, data.clone()),
+            "ana,ben"
+        );
+        assert_eq!(
+            render(r#"{{.title | truncate 6 | printf "[%-8s]"}}"#, data),
+            "[A r...  ]"
+        );
+        assert_eq!(render(r#"{{color "green" "ok"}}"#, json!(null)), "ok");
+        assert_eq!(render("{{len .}}", json!([1, 2, 3])), "3");
+    }
+
+    #[test]
+    fn test_printf_marks_mistakes_like_go() {
+        assert_eq!(
+            render(r#"{{printf "%s-%d" "a"}}"#, json!(null)),
+            "a-%!d(MISSING)"
+        );
+        assert_eq!(
+            render(r#"{{printf "%x %o" "abc" 8}}"#, json!(null)),
+            "%!x(string=abc) %!o(float64=8)"
+        );
+        assert_eq!(render(r#"{{printf "%x" nil}}"#, json!(null)), "%!x(<nil>)");
+        assert_eq!(render(r#"{{printf "100%"}}"#, json!(null)), "100%!(NOVERB)");
+    }
+
+    #[test]
+    fn test_color_rejects_unknown_styles() {
+        let colored = |spec: &str| {
+            Template::parse(&format!(r#"{{{{color "{}" "ok"}}}}"#, spec))
+                .unwrap()
+                .render(&json!(null), true)
+        };
+        assert_eq!(colored("red+b").unwrap(), "\u{1b}[31m\u{1b}[1mok\u{1b}[0m");
+        assert!(colored("bright.cyan+bu").is_ok());
+        assert!(colored("+d").is_ok());
+        assert!(colored("purple").is_err());
+        assert!(colored("green+x").is_err());
+        // Mistakes show up even with colour off
+        assert!(Template::parse(r#"{{color "purple" "ok"}}"#)
+            .unwrap()
+            .render(&json!(null), false)
+            .is_err());
+    }
+
+    #[test]
+    fn test_timeago() {
+        let data =
+            json!({"created_on": "2001-01-01T00:00:00+00:00", "createdDate": 978307200000u64});
+        assert!(render("{{timeago .created_on}}", data.clone()).ends_with("years ago"));
+        assert!(render("{{timeago .createdDate}}", data).ends_with("years ago"));
+    }
+
+    #[test]
+    fn test_tablerow_aligns_columns() {
+        let data = json!([{"id": 1, "title": "Short"}, {"id": 1234, "title": "Longer title"}]);
+        assert_eq!(
+            render(
+                r##"{{range .}}{{tablerow (printf "#%v" .id) .title}}{{end}}"##,
+                data
+            ),
+            "#1     Short\n#1234  Longer title\n"
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(Template::parse("{{range .}}").is_err());
+        assert!(Template::parse("{{.title").is_err());
+        assert!(Template::parse("{{end}}").is_err());
+        assert!(Template::parse("{{`raw`}}").is_err());
+        assert!(Template::parse("{{with .a}}{{else with .b}}{{end}}").is_err());
+        assert!(Template::parse("{{nosuch .}}")
+            .unwrap()
+            .render(&json!({}), false)
+            .is_err());
+    }
+}
@@ -0,0 +1,531 @@
+//
+//  bitbucket-cli
+//  output/theme.rs
+//
+//  Created by Ngonidzashe Mangudya on 2026/01/12.
+//  Copyright (c) 2025 IAMNGONI. All rights reserved.
+//
+
+//! # Theme
+//!
+//! All terminal styling goes through a single [`Theme`], so that the
+//! global `--color` flag, the `NO_COLOR` and `CLICOLOR_FORCE` conventions
+//! and the user's state colours apply everywhere in the same way.
+//!
+//! ## Colour Selection
+//!
+//! | `--color` | Behaviour |
+//! |-----------|-----------|
+//! | `always` | Always colour output |
+//! | `never` | Never colour output |
+//! | `auto` (default) | `NO_COLOR` disables colour, `CLICOLOR_FORCE` forces it, otherwise colour when stdout is a terminal |
+//!
+//! ## Plain Mode
+//!
+//! With `core.theme = "plain"` (or `BB_THEME=plain`), status glyphs such as
+//! `✓` and `✗` are replaced with words such as `OK` and `FAILED`, table
+//! borders use ASCII, and colour is off unless `--color=always` is given.
+//! This suits screen readers and log files.
+//!
+//! ## State Colours
+//!
+//! States such as `OPEN`, `MERGED` and `FAILED` have default colours that
+//! can be overridden in the `[colors]` section of the configuration file:
+//!
+//! ```toml
+//! [colors]
+//! MERGED = "blue"
+//! FAILED = "bright_red"
+//! ```
+//!
+//! ## Example
+//!
+//! ```rust,ignore
+//! use bitbucket_cli::output::theme;
+//!
+//! println!("{} Merged PR #{}", theme().success_mark(), theme().emphasis(42));
+//! println!("State: {}", theme().state("MERGED"));
+//! ```
+
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::str::FromStr;
+
+use anyhow::{bail, Result};
+use comfy_table::Color;
+use console::Style;
+use once_cell::sync::OnceCell;
+
+/// The theme used for the rest of the process.
+static THEME: OnceCell<Theme> = OnceCell::new();
+
+/// Sets the theme used to style output for the rest of the process.
+///
+/// This is called once at startup, after the global `--color` flag and the
+/// configuration file have been read.
+///
+/// # Errors
+///
+/// Returns an error if a theme has already been set.
+pub fn set_theme(theme: Theme) -> Result<()> {
+    THEME
+        .set(theme)
+        .map_err(|_| anyhow::anyhow!("theme has already been set"))
+}
+
+/// Returns the active theme.
+///
+/// If no theme has been set, e.g. in tests, a default theme is used that
+/// colours output when stdout is a terminal.
+pub fn theme() -> &'static Theme {
+    THEME.get_or_init(|| {
+        let color = color_enabled_for(
+            ColorChoice::Auto,
+            |key| std::env::var(key).ok(),
+            || console::Term::stdout().is_term(),
+        );
+        Theme::new(color, false)
+    })
+}
+
+/// The value of the global `--color` flag.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColorChoice {
+    /// Colour when stdout is a terminal, honouring `NO_COLOR` and `CLICOLOR_FORCE`
+    #[default]
+    Auto,
+    /// Always colour output
+    Always,
+    /// Never colour output
+    Never,
+}
+
+impl FromStr for ColorChoice {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "auto" => Ok(ColorChoice::Auto),
+            "always" => Ok(ColorChoice::Always),
+            "never" => Ok(ColorChoice::Never),
+            other => bail!(
+                "Invalid color setting '{}'. Valid values: auto, always, never",
+                other
+            ),
+        }
+    }
+}
+
+/// Decides whether output should be coloured.
+///
+/// # Parameters
+///
+/// * `choice` - The `--color` setting
+/// * `env` - Looks up an environment variable
+/// * `is_tty` - Whether stdout is a terminal; only called for `auto`
+///
+/// # Returns
+///
+/// For [`ColorChoice::Auto`]: `false` if `NO_COLOR` is set to a non-empty
+/// value, `true` if `CLICOLOR_FORCE` is set to anything but `0`, `false`
+/// if `CLICOLOR` is `0` or `TERM` is `dumb`, and otherwise whether stdout
+/// is a terminal.
+pub fn color_enabled_for(
+    choice: ColorChoice,
+    env: impl Fn(&str) -> Option<String>,
+    is_tty: impl FnOnce() -> bool,
+) -> bool {
+    let set = |key: &str| env(key).filter(|v| !v.is_empty());
+
+    match choice {
+        ColorChoice::Always => true,
+        ColorChoice::Never => false,
+        ColorChoice::Auto => {
+            if set("NO_COLOR").is_some() {
+                false
+            } else if set("CLICOLOR_FORCE").is_some_and(|v| v != "0") {
+                true
+            } else if set("CLICOLOR").is_some_and(|v| v == "0")
+                || set("TERM").is_some_and(|v| v == "dumb")
+            {
+                false
+            } else {
+                is_tty()
+            }
+        }
+    }
+}
+
+/// Default colours for states, keyed by upper-case state name.
+const DEFAULT_STATE_COLORS: &[(&str, Color)] = &[
+    // Pull requests
+    ("OPEN", Color::Green),
+    ("MERGED", Color::Magenta),
+    ("DECLINED", Color::Red),
+    ("SUPERSEDED", Color::Yellow),
+    ("DRAFT", Color::DarkGrey),
+    // Issues
+    ("NEW", Color::Green),
+    ("ON_HOLD", Color::Yellow),
+    ("RESOLVED", Color::DarkGrey),
+    ("CLOSED", Color::DarkGrey),
+    ("INVALID", Color::DarkGrey),
+    ("DUPLICATE", Color::DarkGrey),
+    ("WONTFIX", Color::DarkGrey),
+    // Builds, pipelines and deployments
+    ("SUCCESSFUL", Color::Green),
+    ("COMPLETED", Color::Green),
+    ("PASSED", Color::Green),
+    ("FAILED", Color::Red),
+    ("ERROR", Color::Red),
+    ("STOPPED", Color::Yellow),
+    ("PENDING", Color::Yellow),
+    ("WAITING", Color::Yellow),
+    ("IN_PROGRESS", Color::Cyan),
+    ("INPROGRESS", Color::Cyan),
+    ("RUNNING", Color::Cyan),
+    // Runners
+    ("ONLINE", Color::Green),
+    ("OFFLINE", Color::Red),
+];
+
+/// Styling for all terminal output.
+///
+/// A theme knows whether colour is enabled, whether glyphs may be used,
+/// and which colour each state is shown in. Use [`theme`] to get the
+/// active theme.
+#[derive(Debug, Clone)]
+pub struct Theme {
+    color: bool,
+    plain: bool,
+    states: HashMap<String, Color>,
+}
+
+impl Theme {
+    /// Creates a theme with the default state colours.
+    ///
+    /// # Parameters
+    ///
+    /// * `color` - Whether to colour output
+    /// * `plain` - Whether to avoid glyphs and box-drawing characters
+    pub fn new(color: bool, plain: bool) -> Self {
+        Self {
+            color,
+            plain,
+            states: DEFAULT_STATE_COLORS
+                .iter()
+                .map(|(state, color)| (state.to_string(), *color))
+                .collect(),
+        }
+    }
+
+    /// Overrides state colours from the `[colors]` configuration section.
+    ///
+    /// # Parameters
+    ///
+    /// * `colors` - Map of state name to colour name, e.g. `MERGED = "blue"`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error naming the state if a colour is not recognised.
+    pub fn with_state_colors(mut self, colors: &HashMap<String, String>) -> Result<Self> {
+        for (state, name) in colors {
+            let color = parse_color(name)
+                .map_err(|e| anyhow::anyhow!("Invalid color for '{}' in [colors]: {}", state, e))?;
+            self.states.insert(normalize_state(state), color);
+        }
+        Ok(self)
+    }
+
+    /// Returns whether output is coloured.
+    pub fn color_enabled(&self) -> bool {
+        self.color
+    }
+
+    /// Returns whether plain, glyph-free output is used.
+    pub fn is_plain(&self) -> bool {
+        self.plain
+    }
+
+    /// Returns the colour for a state, if it has one.
+    ///
+    /// State names are matched case-insensitively, and `-`/`_`/space are
+    /// treated alike, so `in progress` matches `IN_PROGRESS`.
+    pub fn state_color(&self, state: &str) -> Option<Color> {
+        self.states.get(&normalize_state(state)).copied()
+    }
+
+    /// Shows a state in its colour, e.g. `OPEN` in green.
+    pub fn state(&self, state: &str) -> String {
+        self.paint_state(state, state)
+    }
+
+    /// Shows text in the colour of a state, e.g. `[OK]` in the colour of
+    /// `SUCCESSFUL`.
+    pub fn paint_state(&self, text: impl Display, state: &str) -> String {
+        match self.state_color(state) {
+            Some(color) => self.paint(text, color),
+            None => text.to_string(),
+        }
+    }
+
+    /// Shows text in a colour.
+    ///
+    /// [`Color::Reset`] leaves the text in the terminal's own colour.
+    pub fn paint(&self, text: impl Display, color: Color) -> String {
+        match console_color(color) {
+            Some(color) => self.styled(text, Style::new().fg(color)),
+            None => text.to_string(),
+        }
+    }
+
+    /// Shows a section heading in bold.
+    pub fn heading(&self, text: impl Display) -> String {
+        self.styled(text, Style::new().bold())
+    }
+
+    /// Highlights a name or identifier, e.g. a branch or PR number.
+    pub fn emphasis(&self, text: impl Display) -> String {
+        self.styled(text, Style::new().cyan())
+    }
+
+    /// Shows secondary text, such as labels and hints, dimmed.
+    pub fn muted(&self, text: impl Display) -> String {
+        self.styled(text, Style::new().dim())
+    }
+
+    /// Marks a successful action: `✓`, or `OK` in plain mode.
+    pub fn success_mark(&self) -> String {
+        self.mark("✓", "OK", Color::Green)
+    }
+
+    /// Marks a failed action: `✗`, or `FAILED` in plain mode.
+    pub fn failure_mark(&self) -> String {
+        self.mark("✗", "FAILED", Color::Red)
+    }
+
+    /// Marks a warning or cancellation: `!`, or `WARNING` in plain mode.
+    pub fn warning_mark(&self) -> String {
+        self.mark("!", "WARNING", Color::Yellow)
+    }
+
+    /// Marks an action in progress: `→`, or `...` in plain mode.
+    pub fn info_mark(&self) -> String {
+        self.mark("→", "...", Color::Cyan)
+    }
+
+    /// Marks an unknown outcome: `?`, or `UNKNOWN` in plain mode.
+    pub fn unknown_mark(&self) -> String {
+        self.mark("?", "UNKNOWN", Color::Yellow)
+    }
+
+    /// Marks a pending item: `●`, or `PENDING` in plain mode.
+    pub fn pending_mark(&self) -> String {
+        self.mark("●", "PENDING", Color::Yellow)
+    }
+
+    /// The arrow between a source and a destination, e.g. branches.
+    pub fn arrow(&self) -> &'static str {
+        if self.plain {
+            "->"
+        } else {
+            "→"
+        }
+    }
+
+    /// The string used to show that text has been shortened.
+    pub fn ellipsis(&self) -> &'static str {
+        if self.plain {
+            "..."
+        } else {
+            "…"
+        }
+    }
+
+    fn mark(&self, glyph: &str, word: &str, color: Color) -> String {
+        let text = if self.plain { word } else { glyph };
+        let style = match console_color(color) {
+            Some(color) => Style::new().fg(color).bold(),
+            None => Style::new().bold(),
+        };
+        self.styled(text, style)
+    }
+
+    fn styled(&self, text: impl Display, style: Style) -> String {
+        if self.color {
+            style.force_styling(true).apply_to(text).to_string()
+        } else {
+            text.to_string()
+        }
+    }
+}
+
+/// Normalizes a state name for lookup: upper case, with `-` and spaces as `_`.
+fn normalize_state(state: &str) -> String {
+    state.trim().to_uppercase().replace(['-', ' '], "_")
+}
+
+/// Parses a colour name such as `green`, `bright_red` or `208`.
+///
+/// # Errors
+///
+/// Returns an error listing the valid names if the colour is unknown.
+pub fn parse_color(name: &str) -> Result<Color> {
+    if let Ok(value) = name.trim().parse::<u8>() {
+        return Ok(Color::AnsiValue(value));
+    }
+
+    let color = match name.trim().to_lowercase().replace(['-', ' '], "_").as_str() {
+        "black" => Color::Black,
+        "red" | "dark_red" => Color::DarkRed,
+        "bright_red" => Color::Red,
+        "green" | "dark_green" => Color::DarkGreen,
+        "bright_green" => Color::Green,
+        "yellow" | "dark_yellow" => Color::DarkYellow,
+        "bright_yellow" => Color::Yellow,
+        "blue" | "dark_blue" => Color::DarkBlue,
+        "bright_blue" => Color::Blue,
+        "magenta" | "dark_magenta" => Color::DarkMagenta,
+        "bright_magenta" => Color::Magenta,
+        "cyan" | "dark_cyan" => Color::DarkCyan,
+        "bright_cyan" => Color::Cyan,
+        "white" | "grey" | "gray" => Color::Grey,
+        "bright_white" => Color::White,
+        "dark_grey" | "dark_gray" | "bright_black" => Color::DarkGrey,
+        "none" | "default" => Color::Reset,
+        other => bail!(
+            "unknown color '{}'. Use black, red, green, yellow, blue, magenta, cyan, \
+             white or grey (optionally bright_ or dark_), or a number 0-255",
+            other
+        ),
+    };
+    Ok(color)
+}
+
+/// Converts a table colour to the equivalent terminal style colour.
+///
+/// `comfy_table` names the normal ANSI colours `Dark*` and the bright ones
+/// without a prefix, and [`Color::Grey`] is normal white. [`Color::Reset`]
+/// has no equivalent and means "don't colour".
+fn console_color(color: Color) -> Option<console::Color> {
+    let color = match color {
+        Color::Black => console::Color::Black,
+        Color::DarkRed => console::Color::Red,
+        Color::DarkGreen => console::Color::Green,
+        Color::DarkYellow => console::Color::Yellow,
+        Color::DarkBlue => console::Color::Blue,
+        Color::DarkMagenta => console::Color::Magenta,
+        Color::DarkCyan => console::Color::Cyan,
+        Color::Grey => console::Color::White,
+        Color::DarkGrey => console::Color::Color256(8),
+        Color::Red => console::Color::Color256(9),
+        Color::Green => console::Color::Color256(10),
+        Color::Yellow => console::Color::Color256(11),
+        Color::Blue => console::Color::Color256(12),
+        Color::Magenta => console::Color::Color256(13),
+        Color::Cyan => console::Color::Color256(14),
+        Color::White => console::Color::Color256(15),
+        Color::AnsiValue(value) => console::Color::Color256(value),
+        Color::Rgb { r, g, b } => console::Color::Color256(rgb_to_ansi(r, g, b)),
+        Color::Reset => return None,
+    };
+    Some(color)
+}
+
+/// Approximates an RGB colour with the 6x6x6 ANSI colour cube.
+fn rgb_to_ansi(r: u8, g: u8, b: u8) -> u8 {
+    let level = |c: u8| (c as u16 * 5 / 255) as u8;
+    16 + 36 * level(r) + 6 * level(g) + level(b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env<'a>(vars: &'a [(&'a str, &'a str)]) -> impl Fn(&str) -> Option<String> + 'a {
+        move |key| {
+            vars.iter()
+                .find(|(k, _)| *k == key)
+                .map(|(_, v)| v.to_string())
+        }
+    }
+
+    #[test]
+    fn test_color_choice_overrides_environment() {
+        let vars = [("NO_COLOR", "1")];
+        assert!(color_enabled_for(ColorChoice::Always, env(&vars), || false));
+        assert!(!color_enabled_for(ColorChoice::Never, env(&[]), || true));
+    }
+
+    #[test]
+    fn test_auto_color_honours_no_color_and_clicolor_force() {
+        assert!(color_enabled_for(ColorChoice::Auto, env(&[]), || true));
+        assert!(!color_enabled_for(ColorChoice::Auto, env(&[]), || false));
+        assert!(!color_enabled_for(
+            ColorChoice::Auto,
+            env(&[("NO_COLOR", "1")]),
+            || true
+        ));
+        assert!(color_enabled_for(
+            ColorChoice::Auto,
+            env(&[("CLICOLOR_FORCE", "1")]),
+            || false
+        ));
+        assert!(!color_enabled_for(
+            ColorChoice::Auto,
+            env(&[("NO_COLOR", "1"), ("CLICOLOR_FORCE", "1")]),
+            || true
+        ));
+        // An empty NO_COLOR is ignored
+        assert!(color_enabled_for(
+            ColorChoice::Auto,
+            env(&[("NO_COLOR", "")]),
+            || true
+        ));
+    }
+
+    #[test]
+    fn test_state_colors_are_configurable() {
+        let colors = HashMap::from([("merged".to_string(), "blue".to_string())]);
+        let theme = Theme::new(true, false).with_state_colors(&colors).unwrap();
+
+        assert_eq!(theme.state_color("MERGED"), Some(Color::DarkBlue));
+        assert_eq!(theme.state_color("open"), Some(Color::Green));
+        assert_eq!(theme.state_color("in progress"), Some(Color::Cyan));
+        assert_eq!(theme.state_color("on hold"), Some(Color::Yellow));
+        assert_eq!(theme.state_color("unknown"), None);
+        assert_eq!(theme.state("MERGED"), "\u{1b}[34mMERGED\u{1b}[0m");
+    }
+
+    #[test]
+    fn test_default_color_leaves_text_unstyled() {
+        let colors = HashMap::from([("open".to_string(), "default".to_string())]);
+        let theme = Theme::new(true, false).with_state_colors(&colors).unwrap();
+
+        assert_eq!(theme.state_color("OPEN"), Some(Color::Reset));
+        assert_eq!(theme.state("OPEN"), "OPEN");
+        assert_eq!(theme.paint("text", Color::Reset), "text");
+    }
+
+    #[test]
+    fn test_invalid_state_color_is_an_error() {
+        let colors = HashMap::from([("OPEN".to_string(), "chartreuse".to_string())]);
+        let err = Theme::new(true, false)
+            .with_state_colors(&colors)
+            .unwrap_err();
+        assert!(err.to_string().contains("'OPEN'"));
+    }
+
+    #[test]
+    fn test_plain_theme_uses_words() {
+        let theme = Theme::new(false, true);
+        assert_eq!(theme.success_mark(), "OK");
+        assert_eq!(theme.failure_mark(), "FAILED");
+        assert_eq!(theme.ellipsis(), "...");
+        assert_eq!(theme.state("OPEN"), "OPEN");
+
+        let theme = Theme::new(false, false);
+        assert_eq!(theme.success_mark(), "✓");
+        assert_eq!(theme.emphasis(42), "42");
+    }
+}
@@ -0,0 +1,300 @@
+//
+//  bitbucket-cli
+//  review/mod.rs
+//
+//  Created by Ngonidzashe Mangudya on 2026/01/12.
+//  Copyright (c) 2025 IAMNGONI. All rights reserved.
+//
+
+//! Pending Reviews
+//!
+//! `bb pr review --interactive` collects inline comments and tasks while the
+//! reviewer walks the diff, and posts them together when the review is
+//! submitted. Until then they are kept in a pending review on disk, saved
+//! after every change, so a crash or an interrupted session loses nothing.
+//! Starting the review again resumes it.
+//!
+//! # Storage
+//!
+//! Each pull request has its own file under the data directory:
+//!
+//! ```text
+//! <data_dir>/reviews/<host>/<owner>/<repo>/<pr>.json
+//! ```
+//!
+//! The file is removed once the review has been submitted or discarded.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use bitbucket_cli::review::{PendingComment, PendingReview};
+//!
+//! let mut review = PendingReview::open(&context, 42)?;
+//! review.push(PendingComment {
+//!     path: "src/cache.rs".to_string(),
+//!     line: 12,
+//!     side: Side::New,
+//!     body: "Can this expire sooner?".to_string(),
+//!     task: false,
+//!     posted_id: None,
+//! })?;
+//! ```
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::api::diff::Side;
+use crate::config::Config;
+use crate::context::RepoContext;
+
+/// Comments written during a review that have not been posted yet
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingReview {
+    /// Pull request number
+    pub pr: u32,
+
+    /// Comments waiting to be posted, in the order they were written
+    #[serde(default)]
+    pub comments: Vec<PendingComment>,
+
+    /// When the review was last changed
+    pub updated_at: DateTime<Utc>,
+
+    /// File the review is saved to
+    #[serde(skip)]
+    path: PathBuf,
+}
+
+/// An inline comment waiting to be posted
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PendingComment {
+    /// Path of the file in the diff
+    pub path: String,
+
+    /// Line number on `side`
+    pub line: u32,
+
+    /// Whether `line` refers to the old or new version of the file
+    pub side: Side,
+
+    /// Comment text
+    pub body: String,
+
+    /// Whether a task is created from the comment when it is posted
+    #[serde(default)]
+    pub task: bool,
+
+    /// ID of the posted comment, kept while its task is still to be created
+    /// so that a retry does not post the comment again
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub posted_id: Option<u64>,
+}
+
+impl PendingComment {
+    /// Formats the position as `path:line`, with `(old)` appended for lines
+    /// on the old side
+    pub fn location(&self) -> String {
+        match self.side {
+            Side::New => format!("{}:{}", self.path, self.line),
+            Side::Old => format!("{}:{} (old)", self.path, self.line),
+        }
+    }
+}
+
+impl PendingReview {
+    /// Opens the pending review of a pull request
+    ///
+    /// Returns an empty review if none has been saved. Nothing is written
+    /// until the review is changed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data directory cannot be determined, or if a
+    /// saved review exists but cannot be read.
+    pub fn open(context: &RepoContext, pr: u32) -> Result<Self> {
+        let path = Config::data_dir()?
+            .join("reviews")
+            .join(path_component(&context.host))
+            .join(path_component(&context.owner))
+            .join(path_component(&context.repo_slug))
+            .join(format!("{}.json", pr));
+        Self::open_at(path, pr)
+    }
+
+    /// Opens the pending review saved at `path`
+    fn open_at(path: PathBuf, pr: u32) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self {
+                pr,
+                comments: Vec::new(),
+                updated_at: Utc::now(),
+                path,
+            });
+        }
+
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read pending review {}", path.display()))?;
+        let mut review: Self = serde_json::from_str(&content)
+            .with_context(|| format!("Pending review {} is corrupt", path.display()))?;
+        review.path = path;
+        Ok(review)
+    }
+
+    /// The file the review is saved to
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Whether there are no comments waiting to be posted
+    pub fn is_empty(&self) -> bool {
+        self.comments.is_empty()
+    }
+
+    /// Adds a comment and saves the review
+    pub fn push(&mut self, comment: PendingComment) -> Result<()> {
+        self.comments.push(comment);
+        self.save()
+    }
+
+    /// Records that the oldest comment has been posted as `id`, and saves
+    /// the review
+    pub fn mark_posted(&mut self, id: u64) -> Result<()> {
+        if let Some(comment) = self.comments.first_mut() {
+            comment.posted_id = Some(id);
+        }
+        self.save()
+    }
+
+    /// Removes the oldest comment, once it has been posted, and saves the
+    /// review
+    pub fn pop_front(&mut self) -> Result<Option<PendingComment>> {
+        if self.comments.is_empty() {
+            return Ok(None);
+        }
+        let comment = self.comments.remove(0);
+        self.save()?;
+        Ok(Some(comment))
+    }
+
+    /// Writes the review to disk
+    ///
+    /// The review is written to a temporary file next to it and renamed over
+    /// the old one, so a crash part way through leaves the previous version.
+    pub fn save(&mut self) -> Result<()> {
+        self.updated_at = Utc::now();
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let staging = self.path.with_extension("json.tmp");
+        std::fs::write(&staging, serde_json::to_string_pretty(self)?)
+            .and_then(|()| std::fs::rename(&staging, &self.path))
+            .with_context(|| format!("Failed to save pending review {}", self.path.display()))?;
+        Ok(())
+    }
+
+    /// Deletes the saved review, if there is one
+    pub fn discard(self) -> Result<()> {
+        match std::fs::remove_file(&self.path) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e).with_context(|| {
+                format!("Failed to delete pending review {}", self.path.display())
+            }),
+        }
+    }
+}
+
+/// Makes a host, owner or repository name safe to use as a directory name
+fn path_component(name: &str) -> String {
+    if name.chars().all(|c| c == '.') {
+        return "_".repeat(name.len());
+    }
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_' | '~') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn comment(line: u32) -> PendingComment {
+        PendingComment {
+            path: "src/cache.rs".to_string(),
+            line,
+            side: Side::New,
+            body: format!("Comment on line {}", line),
+            task: line.is_multiple_of(2),
+            posted_id: None,
+        }
+    }
+
+    #[test]
+    fn test_path_component() {
+        assert_eq!(path_component("bitbucket.org"), "bitbucket.org");
+        assert_eq!(
+            path_component("git.example.com:7990"),
+            "git.example.com_7990"
+        );
+        assert_eq!(path_component("~jdoe"), "~jdoe");
+        assert_eq!(path_component("../x"), ".._x");
+        assert_eq!(path_component(".."), "__");
+    }
+
+    #[test]
+    fn test_pending_review_survives_reopen() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir
+            .path()
+            .join("reviews/bitbucket.org/acme/widgets/42.json");
+
+        let mut review = PendingReview::open_at(path.clone(), 42).unwrap();
+        assert!(review.is_empty());
+        assert!(!path.exists());
+
+        review.push(comment(3)).unwrap();
+        review.push(comment(4)).unwrap();
+
+        let mut reopened = PendingReview::open_at(path.clone(), 42).unwrap();
+        assert_eq!(reopened.comments, vec![comment(3), comment(4)]);
+
+        assert_eq!(reopened.pop_front().unwrap(), Some(comment(3)));
+        let reopened = PendingReview::open_at(path.clone(), 42).unwrap();
+        assert_eq!(reopened.comments, vec![comment(4)]);
+
+        reopened.discard().unwrap();
+        assert!(!path.exists());
+        PendingReview::open_at(path, 42).unwrap().discard().unwrap();
+    }
+
+    #[test]
+    fn test_posted_comment_is_remembered_until_removed() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("42.json");
+
+        let mut review = PendingReview::open_at(path.clone(), 42).unwrap();
+        review.push(comment(4)).unwrap();
+        review.mark_posted(901).unwrap();
+
+        let reopened = PendingReview::open_at(path.clone(), 42).unwrap();
+        assert_eq!(reopened.comments[0].posted_id, Some(901));
+        assert!(!path.with_extension("json.tmp").exists());
+    }
+
+    #[test]
+    fn test_location() {
+        let mut c = comment(7);
+        assert_eq!(c.location(), "src/cache.rs:7");
+        c.side = Side::Old;
+        assert_eq!(c.location(), "src/cache.rs:7 (old)");
+    }
+}
@@ -0,0 +1,584 @@
+//
+//  bitbucket-cli
+//  users/mod.rs
+//
+//  Created by Ngonidzashe Mangudya on 2026/01/12.
+//  Copyright (c) 2025 IAMNGONI. All rights reserved.
+//
+
+//! User Resolution
+//!
+//! People are named on the command line the way colleagues know them:
+//! `@jdoe`, `jdoe@corp.com` or "Jane Doe". The APIs want identifiers instead:
+//! Cloud takes account UUIDs and Server/DC takes usernames and user slugs.
+//! [`UserResolver`] maps one to the other for `pr create --reviewer`,
+//! `pr edit --add-reviewer/--remove-reviewer` and `pr list --reviewer`.
+//!
+//! # Accepted Forms
+//!
+//! | Input | Cloud | Server/DC |
+//! |-------|-------|-----------|
+//! | `@jdoe` | nickname | username or slug |
+//! | `jdoe@corp.com` | email (workspace admins only) | email |
+//! | `Jane Doe`, `jane` | display name, nickname or account ID | display name or username |
+//! | `{uuid}` | used as given | - |
+//! | Reviewer group name | - | every member of the group |
+//!
+//! An exact match on the username, email or display name wins; otherwise
+//! every user whose username or display name contains the input is a
+//! candidate.
+//!
+//! # Lookups
+//!
+//! Cloud searches the members of the workspace:
+//!
+//! ```text
+//! GET /2.0/workspaces/{workspace}/members
+//! ```
+//!
+//! Server/DC searches its users, and the reviewer groups of the repository
+//! for inputs that are neither a handle nor an email:
+//!
+//! ```text
+//! GET /rest/api/1.0/users?filter={input}
+//! GET /rest/api/1.0/projects/{projectKey}/repos/{repositorySlug}/settings/reviewer-groups
+//! ```
+//!
+//! # Ambiguous Names
+//!
+//! When several users match, an interactive session picks one with a fuzzy
+//! finder. Without a terminal the lookup fails and lists the candidates.
+//!
+//! # Cache
+//!
+//! Resolved inputs, including the user picked for an ambiguous name, are
+//! cached per host in `users.json` under the CLI data directory for
+//! [`CACHE_TTL_HOURS`] hours.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use bitbucket_cli::users::UserResolver;
+//!
+//! let resolver = UserResolver::new(&context, &token)?;
+//! let reviewers = resolver.resolve_all(&["@jdoe".into(), "Ann Lee".into()]).await?;
+//! ```
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+
+use crate::api::cloud::workspaces::WorkspaceMember;
+use crate::api::common::{PaginatedResponse, ServerPaginatedResponse};
+use crate::api::format_api_error;
+use crate::api::query::{Bbql, QueryParams};
+use crate::api::replay::ReplayExt;
+use crate::api::server::pullrequests as server_prs;
+use crate::config::Config;
+use crate::context::{HostType, RepoContext};
+use crate::interactive::{fuzzy_select, is_interactive};
+
+/// Number of hours a resolved user stays cached.
+pub const CACHE_TTL_HOURS: i64 = 24;
+
+/// Name of the cache file in the CLI data directory.
+const CACHE_FILE: &str = "users.json";
+
+/// A user that an input resolved to
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResolvedUser {
+    /// Cloud account UUID, with braces, or Server/DC user slug
+    pub id: String,
+
+    /// Cloud nickname or Server/DC username
+    #[serde(default)]
+    pub username: Option<String>,
+
+    /// Display name
+    pub display_name: String,
+
+    /// Email address, when the API reveals it
+    #[serde(default)]
+    pub email: Option<String>,
+
+    /// Atlassian account ID (Cloud)
+    #[serde(default)]
+    pub account_id: Option<String>,
+}
+
+impl ResolvedUser {
+    /// The username, falling back to the id; Server/DC APIs that take a
+    /// `user.name` want this
+    pub fn name(&self) -> &str {
+        self.username.as_deref().unwrap_or(&self.id)
+    }
+}
+
+impl fmt::Display for ResolvedUser {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.display_name)?;
+        if let Some(username) = &self.username {
+            write!(f, " (@{})", username)?;
+        }
+        if let Some(email) = &self.email {
+            write!(f, " <{}>", email)?;
+        }
+        Ok(())
+    }
+}
+
+impl From<WorkspaceMember> for ResolvedUser {
+    fn from(member: WorkspaceMember) -> Self {
+        Self {
+            id: member.user.uuid,
+            username: member.user.nickname,
+            display_name: member.user.display_name,
+            email: None,
+            account_id: member.user.account_id,
+        }
+    }
+}
+
+impl From<server_prs::User> for ResolvedUser {
+    fn from(user: server_prs::User) -> Self {
+        Self {
+            id: user.slug.unwrap_or_else(|| user.name.clone()),
+            username: Some(user.name),
+            display_name: user.display_name,
+            email: user.email_address,
+            account_id: None,
+        }
+    }
+}
+
+/// A Server/DC default reviewer group
+#[derive(Debug, Clone, Deserialize)]
+struct ReviewerGroup {
+    name: String,
+    #[serde(default)]
+    users: Vec<server_prs::User>,
+}
+
+/// Resolved users of one input, as cached
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedUsers {
+    users: Vec<ResolvedUser>,
+    fetched_at: DateTime<Utc>,
+}
+
+impl CachedUsers {
+    fn is_stale(&self) -> bool {
+        Utc::now() - self.fetched_at > Duration::hours(CACHE_TTL_HOURS)
+    }
+}
+
+/// Resolves user references for one repository
+pub struct UserResolver<'a> {
+    context: &'a RepoContext,
+    token: &'a str,
+    client: Client,
+}
+
+impl<'a> UserResolver<'a> {
+    /// Creates a resolver for the users who can access `context`
+    pub fn new(context: &'a RepoContext, token: &'a str) -> Result<Self> {
+        let client = Client::builder()
+            .user_agent(format!("bb/{}", crate::VERSION))
+            .build()?;
+
+        Ok(Self {
+            context,
+            token,
+            client,
+        })
+    }
+
+    /// Resolves each input, keeping the first occurrence of users named
+    /// more than once
+    pub async fn resolve_all(&self, inputs: &[String]) -> Result<Vec<ResolvedUser>> {
+        let mut users: Vec<ResolvedUser> = Vec::new();
+        for input in inputs {
+            for user in self.resolve(input).await? {
+                if !users.iter().any(|u| u.id == user.id) {
+                    users.push(user);
+                }
+            }
+        }
+        Ok(users)
+    }
+
+    /// Resolves one input to exactly one user
+    ///
+    /// Used where the API takes a single user, such as `pr list --reviewer`.
+    pub async fn resolve_one(&self, input: &str) -> Result<ResolvedUser> {
+        let mut users = self.resolve(input).await?;
+        if users.len() > 1 {
+            anyhow::bail!(
+                "'{}' is a reviewer group of {} users; name a single user instead",
+                input,
+                users.len()
+            );
+        }
+        Ok(users.remove(0))
+    }
+
+    /// Resolves one input to the users it names: one user, or every member
+    /// of a Server/DC reviewer group
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if nobody matches, or if several users match and
+    /// there is no terminal to choose one on.
+    pub async fn resolve(&self, input: &str) -> Result<Vec<ResolvedUser>> {
+        let input = input.trim();
+        if input.is_empty() || input == "@" {
+            anyhow::bail!("Empty user name");
+        }
+
+        if self.context.host_type == HostType::Cloud && input.starts_with('{') {
+            return Ok(vec![ResolvedUser {
+                id: input.to_string(),
+                username: None,
+                display_name: input.to_string(),
+                email: None,
+                account_id: None,
+            }]);
+        }
+
+        let key = self.cache_key(input);
+        let mut cache = load_cache();
+        if let Some(cached) = cache
+            .get(&self.context.host)
+            .and_then(|entries| entries.get(&key))
+        {
+            if !cached.is_stale() {
+                return Ok(cached.users.clone());
+            }
+        }
+
+        let users = match self.find_group(input).await? {
+            Some(group) => group,
+            None => {
+                let candidates = self.search(input).await?;
+                // Cloud members have no email to compare; the search
+                // already returned only the member who has it
+                let matches = if self.context.host_type == HostType::Cloud && is_email(input) {
+                    candidates
+                } else {
+                    best_matches(input, candidates)
+                };
+                vec![self.choose(input, matches)?]
+            }
+        };
+
+        cache.entry(self.context.host.clone()).or_default().insert(
+            key,
+            CachedUsers {
+                users: users.clone(),
+                fetched_at: Utc::now(),
+            },
+        );
+        if let Err(e) = save_cache(&cache) {
+            tracing::debug!("Failed to write user cache: {}", e);
+        }
+
+        Ok(users)
+    }
+
+    /// Picks the user an input means from its best matches
+    fn choose(&self, input: &str, mut matches: Vec<ResolvedUser>) -> Result<ResolvedUser> {
+        match matches.len() {
+            0 => anyhow::bail!(
+                "No user matching '{}' found in {}",
+                input,
+                self.context.owner
+            ),
+            1 => Ok(matches.remove(0)),
+            _ if is_interactive() => {
+                let idx = fuzzy_select(&format!("Which user is '{}'?", input), &matches)?;
+                Ok(matches.remove(idx))
+            }
+            _ => {
+                let names: Vec<String> = matches.iter().map(|u| u.to_string()).collect();
+                anyhow::bail!(
+                    "'{}' matches several users: {}\nUse @username or an email to pick one.",
+                    input,
+                    names.join(", ")
+                )
+            }
+        }
+    }
+
+    /// Fetches the users an input may refer to
+    async fn search(&self, input: &str) -> Result<Vec<ResolvedUser>> {
+        if self.context.host_type == HostType::Cloud {
+            self.search_cloud(input).await
+        } else {
+            self.search_server(input).await
+        }
+    }
+
+    /// Lists the workspace members, or with an email only the member who
+    /// has it
+    async fn search_cloud(&self, input: &str) -> Result<Vec<ResolvedUser>> {
+        let email = is_email(input).then_some(input);
+
+        let mut query = QueryParams::new();
+        query.push("pagelen", 100);
+        if let Some(email) = email {
+            let mut q = Bbql::new();
+            q.in_list("user.email", &[email]);
+            query.push_opt("q", q.build());
+        }
+
+        let mut url = Some(format!(
+            "https://api.bitbucket.org/2.0/workspaces/{}/members?{}",
+            self.context.owner, query
+        ));
+        let mut users = Vec::new();
+
+        while let Some(next) = url {
+            let response = self
+                .client
+                .get(&next)
+                .bearer_auth(self.token)
+                .send_replayable()
+                .await
+                .context("Failed to connect to Bitbucket Cloud")?;
+
+            if !response.status().is_success() {
+                let status = response.status();
+                let text = response.text().await.unwrap_or_default();
+                return Err(format_api_error(status, &text));
+            }
+
+            let page: PaginatedResponse<WorkspaceMember> = response.json().await?;
+            users.extend(page.values.into_iter().map(ResolvedUser::from));
+            url = page.next;
+        }
+
+        // Members carry no email, and without admin rights the email filter
+        // is ignored, so only a single result shows the filter was applied
+        if email.is_some() && users.len() != 1 {
+            anyhow::bail!(
+                "Looking up '{}' by email needs workspace admin access in {}; use @username or a name instead",
+                input,
+                self.context.owner
+            );
+        }
+
+        Ok(users)
+    }
+
+    /// Searches Server/DC users by username, display name and email
+    async fn search_server(&self, input: &str) -> Result<Vec<ResolvedUser>> {
+        let mut query = QueryParams::new();
+        query
+            .push("filter", input.trim_start_matches('@'))
+            .push("limit", 100);
+
+        let url = format!("https://{}/rest/api/1.0/users?{}", self.context.host, query);
+
+        let response = self
+            .client
+            .get(&url)
+            .bearer_auth(self.token)
+            .send_replayable()
+            .await
+            .context("Failed to connect to Bitbucket Server")?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            return Err(format_api_error(status, &text));
+        }
+
+        let page: ServerPaginatedResponse<server_prs::User> = response.json().await?;
+        Ok(page.values.into_iter().map(ResolvedUser::from).collect())
+    }
+
+    /// Expands a Server/DC reviewer group named by the input
+    ///
+    /// Handles and emails never name a group. Failing to read the groups,
+    /// for instance on versions without them, is treated as no group.
+    async fn find_group(&self, input: &str) -> Result<Option<Vec<ResolvedUser>>> {
+        if self.context.host_type == HostType::Cloud || input.contains('@') {
+            return Ok(None);
+        }
+
+        let url = format!(
+            "https://{}/rest/api/1.0/projects/{}/repos/{}/settings/reviewer-groups?limit=100",
+            self.context.host, self.context.owner, self.context.repo_slug
+        );
+
+        let response = self
+            .client
+            .get(&url)
+            .bearer_auth(self.token)
+            .send_replayable()
+            .await
+            .context("Failed to connect to Bitbucket Server")?;
+
+        if !response.status().is_success() {
+            tracing::debug!(
+                "Could not read reviewer groups of {}/{}: {}",
+                self.context.owner,
+                self.context.repo_slug,
+                response.status()
+            );
+            return Ok(None);
+        }
+
+        let groups: ServerPaginatedResponse<ReviewerGroup> = response.json().await?;
+        let Some(group) = groups
+            .values
+            .into_iter()
+            .find(|g| g.name.eq_ignore_ascii_case(input))
+        else {
+            return Ok(None);
+        };
+
+        if group.users.is_empty() {
+            anyhow::bail!("Reviewer group '{}' has no members", group.name);
+        }
+
+        Ok(Some(
+            group.users.into_iter().map(ResolvedUser::from).collect(),
+        ))
+    }
+
+    fn cache_key(&self, input: &str) -> String {
+        format!(
+            "{}/{}:{}",
+            self.context.owner,
+            self.context.repo_slug,
+            input.to_lowercase()
+        )
+    }
+}
+
+/// Whether an input is an email address rather than a handle
+fn is_email(input: &str) -> bool {
+    !input.starts_with('@') && input.contains('@')
+}
+
+/// Narrows candidates down to the users an input most likely means
+///
+/// `@handle` and emails only match exactly. Other inputs prefer an exact
+/// username, account ID or display name, then fall back to every username
+/// or display name containing the input. Comparisons ignore case.
+fn best_matches(input: &str, candidates: Vec<ResolvedUser>) -> Vec<ResolvedUser> {
+    let eq = |value: Option<&str>, expected: &str| {
+        value.is_some_and(|v| v.eq_ignore_ascii_case(expected))
+    };
+
+    if let Some(handle) = input.strip_prefix('@') {
+        return candidates
+            .into_iter()
+            .filter(|u| eq(u.username.as_deref(), handle) || u.id.eq_ignore_ascii_case(handle))
+            .collect();
+    }
+
+    if is_email(input) {
+        return candidates
+            .into_iter()
+            .filter(|u| eq(u.email.as_deref(), input))
+            .collect();
+    }
+
+    let (exact, rest): (Vec<_>, Vec<_>) = candidates.into_iter().partition(|u| {
+        eq(u.username.as_deref(), input)
+            || eq(u.account_id.as_deref(), input)
+            || u.display_name.eq_ignore_ascii_case(input)
+    });
+    if !exact.is_empty() {
+        return exact;
+    }
+
+    let needle = input.to_lowercase();
+    rest.into_iter()
+        .filter(|u| {
+            u.display_name.to_lowercase().contains(&needle)
+                || u.username
+                    .as_deref()
+                    .is_some_and(|n| n.to_lowercase().contains(&needle))
+        })
+        .collect()
+}
+
+fn cache_path() -> Result<PathBuf> {
+    Ok(Config::data_dir()?.join(CACHE_FILE))
+}
+
+fn load_cache() -> HashMap<String, HashMap<String, CachedUsers>> {
+    cache_path()
+        .ok()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_cache(cache: &HashMap<String, HashMap<String, CachedUsers>>) -> Result<()> {
+    let path = cache_path()?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, serde_json::to_string_pretty(cache)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user(id: &str, username: &str, display_name: &str, email: Option<&str>) -> ResolvedUser {
+        ResolvedUser {
+            id: id.to_string(),
+            username: Some(username.to_string()),
+            display_name: display_name.to_string(),
+            email: email.map(str::to_string),
+            account_id: None,
+        }
+    }
+
+    fn people() -> Vec<ResolvedUser> {
+        vec![
+            user("jdoe", "jdoe", "Jane Doe", Some("jdoe@corp.com")),
+            user("jdough", "jdough", "John Dough", None),
+            user("alee", "alee", "Ann Lee", Some("ann@corp.com")),
+        ]
+    }
+
+    fn ids(users: Vec<ResolvedUser>) -> Vec<String> {
+        users.into_iter().map(|u| u.id).collect()
+    }
+
+    #[test]
+    fn test_best_matches_handles_and_emails_are_exact() {
+        assert_eq!(ids(best_matches("@JDoe", people())), ["jdoe"]);
+        assert!(best_matches("@jd", people()).is_empty());
+        assert_eq!(ids(best_matches("ann@corp.com", people())), ["alee"]);
+        assert!(best_matches("ann@corp", people()).is_empty());
+    }
+
+    #[test]
+    fn test_best_matches_prefers_exact_names() {
+        assert_eq!(ids(best_matches("jane doe", people())), ["jdoe"]);
+        assert_eq!(ids(best_matches("jdoe", people())), ["jdoe"]);
+        assert_eq!(ids(best_matches("lee", people())), ["alee"]);
+        assert_eq!(ids(best_matches("jd", people())), ["jdoe", "jdough"]);
+        assert!(best_matches("Bob", people()).is_empty());
+    }
+
+    #[test]
+    fn test_resolved_user_display() {
+        let jane = user("jdoe", "jdoe", "Jane Doe", Some("jdoe@corp.com"));
+        assert_eq!(jane.to_string(), "Jane Doe (@jdoe) <jdoe@corp.com>");
+        assert_eq!(jane.name(), "jdoe");
+    }
+}
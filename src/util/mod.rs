@@ -14,8 +14,9 @@
 //!
 //! ## Categories
 //!
-//! - **Time Utilities**: [`format_time`], [`format_duration`], [`format_relative_time`]
-//! - **String Utilities**: [`slugify`], [`truncate`]
+//! - **Time Utilities**: [`format_time`], [`format_duration`], [`parse_duration`],
+//!   [`format_relative_time`], [`parse_date`]
+//! - **String Utilities**: [`slugify`], [`truncate`], [`glob_match`]
 //! - **Size Utilities**: [`parse_size`], [`format_size`]
 //! - **System Utilities**: [`open_browser`], [`get_pager`], [`page_output`]
 //!
@@ -34,10 +35,11 @@
 //! let size = format_size(1536); // "1.5 KB"
 //! ```
 
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
-use anyhow::Result;
-use chrono::{DateTime, Local};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local, NaiveDate, Utc};
 
 /// Formats a Unix timestamp into a human-readable local datetime string.
 ///
@@ -126,6 +128,83 @@ pub fn format_duration(duration: Duration) -> String {
     }
 }
 
+/// Parses a human-readable duration string.
+///
+/// Accepts one or more `<number><unit>` parts, where the unit is `s`, `m`,
+/// `h` or `d`, in the same notation [`format_duration`] produces. A bare
+/// number is taken as seconds.
+///
+/// # Parameters
+///
+/// * `s` - A duration such as `"90s"`, `"30m"`, `"1h 30m"` or `"2d"`.
+///
+/// # Returns
+///
+/// Returns `Ok(Duration)` with the total length, or an error if parsing fails.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The string is empty or contains only whitespace.
+/// - A part has no number, or a unit other than `s`, `m`, `h` or `d`.
+/// - The total does not fit in a `u64` number of seconds.
+///
+/// # Example
+///
+/// ```rust
+/// use bitbucket_cli::util::parse_duration;
+/// use std::time::Duration;
+///
+/// assert_eq!(parse_duration("45").unwrap(), Duration::from_secs(45));
+/// assert_eq!(parse_duration("30m").unwrap(), Duration::from_secs(1800));
+/// assert_eq!(parse_duration("1h 30m").unwrap(), Duration::from_secs(5400));
+/// assert_eq!(parse_duration("1h30m").unwrap(), Duration::from_secs(5400));
+/// ```
+///
+/// # Notes
+///
+/// - Units are case-insensitive.
+/// - Whitespace between parts is ignored.
+pub fn parse_duration(s: &str) -> Result<Duration> {
+    let s = s.trim().to_lowercase();
+    if s.is_empty() {
+        anyhow::bail!("Empty duration");
+    }
+    if let Ok(secs) = s.parse::<u64>() {
+        return Ok(Duration::from_secs(secs));
+    }
+
+    let mut total = 0u64;
+    let mut digits = String::new();
+    for c in s.chars() {
+        match c {
+            '0'..='9' => digits.push(c),
+            ' ' if digits.is_empty() => {}
+            's' | 'm' | 'h' | 'd' if !digits.is_empty() => {
+                let unit = match c {
+                    's' => 1,
+                    'm' => 60,
+                    'h' => 3600,
+                    _ => 86400,
+                };
+                total = digits
+                    .parse::<u64>()
+                    .ok()
+                    .and_then(|n| n.checked_mul(unit))
+                    .and_then(|secs| total.checked_add(secs))
+                    .ok_or_else(|| anyhow::anyhow!("Duration '{}' is too long", s))?;
+                digits.clear();
+            }
+            _ => anyhow::bail!("Invalid duration '{}'. Use e.g. 90s, 30m, 1h or 1h 30m", s),
+        }
+    }
+    if !digits.is_empty() {
+        anyhow::bail!("Invalid duration '{}'. Use e.g. 90s, 30m, 1h or 1h 30m", s);
+    }
+
+    Ok(Duration::from_secs(total))
+}
+
 /// Formats a Unix timestamp as a human-readable relative time string.
 ///
 /// Converts a Unix timestamp into a natural language description of how long
@@ -208,6 +287,53 @@ pub fn format_relative_time(timestamp: i64) -> String {
     }
 }
 
+/// Parses a date given on the command line.
+///
+/// Accepts either a calendar date, taken as midnight UTC, or a full RFC 3339
+/// timestamp.
+///
+/// # Parameters
+///
+/// * `s` - A date such as `2026-01-31` or `2026-01-31T09:00:00+02:00`
+///
+/// # Returns
+///
+/// The instant in UTC.
+///
+/// # Errors
+///
+/// Returns an error if `s` is neither format.
+///
+/// # Example
+///
+/// ```rust
+/// use bitbucket_cli::util::parse_date;
+///
+/// let date = parse_date("2026-01-31").unwrap();
+/// assert_eq!(date.to_rfc3339(), "2026-01-31T00:00:00+00:00");
+///
+/// let time = parse_date("2026-01-31T09:00:00+02:00").unwrap();
+/// assert_eq!(time.to_rfc3339(), "2026-01-31T07:00:00+00:00");
+/// ```
+pub fn parse_date(s: &str) -> Result<DateTime<Utc>> {
+    let s = s.trim();
+
+    if let Ok(date) = NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+        if let Some(midnight) = date.and_hms_opt(0, 0, 0) {
+            return Ok(midnight.and_utc());
+        }
+    }
+
+    DateTime::parse_from_rfc3339(s)
+        .map(|dt| dt.with_timezone(&Utc))
+        .with_context(|| {
+            format!(
+                "Invalid date '{}'. Use YYYY-MM-DD or an RFC 3339 timestamp",
+                s
+            )
+        })
+}
+
 /// Converts a string into a URL-safe slug format.
 ///
 /// Transforms an arbitrary string into a slug suitable for use in URLs,
@@ -298,6 +424,122 @@ pub fn truncate(s: &str, max_len: usize) -> String {
     }
 }
 
+/// Matches a name against a glob pattern.
+///
+/// `*` matches any run of characters, including `/`; every other character
+/// matches itself. This is the pattern syntax of branch restrictions and
+/// branch-based merge checks, e.g. `release/*`.
+///
+/// # Parameters
+///
+/// * `pattern` - The glob pattern.
+/// * `name` - The name to test, such as a branch name.
+///
+/// # Returns
+///
+/// `true` if the whole of `name` matches `pattern`.
+///
+/// # Example
+///
+/// ```rust
+/// use bitbucket_cli::util::glob_match;
+///
+/// assert!(glob_match("release/*", "release/2.0"));
+/// assert!(glob_match("*", "main"));
+/// assert!(!glob_match("release/*", "main"));
+/// ```
+pub fn glob_match(pattern: &str, name: &str) -> bool {
+    match pattern.split_once('*') {
+        None => pattern == name,
+        Some((prefix, rest)) => {
+            let Some(name) = name.strip_prefix(prefix) else {
+                return false;
+            };
+            (0..=name.len())
+                .filter(|&i| name.is_char_boundary(i))
+                .any(|i| glob_match(rest, &name[i..]))
+        }
+    }
+}
+
+/// Compares two semantic version numbers.
+///
+/// Versions are `MAJOR.MINOR.PATCH` with an optional `-PRERELEASE` and
+/// `+BUILD`; a missing minor or patch counts as 0. As in Semantic
+/// Versioning, a pre-release sorts below its release (`0.3.0-rc1` <
+/// `0.3.0`), pre-release identifiers compare numerically when both are
+/// numbers, and build metadata is ignored.
+///
+/// # Parameters
+///
+/// * `a` - The first version.
+/// * `b` - The second version.
+///
+/// # Returns
+///
+/// The ordering of `a` relative to `b`, or `None` if either is not a
+/// version number.
+///
+/// # Example
+///
+/// ```rust
+/// use std::cmp::Ordering;
+/// use bitbucket_cli::util::compare_versions;
+///
+/// assert_eq!(compare_versions("0.10.0", "0.9.1"), Some(Ordering::Greater));
+/// assert_eq!(compare_versions("0.3.0-rc1", "0.3.0"), Some(Ordering::Less));
+/// assert_eq!(compare_versions("1.2", "1.2.0+build.5"), Some(Ordering::Equal));
+/// assert_eq!(compare_versions("latest", "1.0.0"), None);
+/// ```
+pub fn compare_versions(a: &str, b: &str) -> Option<std::cmp::Ordering> {
+    use std::cmp::Ordering;
+
+    fn parse(version: &str) -> Option<([u64; 3], Vec<&str>)> {
+        let version = version.split_once('+').map_or(version, |(v, _)| v);
+        let (core, pre) = match version.split_once('-') {
+            Some((core, pre)) if !pre.is_empty() => (core, pre.split('.').collect()),
+            Some(_) => return None,
+            None => (version, Vec::new()),
+        };
+
+        let mut numbers = [0; 3];
+        let mut parts = core.split('.');
+        for (i, part) in parts.by_ref().take(3).enumerate() {
+            numbers[i] = part.parse().ok()?;
+        }
+        if parts.next().is_some() {
+            return None;
+        }
+        Some((numbers, pre))
+    }
+
+    let (a_core, a_pre) = parse(a)?;
+    let (b_core, b_pre) = parse(b)?;
+
+    let pre =
+        match (a_pre.is_empty(), b_pre.is_empty()) {
+            (true, true) => Ordering::Equal,
+            (true, false) => Ordering::Greater,
+            (false, true) => Ordering::Less,
+            (false, false) => {
+                let identifiers = a_pre.iter().zip(&b_pre).map(|(x, y)| {
+                    match (x.parse::<u64>(), y.parse::<u64>()) {
+                        (Ok(x), Ok(y)) => x.cmp(&y),
+                        (Ok(_), Err(_)) => Ordering::Less,
+                        (Err(_), Ok(_)) => Ordering::Greater,
+                        (Err(_), Err(_)) => x.cmp(y),
+                    }
+                });
+                identifiers
+                    .chain([a_pre.len().cmp(&b_pre.len())])
+                    .find(|o| o.is_ne())
+                    .unwrap_or(Ordering::Equal)
+            }
+        };
+
+    Some(a_core.cmp(&b_core).then(pre))
+}
+
 /// Opens a URL in the user's default web browser.
 ///
 /// Launches the system's default browser application to display the specified
@@ -363,16 +605,28 @@ pub fn open_browser(url: &str) -> Result<()> {
     Ok(())
 }
 
-/// Retrieves the user's preferred pager command from environment variables.
+/// Whether paging has been turned off with `--no-pager`.
+static PAGER_DISABLED: AtomicBool = AtomicBool::new(false);
+
+/// Turns paging off for the rest of the process.
+///
+/// This is called at startup when the global `--no-pager` flag is given,
+/// after which [`page_output`] always prints directly.
+pub fn disable_pager() {
+    PAGER_DISABLED.store(true, Ordering::Relaxed);
+}
+
+/// Retrieves the user's preferred pager command.
 ///
-/// Checks for a pager program to use when displaying long output. The function
-/// first checks for a Bitbucket CLI-specific pager setting, then falls back to
-/// the standard PAGER environment variable.
+/// Checks for a pager program to use when displaying long output, in order:
+/// the `BB_PAGER` environment variable, `core.pager` in the configuration
+/// file, and the standard `PAGER` environment variable. When none is set,
+/// `less` is used.
 ///
 /// # Returns
 ///
-/// Returns `Some(String)` containing the pager command if one is configured,
-/// or `None` if no pager is set.
+/// Returns `Some(String)` containing the pager command, or `None` if paging
+/// is turned off with `--no-pager`, an empty pager, or `cat`.
 ///
 /// # Example
 ///
@@ -382,28 +636,44 @@ pub fn open_browser(url: &str) -> Result<()> {
 /// // Check if a pager is configured
 /// match get_pager() {
 ///     Some(pager) => println!("Using pager: {}", pager),
-///     None => println!("No pager configured, output will be printed directly"),
+///     None => println!("Paging is disabled, output will be printed directly"),
 /// }
 /// ```
 ///
 /// # Notes
 ///
-/// - First checks the `BB_PAGER` environment variable (Bitbucket CLI-specific).
-/// - Falls back to the standard `PAGER` environment variable.
-/// - Common pager values include "less", "more", "less -R" (with color support).
-/// - Returns `None` if neither environment variable is set.
+/// - The pager may include arguments, e.g. `less -S`; it is split like a
+///   shell word list, not run through a shell.
 /// - Used internally by [`page_output`] to determine how to display long content.
 pub fn get_pager() -> Option<String> {
-    std::env::var("BB_PAGER")
+    if PAGER_DISABLED.load(Ordering::Relaxed) {
+        return None;
+    }
+
+    let pager = std::env::var("BB_PAGER")
         .ok()
+        .or_else(|| {
+            crate::config::Config::load()
+                .ok()
+                .and_then(|config| config.core.pager)
+        })
         .or_else(|| std::env::var("PAGER").ok())
+        .unwrap_or_else(|| "less".to_string());
+
+    let pager = pager.trim();
+    if pager.is_empty() || pager == "cat" {
+        None
+    } else {
+        Some(pager.to_string())
+    }
 }
 
 /// Displays content through the user's configured pager or prints directly.
 ///
 /// Pipes the provided content through a pager program (like `less` or `more`)
-/// if one is configured, otherwise prints the content directly to stdout.
-/// This provides a better user experience for viewing long output.
+/// when stdout is a terminal and a pager is configured, otherwise prints the
+/// content directly to stdout. This provides a better user experience for
+/// viewing long output such as diffs and build logs.
 ///
 /// # Parameters
 ///
@@ -412,14 +682,15 @@ pub fn get_pager() -> Option<String> {
 /// # Returns
 ///
 /// Returns `Ok(())` if the content was successfully displayed, or an error
-/// if the pager process failed to execute or write.
+/// if the pager process failed.
 ///
 /// # Errors
 ///
 /// Returns an error if:
-/// - The pager command fails to spawn.
-/// - Writing to the pager's stdin fails.
-/// - The pager process terminates with an error.
+/// - The pager command cannot be parsed.
+/// - Writing to the pager's stdin fails for a reason other than the pager
+///   having been closed.
+/// - Waiting for the pager process fails.
 ///
 /// # Example
 ///
@@ -434,30 +705,80 @@ pub fn get_pager() -> Option<String> {
 /// # Notes
 ///
 /// - Uses [`get_pager`] to determine the pager command.
-/// - If no pager is configured, content is printed directly to stdout.
+/// - `LESS=FRX` is set unless `LESS` is already set, so `less` passes ANSI
+///   colors through, exits when the content fits on one screen and leaves
+///   the content on the terminal afterwards. `LV=-c` does the same for `lv`.
+/// - If the pager cannot be started, content is printed directly to stdout.
 /// - The function blocks until the pager is closed by the user.
-/// - Useful for displaying long lists, diffs, or other scrollable content.
-/// - Set `BB_PAGER=less -R` to support ANSI color codes in paged output.
-/// - Set `BB_PAGER=cat` or unset both pager variables to disable paging.
 pub fn page_output(content: &str) -> Result<()> {
-    if let Some(pager) = get_pager() {
-        use std::io::Write;
-        use std::process::{Command, Stdio};
+    use std::io::Write;
+    use std::process::{Command, Stdio};
 
-        let mut child = Command::new(&pager).stdin(Stdio::piped()).spawn()?;
+    let pager = if console::Term::stdout().is_term() {
+        get_pager()
+    } else {
+        None
+    };
 
-        if let Some(stdin) = child.stdin.as_mut() {
-            stdin.write_all(content.as_bytes())?;
-        }
+    let Some(pager) = pager else {
+        return print_unpaged(content);
+    };
 
-        child.wait()?;
-    } else {
-        print!("{}", content);
+    let words = shell_words::split(&pager)
+        .map_err(|e| anyhow::anyhow!("Invalid pager command '{}': {}", pager, e))?;
+    let Some((program, args)) = words.split_first() else {
+        return print_unpaged(content);
+    };
+
+    let mut command = Command::new(program);
+    command.args(args).stdin(Stdio::piped());
+    if std::env::var_os("LESS").is_none() {
+        command.env("LESS", "FRX");
     }
+    if std::env::var_os("LV").is_none() {
+        command.env("LV", "-c");
+    }
+
+    let Ok(mut child) = command.spawn() else {
+        return print_unpaged(content);
+    };
 
+    if let Some(mut stdin) = child.stdin.take() {
+        // The user may quit the pager before reading everything
+        if let Err(e) = stdin.write_all(content.as_bytes()) {
+            if e.kind() != std::io::ErrorKind::BrokenPipe {
+                return Err(e.into());
+            }
+        }
+    }
+
+    child.wait()?;
     Ok(())
 }
 
+/// Whether [`page_output`] would use a pager: stdout is a terminal and a
+/// pager is configured
+///
+/// Commands that produce output piece by piece check this to stream it
+/// straight to stdout instead of collecting it for the pager.
+pub fn pager_enabled() -> bool {
+    console::Term::stdout().is_term() && get_pager().is_some()
+}
+
+/// Writes content to stdout, ignoring a closed pipe
+pub fn print_unpaged(content: &str) -> Result<()> {
+    use std::io::Write;
+
+    let mut stdout = std::io::stdout().lock();
+    match stdout
+        .write_all(content.as_bytes())
+        .and_then(|_| stdout.flush())
+    {
+        Err(e) if e.kind() != std::io::ErrorKind::BrokenPipe => Err(e.into()),
+        _ => Ok(()),
+    }
+}
+
 /// Parses a human-readable size string into bytes.
 ///
 /// Converts a size string with optional unit suffix (KB, MB, GB, B) into
@@ -582,6 +903,24 @@ mod tests {
         assert_eq!(slugify("test_123"), "test-123");
     }
 
+    #[test]
+    fn test_compare_versions() {
+        use std::cmp::Ordering::*;
+
+        assert_eq!(compare_versions("0.2.0", "0.2.0"), Some(Equal));
+        assert_eq!(compare_versions("0.10.1", "0.2.0"), Some(Greater));
+        assert_eq!(compare_versions("1", "1.0.0"), Some(Equal));
+        assert_eq!(compare_versions("0.3.0-rc1", "0.3.0"), Some(Less));
+        assert_eq!(compare_versions("0.3.0-rc.2", "0.3.0-rc.10"), Some(Less));
+        assert_eq!(compare_versions("0.3.0-alpha", "0.3.0-alpha.1"), Some(Less));
+        assert_eq!(compare_versions("0.3.0-1", "0.3.0-alpha"), Some(Less));
+        assert_eq!(compare_versions("0.3.0-rc1", "0.2.9"), Some(Greater));
+        assert_eq!(compare_versions("0.3.0+abc", "0.3.0"), Some(Equal));
+        assert_eq!(compare_versions("1.2.3.4", "1.2.3"), None);
+        assert_eq!(compare_versions("1.x", "1.0"), None);
+        assert_eq!(compare_versions("1.0-", "1.0"), None);
+    }
+
     #[test]
     fn test_truncate() {
         assert_eq!(truncate("hello", 10), "hello");
@@ -601,4 +940,64 @@ mod tests {
         assert_eq!(parse_size("1KB").unwrap(), 1024);
         assert_eq!(parse_size("1MB").unwrap(), 1024 * 1024);
     }
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("main", "main"));
+        assert!(!glob_match("main", "main2"));
+        assert!(glob_match("feature/*/ui", "feature/cache/ui"));
+        assert!(glob_match("*-hotfix", "2.0-hotfix"));
+        assert!(!glob_match("*-hotfix", "2.0-hotfix/x"));
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("90").unwrap(), Duration::from_secs(90));
+        assert_eq!(parse_duration("2m 5s").unwrap(), Duration::from_secs(125));
+        assert_eq!(parse_duration("1H").unwrap(), Duration::from_secs(3600));
+        assert_eq!(parse_duration("1d").unwrap(), Duration::from_secs(86400));
+        assert!(parse_duration("").is_err());
+        assert!(parse_duration("10x").is_err());
+        assert!(parse_duration("m").is_err());
+        assert!(parse_duration("5m 3").is_err());
+        assert!(parse_duration("99999999999999999999s").is_err());
+        assert!(parse_duration("300000000000000d").is_err());
+        assert!(parse_duration("18446744073709551615s 1s").is_err());
+    }
+
+    #[test]
+    fn test_parse_date() {
+        assert_eq!(
+            parse_date("2026-01-31").unwrap().to_rfc3339(),
+            "2026-01-31T00:00:00+00:00"
+        );
+        assert_eq!(
+            parse_date("2026-01-31T09:00:00+02:00")
+                .unwrap()
+                .to_rfc3339(),
+            "2026-01-31T07:00:00+00:00"
+        );
+        assert!(parse_date("last week").is_err());
+    }
+
+    /// Serialises the tests that flip the process-wide pager switch
+    static PAGER_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+    /// Turns paging back on when a test ends, even if it fails
+    struct PagerGuard;
+
+    impl Drop for PagerGuard {
+        fn drop(&mut self) {
+            PAGER_DISABLED.store(false, Ordering::Relaxed);
+        }
+    }
+
+    #[test]
+    fn test_disable_pager() {
+        let _lock = PAGER_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let _guard = PagerGuard;
+
+        disable_pager();
+        assert_eq!(get_pager(), None);
+    }
 }
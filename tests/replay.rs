@@ -0,0 +1,2118 @@
+//
+//  bitbucket-cli
+//  tests/replay.rs
+//
+//  Created by Ngonidzashe Mangudya on 2026/01/12.
+//  Copyright (c) 2025 IAMNGONI. All rights reserved.
+//
+
+//! Regression tests that run `bb` against recorded API fixtures.
+
+use std::path::PathBuf;
+
+use assert_cmd::cargo::cargo_bin_cmd;
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+fn fixtures(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join(name)
+}
+
+/// Runs `bb` against a fixture directory
+///
+/// Each run gets its own empty home, config and data directories and runs
+/// outside any repository, so no real configuration, hooks or caches are
+/// used or written.
+fn bb(fixture_dir: &str) -> Command {
+    let home = tempfile::Builder::new()
+        .prefix("home-")
+        .tempdir_in(env!("CARGO_TARGET_TMPDIR"))
+        .unwrap()
+        .keep();
+
+    let mut cmd = cargo_bin_cmd!("bb");
+    cmd.env("BB_REPLAY", fixtures(fixture_dir))
+        .env_remove("BB_RECORD")
+        .env_remove("BB_HOST")
+        .env_remove("BB_REPO")
+        .current_dir(&home);
+    isolate_home(&mut cmd, &home);
+    cmd
+}
+
+/// Points `bb`'s home, config and data directories at `home`
+fn isolate_home(cmd: &mut Command, home: &std::path::Path) {
+    cmd.env("HOME", home)
+        .env("XDG_CONFIG_HOME", home.join("config"))
+        .env("XDG_DATA_HOME", home.join("data"))
+        .env("XDG_CACHE_HOME", home.join("cache"));
+}
+
+#[test]
+fn test_pr_view_json_from_fixture() {
+    bb("pr_view")
+        .args([
+            "pr",
+            "view",
+            "42",
+            "-R",
+            "acme/widgets",
+            "--json",
+            "title,participants.approved",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "\"title\": \"Add widget caching\"",
+        ))
+        .stdout(predicate::str::contains("\"approved\": true"))
+        .stdout(predicate::str::contains("\"state\"").not());
+}
+
+#[test]
+fn test_pr_view_json_links_and_misplaced_arguments() {
+    bb("pr_view")
+        .args(["pr", "view", "42", "-R", "acme/widgets"])
+        .args(["--json", "links", "--jq", ".links.html.href"])
+        .assert()
+        .success()
+        .stdout("https://bitbucket.org/acme/widgets/pull-requests/42\n");
+
+    bb("pr_view")
+        .args(["pr", "view", "-R", "acme/widgets", "--json", "42"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid JSON field: \"42\""));
+}
+
+#[test]
+fn test_json_without_fields_lists_fields() {
+    bb("pr_view")
+        .args(["pr", "view", "42", "-R", "acme/widgets", "--json"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Specify one or more comma-separated fields for `--json`",
+        ))
+        .stderr(predicate::str::contains("merge_commit"));
+}
+
+#[test]
+fn test_bare_json_with_jq_or_template_uses_whole_model() {
+    bb("pr_list")
+        .args(["pr", "list", "-R", "acme/widgets", "--json"])
+        .args(["--jq", r#".[] | select(.state=="OPEN") | .id"#])
+        .assert()
+        .success()
+        .stdout("42\n7\n19\n");
+
+    bb("pr_view")
+        .args(["pr", "view", "42", "-R", "acme/widgets", "--json"])
+        .args(["--template", "{{.title}}"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Add widget caching"));
+}
+
+#[test]
+fn test_json_unknown_field_fails() {
+    bb("pr_view")
+        .args([
+            "pr",
+            "view",
+            "42",
+            "-R",
+            "acme/widgets",
+            "--json",
+            "id,nope",
+        ])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Unknown JSON field: \"nope\""));
+}
+
+#[test]
+fn test_pr_view_table_from_fixture() {
+    bb("pr_view")
+        .args(["pr", "view", "42", "-R", "acme/widgets"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("PR #42: Add widget caching"))
+        .stdout(predicate::str::contains("feature/cache"))
+        .stdout(predicate::str::is_match(r"Mergeable:\s+Yes").unwrap());
+}
+
+#[test]
+fn test_unmatched_request_fails() {
+    bb("pr_view")
+        .args(["pr", "view", "7", "-R", "acme/widgets", "--json"])
+        .args(["--jq", ".id"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("No recorded response for GET"));
+}
+
+#[test]
+fn test_api_command_replays_fixture() {
+    bb("pr_view")
+        .args([
+            "api",
+            "/2.0/repositories/acme/widgets/pullrequests/42",
+            "--hostname",
+            "bitbucket.org",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"full_name\": \"acme/widgets\""));
+}
+
+#[test]
+fn test_request_with_secret_query_matches_redacted_fixture() {
+    bb("api_redacted")
+        .args([
+            "api",
+            "/2.0/user?access_token=secret",
+            "--hostname",
+            "bitbucket.org",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"nickname\": \"jdoe\""));
+}
+
+#[test]
+fn test_jq_filter_on_json_output() {
+    bb("pr_view")
+        .args([
+            "pr",
+            "view",
+            "42",
+            "-R",
+            "acme/widgets",
+            "--jq",
+            r#"select(.state == "OPEN") | "\(.id): \(.title)""#,
+        ])
+        .assert()
+        .success()
+        .stdout("42: Add widget caching\n");
+}
+
+#[test]
+fn test_invalid_jq_filter_fails_before_requests() {
+    bb("pr_view")
+        .args([
+            "pr",
+            "view",
+            "42",
+            "-R",
+            "acme/widgets",
+            "--jq",
+            ".[] | select(",
+        ])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid jq filter"));
+}
+
+#[test]
+fn test_template_on_pr_view() {
+    bb("pr_view")
+        .args([
+            "pr",
+            "view",
+            "42",
+            "-R",
+            "acme/widgets",
+            "--template",
+            r#"#{{.id}} {{.title}} [{{.source.branch.name}} -> {{.destination.branch.name}}]{{"\n"}}"#,
+        ])
+        .assert()
+        .success()
+        .stdout("#42 Add widget caching [feature/cache -> main]\n");
+}
+
+#[test]
+fn test_csv_format_on_pr_view() {
+    bb("pr_view")
+        .args([
+            "pr",
+            "view",
+            "42",
+            "-R",
+            "acme/widgets",
+            "--json",
+            "id,title,source.branch.name",
+            "--format",
+            "csv",
+        ])
+        .assert()
+        .success()
+        .stdout("id,title,source.branch.name\n42,Add widget caching,feature/cache\n");
+}
+
+#[test]
+fn test_pr_list_ndjson_follows_pages() {
+    bb("pr_list_pages")
+        .args(["pr", "list", "-R", "acme/widgets", "-L", "3"])
+        .args(["--json", "id", "--format", "ndjson"])
+        .assert()
+        .success()
+        .stdout("{\"id\":42}\n{\"id\":7}\n{\"id\":19}\n");
+
+    bb("pr_list_pages")
+        .args([
+            "pr",
+            "list",
+            "-R",
+            "acme/widgets",
+            "-L",
+            "3",
+            "--json",
+            "id",
+        ])
+        .args(["--jq", ".[].id"])
+        .assert()
+        .success()
+        .stdout("42\n7\n19\n");
+}
+
+#[test]
+fn test_pr_list_is_tab_separated_when_piped() {
+    bb("pr_list")
+        .args(["pr", "list", "-R", "acme/widgets"])
+        .assert()
+        .success()
+        .stdout(
+            "#42\tOPEN\tAdd widget caching\tjane\tfeature/cache → main\n\
+             #7\tOPEN\tFix gadget alignment\tjohn\tfix/align → main\n\
+             #19\tOPEN\tBump dependencies\tjane\tchore/deps → main\n",
+        );
+}
+
+#[test]
+fn test_pr_list_columns_and_sort() {
+    bb("pr_list")
+        .args([
+            "pr",
+            "list",
+            "-R",
+            "acme/widgets",
+            "--columns",
+            "id,updated_on",
+            "--sort",
+            "updated_on",
+            "--reverse",
+        ])
+        .assert()
+        .success()
+        .stdout(
+            "#7\t2026-01-12T08:00:00.000000+00:00\n\
+             #42\t2026-01-11T15:30:00.000000+00:00\n\
+             #19\t2026-01-09T10:15:00.000000+00:00\n",
+        );
+}
+
+#[test]
+fn test_pr_list_filters_are_sent_as_bbql() {
+    bb("pr_list")
+        .args([
+            "pr",
+            "list",
+            "-R",
+            "acme/widgets",
+            "--author",
+            "jane",
+            "--base",
+            "main",
+            "--search",
+            "widget cache",
+            "--updated-after",
+            "2026-01-10",
+            "--columns",
+            "id,title",
+        ])
+        .assert()
+        .success()
+        .stdout("#42\tAdd widget caching\n");
+}
+
+#[test]
+fn test_pr_list_sorts_by_created_on() {
+    bb("pr_list")
+        .args(["pr", "list", "-R", "acme/widgets"])
+        .args(["--created-after", "2026-01-10", "--sort", "created_on"])
+        .args(["--columns", "id,created_on"])
+        .assert()
+        .success()
+        .stdout(
+            "#7\t2026-01-10T12:00:00.000000+00:00\n\
+             #42\t2026-01-11T09:00:00.000000+00:00\n",
+        );
+}
+
+#[test]
+fn test_server_pr_list_pages_until_limit_matches() {
+    bb("pr_list_server")
+        .args([
+            "pr",
+            "list",
+            "-R",
+            "PROJ/widgets",
+            "--host",
+            "bitbucket.example.com",
+        ])
+        .args(["--created-after", "2026-01-10", "-L", "2"])
+        .args(["--columns", "id,title,created_on"])
+        .assert()
+        .success()
+        .stdout(
+            "#42\tAdd widget caching\t2026-01-10 09:00:00\n\
+             #40\tWarm up caches\t2026-01-10 08:00:00\n",
+        );
+}
+
+#[test]
+fn test_server_pr_list_resolves_author_and_rejects_superseded() {
+    let server = ["-R", "PROJ/widgets", "--host", "bitbucket.example.com"];
+    bb("pr_list_server_author")
+        .args(["pr", "list"])
+        .args(server)
+        .args(["--author", "jdoe@corp.com", "--columns", "id,title"])
+        .assert()
+        .success()
+        .stdout("#42\tAdd widget caching\n");
+
+    bb("pr_list_server_author")
+        .args(["pr", "list"])
+        .args(server)
+        .args(["--state", "superseded"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "--state superseded is only available on Bitbucket Cloud",
+        ));
+}
+
+#[test]
+fn test_server_pr_list_stops_at_pages_older_than_the_dates() {
+    // The page ends with a PR last updated before the date, so nothing
+    // after it can match and no second page is requested
+    bb("pr_list_server_dates")
+        .args([
+            "pr",
+            "list",
+            "-R",
+            "PROJ/widgets",
+            "--host",
+            "bitbucket.example.com",
+        ])
+        .args(["--created-after", "2026-01-10", "-L", "5"])
+        .args(["--columns", "id"])
+        .assert()
+        .success()
+        .stdout("#42\n#40\n");
+}
+
+#[test]
+fn test_pr_list_unsupported_filter_fails() {
+    bb("pr_list")
+        .args(["pr", "list", "-R", "acme/widgets", "--label", "bug"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Bitbucket pull requests have no labels",
+        ));
+}
+
+#[test]
+fn test_pr_list_unknown_column_fails() {
+    bb("pr_list")
+        .args(["pr", "list", "-R", "acme/widgets", "--columns", "id,nope"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Unknown column: \"nope\""));
+}
+
+#[test]
+fn test_inline_comment_is_anchored_to_new_line() {
+    bb("pr_inline_comment")
+        .args([
+            "pr",
+            "comment",
+            "42",
+            "-R",
+            "acme/widgets",
+            "--file",
+            "src/cache.rs",
+            "--line",
+            "12",
+            "-b",
+            "Should the fallback be cached too?",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Added comment on src/cache.rs:12 to PR #42",
+        ));
+}
+
+#[test]
+fn test_inline_comment_outside_diff_fails() {
+    bb("pr_inline_comment")
+        .args([
+            "pr",
+            "comment",
+            "42",
+            "-R",
+            "acme/widgets",
+            "--file",
+            "src/cache.rs",
+            "--line",
+            "30",
+            "-b",
+            "Typo?",
+        ])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Line 30 of src/cache.rs is not in the diff (new side). \
+             Lines you can comment on: 10-13",
+        ));
+}
+
+#[test]
+fn test_server_inline_comment_uses_anchor() {
+    bb("pr_inline_comment_server")
+        .args([
+            "pr",
+            "comment",
+            "42",
+            "-R",
+            "PROJ/widgets",
+            "--host",
+            "bitbucket.example.com",
+            "--file",
+            "src/cache.rs",
+            "--range",
+            "11-12",
+            "-b",
+            "Should the fallback be cached too?",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Added comment on src/cache.rs:11-12 to PR #42",
+        ));
+}
+
+#[test]
+fn test_pr_comments_groups_inline_comments_with_hunk() {
+    bb("pr_inline_comment")
+        .args(["pr", "comments", "42", "-R", "acme/widgets"])
+        .assert()
+        .success()
+        .stdout(
+            "#300 @jdoe (2026-01-12T09:00:00.000000+00:00):\n  Looks good overall.\n\n\
+             src/cache.rs\n\
+             @@ -10,3 +10,4 @@ impl Cache {\n     \
+             fn get(&self) -> Option<Widget> {\n\
+             -        self.load()\n\
+             +        self.lookup()\n\
+             +            .or_else(|| self.load())\n\
+             #301 @jdoe (2026-01-12T10:00:00.000000+00:00):\n  Should the fallback be cached too?\n\n",
+        );
+}
+
+#[test]
+fn test_pr_comments_render_threads_as_tree() {
+    bb("pr_comment_threads")
+        .args(["pr", "comments", "42", "-R", "acme/widgets"])
+        .assert()
+        .success()
+        .stdout(
+            "#300 @slee (2026-01-12T09:00:00.000000+00:00) [resolved]:\n  Should this be configurable?\n\n\
+             \x20   #302 @jdoe (2026-01-12T10:00:00.000000+00:00):\n      Yes, added a setting.\n\n\
+             \x20       #303 @slee (2026-01-12T11:00:00.000000+00:00):\n          Thanks!\n\n\
+             #305 @jdoe (2026-01-12T13:00:00.000000+00:00):\n  Ship it.\n\n",
+        );
+}
+
+#[test]
+fn test_pr_comment_reply_sends_parent() {
+    bb("pr_comment_threads")
+        .args([
+            "pr",
+            "comment",
+            "42",
+            "-R",
+            "acme/widgets",
+            "--reply-to",
+            "300",
+            "-b",
+            "Done in the latest push.",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Replied to comment #300 on PR #42",
+        ));
+}
+
+#[test]
+fn test_pr_comment_edit_last_updates_own_comment() {
+    bb("pr_comment_threads")
+        .args([
+            "pr",
+            "comment",
+            "42",
+            "-R",
+            "acme/widgets",
+            "--edit-last",
+            "-b",
+            "Ship it once CI is green.",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Updated comment #305 on PR #42"));
+}
+
+#[test]
+fn test_pr_comment_resolve_thread() {
+    bb("pr_comment_threads")
+        .args([
+            "pr",
+            "comment",
+            "resolve",
+            "300",
+            "--pr",
+            "42",
+            "-R",
+            "acme/widgets",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Resolved thread #300 on PR #42"));
+}
+
+#[test]
+fn test_server_comment_edit_sends_version() {
+    bb("pr_comment_threads_server")
+        .args([
+            "pr",
+            "comment",
+            "42",
+            "-R",
+            "PROJ/widgets",
+            "--host",
+            "bitbucket.example.com",
+            "--edit",
+            "501",
+            "-b",
+            "Please add a regression test.",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Updated comment #501 on PR #42"));
+}
+
+#[test]
+fn test_server_comment_delete_and_resolve_use_version() {
+    for (action, message) in [
+        (
+            ["delete", "-y"].as_slice(),
+            "Deleted comment #501 from PR #42",
+        ),
+        (["resolve"].as_slice(), "Resolved thread #501 on PR #42"),
+    ] {
+        bb("pr_comment_threads_server")
+            .args(["pr", "comment"])
+            .args(action)
+            .args([
+                "501",
+                "--pr",
+                "42",
+                "-R",
+                "PROJ/widgets",
+                "--host",
+                "bitbucket.example.com",
+            ])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains(message));
+    }
+}
+
+#[test]
+fn test_server_comments_rebuild_threads_from_activities() {
+    bb("pr_comment_threads_server")
+        .args([
+            "pr",
+            "comments",
+            "42",
+            "-R",
+            "PROJ/widgets",
+            "--host",
+            "bitbucket.example.com",
+        ])
+        .assert()
+        .success()
+        .stdout(
+            "#501 @Jane Doe (2026-01-12 09:00:00) [resolved]:\n  Please add a test.\n\n\
+             \x20   #502 @Sam Lee (2026-01-12 10:00:00):\n      Added one.\n\n",
+        );
+}
+
+#[test]
+fn test_pr_task_list_normalizes_state() {
+    bb("pr_tasks")
+        .args(["pr", "task", "list", "42", "-R", "acme/widgets"])
+        .assert()
+        .success()
+        .stdout(
+            "#7\tOPEN\tUpdate the changelog\tslee\n\
+             #8\tRESOLVED\tAdd a migration note\tjdoe\n",
+        );
+
+    bb("pr_tasks")
+        .args([
+            "pr",
+            "task",
+            "list",
+            "42",
+            "-R",
+            "acme/widgets",
+            "--state",
+            "open",
+        ])
+        .assert()
+        .success()
+        .stdout("#7\tOPEN\tUpdate the changelog\tslee\n");
+}
+
+#[test]
+fn test_pr_task_create_resolve_reopen_delete() {
+    for (action, message) in [
+        (
+            ["create", "42", "-b", "Bump the version", "--comment", "300"].as_slice(),
+            "Created task #9 on PR #42",
+        ),
+        (
+            ["resolve", "7", "--pr", "42"].as_slice(),
+            "Resolved task #7 on PR #42",
+        ),
+        (
+            ["reopen", "8", "--pr", "42"].as_slice(),
+            "Reopened task #8 on PR #42",
+        ),
+        (
+            ["delete", "8", "--pr", "42", "-y"].as_slice(),
+            "Deleted task #8 from PR #42",
+        ),
+    ] {
+        bb("pr_tasks")
+            .args(["pr", "task"])
+            .args(action)
+            .args(["-R", "acme/widgets"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains(message));
+    }
+}
+
+#[test]
+fn test_pr_merge_refuses_open_tasks_without_force() {
+    bb("pr_tasks")
+        .args(["pr", "merge", "42", "-R", "acme/widgets"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "PR #42 has 1 unresolved task. Resolve them or use --force to merge anyway.",
+        ));
+
+    bb("pr_tasks")
+        .args(["pr", "merge", "42", "-R", "acme/widgets", "--force"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Merged PR #42"));
+}
+
+#[test]
+fn test_pr_merge_auto_merges_once_requirements_are_met() {
+    bb("pr_auto_merge")
+        .args([
+            "pr",
+            "merge",
+            "42",
+            "-R",
+            "acme/widgets",
+            "--auto",
+            "--squash",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Merged PR #42"));
+}
+
+#[test]
+fn test_pr_merge_auto_stops_with_distinct_exit_codes() {
+    let cases: [(&str, &[&str], i32, &str); 4] = [
+        (
+            "43",
+            &[],
+            17,
+            "Check 'build' failed on PR #43; auto-merge cancelled",
+        ),
+        ("44", &[], 19, "PR #44 is declined; auto-merge cancelled"),
+        (
+            "45",
+            &["--timeout", "0s"],
+            18,
+            "Timed out after 0s waiting for PR #45 (0/1 approvals, 1 build running, \
+             0/1 passing builds, 1 open task)",
+        ),
+        (
+            "46",
+            &["--timeout", "1s"],
+            19,
+            "PR #46 was updated with new commits; auto-merge cancelled",
+        ),
+    ];
+
+    for (pr, extra, code, message) in cases {
+        bb("pr_auto_merge")
+            .args(["pr", "merge", pr, "-R", "acme/widgets", "--auto"])
+            .args(extra)
+            .assert()
+            .code(code)
+            .stderr(predicate::str::contains(message));
+    }
+}
+
+#[test]
+fn test_pr_merge_auto_ignores_builds_no_check_requires() {
+    bb("pr_auto_merge_optional_builds")
+        .args([
+            "pr",
+            "merge",
+            "42",
+            "-R",
+            "acme/widgets",
+            "--auto",
+            "--squash",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Merged PR #42"));
+}
+
+#[test]
+fn test_pr_mergeability_checklist_and_json() {
+    bb("pr_auto_merge")
+        .args(["pr", "mergeability", "45", "-R", "acme/widgets"])
+        .assert()
+        .success()
+        .stdout(
+            "PR #45 cannot be merged\n\n\
+             [ ] Approvals: 0/1 approvals\n\
+             [~] Builds: 1 build running, 0/1 passing builds\n\
+             [ ] Tasks: 1 open task\n",
+        );
+
+    bb("pr_auto_merge")
+        .args(["pr", "mergeability", "42", "-R", "acme/widgets", "--json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"can_merge\": true"))
+        .stdout(predicate::str::contains("\"detail\": \"1/1 approvals\""));
+}
+
+#[test]
+fn test_pr_merge_preflight_explains_refusal() {
+    bb("pr_auto_merge")
+        .args(["pr", "merge", "43", "-R", "acme/widgets"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "PR #43 cannot be merged:\n  Builds: failed: build\n\
+             Run 'bb pr mergeability 43' for the full checklist",
+        ));
+}
+
+#[test]
+fn test_pr_reviewers_resolve_from_workspace_members() {
+    let home = tempfile::tempdir().unwrap();
+    bb_in_home("pr_reviewers", home.path())
+        .args(["pr", "edit", "42", "-R", "acme/widgets"])
+        .args(["--add-reviewer", "jane roe", "--remove-reviewer", "@asmith"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Updated PR #42"));
+
+    bb_in_home("pr_reviewers", home.path())
+        .args([
+            "pr",
+            "create",
+            "-R",
+            "acme/widgets",
+            "--head",
+            "feature/review",
+        ])
+        .args(["--base", "main", "--title", "WID-14 Review me"])
+        .args(["-r", "@jdoe", "-r", "Jane Doe"])
+        .args(["-r", "{cccc0000-0000-0000-0000-000000000009}"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Created PR #45"));
+
+    bb_in_home("pr_reviewers", home.path())
+        .args(["pr", "list", "-R", "acme/widgets", "--reviewer", "@asmith"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Add widget caching"));
+
+    bb_in_home("pr_reviewers", home.path())
+        .args(["pr", "list", "-R", "acme/widgets", "--reviewer", "Jane"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "'Jane' matches several users: Jane Doe (@jdoe), Jane Roe (@jroe)",
+        ));
+
+    bb_in_home("pr_reviewers", home.path())
+        .args(["pr", "list", "-R", "acme/widgets", "--reviewer", "@nobody"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "No user matching '@nobody' found in acme",
+        ));
+}
+
+#[test]
+fn test_pr_reviewers_by_email_need_a_filtered_member_list() {
+    bb("pr_reviewers_email")
+        .args(["pr", "list", "-R", "acme/widgets"])
+        .args(["--reviewer", "alice@corp.com"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Add widget caching"));
+
+    // Without admin rights the email filter is ignored and every member
+    // comes back
+    bb("pr_reviewers_email")
+        .args(["pr", "list", "-R", "acme/widgets"])
+        .args(["--reviewer", "jdoe@corp.com"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Looking up 'jdoe@corp.com' by email needs workspace admin access in acme",
+        ));
+}
+
+#[test]
+fn test_server_pr_reviewers_expand_groups_and_emails() {
+    let server = ["-R", "PROJ/widgets", "--host", "bitbucket.example.com"];
+
+    let home = tempfile::tempdir().unwrap();
+    bb_in_home("pr_reviewers_server", home.path())
+        .args(["pr", "edit", "42"])
+        .args(["--add-reviewer", "widgets-core"])
+        .args(["--remove-reviewer", "jdoe@corp.com"])
+        .args(server)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Updated PR #42"));
+
+    // A new title is sent with the version read and the current reviewers
+    bb_in_home("pr_reviewers_server", home.path())
+        .args(["pr", "edit", "42", "--title", "Cache widget lookups"])
+        .args(server)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Updated PR #42"));
+
+    bb_in_home("pr_reviewers_server", home.path())
+        .args(["pr", "list", "--reviewer", "ann lee"])
+        .args(server)
+        .assert()
+        .success();
+
+    bb_in_home("pr_reviewers_server", home.path())
+        .args(["pr", "list", "--reviewer", "widgets-core"])
+        .args(server)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "'widgets-core' is a reviewer group of 2 users",
+        ));
+}
+
+#[test]
+fn test_pr_checks_lists_cloud_statuses() {
+    bb("pr_checks")
+        .args(["pr", "checks", "42", "-R", "acme/widgets"])
+        .assert()
+        .success()
+        .stdout(
+            "SUCCESSFUL\tBuild #12\t\t2m 5s\thttps://ci.example.com/build/12\n\
+             INPROGRESS\tDeploy preview\t\t\thttps://ci.example.com/deploy/3\n",
+        );
+}
+
+#[test]
+fn test_pr_checks_watch_redraws_until_checks_finish() {
+    bb("pr_checks_watch")
+        .args(["pr", "checks", "42", "-R", "acme/widgets"])
+        .args(["--watch", "--interval", "1s", "--steps"])
+        .assert()
+        .success()
+        .stdout(
+            "INPROGRESS\tPipeline #12 (1/3 steps, running Test)\t\t\t\
+             https://bitbucket.org/acme/widgets/pipelines/results/12\n\
+             SUCCESSFUL\tLint\t\t30s\thttps://ci.example.com/lint/7\n\
+             SUCCESSFUL\tPipeline #12\t\t3m 20s\thttps://bitbucket.org/acme/widgets/pipelines/results/12\n\
+             SUCCESSFUL\tLint\t\t30s\thttps://ci.example.com/lint/7\n",
+        );
+}
+
+#[test]
+fn test_pr_checks_exit_codes_gate_ci() {
+    let cases: [(&str, &[&str], i32, &str); 4] = [
+        ("43", &[], 17, "1 check failed on PR #43: Build #13"),
+        (
+            "43",
+            &["--watch", "--fail-fast"],
+            17,
+            "1 check failed on PR #43: Build #13",
+        ),
+        (
+            "44",
+            &["--watch", "--timeout", "0s"],
+            18,
+            "Timed out after 0s waiting for checks on PR #44 (1 still running)",
+        ),
+        (
+            "45",
+            &["--watch", "--timeout", "0s"],
+            18,
+            "Timed out after 0s waiting for checks on PR #45 (no checks reported)",
+        ),
+    ];
+
+    for (pr, extra, code, message) in cases {
+        bb("pr_checks_watch")
+            .args(["pr", "checks", pr, "-R", "acme/widgets"])
+            .args(extra)
+            .assert()
+            .code(code)
+            .stderr(predicate::str::contains(message));
+    }
+
+    bb("pr_checks_watch")
+        .args([
+            "pr",
+            "checks",
+            "44",
+            "-R",
+            "acme/widgets",
+            "--timeout",
+            "5m",
+        ])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--watch"));
+}
+
+#[test]
+fn test_pr_checks_watch_waits_for_checks_to_appear() {
+    bb("pr_checks_watch")
+        .args(["pr", "checks", "45", "-R", "acme/widgets"])
+        .assert()
+        .success()
+        .stdout("No build checks found for PR #45\n");
+
+    bb("pr_checks_watch")
+        .args(["pr", "checks", "45", "-R", "acme/widgets"])
+        .args(["--watch", "--allow-empty"])
+        .assert()
+        .success()
+        .stdout("No build checks found for PR #45\n");
+}
+
+#[test]
+fn test_server_pr_checks_read_commit_build_status() {
+    let server = ["-R", "PROJ/widgets", "--host", "bitbucket.example.com"];
+
+    let home = tempfile::tempdir().unwrap();
+    bb_in_home("pr_checks_server", home.path())
+        .args(["pr", "checks", "42"])
+        .args(server)
+        .assert()
+        .success()
+        .stdout(
+            "SUCCESSFUL\tWidgets build\trequired\t2m 5s\thttps://ci.example.com/WID-BUILD/12\n\
+             INPROGRESS\tLint\t\t\thttps://ci.example.com/WID-LINT/7\n\
+             PENDING\tWID-SECURITY\trequired\t\t\n",
+        );
+
+    let home = tempfile::tempdir().unwrap();
+    bb_in_home("pr_checks_server", home.path())
+        .args(["pr", "checks", "42", "--json"])
+        .args(server)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"key\": \"WID-BUILD\""))
+        .stdout(predicate::str::contains("\"required\": true"))
+        .stdout(predicate::str::contains("\"duration\": 125"));
+}
+
+#[test]
+fn test_server_pr_checks_resolve_branching_model_conditions() {
+    let server = ["-R", "PROJ/widgets", "--host", "bitbucket.example.com"];
+
+    let home = tempfile::tempdir().unwrap();
+    bb_in_home("pr_checks_server_model", home.path())
+        .args(["pr", "checks", "42"])
+        .args(server)
+        .assert()
+        .success()
+        .stdout(
+            "SUCCESSFUL\tWidgets build\trequired\t2m 5s\thttps://ci.example.com/WID-BUILD/12\n\
+             INPROGRESS\tLint\t\t\thttps://ci.example.com/WID-LINT/7\n",
+        );
+}
+
+#[test]
+fn test_server_pr_checks_without_access_to_required_builds() {
+    let server = ["-R", "PROJ/widgets", "--host", "bitbucket.example.com"];
+
+    let home = tempfile::tempdir().unwrap();
+    bb_in_home("pr_checks_server_forbidden", home.path())
+        .args(["pr", "checks", "42"])
+        .args(server)
+        .assert()
+        .success()
+        .stdout(
+            "SUCCESSFUL\tWidgets build\t\t2m 5s\thttps://ci.example.com/WID-BUILD/12\n\
+             INPROGRESS\tLint\t\t\thttps://ci.example.com/WID-LINT/7\n",
+        )
+        .stderr(predicate::str::contains(
+            "Cannot read the repository's required builds (permission denied)",
+        ));
+}
+
+#[test]
+fn test_server_pr_merge_auto_uses_native_auto_merge() {
+    let server = ["-R", "PROJ/widgets", "--host", "bitbucket.example.com"];
+
+    let home = tempfile::tempdir().unwrap();
+    bb_in_home("pr_auto_merge_server", home.path())
+        .args(["pr", "merge", "42", "--auto", "--squash"])
+        .args(server)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Auto-merge enabled for PR #42"));
+
+    let home = tempfile::tempdir().unwrap();
+    bb_in_home("pr_drafts_server_old", home.path())
+        .args(["pr", "merge", "42", "--auto"])
+        .args(server)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "requires Bitbucket DC 8.15+ (this server runs 8.9)",
+        ));
+
+    bb("pr_auto_merge_server")
+        .args(["pr", "merge", "42", "--auto", "--timeout", "30m"])
+        .args(server)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "--timeout only applies to --auto on Bitbucket Cloud",
+        ));
+}
+
+#[test]
+fn test_server_tasks_use_blocker_comments() {
+    let server = ["-R", "PROJ/widgets", "--host", "bitbucket.example.com"];
+
+    bb("pr_tasks_server")
+        .args(["pr", "task", "list", "42"])
+        .args(server)
+        .assert()
+        .success()
+        .stdout("#601\tOPEN\tUpdate the changelog\tJane Doe\n");
+
+    bb("pr_tasks_server")
+        .args(["pr", "task", "create", "42", "-b", "Bump the version"])
+        .args(server)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Created task #602 on PR #42"));
+
+    bb("pr_tasks_server")
+        .args(["pr", "task", "resolve", "601", "--pr", "42"])
+        .args(server)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Resolved task #601 on PR #42"));
+}
+
+#[test]
+fn test_server_pr_merge_without_blocker_comments_skips_task_check() {
+    let home = tempfile::tempdir().unwrap();
+    bb_in_home("pr_merge_server_old", home.path())
+        .args(["pr", "merge", "42", "-R", "PROJ/widgets"])
+        .args(["--host", "bitbucket.example.com"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Merged PR #42"));
+
+    let home = tempfile::tempdir().unwrap();
+    bb_in_home("pr_merge_server_old", home.path())
+        .args(["pr", "task", "list", "42", "-R", "PROJ/widgets"])
+        .args(["--host", "bitbucket.example.com"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Pull request tasks requires Bitbucket DC 7.2+ (this server runs 7.1.4)",
+        ));
+}
+
+#[test]
+fn test_server_deploy_is_not_supported() {
+    bb("pr_merge_server_old")
+        .args(["deploy", "list", "-R", "PROJ/widgets"])
+        .args(["--host", "bitbucket.example.com"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Deployments on Bitbucket Server/DC are not supported yet",
+        ));
+}
+
+#[test]
+fn test_server_archive_needs_a_detected_version() {
+    let home = tempfile::tempdir().unwrap();
+    bb_in_home("repo_archive_server_unknown", home.path())
+        .args(["repo", "archive", "-R", "PROJ/widgets"])
+        .args(["--host", "bitbucket.example.com"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Could not detect the Bitbucket Server version of bitbucket.example.com",
+        ))
+        .stderr(predicate::str::contains(
+            "Archived repositories requires Bitbucket DC 8.0+, but the server version could not be detected",
+        ));
+}
+
+#[test]
+fn test_server_pr_view_shows_open_tasks() {
+    bb("pr_tasks_server")
+        .args([
+            "pr",
+            "view",
+            "42",
+            "-R",
+            "PROJ/widgets",
+            "--host",
+            "bitbucket.example.com",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Comments:"))
+        .stdout(predicate::str::is_match(r"Open tasks:\s+1").unwrap())
+        .stdout(
+            predicate::str::is_match(
+                r"Mergeable:\s+No \(This pull request has 1 incomplete task\.\)",
+            )
+            .unwrap(),
+        );
+}
+
+#[test]
+fn test_server_pr_view_survives_unreadable_mergeability() {
+    bb("pr_view_server")
+        .args([
+            "pr",
+            "view",
+            "42",
+            "-R",
+            "PROJ/widgets",
+            "--host",
+            "bitbucket.example.com",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Add widget caching"))
+        .stdout(predicate::str::is_match(r"Mergeable:\s+Unknown").unwrap())
+        .stderr(predicate::str::contains(
+            "Could not check whether the PR can be merged",
+        ))
+        .stderr(predicate::str::contains(
+            "You are not permitted to access this resource",
+        ));
+}
+
+#[test]
+fn test_server_pr_mergeability_lists_vetoes() {
+    bb("pr_tasks_server")
+        .args([
+            "pr",
+            "mergeability",
+            "42",
+            "-R",
+            "PROJ/widgets",
+            "--host",
+            "bitbucket.example.com",
+        ])
+        .assert()
+        .success()
+        .stdout(
+            "PR #42 cannot be merged\n\n\
+             [x] Conflicts: none\n\
+             [ ] Incomplete tasks: This pull request has 1 incomplete task.\n",
+        );
+}
+
+/// Saves a pending review of acme/widgets#42 under `home`'s data directory
+fn write_pending_review(home: &std::path::Path, comments: &str) -> std::path::PathBuf {
+    let path = home.join("data/bb/reviews/bitbucket.org/acme/widgets/42.json");
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(
+        &path,
+        format!(
+            "{{\"pr\": 42, \"updated_at\": \"2026-01-12T09:00:00Z\", \"comments\": {}}}",
+            comments
+        ),
+    )
+    .unwrap();
+    path
+}
+
+#[test]
+fn test_pr_review_submits_pending_comments_and_tasks() {
+    let home = tempfile::tempdir().unwrap();
+    let pending = write_pending_review(
+        home.path(),
+        r#"[
+            {"path": "src/cache.rs", "line": 12, "side": "new", "body": "Should the fallback be cached too?"},
+            {"path": "src/cache.rs", "line": 11, "side": "old", "body": "Was load() ever slow?", "task": true}
+        ]"#,
+    );
+
+    bb_in_home("pr_review_pending", home.path())
+        .args([
+            "pr",
+            "review",
+            "42",
+            "-R",
+            "acme/widgets",
+            "--approve",
+            "-b",
+            "Looks good apart from the cache question.",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Posted 2 review comments on PR #42",
+        ))
+        .stdout(predicate::str::contains("Approved PR #42"));
+
+    assert!(!pending.exists());
+}
+
+#[test]
+fn test_pr_review_retry_only_creates_the_missing_task() {
+    // The comment was posted before its task failed; its anchor no longer
+    // needs to fit the diff and it must not be posted again
+    let home = tempfile::tempdir().unwrap();
+    let pending = write_pending_review(
+        home.path(),
+        r#"[{"path": "src/cache.rs", "line": 40, "side": "old", "body": "Was load() ever slow?",
+             "task": true, "posted_id": 311}]"#,
+    );
+
+    bb_in_home("pr_review_pending", home.path())
+        .args(["pr", "review", "42", "-R", "acme/widgets", "--comment"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Posted 1 review comment on PR #42",
+        ));
+
+    assert!(!pending.exists());
+}
+
+#[test]
+fn test_pr_review_keeps_pending_comments_that_no_longer_fit() {
+    let home = tempfile::tempdir().unwrap();
+    let pending = write_pending_review(
+        home.path(),
+        r#"[{"path": "src/cache.rs", "line": 40, "side": "new", "body": "Moved?"}]"#,
+    );
+
+    bb_in_home("pr_review_pending", home.path())
+        .args(["pr", "review", "42", "-R", "acme/widgets", "--comment"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Pending comment on src/cache.rs:40 no longer matches the diff",
+        ))
+        .stderr(predicate::str::contains(
+            "Line 40 of src/cache.rs is not in the diff",
+        ));
+
+    assert!(pending.exists());
+
+    bb_in_home("pr_review_pending", home.path())
+        .args(["pr", "review", "42", "-R", "acme/widgets", "--discard"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Discarded pending review of PR #42 (1 comment)",
+        ));
+
+    assert!(!pending.exists());
+}
+
+#[test]
+fn test_pipeline_logs_stream_when_piped() {
+    bb("pipeline_logs")
+        .args(["pipeline", "logs", "12", "-R", "acme/widgets"])
+        .assert()
+        .success()
+        .stdout(
+            "\n=== Step: Build ===\n\ncargo build\nFinished\n\
+             \n=== Step: Test ===\n\n(logs not available: 404 Not Found)\n",
+        );
+}
+
+#[test]
+fn test_artifact_download_is_replayed() {
+    let dir = tempfile::tempdir().unwrap();
+    bb("artifact_download")
+        .args([
+            "artifact",
+            "download",
+            "report.txt",
+            "-p",
+            "12",
+            "-R",
+            "acme/widgets",
+        ])
+        .arg("-d")
+        .arg(dir.path())
+        .assert()
+        .success();
+
+    assert_eq!(
+        std::fs::read_to_string(dir.path().join("report.txt")).unwrap(),
+        "12 tests passed\n"
+    );
+}
+
+#[test]
+fn test_binary_artifact_download_is_replayed() {
+    let dir = tempfile::tempdir().unwrap();
+    bb("artifact_download_binary")
+        .args(["artifact", "download", "logo.png", "-p", "12"])
+        .args(["-R", "acme/widgets"])
+        .arg("-d")
+        .arg(dir.path())
+        .assert()
+        .success();
+
+    assert_eq!(
+        std::fs::read(dir.path().join("logo.png")).unwrap(),
+        [
+            0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n', 0, 0, 0, 0x0d, 0xff, 0xfe, 0x80,
+            0x00
+        ]
+    );
+}
+
+#[test]
+fn test_pr_review_interactive_needs_a_terminal() {
+    bb("pr_review_pending")
+        .args(["pr", "review", "42", "-R", "acme/widgets", "--interactive"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--interactive needs a terminal"));
+}
+
+#[test]
+fn test_pr_create_sends_draft_flag() {
+    bb("pr_drafts")
+        .args([
+            "pr",
+            "create",
+            "-R",
+            "acme/widgets",
+            "--head",
+            "feature/warm-up",
+            "--base",
+            "main",
+            "--title",
+            "WID-13 Cache warm-up",
+            "--draft",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Created PR #44"));
+}
+
+#[test]
+fn test_pr_list_shows_and_filters_drafts() {
+    bb("pr_drafts")
+        .args(["pr", "list", "-R", "acme/widgets"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("#42\tOPEN\t"))
+        .stdout(predicate::str::contains("#44\tDRAFT\t"));
+
+    bb("pr_drafts")
+        .args(["pr", "list", "-R", "acme/widgets", "--draft"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("#44\tDRAFT\t"))
+        .stdout(predicate::str::contains("#42").not());
+}
+
+#[test]
+fn test_pr_ready_clears_draft() {
+    bb("pr_drafts")
+        .args(["pr", "ready", "44", "-R", "acme/widgets"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("PR #44 is ready for review"));
+
+    bb("pr_drafts")
+        .args(["pr", "ready", "44", "-R", "acme/widgets", "--undo"])
+        .assert()
+        .success()
+        .stdout("PR #44 is already a draft\n");
+}
+
+#[test]
+fn test_server_pr_ready_sends_version() {
+    let home = tempfile::tempdir().unwrap();
+    bb_in_home("pr_drafts_server", home.path())
+        .args([
+            "pr",
+            "ready",
+            "42",
+            "-R",
+            "PROJ/widgets",
+            "--host",
+            "bitbucket.example.com",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("PR #42 is ready for review"));
+}
+
+#[test]
+fn test_server_drafts_need_dc_8_18() {
+    let server = ["-R", "PROJ/widgets", "--host", "bitbucket.example.com"];
+    let cases: [&[&str]; 3] = [
+        &["pr", "ready", "42"],
+        &["pr", "list", "--draft"],
+        &[
+            "pr",
+            "create",
+            "--head",
+            "feature/warm-up",
+            "--base",
+            "main",
+            "--title",
+            "Warm-up",
+            "--draft",
+        ],
+    ];
+
+    for args in cases {
+        let home = tempfile::tempdir().unwrap();
+        bb_in_home("pr_drafts_server_old", home.path())
+            .args(args)
+            .args(server)
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(
+                "Draft pull requests requires Bitbucket DC 8.18+ (this server runs 8.9)",
+            ));
+    }
+}
+
+#[test]
+fn test_color_flag_overrides_piped_output() {
+    bb("pr_view")
+        .args([
+            "pr",
+            "view",
+            "42",
+            "-R",
+            "acme/widgets",
+            "--color",
+            "always",
+        ])
+        .env_remove("BB_THEME")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\u{1b}["));
+}
+
+#[test]
+fn test_no_color_wins_over_clicolor_force() {
+    bb("pr_view")
+        .args(["pr", "view", "42", "-R", "acme/widgets"])
+        .env("CLICOLOR_FORCE", "1")
+        .env("NO_COLOR", "1")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Add widget caching"))
+        .stdout(predicate::str::contains("\u{1b}[").not());
+}
+
+#[test]
+fn test_bad_theme_settings_warn_and_fall_back() {
+    let (mut cmd, _home) = bb_with_config(
+        "pr_view",
+        "[core]\ntheme = \"neon\"\n\n[colors]\nOPEN = \"chartreuse\"\n",
+    );
+    cmd.args(["pr", "view", "42", "-R", "acme/widgets"])
+        .env_remove("BB_THEME")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Add widget caching"))
+        .stderr(predicate::str::contains(
+            "Invalid theme 'neon'. Valid themes: default, plain; using default",
+        ))
+        .stderr(predicate::str::contains(
+            "Invalid color for 'OPEN' in [colors]",
+        ));
+
+    let (mut cmd, _home) = bb_with_config("pr_view", "");
+    cmd.args(["config", "set", "theme", "neon"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Invalid value for theme. Valid values: default, plain",
+        ));
+}
+
+/// A `bb` command using a config file with the given contents.
+fn bb_with_config(fixture_dir: &str, config: &str) -> (Command, tempfile::TempDir) {
+    let home = tempfile::tempdir().unwrap();
+    let config_dir = home.path().join("config").join("bb");
+    std::fs::create_dir_all(&config_dir).unwrap();
+    std::fs::write(config_dir.join("config.toml"), config).unwrap();
+
+    let cmd = bb_in_home(fixture_dir, home.path());
+    (cmd, home)
+}
+
+/// Runs `bb` with its config and data directories under `home`
+fn bb_in_home(fixture_dir: &str, home: &std::path::Path) -> Command {
+    let mut cmd = bb(fixture_dir);
+    isolate_home(&mut cmd, home);
+    cmd
+}
+
+#[test]
+fn test_alias_expands_before_parsing() {
+    let (mut cmd, _home) = bb_with_config(
+        "pr_view",
+        "[aliases]\npv = \"pr view $1 -R acme/widgets --json\"\n",
+    );
+    cmd.args(["pv", "42", "title"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "\"title\": \"Add widget caching\"",
+        ));
+}
+
+#[test]
+fn test_shell_alias_gets_arguments() {
+    let (mut cmd, _home) =
+        bb_with_config("pr_view", "[aliases]\nhi = \"!echo hello $1; exit 3\"\n");
+    cmd.args(["hi", "world"])
+        .assert()
+        .code(3)
+        .stdout("hello world\n");
+}
+
+#[test]
+fn test_recursive_alias_fails() {
+    let (mut cmd, _home) = bb_with_config("pr_view", "[aliases]\na = \"b\"\nb = \"a\"\n");
+    cmd.arg("a")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("recursive: a -> b -> a"));
+}
+
+/// Puts a `bb-<name>` shell script on `PATH` and isolates the extension dir
+#[cfg(unix)]
+fn bb_with_extension(fixture_dir: &str, name: &str, script: &str) -> (Command, tempfile::TempDir) {
+    use std::os::unix::fs::PermissionsExt;
+
+    let (mut cmd, home) = bb_with_config(fixture_dir, "");
+    let bin = home.path().join("bin");
+    std::fs::create_dir_all(&bin).unwrap();
+    let path = bin.join(format!("bb-{}", name));
+    std::fs::write(&path, format!("#!/bin/sh\n{}\n", script)).unwrap();
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+    let system_path = std::env::var_os("PATH").unwrap_or_default();
+    let mut paths = vec![bin];
+    paths.extend(std::env::split_paths(&system_path));
+    cmd.env("PATH", std::env::join_paths(paths).unwrap());
+    (cmd, home)
+}
+
+#[test]
+#[cfg(unix)]
+fn test_unknown_command_runs_extension_with_context() {
+    let (mut cmd, _home) = bb_with_extension(
+        "extension_token_refresh",
+        "hello",
+        "echo \"$BB_HOST $BB_REPO $BB_HOST_TYPE $BB_JSON $BB_TOKEN $*\"; exit 5",
+    );
+    cmd.args(["-R", "acme/widgets", "hello", "a", "--flag"])
+        .assert()
+        .code(5)
+        .stdout("bitbucket.org acme/widgets cloud 0 refreshed-token a --flag\n");
+}
+
+#[test]
+#[cfg(unix)]
+fn test_extension_gets_no_token_when_refresh_fails() {
+    let (mut cmd, _home) = bb_with_extension(
+        "extension_token_refresh_failed",
+        "hello",
+        "echo \"token=${BB_TOKEN-unset}\"",
+    );
+    cmd.args(["-R", "acme/widgets", "hello"])
+        .assert()
+        .success()
+        .stdout("token=unset\n");
+}
+
+#[test]
+#[cfg(unix)]
+fn test_extensions_listed_in_help() {
+    let (mut cmd, _home) = bb_with_extension("pr_view", "hello", "exit 0");
+    cmd.arg("--help")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Extension commands:"))
+        .stdout(predicate::str::contains("  hello"));
+}
+
+#[test]
+fn test_unknown_command_without_extension_fails() {
+    bb("pr_view")
+        .arg("no-such-command")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "unknown command 'no-such-command'",
+        ));
+}
+
+/// Runs git in `dir`, failing the test if it fails
+#[cfg(unix)]
+fn git_in(dir: &std::path::Path, args: &[&str]) {
+    let status = std::process::Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+        .args(args)
+        .status()
+        .unwrap();
+    assert!(status.success(), "git {:?} failed", args);
+}
+
+/// Commits a `bb-hello` script that prints `message` to the repo at `dir`
+#[cfg(unix)]
+fn commit_hello_script(dir: &std::path::Path, message: &str) {
+    use std::os::unix::fs::PermissionsExt;
+
+    let script = dir.join("bb-hello");
+    std::fs::write(&script, format!("#!/bin/sh\necho {}\n", message)).unwrap();
+    std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+    git_in(dir, &["add", "bb-hello"]);
+    git_in(dir, &["commit", "-q", "-m", message]);
+}
+
+#[test]
+#[cfg(unix)]
+fn test_extension_install_upgrade_and_pin_from_git() {
+    let home = tempfile::tempdir().unwrap();
+    let source = home.path().join("src").join("bb-hello");
+    std::fs::create_dir_all(&source).unwrap();
+    git_in(&source, &["-c", "init.defaultBranch=main", "init", "-q"]);
+    commit_hello_script(&source, "v1");
+    git_in(&source, &["tag", "v1"]);
+    let source_arg = source.to_str().unwrap();
+
+    bb_in_home("pr_view", home.path())
+        .args(["extension", "install", source_arg])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Installed extension 'hello'"));
+
+    bb_in_home("pr_view", home.path())
+        .arg("hello")
+        .assert()
+        .success()
+        .stdout("v1\n");
+
+    let lock_path = home.path().join("data/bb/extensions/extensions.lock");
+    let lock = std::fs::read_to_string(&lock_path).unwrap();
+    assert!(lock.contains("[extensions.hello]"));
+    assert!(lock.contains(&format!("source = \"{}\"", source_arg)));
+    assert!(lock.contains("kind = \"script\""));
+    assert!(lock.contains("ref = \"main\""));
+    assert!(lock.contains("pinned = false"));
+
+    commit_hello_script(&source, "v2");
+    bb_in_home("pr_view", home.path())
+        .args(["extension", "upgrade", "hello"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Upgraded hello"));
+    bb_in_home("pr_view", home.path())
+        .arg("hello")
+        .assert()
+        .success()
+        .stdout("v2\n");
+
+    bb_in_home("pr_view", home.path())
+        .args(["extension", "remove", "hello", "--no-prompt"])
+        .assert()
+        .success();
+    let lock = std::fs::read_to_string(&lock_path).unwrap();
+    assert!(!lock.contains("hello"));
+
+    bb_in_home("pr_view", home.path())
+        .args(["extension", "install", source_arg, "--pin", "v1"])
+        .assert()
+        .success();
+    bb_in_home("pr_view", home.path())
+        .args(["extension", "upgrade", "--all"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("hello is pinned to v1"));
+    bb_in_home("pr_view", home.path())
+        .arg("hello")
+        .assert()
+        .success()
+        .stdout("v1\n");
+}
+
+#[test]
+fn test_extension_install_refuses_builtin_name() {
+    bb("pr_view")
+        .args(["extension", "install", "myteam/bb-pr"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("'pr' is a built-in command"));
+}
+
+#[test]
+fn test_extension_install_refuses_option_like_pins() {
+    bb("pr_view")
+        .args(["extension", "install", "myteam/bb-lint", "--pin=--orphan"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Invalid pin '--orphan': a branch, tag or commit cannot start with '-'",
+        ));
+}
+
+#[test]
+#[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+fn test_extension_install_prefers_platform_download() {
+    let home = tempfile::tempdir().unwrap();
+
+    bb_in_home("extension_download", home.path())
+        .args(["extension", "install", "acme/bb-hello", "--json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"kind\": \"binary\""))
+        .stdout(predicate::str::contains(
+            "\"asset\": \"bb-hello-v1.0.0-linux-amd64\"",
+        ));
+
+    bb_in_home("extension_download", home.path())
+        .arg("hello")
+        .assert()
+        .success()
+        .stdout("prebuilt\n");
+}
+
+#[test]
+#[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+fn test_extension_upgrade_replaces_reuploaded_download() {
+    let home = tempfile::tempdir().unwrap();
+
+    bb_in_home("extension_download", home.path())
+        .args(["extension", "install", "acme/bb-hello"])
+        .assert()
+        .success();
+
+    // Same file name, but uploaded again
+    bb_in_home("extension_download_replaced", home.path())
+        .args(["extension", "upgrade", "hello"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Upgraded hello"));
+    bb_in_home("extension_download_replaced", home.path())
+        .arg("hello")
+        .assert()
+        .success()
+        .stdout("rebuilt\n");
+
+    // The new binary was renamed into place, leaving no partial download
+    let dir = home.path().join("data/bb/extensions/bb-hello");
+    let files: Vec<_> = std::fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name())
+        .collect();
+    assert_eq!(files, ["bb-hello"]);
+
+    bb_in_home("extension_download_replaced", home.path())
+        .args(["extension", "upgrade", "hello"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("hello is up to date"));
+}
+
+/// Puts a `bb-hello` extension with a `bb-extension.toml` on `PATH`
+#[cfg(unix)]
+fn bb_with_manifest_extension(manifest: &str) -> (Command, tempfile::TempDir) {
+    use std::os::unix::fs::PermissionsExt;
+
+    let (mut cmd, home) = bb_with_config("pr_view", "");
+    let dir = home.path().join("src").join("bb-hello");
+    std::fs::create_dir_all(&dir).unwrap();
+    let script = dir.join("bb-hello");
+    std::fs::write(&script, "#!/bin/sh\necho hello\n").unwrap();
+    std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+    std::fs::write(dir.join("bb-extension.toml"), manifest).unwrap();
+
+    let system_path = std::env::var_os("PATH").unwrap_or_default();
+    let mut paths = vec![dir];
+    paths.extend(std::env::split_paths(&system_path));
+    cmd.env("PATH", std::env::join_paths(paths).unwrap());
+    (cmd, home)
+}
+
+#[test]
+#[cfg(unix)]
+fn test_extension_manifest_shown_in_info_and_list() {
+    let manifest = "description = \"Say hello\"\nversion = \"1.2.0\"\nscopes = [\"repository\"]\n\n[commands]\nworld = \"Greet the world\"\n";
+
+    let (mut cmd, _home) = bb_with_manifest_extension(manifest);
+    cmd.args(["extension", "info", "hello", "--json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"description\": \"Say hello\""))
+        .stdout(predicate::str::contains("\"version\": \"1.2.0\""))
+        .stdout(predicate::str::contains("\"world\": \"Greet the world\""))
+        .stdout(predicate::str::contains("\"compatible\": true"));
+
+    let (mut cmd, _home) = bb_with_manifest_extension(manifest);
+    cmd.args(["extension", "list"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("hello\tSay hello\t"))
+        .stdout(predicate::str::contains("extension.toml").not());
+
+    let (mut cmd, _home) = bb_with_manifest_extension(manifest);
+    cmd.arg("hello").assert().success().stdout("hello\n");
+}
+
+#[test]
+#[cfg(unix)]
+fn test_extension_requiring_newer_bb_is_refused() {
+    let (mut cmd, _home) = bb_with_manifest_extension("min_bb_version = \"99.0.0\"\n");
+    cmd.arg("hello")
+        .assert()
+        .failure()
+        .stdout("")
+        .stderr(predicate::str::contains(
+            "Extension 'hello' requires bb 99.0.0 or newer",
+        ));
+}
+
+#[test]
+#[cfg(unix)]
+fn test_extension_with_invalid_manifest_is_refused() {
+    let manifest = "min_bb_version = [\"99.0.0\"]\n";
+
+    let (mut cmd, _home) = bb_with_manifest_extension(manifest);
+    cmd.arg("hello")
+        .assert()
+        .failure()
+        .stdout("")
+        .stderr(predicate::str::contains(
+            "Not running extension 'hello': Invalid extension manifest",
+        ));
+
+    let (mut cmd, _home) = bb_with_manifest_extension(manifest);
+    cmd.args(["extension", "list"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("hello"))
+        .stderr(predicate::str::contains("Invalid extension manifest"));
+}
+
+/// Writes an executable `sh` script
+#[cfg(unix)]
+fn write_script(path: &std::path::Path, body: &str) {
+    use std::os::unix::fs::PermissionsExt;
+
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, format!("#!/bin/sh\n{}\n", body)).unwrap();
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755)).unwrap();
+}
+
+#[test]
+#[cfg(unix)]
+fn test_pre_hook_can_reject_pr_create() {
+    let config = "[hooks.\"pr create\"]\npre = [\"hooks/require-jira-key.sh\"]\n";
+    let pr_create = |title: &str| {
+        let (mut cmd, home) = bb_with_config("pr_hooks", config);
+        write_script(
+            &home.path().join("config/bb/hooks/require-jira-key.sh"),
+            "grep -q '\"title\":\"[A-Z][A-Z]*-[0-9][0-9]* ' || { echo 'PR titles need a Jira key' >&2; exit 1; }",
+        );
+        cmd.current_dir(home.path()).args([
+            "pr",
+            "create",
+            "-R",
+            "acme/widgets",
+            "--head",
+            "feature/cache",
+            "--base",
+            "main",
+            "--title",
+            title,
+        ]);
+        (cmd, home)
+    };
+
+    let (mut cmd, _home) = pr_create("Add widget caching");
+    cmd.assert()
+        .failure()
+        .stdout("")
+        .stderr(predicate::str::contains("PR titles need a Jira key"))
+        .stderr(predicate::str::contains(
+            "'pr create' was stopped by the pre hook 'hooks/require-jira-key.sh' (exit code 1)",
+        ));
+
+    let (mut cmd, _home) = pr_create("WID-12 Add widget caching");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Created PR #43"));
+}
+
+#[test]
+#[cfg(unix)]
+fn test_post_hook_gets_result_and_failures_only_warn() {
+    let (mut cmd, home) = bb_with_config(
+        "pr_hooks",
+        "[hooks.\"pr merge\"]\npost = [\"hooks/record.sh\", \"false\"]\n",
+    );
+    let record = home.path().join("record.txt");
+    write_script(
+        &home.path().join("config/bb/hooks/record.sh"),
+        &format!(
+            "echo \"$BB_HOOK $BB_HOOK_COMMAND $BB_REPO\" > {0}; cat >> {0}; echo recorded",
+            record.display()
+        ),
+    );
+
+    cmd.current_dir(home.path())
+        .args(["pr", "merge", "42", "-R", "acme/widgets", "--squash"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Merged PR #42"))
+        .stdout(predicate::str::contains("recorded").not())
+        .stderr(predicate::str::contains("recorded"))
+        .stderr(predicate::str::contains(
+            "Post hook 'false' exited with code 1",
+        ));
+
+    let recorded = std::fs::read_to_string(record).unwrap();
+    assert!(recorded.starts_with("post pr merge acme/widgets\n"));
+    assert!(recorded.contains("\"event\":\"post\""));
+    assert!(recorded.contains("\"strategy\":\"squash\""));
+    assert!(recorded.contains("\"result\":{\"merged\":true}"));
+}
+
+#[test]
+#[cfg(unix)]
+fn test_repo_hooks_run_only_when_trusted() {
+    let merge = |config: &str, origin: &str, bb_toml: &str| {
+        let (mut cmd, home) = bb_with_config("pr_hooks", config);
+        let repo = home.path().join("repo");
+        std::fs::create_dir_all(&repo).unwrap();
+        git_in(&repo, &["init", "-q"]);
+        git_in(&repo, &["remote", "add", "origin", origin]);
+        std::fs::write(repo.join(".bb.toml"), bb_toml).unwrap();
+        write_script(
+            &repo.join("no-merges.sh"),
+            "echo 'merge freeze' >&2; exit 3",
+        );
+        cmd.current_dir(&repo)
+            .args(["pr", "merge", "42", "-R", "acme/widgets"]);
+        (cmd, home)
+    };
+    let widgets = "git@bitbucket.org:acme/widgets.git";
+    let freeze = "[hooks.\"pr merge\"]\npre = [\"./no-merges.sh\"]\n";
+    let trusted = "[core]\nrepo_hooks = \"enabled\"\n";
+
+    let (mut cmd, _home) = merge("", widgets, freeze);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Merged PR #42"))
+        .stderr(predicate::str::contains(
+            "run 'bb config set repo_hooks enabled' to trust them",
+        ));
+
+    let (mut cmd, _home) = merge(trusted, widgets, freeze);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("merge freeze"))
+        .stderr(predicate::str::contains("(exit code 3)"));
+
+    // -R names another repository than the checkout
+    let (mut cmd, _home) = merge(trusted, "git@bitbucket.org:acme/gadgets.git", freeze);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Merged PR #42"))
+        .stderr(predicate::str::contains("merge freeze").not());
+
+    // An untrusted .bb.toml is not validated
+    let (mut cmd, _home) = merge("", widgets, "[hooks.\"pr approve\"]\npre = [\"true\"]\n");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Merged PR #42"));
+
+    let (mut cmd, _home) = merge(
+        trusted,
+        widgets,
+        "[hooks.\"pr approve\"]\npre = [\"true\"]\n",
+    );
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "Unknown hook command 'pr approve'",
+    ));
+}